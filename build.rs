@@ -2,6 +2,19 @@ fn main() {
     println!("cargo:rerun-if-changed=proto/");
     println!("cargo:rerun-if-changed=build.rs");
 
+    // MEXC spot uses protobuf; skip prost entirely when the connector is off.
+    #[cfg(feature = "mexc")]
+    compile_mexc_protos();
+
+    // Edge -> collector gRPC service.
+    #[cfg(feature = "sinks")]
+    tonic_build::configure()
+        .compile_protos(&["proto/collector.proto"], &["proto/"])
+        .unwrap();
+}
+
+#[cfg(feature = "mexc")]
+fn compile_mexc_protos() {
     let mut config = prost_build::Config::new();
 
     // Compile the proto files we need
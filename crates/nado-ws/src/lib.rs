@@ -2,7 +2,7 @@ use anyhow::{Result, Context};
 use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::extensions::compression::deflate::DeflateConfig;
 
-// Re-export types that nado.rs needs.
+// Re-export types that nado.rs and vertex.rs need.
 pub use tokio_tungstenite::tungstenite::Message;
 pub use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 pub use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
@@ -11,12 +11,18 @@ pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// Connect to a WebSocket endpoint with permessage-deflate enabled.
 pub async fn connect(url: &str) -> Result<WsStream> {
+    connect_with_origin(url, "https://app.nado.xyz").await
+}
+
+/// `connect` with a different `Origin` header, for other gateways on the
+/// same engine (Vertex).
+pub async fn connect_with_origin(url: &str, origin: &'static str) -> Result<WsStream> {
     use tokio_tungstenite::tungstenite::{client::IntoClientRequest, http};
 
     let mut request = url.into_client_request().context("build ws request")?;
     request.headers_mut().insert(
         http::header::HeaderName::from_static("origin"),
-        http::header::HeaderValue::from_static("https://app.nado.xyz"),
+        http::header::HeaderValue::from_static(origin),
     );
     request.headers_mut().insert(
         http::header::HeaderName::from_static("user-agent"),
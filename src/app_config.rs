@@ -1,9 +1,37 @@
 use crate::exchanges::*;
-use crate::market_data::{AllMarketData, ClockCorrectionConfig};
+use crate::market_data::{AllMarketData, ClockCorrectionConfig, Exchange, StaleTtlConfig};
 use crate::trade_data::AllTradeData;
+use crate::trade_flow::TradeFlowConfig;
+use crate::options_data::AllOptionData;
+use crate::futures_data::AllFuturesData;
+use crate::index_data::AllIndexData;
+use crate::deriv_stats::AllDerivStats;
+use crate::liquidation_data::AllLiquidationData;
+use crate::candle_data::{CandleInterval, CandleStore, KlineConfig};
+use crate::stablecoin::StablecoinConfig;
+use crate::maintenance::MaintenanceConfig;
+use crate::sanitizer::SanitizerConfig;
+use crate::interest::LazySubscriptionConfig;
+use crate::fx::QuoteConversionConfig;
+use crate::watchdog::{DeadManCallback, DeadManConfig};
+#[cfg(feature = "onchain")]
 use crate::onchain::OnchainConfig;
+#[cfg(feature = "analytics")]
+use crate::candle_builder::CandleConfig;
+#[cfg(feature = "analytics")]
+use crate::synthetic::SyntheticConfig;
+#[cfg(feature = "analytics")]
+use crate::instruments::InstrumentStore;
+#[cfg(feature = "analytics")]
+use crate::market_data::InstrumentType;
+#[cfg(feature = "sinks")]
+use crate::collector::CollectorConfig;
+#[cfg(feature = "sinks")]
+use crate::fix::FixConfig;
+#[cfg(feature = "sinks")]
+use crate::udp_sink::UdpSinkConfig;
 use anyhow::{Context, Result};
-use log::error;
+use log::{error, info, warn};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -203,6 +231,7 @@ impl Default for FairPriceParamsConfig {
     }
 }
 
+#[cfg(feature = "analytics")]
 impl FairPriceParamsConfig {
     pub fn parse_model(&self) -> crate::fair_price::FairPriceModel {
         match self.model.as_str() {
@@ -245,6 +274,65 @@ impl FairPriceParamsConfig {
     }
 }
 
+/// Which Coinbase WebSocket serves spot quotes.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CoinbaseSpotMode {
+    /// `ws-feed.exchange.coinbase.com` `ticker` channel (deprecated).
+    #[default]
+    Legacy,
+    /// Advanced Trade `ticker` channel.
+    Advanced,
+}
+
+/// How Binance spot and perp BBO is derived.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BinanceBookMode {
+    /// `@bookTicker` stream.
+    #[default]
+    BookTicker,
+    /// `@depth{binance_depth_levels}@100ms` partial books.
+    Depth,
+}
+
+fn default_binance_depth_levels() -> u8 {
+    5
+}
+
+/// How OKX spot and perp BBO is derived.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OkxBookMode {
+    /// `bbo-tbt` channel.
+    #[default]
+    BboTbt,
+    /// `books` channel: full books validated against OKX's checksum.
+    Books,
+}
+
+/// Which Kraken spot WebSocket API to use.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KrakenSpotMode {
+    /// `wss://ws.kraken.com` `spread` channel.
+    #[default]
+    V1,
+    /// `wss://ws.kraken.com/v2` `ticker` channel.
+    V2,
+}
+
+/// How MEXC perp BBO is derived.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MexcPerpMode {
+    /// `sub.depth` order books; BBO with sizes.
+    #[default]
+    Depth,
+    /// `sub.ticker`; best bid/ask prices only, no books.
+    Ticker,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
@@ -253,9 +341,25 @@ pub struct AppConfig {
     #[serde(default)]
     pub perp: HashMap<String, Vec<String>>,
 
+    /// Bases `SymbolRegistry::from_app_config` registers beyond those the
+    /// symbol lists name, e.g. stablecoins priced against USD.
+    #[serde(default)]
+    pub extra_bases: Vec<String>,
+
+    /// Quotes `SymbolRegistry::from_app_config` pairs every base with,
+    /// e.g. `[USDT, USD, EUR, BTC]`; empty for the registry defaults.
+    #[serde(default)]
+    pub quote_currencies: Vec<String>,
+
+    /// JSON file pinning SymbolIds across config edits; created if missing.
+    /// See `SymbolRegistry::apply_id_map`.
+    #[serde(default)]
+    pub symbol_id_map: Option<String>,
+
     #[serde(default = "default_sample_interval_ms")]
     pub sample_interval_ms: u64,
 
+    #[cfg(feature = "onchain")]
     #[serde(default)]
     pub onchain: Option<OnchainConfig>,
 
@@ -268,8 +372,149 @@ pub struct AppConfig {
     #[serde(default)]
     pub clock_correction: ClockCorrectionConfig,
 
+    /// Per-venue quote TTLs; quotes older than this read as None.
+    #[serde(default)]
+    pub stale_ttl: StaleTtlConfig,
+
+    /// Stablecoin rate sources and the reference currency quotes can be
+    /// normalised into (`StablecoinRates`).
+    #[serde(default)]
+    pub stablecoins: Option<StablecoinConfig>,
+
+    /// Known downtime per venue; connection loops wait it out.
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// Fire an alert / exit when every watched quote is stale at once.
+    #[serde(default)]
+    pub dead_man: Option<DeadManConfig>,
+
+    /// Spike / fat-finger filter applied to every pushed quote.
+    #[serde(default)]
+    pub sanitizer: Option<SanitizerConfig>,
+
+    /// Subscribe to symbols only once a consumer asks for them.
+    #[serde(default)]
+    pub lazy_subscriptions: Option<LazySubscriptionConfig>,
+
+    /// Heartbeat, timeout and backoff overrides for every feed and per venue.
+    #[serde(default)]
+    pub connection: crate::exchanges::connection::ConnectionSettings,
+
+    /// Publish every feed write as a `MarketEvent` (see `event_bus`).
+    #[serde(default)]
+    pub event_bus: Option<crate::event_bus::EventBusConfig>,
+
+    /// Per venue: convert its local-fiat quotes before storage, e.g.
+    /// `upbit: { to: USD, via: USDT }`.
+    #[serde(default)]
+    pub quote_conversion: HashMap<String, QuoteConversionConfig>,
+
+    /// Per venue: fee defaults and per-symbol overrides on top of the
+    /// connector's schedule, e.g. `binance: { perp: {...} }`.
+    #[serde(default)]
+    pub fees: HashMap<String, crate::exchange_fees::VenueFeesConfig>,
+
+    #[serde(default)]
+    pub coinbase_spot_mode: CoinbaseSpotMode,
+
+    #[serde(default)]
+    pub mexc_perp_mode: MexcPerpMode,
+
+    #[serde(default)]
+    pub kraken_spot_mode: KrakenSpotMode,
+
+    #[serde(default)]
+    pub binance_book_mode: BinanceBookMode,
+
+    #[serde(default)]
+    pub okx_book_mode: OkxBookMode,
+
+    /// Partial book levels in `depth` mode: 5, 10 or 20.
+    #[serde(default = "default_binance_depth_levels")]
+    pub binance_depth_levels: u8,
+
+    /// Top-N book levels to publish into `AllMarketData::depth`, per venue
+    /// (MEXC perps in depth mode, Lighter). At most 32.
+    #[serde(default)]
+    pub depth_levels: HashMap<String, usize>,
+
     #[serde(default)]
     pub trades: HashMap<String, Vec<String>>,
+
+    /// Rolling traded volume / VWAP windows over the `trades` streams.
+    #[serde(default)]
+    pub trade_flow: Option<TradeFlowConfig>,
+
+    /// Option contracts or `UNDERLYING@EXPIRY` chains per venue.
+    #[serde(default)]
+    pub options: HashMap<String, Vec<String>>,
+
+    /// Dated futures per venue: contracts ("BTC_USDT_260327") or pairs
+    /// ("BTC_USDT") for every listed expiry.
+    #[serde(default)]
+    pub futures: HashMap<String, Vec<String>>,
+
+    /// Reference indices per source, e.g. `deribit: ["BTC", "ETH"]` for DVOL.
+    #[serde(default)]
+    pub indices: HashMap<String, Vec<String>>,
+
+    /// Perps to collect funding / open interest / mark and oracle prices
+    /// for, per venue.
+    #[serde(default)]
+    pub deriv_stats: HashMap<String, Vec<String>>,
+
+    /// Perps to stream forced liquidations for, per venue.
+    #[serde(default)]
+    pub liquidations: HashMap<String, Vec<String>>,
+
+    /// Poll period for REST-only stats (Binance, Bybit, OKX open interest;
+    /// Binance and Bybit premium index).
+    #[serde(default = "default_oi_poll_interval_s")]
+    pub oi_poll_interval_s: u64,
+
+    /// Closed OHLCV bars from venue kline streams.
+    #[serde(default)]
+    pub klines: Option<KlineConfig>,
+
+    /// Tick-to-bar OHLCV candles.
+    #[cfg(feature = "analytics")]
+    #[serde(default)]
+    pub candles: Option<CandleConfig>,
+
+    /// Cross pairs derived from two legs on the same venue.
+    #[cfg(feature = "analytics")]
+    #[serde(default)]
+    pub synthetic: Option<SyntheticConfig>,
+
+    /// Fetch tick size, lot size and minimum notional for the configured
+    /// spot and perp symbols at startup.
+    #[cfg(feature = "analytics")]
+    #[serde(default)]
+    pub instruments: bool,
+
+    /// Check the configured spot and perp symbols against the venues'
+    /// listings at startup and warn about ones they don't list.
+    #[cfg(feature = "analytics")]
+    #[serde(default)]
+    pub check_listings: bool,
+
+    #[cfg(feature = "sinks")]
+    #[serde(default)]
+    pub collector: Option<CollectorConfig>,
+
+    #[cfg(feature = "sinks")]
+    #[serde(default)]
+    pub fix: Option<FixConfig>,
+
+    /// SBE quotes and trades over UDP; turns on the event bus.
+    #[cfg(feature = "sinks")]
+    #[serde(default)]
+    pub udp_sink: Option<UdpSinkConfig>,
+}
+
+fn default_oi_poll_interval_s() -> u64 {
+    30
 }
 
 fn default_sample_interval_ms() -> u64 {
@@ -302,37 +547,97 @@ pub fn load_config(path: &str) -> Result<AppConfig> {
     Ok(config)
 }
 
+/// Warn about configured venues with no compiled connector.
+/// Depth publisher for `exchange` if `depth_levels` lists it.
+#[cfg(any(feature = "mexc", feature = "lighter"))]
+fn depth_publisher(
+    cfg: &AppConfig,
+    exchange: &str,
+    books: &Arc<crate::market_data::BookCollection>,
+) -> Option<crate::orderbook::DepthPublisher> {
+    let levels = *cfg.depth_levels.get(exchange).filter(|n| **n > 0)?;
+    let venue = exchange.parse::<Exchange>().ok()?;
+    Some(crate::orderbook::DepthPublisher::new(venue, Arc::clone(books), levels))
+}
+
+fn warn_uncompiled(kind: &str, venues: &HashMap<String, Vec<String>>) {
+    for name in venues.keys() {
+        match name.parse::<Exchange>() {
+            Ok(exchange) if exchange.as_str() != name => {
+                warn!("{} venue '{}' must be spelled '{}'; skipping", kind, name, exchange)
+            }
+            Ok(exchange) if !is_compiled(&exchange) => {
+                warn!("{} venue '{}' has no connector compiled in; skipping", kind, name)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("{} {}; skipping", kind, e),
+        }
+    }
+}
+
+/// Install the registry `cfg` describes before its feeds resolve symbols;
+/// a no-op once one is in use.
+fn use_config_registry(cfg: &AppConfig) -> Result<()> {
+    crate::symbol_registry::install_from_app_config(cfg).map_err(anyhow::Error::msg)
+}
+
 pub fn load_spot(
     handles: &mut Vec<JoinHandle<()>>,
     cfg: &AppConfig,
     market_data: &Arc<AllMarketData>,
     shutdown: &Arc<Notify>,
 ) -> Result<()> {
+    use_config_registry(cfg)?;
+    if let Some(s_cfg) = &cfg.sanitizer {
+        market_data.install_sanitizer(s_cfg.clone());
+    }
+    if let Some(l_cfg) = &cfg.lazy_subscriptions {
+        crate::interest::install(l_cfg);
+    }
+    if let Some(b_cfg) = &cfg.event_bus {
+        crate::event_bus::install(b_cfg);
+    }
+    #[cfg(feature = "sinks")]
+    if cfg.udp_sink.is_some() {
+        crate::event_bus::install(&Default::default());
+    }
+    crate::exchanges::connection::install_settings(&cfg.connection);
+    crate::exchanges::install_fees(&cfg.fees);
     // Helper: grab spot symbols for an exchange and make them spawn-friendly ('static)
     let spot_syms = |exchange: &str| -> Option<Arc<[String]>> {
         cfg.spot.get(exchange).cloned().map(Arc::<[String]>::from)
     };
+    warn_uncompiled("spot", &cfg.spot);
+    #[cfg(feature = "analytics")]
+    if cfg.check_listings {
+        spawn_listing_check(handles, InstrumentType::Spot, &cfg.spot, shutdown);
+    }
+    #[cfg(feature = "binance")]
     if let Some(syms) = spot_syms("binance") {
         let data = Arc::clone(&market_data.binance);
         let shutdown = shutdown.clone();
+        let (mode, levels) = (cfg.binance_book_mode, cfg.binance_depth_levels);
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = binance::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = binance::listen_spot_bbo(data, &symbol_refs, mode, levels, shutdown).await {
                 error!("Binance spot listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "coinbase")]
     if let Some(syms) = spot_syms("coinbase") {
         let data = Arc::clone(&market_data.coinbase);
         let shutdown = shutdown.clone();
+        let mode = cfg.coinbase_spot_mode;
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = coinbase::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = coinbase::listen_spot_bbo(data, &symbol_refs, mode, shutdown).await {
                 error!("Coinbase spot listener exited with error {:?}", e);
             }
         }));
     }
 
+    #[cfg(feature = "mexc")]
     if let Some(syms) = spot_syms("mexc") {
         let data = Arc::clone(&market_data.mexc);
         let shutdown = shutdown.clone();
@@ -343,6 +648,7 @@ pub fn load_spot(
             }
         }));
     }
+    #[cfg(feature = "bybit")]
     if let Some(syms) = spot_syms("bybit") {
         let data = Arc::clone(&market_data.bybit);
         let shutdown = shutdown.clone();
@@ -353,26 +659,31 @@ pub fn load_spot(
             }
         }));
     }
+    #[cfg(feature = "kraken")]
     if let Some(syms) = spot_syms("kraken") {
         let data = Arc::clone(&market_data.kraken);
         let shutdown = shutdown.clone();
+        let mode = cfg.kraken_spot_mode;
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = kraken::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = kraken::listen_spot_bbo(data, &symbol_refs, mode, shutdown).await {
                 error!("Kraken spot listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "okx")]
     if let Some(syms) = spot_syms("okx") {
         let data = Arc::clone(&market_data.okx);
         let shutdown = shutdown.clone();
+        let mode = cfg.okx_book_mode;
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = okx::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = okx::listen_spot_bbo(data, &symbol_refs, mode, shutdown).await {
                 error!("OKX spot listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "kucoin")]
     if let Some(syms) = spot_syms("kucoin") {
         let data = Arc::clone(&market_data.kucoin);
         let shutdown = shutdown.clone();
@@ -383,6 +694,7 @@ pub fn load_spot(
             }
         }));
     }
+    #[cfg(feature = "bingx")]
     if let Some(syms) = spot_syms("bingx") {
         let data = Arc::clone(&market_data.bingx);
         let shutdown = shutdown.clone();
@@ -393,6 +705,7 @@ pub fn load_spot(
             }
         }));
     }
+    #[cfg(feature = "hibachi")]
     if let Some(syms) = spot_syms("hibachi") {
         let data = Arc::clone(&market_data.hibachi);
         let shutdown = shutdown.clone();
@@ -403,15 +716,76 @@ pub fn load_spot(
             }
         }));
     }
+    #[cfg(feature = "pyth")]
+    if let Some(syms) = spot_syms("pyth") {
+        let data = Arc::clone(&market_data.pyth);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = pyth::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+                error!("Pyth listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "gateio")]
+    if let Some(syms) = spot_syms("gateio") {
+        let data = Arc::clone(&market_data.gateio);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = gateio::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+                error!("Gate.io spot listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "htx")]
+    if let Some(syms) = spot_syms("htx") {
+        let data = Arc::clone(&market_data.htx);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = htx::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+                error!("HTX spot listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bitfinex")]
+    if let Some(syms) = spot_syms("bitfinex") {
+        let data = Arc::clone(&market_data.bitfinex);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bitfinex::listen_spot_bbo(data, &symbol_refs, shutdown).await {
+                error!("Bitfinex spot listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "upbit")]
+    if let Some(syms) = spot_syms("upbit") {
+        let data = Arc::clone(&market_data.upbit);
+        let shutdown = shutdown.clone();
+        let conversion = cfg
+            .quote_conversion
+            .get("upbit")
+            .and_then(|c| crate::fx::QuoteConversion::from_config(c, "KRW"));
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = upbit::listen_spot_bbo(data, &symbol_refs, conversion, shutdown).await {
+                error!("Upbit spot listener exited with error {:?}", e);
+            }
+        }));
+    }
     Ok(())
 }
 
+#[cfg(feature = "onchain")]
 pub fn load_onchain(
     handles: &mut Vec<JoinHandle<()>>,
     cfg: &AppConfig,
     market_data: &Arc<AllMarketData>,
     shutdown: &Arc<Notify>,
 ) -> Result<()> {
+    use_config_registry(cfg)?;
     if let Some(ref onchain_cfg) = cfg.onchain {
         let rpc_url = onchain_cfg.rpc_url()?;
 
@@ -420,6 +794,23 @@ pub fn load_onchain(
         let uni_data = onchain_cfg.uniswap.as_ref().map(|_| Arc::clone(&market_data.uniswap));
         let uni_pools = onchain_cfg.uniswap.as_ref().map(|u| u.validated_pools("Uniswap")).unwrap_or_default();
 
+        if let Some(ref chainlink_cfg) = onchain_cfg.chainlink {
+            let data = Arc::clone(&market_data.chainlink);
+            let chainlink_cfg = chainlink_cfg.clone();
+            let rpc_url = rpc_url.clone();
+            let shutdown = shutdown.clone();
+            handles.push(tokio::spawn(async move {
+                if let Err(e) = crate::onchain::chainlink::listen_chainlink(
+                    data, chainlink_cfg, rpc_url, shutdown,
+                ).await {
+                    error!("Chainlink listener exited with error {:?}", e);
+                }
+            }));
+        }
+
+        if aero_data.is_none() && uni_data.is_none() {
+            return Ok(());
+        }
         let shutdown = shutdown.clone();
         handles.push(tokio::spawn(async move {
             if let Err(e) = crate::onchain::listener::listen_onchain(
@@ -432,26 +823,123 @@ pub fn load_onchain(
     Ok(())
 }
 
+#[cfg(feature = "sinks")]
+pub fn load_collector(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    if let Some(ref collector_cfg) = cfg.collector {
+        let data = Arc::clone(market_data);
+        let shutdown = shutdown.clone();
+        match collector_cfg.mode.as_str() {
+            "edge" => {
+                let collector_cfg = collector_cfg.clone();
+                handles.push(tokio::spawn(async move {
+                    if let Err(e) = crate::collector::run_edge(data, collector_cfg, shutdown).await {
+                        error!("Edge push exited with error {:?}", e);
+                    }
+                }));
+            }
+            "collector" => {
+                let collector_cfg = collector_cfg.clone();
+                handles.push(tokio::spawn(async move {
+                    if let Err(e) = crate::collector::run_collector(data, collector_cfg, shutdown).await {
+                        error!("Collector exited with error {:?}", e);
+                    }
+                }));
+            }
+            other => anyhow::bail!("Unknown collector mode '{}'. Valid: edge, collector", other),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sinks")]
+pub fn load_fix(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    if let Some(ref fix_cfg) = cfg.fix {
+        let data = Arc::clone(market_data);
+        let fix_cfg = fix_cfg.clone();
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = crate::fix::run_fix_acceptor(data, fix_cfg, shutdown).await {
+                error!("FIX acceptor exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+/// Start the UDP sink. Call after `load_spot`/`load_perp`, which install
+/// the event bus it reads.
+#[cfg(feature = "sinks")]
+pub fn load_udp_sink(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    if let Some(ref udp_cfg) = cfg.udp_sink {
+        let udp_cfg = udp_cfg.clone();
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = crate::udp_sink::run_udp_sink(udp_cfg, shutdown).await {
+                error!("UDP sink exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
 pub fn load_perp(
     handles: &mut Vec<JoinHandle<()>>,
     cfg: &AppConfig,
     market_data: &Arc<AllMarketData>,
     shutdown: &Arc<Notify>,
 ) -> Result<()> {
+    use_config_registry(cfg)?;
+    if let Some(s_cfg) = &cfg.sanitizer {
+        market_data.install_sanitizer(s_cfg.clone());
+    }
+    if let Some(l_cfg) = &cfg.lazy_subscriptions {
+        crate::interest::install(l_cfg);
+    }
+    if let Some(b_cfg) = &cfg.event_bus {
+        crate::event_bus::install(b_cfg);
+    }
+    #[cfg(feature = "sinks")]
+    if cfg.udp_sink.is_some() {
+        crate::event_bus::install(&Default::default());
+    }
+    crate::exchanges::connection::install_settings(&cfg.connection);
+    crate::exchanges::install_fees(&cfg.fees);
     // Helper: grab spot symbols for an exchange and make them spawn-friendly ('static)
     let perp_syms = |exchange: &str| -> Option<Arc<[String]>> {
         cfg.perp.get(exchange).cloned().map(Arc::<[String]>::from)
     };
+    warn_uncompiled("perp", &cfg.perp);
+    #[cfg(feature = "analytics")]
+    if cfg.check_listings {
+        spawn_listing_check(handles, InstrumentType::Perp, &cfg.perp, shutdown);
+    }
+    #[cfg(feature = "binance")]
     if let Some(syms) = perp_syms("binance") {
         let data = Arc::clone(&market_data.binance);
         let shutdown = shutdown.clone();
+        let (mode, levels) = (cfg.binance_book_mode, cfg.binance_depth_levels);
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = binance::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = binance::listen_perp_bbo(data, &symbol_refs, mode, levels, shutdown).await {
                 error!("Binance perp listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "coinbase")]
     if let Some(syms) = perp_syms("coinbase") {
         let data = Arc::clone(&market_data.coinbase);
         let shutdown = shutdown.clone();
@@ -462,16 +950,21 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "mexc")]
     if let Some(syms) = perp_syms("mexc") {
         let data = Arc::clone(&market_data.mexc);
         let shutdown = shutdown.clone();
+        let mode = cfg.mexc_perp_mode;
+        let depth = depth_publisher(cfg, "mexc", &market_data.depth.mexc);
+        let live_books = Some(Arc::clone(&market_data.live_books));
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = mexc::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = mexc::listen_perp_bbo(data, &symbol_refs, mode, depth, live_books, shutdown).await {
                 error!("Mexc perp listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "bybit")]
     if let Some(syms) = perp_syms("bybit") {
         let data = Arc::clone(&market_data.bybit);
         let shutdown = shutdown.clone();
@@ -482,6 +975,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "kraken")]
     if let Some(syms) = perp_syms("kraken") {
         let data = Arc::clone(&market_data.kraken);
         let shutdown = shutdown.clone();
@@ -492,16 +986,20 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "lighter")]
     if let Some(syms) = perp_syms("lighter") {
         let data = Arc::clone(&market_data.lighter);
+        let depth = depth_publisher(cfg, "lighter", &market_data.depth.lighter);
+        let live_books = Some(Arc::clone(&market_data.live_books));
         let shutdown = shutdown.clone();
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = lighter::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = lighter::listen_perp_bbo(data, &symbol_refs, depth, live_books, shutdown).await {
                 error!("Lighter perp listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "extended")]
     if let Some(syms) = perp_syms("extended") {
         let data = Arc::clone(&market_data.extended);
         let shutdown = shutdown.clone();
@@ -512,6 +1010,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "nado")]
     if let Some(syms) = perp_syms("nado") {
         let data = Arc::clone(&market_data.nado);
         let shutdown = shutdown.clone();
@@ -522,16 +1021,19 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "okx")]
     if let Some(syms) = perp_syms("okx") {
         let data = Arc::clone(&market_data.okx);
         let shutdown = shutdown.clone();
+        let mode = cfg.okx_book_mode;
         handles.push(tokio::spawn(async move {
             let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = okx::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+            if let Err(e) = okx::listen_perp_bbo(data, &symbol_refs, mode, shutdown).await {
                 error!("OKX perp listener exited with error {:?}", e);
             }
         }));
     }
+    #[cfg(feature = "kucoin")]
     if let Some(syms) = perp_syms("kucoin") {
         let data = Arc::clone(&market_data.kucoin);
         let shutdown = shutdown.clone();
@@ -542,6 +1044,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "bingx")]
     if let Some(syms) = perp_syms("bingx") {
         let data = Arc::clone(&market_data.bingx);
         let shutdown = shutdown.clone();
@@ -552,6 +1055,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "apex")]
     if let Some(syms) = perp_syms("apex") {
         let data = Arc::clone(&market_data.apex);
         let shutdown = shutdown.clone();
@@ -562,6 +1066,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "hyperliquid")]
     if let Some(syms) = perp_syms("hyperliquid") {
         let data = Arc::clone(&market_data.hyperliquid);
         let shutdown = shutdown.clone();
@@ -572,6 +1077,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "hibachi")]
     if let Some(syms) = perp_syms("hibachi") {
         let data = Arc::clone(&market_data.hibachi);
         let shutdown = shutdown.clone();
@@ -582,6 +1088,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "hotstuff")]
     if let Some(syms) = perp_syms("hotstuff") {
         let data = Arc::clone(&market_data.hotstuff);
         let shutdown = shutdown.clone();
@@ -592,6 +1099,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "zeroone")]
     if let Some(syms) = perp_syms("zeroone") {
         let data = Arc::clone(&market_data.zeroone);
         let shutdown = shutdown.clone();
@@ -602,6 +1110,7 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "risex")]
     if let Some(syms) = perp_syms("risex") {
         let data = Arc::clone(&market_data.risex);
         let shutdown = shutdown.clone();
@@ -612,6 +1121,84 @@ pub fn load_perp(
             }
         }));
     }
+    #[cfg(feature = "dydx")]
+    if let Some(syms) = perp_syms("dydx") {
+        let data = Arc::clone(&market_data.dydx);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = dydx::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("dYdX perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+
+    #[cfg(feature = "deribit")]
+    if let Some(syms) = perp_syms("deribit") {
+        let data = Arc::clone(&market_data.deribit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = deribit::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("Deribit perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "gateio")]
+    if let Some(syms) = perp_syms("gateio") {
+        let data = Arc::clone(&market_data.gateio);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = gateio::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("Gate.io perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "htx")]
+    if let Some(syms) = perp_syms("htx") {
+        let data = Arc::clone(&market_data.htx);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = htx::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("HTX perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bitfinex")]
+    if let Some(syms) = perp_syms("bitfinex") {
+        let data = Arc::clone(&market_data.bitfinex);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bitfinex::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("Bitfinex perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "phemex")]
+    if let Some(syms) = perp_syms("phemex") {
+        let data = Arc::clone(&market_data.phemex);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = phemex::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("Phemex perp listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "vertex")]
+    if let Some(syms) = perp_syms("vertex") {
+        let data = Arc::clone(&market_data.vertex);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = vertex::listen_perp_bbo(data, &symbol_refs, shutdown).await {
+                error!("Vertex perp listener exited with error {:?}", e);
+            }
+        }));
+    }
     Ok(())
 }
 
@@ -621,9 +1208,15 @@ pub fn load_trades(
     trade_data: &Arc<AllTradeData>,
     shutdown: &Arc<Notify>,
 ) -> Result<()> {
+    use_config_registry(cfg)?;
+    if let Some(f_cfg) = &cfg.trade_flow {
+        trade_data.install_flow(f_cfg);
+    }
     let trade_syms = |exchange: &str| -> Option<Arc<[String]>> {
         cfg.trades.get(exchange).cloned().map(Arc::<[String]>::from)
     };
+    warn_uncompiled("trades", &cfg.trades);
+    #[cfg(feature = "binance")]
     if let Some(syms) = trade_syms("binance") {
         let data = Arc::clone(&trade_data.binance);
         let shutdown = shutdown.clone();
@@ -634,6 +1227,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "bybit")]
     if let Some(syms) = trade_syms("bybit") {
         let data = Arc::clone(&trade_data.bybit);
         let shutdown = shutdown.clone();
@@ -644,6 +1238,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "hotstuff")]
     if let Some(syms) = trade_syms("hotstuff") {
         let data = Arc::clone(&trade_data.hotstuff);
         let shutdown = shutdown.clone();
@@ -654,6 +1249,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "zeroone")]
     if let Some(syms) = trade_syms("zeroone") {
         let data = Arc::clone(&trade_data.zeroone);
         let shutdown = shutdown.clone();
@@ -664,6 +1260,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "risex")]
     if let Some(syms) = trade_syms("risex") {
         let data = Arc::clone(&trade_data.risex);
         let shutdown = shutdown.clone();
@@ -674,6 +1271,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "hibachi")]
     if let Some(syms) = trade_syms("hibachi") {
         let data = Arc::clone(&trade_data.hibachi);
         let shutdown = shutdown.clone();
@@ -684,6 +1282,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "extended")]
     if let Some(syms) = trade_syms("extended") {
         let data = Arc::clone(&trade_data.extended);
         let shutdown = shutdown.clone();
@@ -694,6 +1293,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "nado")]
     if let Some(syms) = trade_syms("nado") {
         let data = Arc::clone(&trade_data.nado);
         let shutdown = shutdown.clone();
@@ -704,6 +1304,7 @@ pub fn load_trades(
             }
         }));
     }
+    #[cfg(feature = "hyperliquid")]
     if let Some(syms) = trade_syms("hyperliquid") {
         let data = Arc::clone(&trade_data.hyperliquid);
         let shutdown = shutdown.clone();
@@ -716,3 +1317,537 @@ pub fn load_trades(
     }
     Ok(())
 }
+
+pub fn load_options(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    option_data: &Arc<AllOptionData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    let option_syms = |exchange: &str| -> Option<Arc<[String]>> {
+        cfg.options.get(exchange).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("options", &cfg.options);
+    #[cfg(feature = "binance")]
+    if let Some(syms) = option_syms("binance") {
+        let data = Arc::clone(&option_data.binance);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::listen_options_ticker(data, &symbol_refs, shutdown).await {
+                error!("Binance options listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "deribit")]
+    if let Some(syms) = option_syms("deribit") {
+        let data = Arc::clone(&option_data.deribit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = deribit::listen_options_ticker(data, &symbol_refs, shutdown).await {
+                error!("Deribit options listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bybit")]
+    if let Some(syms) = option_syms("bybit") {
+        let data = Arc::clone(&option_data.bybit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::listen_options_ticker(data, &symbol_refs, shutdown).await {
+                error!("Bybit options listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+pub fn load_futures(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    futures_data: &Arc<AllFuturesData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    let futures_syms = |exchange: &str| -> Option<Arc<[String]>> {
+        cfg.futures.get(exchange).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("futures", &cfg.futures);
+    #[cfg(feature = "binance")]
+    if let Some(syms) = futures_syms("binance") {
+        let data = Arc::clone(&futures_data.binance);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::listen_dated_futures_bbo(data, &symbol_refs, shutdown).await {
+                error!("Binance futures listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "deribit")]
+    if let Some(syms) = futures_syms("deribit") {
+        let data = Arc::clone(&futures_data.deribit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = deribit::listen_dated_futures_ticker(data, &symbol_refs, shutdown).await {
+                error!("Deribit futures listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+pub fn load_indices(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    index_data: &Arc<AllIndexData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    let index_syms = |source: &str| -> Option<Arc<[String]>> {
+        cfg.indices.get(source).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("indices", &cfg.indices);
+    #[cfg(feature = "deribit")]
+    if let Some(syms) = index_syms("deribit") {
+        let data = Arc::clone(&index_data.deribit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = deribit::listen_dvol(data, &symbol_refs, shutdown).await {
+                error!("Deribit DVOL listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+pub fn load_deriv_stats(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    deriv_stats: &Arc<AllDerivStats>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    use_config_registry(cfg)?;
+    let stats_syms = |exchange: &str| -> Option<Arc<[String]>> {
+        cfg.deriv_stats.get(exchange).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("deriv_stats", &cfg.deriv_stats);
+    let interval = std::time::Duration::from_secs(cfg.oi_poll_interval_s.max(1));
+    #[cfg(feature = "hyperliquid")]
+    if let Some(syms) = stats_syms("hyperliquid") {
+        let data = Arc::clone(&deriv_stats.hyperliquid);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = hyperliquid::listen_perp_asset_ctx(data, &symbol_refs, shutdown).await {
+                error!("Hyperliquid asset ctx listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "binance")]
+    if let Some(syms) = stats_syms("binance") {
+        let data = Arc::clone(&deriv_stats.binance);
+        let shutdown = shutdown.clone();
+        let mark_syms = Arc::clone(&syms);
+        let mark_data = Arc::clone(&data);
+        let mark_shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = mark_syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::listen_perp_mark_price(mark_data, &symbol_refs, mark_shutdown).await {
+                error!("Binance mark price listener exited with error {:?}", e);
+            }
+        }));
+        let premium_syms = Arc::clone(&syms);
+        let premium_data = Arc::clone(&data);
+        let premium_shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = premium_syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::poll_premium_index(premium_data, &symbol_refs, interval, premium_shutdown).await {
+                error!("Binance premium index poller exited with error {:?}", e);
+            }
+        }));
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::poll_open_interest(data, &symbol_refs, interval, shutdown).await {
+                error!("Binance open interest poller exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bybit")]
+    if let Some(syms) = stats_syms("bybit") {
+        let data = Arc::clone(&deriv_stats.bybit);
+        let shutdown = shutdown.clone();
+        let mark_syms = Arc::clone(&syms);
+        let mark_data = Arc::clone(&data);
+        let mark_shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = mark_syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::listen_perp_mark_price(mark_data, &symbol_refs, mark_shutdown).await {
+                error!("Bybit mark price listener exited with error {:?}", e);
+            }
+        }));
+        let premium_syms = Arc::clone(&syms);
+        let premium_data = Arc::clone(&data);
+        let premium_shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = premium_syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::poll_premium_index(premium_data, &symbol_refs, interval, premium_shutdown).await {
+                error!("Bybit premium index poller exited with error {:?}", e);
+            }
+        }));
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::poll_open_interest(data, &symbol_refs, interval, shutdown).await {
+                error!("Bybit open interest poller exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "okx")]
+    if let Some(syms) = stats_syms("okx") {
+        let data = Arc::clone(&deriv_stats.okx);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = okx::poll_open_interest(data, &symbol_refs, interval, shutdown).await {
+                error!("OKX open interest poller exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "mexc")]
+    if let Some(syms) = stats_syms("mexc") {
+        let data = Arc::clone(&deriv_stats.mexc);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = mexc::listen_perp_mark_price(data, &symbol_refs, shutdown).await {
+                error!("MEXC mark price listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+pub fn load_liquidations(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    liquidation_data: &Arc<AllLiquidationData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    use_config_registry(cfg)?;
+    let liq_syms = |exchange: &str| -> Option<Arc<[String]>> {
+        cfg.liquidations.get(exchange).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("liquidations", &cfg.liquidations);
+    #[cfg(feature = "binance")]
+    if let Some(syms) = liq_syms("binance") {
+        let data = Arc::clone(&liquidation_data.binance);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::listen_perp_liquidations(data, &symbol_refs, shutdown).await {
+                error!("Binance liquidation listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bybit")]
+    if let Some(syms) = liq_syms("bybit") {
+        let data = Arc::clone(&liquidation_data.bybit);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::listen_perp_liquidations(data, &symbol_refs, shutdown).await {
+                error!("Bybit liquidation listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(())
+}
+
+/// Spawn the venue kline feeds if a `klines` section is configured.
+/// Returns the store they write to.
+pub fn load_klines(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    shutdown: &Arc<Notify>,
+) -> Result<Option<Arc<CandleStore>>> {
+    use_config_registry(cfg)?;
+    let Some(ref kline_cfg) = cfg.klines else {
+        return Ok(None);
+    };
+    let intervals: Arc<[CandleInterval]> = kline_cfg.parsed_intervals()?.into();
+    if !kline_cfg.capacity.is_power_of_two() {
+        anyhow::bail!("klines capacity must be a power of 2, got {}", kline_cfg.capacity);
+    }
+    let store = Arc::new(CandleStore::new(kline_cfg.capacity));
+    let kline_syms = |exchange: &str| -> Option<Arc<[String]>> {
+        kline_cfg.symbols.get(exchange).cloned().map(Arc::<[String]>::from)
+    };
+    warn_uncompiled("klines", &kline_cfg.symbols);
+    #[cfg(feature = "binance")]
+    if let Some(syms) = kline_syms("binance") {
+        let store = Arc::clone(&store);
+        let intervals = Arc::clone(&intervals);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = binance::listen_perp_klines(store, &symbol_refs, &intervals, shutdown).await {
+                error!("Binance kline listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "bybit")]
+    if let Some(syms) = kline_syms("bybit") {
+        let store = Arc::clone(&store);
+        let intervals = Arc::clone(&intervals);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = bybit::listen_perp_klines(store, &symbol_refs, &intervals, shutdown).await {
+                error!("Bybit kline listener exited with error {:?}", e);
+            }
+        }));
+    }
+    #[cfg(feature = "coinbase")]
+    if let Some(syms) = kline_syms("coinbase") {
+        let store = Arc::clone(&store);
+        let intervals = Arc::clone(&intervals);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            let symbol_refs: Vec<&str> = syms.iter().map(|s| s.as_str()).collect();
+            if let Err(e) = coinbase::listen_spot_klines(store, &symbol_refs, &intervals, shutdown).await {
+                error!("Coinbase kline listener exited with error {:?}", e);
+            }
+        }));
+    }
+    Ok(Some(store))
+}
+
+/// Spawn the instrument metadata fetch if `instruments` is set; see
+/// `spawn_instrument_fetch`.
+#[cfg(feature = "analytics")]
+pub fn load_instruments(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    shutdown: &Arc<Notify>,
+) -> Option<Arc<InstrumentStore>> {
+    cfg.instruments.then(|| spawn_instrument_fetch(handles, cfg, shutdown))
+}
+
+/// Fetch instrument metadata for the configured spot and perp symbols of
+/// the venues `instruments::SUPPORTED` covers. Returns the store, filled
+/// as each venue's listing arrives.
+#[cfg(feature = "analytics")]
+pub fn spawn_instrument_fetch(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    shutdown: &Arc<Notify>,
+) -> Arc<InstrumentStore> {
+    let store = Arc::new(InstrumentStore::new());
+    for &(exchange, itype) in crate::instruments::SUPPORTED {
+        let venues = match itype {
+            InstrumentType::Spot => &cfg.spot,
+            _ => &cfg.perp,
+        };
+        let Some(syms) = venues.get(exchange.as_str()).filter(|s| !s.is_empty()).cloned() else {
+            continue;
+        };
+        let store = Arc::clone(&store);
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            tokio::select! {
+                res = crate::instruments::fetch_instruments(exchange, itype, &syms) => match res {
+                    Ok(list) => {
+                        let n = list.len();
+                        for info in list {
+                            store.insert(info);
+                        }
+                        info!("{} {} instruments: {} of {} symbols", exchange, itype.as_str(), n, syms.len());
+                    }
+                    Err(e) => error!("{} {} instruments fetch failed: {:?}", exchange, itype.as_str(), e),
+                },
+                _ = shutdown.notified() => {}
+            }
+        }));
+    }
+    store
+}
+
+/// Warn about `venues` symbols their venue doesn't list, for the venues
+/// `instruments::SUPPORTED` covers as `itype`.
+#[cfg(feature = "analytics")]
+fn spawn_listing_check(
+    handles: &mut Vec<JoinHandle<()>>,
+    itype: InstrumentType,
+    venues: &HashMap<String, Vec<String>>,
+    shutdown: &Arc<Notify>,
+) {
+    for &(exchange, listed_type) in crate::instruments::SUPPORTED {
+        if listed_type.as_str() != itype.as_str() {
+            continue;
+        }
+        let Some(syms) = venues.get(exchange.as_str()).filter(|s| !s.is_empty()).cloned() else {
+            continue;
+        };
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(async move {
+            tokio::select! {
+                res = crate::instruments::unlisted(exchange, itype, &syms) => match res {
+                    Ok(missing) => {
+                        for (symbol, native) in missing {
+                            warn!("{} {} does not list '{}' ({}); it will get no data", exchange, itype.as_str(), native, symbol);
+                        }
+                    }
+                    Err(e) => warn!("{} {} listing check failed: {:?}", exchange, itype.as_str(), e),
+                },
+                _ = shutdown.notified() => {}
+            }
+        }));
+    }
+}
+
+/// Install configured maintenance windows and start status-endpoint
+/// polling. Call before starting feeds so the first connect already sees
+/// the schedule.
+pub fn load_maintenance(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    crate::maintenance::install(&cfg.maintenance);
+    #[cfg(feature = "analytics")]
+    if !cfg.maintenance.status_poll.is_empty() {
+        let m_cfg = cfg.maintenance.clone();
+        let shutdown = shutdown.clone();
+        handles.push(tokio::spawn(crate::maintenance::run_status_poller(m_cfg, shutdown)));
+    }
+    #[cfg(not(feature = "analytics"))]
+    {
+        let _ = (handles, shutdown);
+        if !cfg.maintenance.status_poll.is_empty() {
+            warn!("maintenance: status polling needs the analytics feature");
+        }
+    }
+    Ok(())
+}
+
+/// Spawn the dead-man's switch if configured. `callback` runs in addition
+/// to the configured webhook / exit code.
+pub fn load_dead_man(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    callback: Option<DeadManCallback>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    let Some(ref dm_cfg) = cfg.dead_man else {
+        return Ok(());
+    };
+    let universe = dm_cfg.universe(&cfg.spot, &cfg.perp);
+    info!("Dead-man switch watching {} quotes (max age {}s)", universe.len(), dm_cfg.max_age_s);
+    handles.push(tokio::spawn(crate::watchdog::run_dead_man(
+        dm_cfg.clone(),
+        universe,
+        Arc::clone(market_data),
+        callback,
+        shutdown.clone(),
+    )));
+    Ok(())
+}
+
+/// Spawn the stale-quote sweeper if any TTL is configured.
+pub fn load_stale_sweeper(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    if !cfg.stale_ttl.is_enabled() {
+        return Ok(());
+    }
+    let ttl_cfg = cfg.stale_ttl.clone();
+    let market_data = Arc::clone(market_data);
+    let shutdown = shutdown.clone();
+    handles.push(tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(ttl_cfg.sweep_interval_ms.max(1)));
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => break,
+                _ = interval.tick() => {
+                    let now = std::time::Instant::now();
+                    let wall = chrono::Utc::now();
+                    // Still evict during known downtime, just don't report it.
+                    let evicted: usize = market_data
+                        .iter()
+                        .filter_map(|(exchange, coll)| {
+                            let n = coll.evict_stale(ttl_cfg.ttl_for(exchange.as_str())?, now);
+                            crate::maintenance::downtime(&exchange, wall).is_none().then_some(n)
+                        })
+                        .sum();
+                    if evicted > 0 {
+                        info!("Stale sweeper evicted {} quote(s)", evicted);
+                    }
+                }
+            }
+        }
+    }));
+    Ok(())
+}
+
+/// Spawn the candle builder if a `candles` section is configured. Returns
+/// the store it writes to.
+#[cfg(feature = "analytics")]
+pub fn load_candles(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    trade_data: Option<&Arc<AllTradeData>>,
+    shutdown: &Arc<Notify>,
+) -> Result<Option<Arc<CandleStore>>> {
+    let Some(ref candle_cfg) = cfg.candles else {
+        return Ok(None);
+    };
+    candle_cfg.parsed_intervals()?;
+    let store = Arc::new(CandleStore::new(candle_cfg.capacity));
+    let series = candle_cfg.series();
+    let candle_cfg = candle_cfg.clone();
+    let market_data = Arc::clone(market_data);
+    let trade_data = trade_data.cloned();
+    let store_clone = Arc::clone(&store);
+    let shutdown = shutdown.clone();
+    handles.push(tokio::spawn(async move {
+        if let Err(e) = crate::candle_builder::run_candle_builder(
+            candle_cfg, series, market_data, trade_data, store_clone, shutdown,
+        )
+        .await
+        {
+            error!("Candle builder exited with error {:?}", e);
+        }
+    }));
+    Ok(Some(store))
+}
+
+/// Spawn cross-pair synthesis if a `synthetic` section is configured.
+#[cfg(feature = "analytics")]
+pub fn load_synthetic(
+    handles: &mut Vec<JoinHandle<()>>,
+    cfg: &AppConfig,
+    market_data: &Arc<AllMarketData>,
+    shutdown: &Arc<Notify>,
+) -> Result<()> {
+    let Some(ref synth_cfg) = cfg.synthetic else {
+        return Ok(());
+    };
+    let series = synth_cfg.series_for(cfg)?;
+    let poll_interval_ms = synth_cfg.poll_interval_ms;
+    let market_data = Arc::clone(market_data);
+    let shutdown = shutdown.clone();
+    handles.push(tokio::spawn(async move {
+        if let Err(e) = crate::synthetic::run_synthesis(series, poll_interval_ms, market_data, shutdown).await {
+            error!("Synthetic pairs exited with error {:?}", e);
+        }
+    }));
+    Ok(())
+}
@@ -93,6 +93,20 @@ fn build_targets(cfg: &AppConfig, market_data: &AllMarketData) -> Vec<SampleTarg
         }
     }
 
+    // Chainlink reference prices
+    if let Some(chainlink) = cfg.onchain.as_ref().and_then(|o| o.chainlink.as_ref()) {
+        for (_, symbol, id) in chainlink.validated_feeds() {
+            let canonical = REGISTRY.get_symbol(id).unwrap_or(&symbol).to_string();
+            targets.push(SampleTarget {
+                exchange_name: "chainlink",
+                canonical,
+                collection: Arc::clone(&market_data.chainlink),
+                symbol_id: id,
+                prev_write_pos: 0,
+            });
+        }
+    }
+
     targets
 }
 
@@ -197,9 +211,9 @@ fn auto_discover_groups(cfg: &AppConfig) -> Vec<FairPriceGroupConfig> {
         let entries = &base_map[&base];
         let mut members = Vec::new();
         for (exchange_name, symbol_str, itype, reprice) in entries {
-            let exchange = match Exchange::from_str(exchange_name) {
-                Some(e) => e,
-                None => continue,
+            let exchange = match exchange_name.parse::<Exchange>() {
+                Ok(e) => e,
+                Err(_) => continue,
             };
             let symbol_id = match REGISTRY.lookup(symbol_str, itype) {
                 Some(&id) => id,
@@ -78,9 +78,9 @@ fn auto_discover_groups(cfg: &AppConfig) -> Vec<FairPriceGroupConfig> {
         let mut members = Vec::new();
 
         for (exchange_name, symbol_str, itype, reprice) in entries {
-            let exchange = match Exchange::from_str(exchange_name) {
-                Some(e) => e,
-                None => continue,
+            let exchange = match exchange_name.parse::<Exchange>() {
+                Ok(e) => e,
+                Err(_) => continue,
             };
             let symbol_id = match REGISTRY.lookup(symbol_str, itype) {
                 Some(&id) => id,
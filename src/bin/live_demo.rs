@@ -6,7 +6,7 @@ use tokio::signal;
 use tokio::sync::Notify;
 
 use crypto_feeds::analytics::Analytics;
-use crypto_feeds::app_config::{load_config, load_onchain, load_perp, load_spot, AppConfig};
+use crypto_feeds::app_config::{load_collector, load_config, load_dead_man, load_fix, load_maintenance, load_onchain, load_perp, load_spot, load_stale_sweeper, load_synthetic, load_udp_sink, AppConfig};
 use crypto_feeds::display::{init_display_logger, print_bbo_with_analytics};
 use crypto_feeds::market_data::AllMarketData;
 use crypto_feeds::snapshot::{run_snapshot_task, AllSnapshotData, SnapshotConfig};
@@ -21,10 +21,20 @@ async fn main() -> Result<()> {
 
     let mut handles = Vec::new();
 
-    // Start spot, perp, and on-chain feeds
+    let _ = load_maintenance(&mut handles, &cfg, &shutdown);
+
+    // Start spot, perp, and on-chain feeds, plus edge/collector push if configured
     let _ = load_spot(&mut handles, &cfg, &market_data, &shutdown);
     let _ = load_perp(&mut handles, &cfg, &market_data, &shutdown);
     let _ = load_onchain(&mut handles, &cfg, &market_data, &shutdown);
+    if let Err(e) = load_collector(&mut handles, &cfg, &market_data, &shutdown) {
+        error!("Failed to start collector: {:?}", e);
+    }
+    let _ = load_fix(&mut handles, &cfg, &market_data, &shutdown);
+    let _ = load_udp_sink(&mut handles, &cfg, &shutdown);
+    let _ = load_stale_sweeper(&mut handles, &cfg, &market_data, &shutdown);
+    let _ = load_synthetic(&mut handles, &cfg, &market_data, &shutdown);
+    let _ = load_dead_man(&mut handles, &cfg, &market_data, None, &shutdown);
 
     // Start snapshot engine (100ms interval, 65536 buffer ≈ 109 min at 100ms)
     // Must be >= 36_000 to support 1-hour analytics (fills/hr, median spread, etc.)
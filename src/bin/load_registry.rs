@@ -1,26 +1,25 @@
-use crypto_feeds::{market_data::InstrumentType, symbol_registry::REGISTRY};
+use crypto_feeds::{
+    market_data::InstrumentType,
+    symbol_registry::{REGISTRY, symbol_capacity},
+};
 
 fn main() {
-    println!("Loading symbol registry...\n");
-
-    // Count symbols
-    let mut count = 0;
-    let mut canonical_symbols = Vec::new();
-
-    for id in 0..500 {
-        if let Some(symbol) = REGISTRY.get_symbol(id) {
-            canonical_symbols.push((id, symbol.to_string()));
-            count += 1;
-        }
+    // `--json` dumps every symbol with its aliases instead.
+    if std::env::args().any(|a| a == "--json") {
+        println!("{}", REGISTRY.to_json().expect("registry serializes"));
+        return;
     }
 
+    println!("Loading symbol registry...\n");
+
+    let count = REGISTRY.len();
     println!("✓ Loaded {} canonical symbols\n", count);
 
     // Display all canonical symbols
     println!("Canonical Symbols:");
     println!("{:-<60}", "");
-    for (id, symbol) in &canonical_symbols {
-        println!("{:3} | {}", id, symbol);
+    for (id, symbol) in REGISTRY.iter() {
+        println!("{:3} | {} ({} aliases)", id, symbol, REGISTRY.aliases_for(id).len());
     }
     println!();
 
@@ -71,6 +70,7 @@ fn main() {
     println!("Registry Statistics:");
     println!("{:-<60}", "");
     println!("Total canonical symbols: {}", count);
-    println!("Maximum capacity: 500");
-    println!("Utilization: {:.1}%", (count as f64 / 500.0) * 100.0);
+    println!("Quote currencies: {}", REGISTRY.quotes().join(", "));
+    println!("Collection slots: {}", symbol_capacity());
+    println!("Utilization: {:.1}%", (REGISTRY.id_bound() as f64 / symbol_capacity() as f64) * 100.0);
 }
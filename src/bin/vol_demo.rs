@@ -81,7 +81,7 @@ fn discover_venues_for_base(cfg: &AppConfig, base: &str) -> Vec<SymbolVenue> {
     for (exchange_name, symbols) in &cfg.spot {
         for sym in symbols {
             if sym.starts_with(&prefix) {
-                if let Some(exchange) = Exchange::from_str(exchange_name) {
+                if let Ok(exchange) = exchange_name.parse::<Exchange>() {
                     if let Some(&id) = REGISTRY.lookup(sym, &InstrumentType::Spot) {
                         venues.push(SymbolVenue {
                             exchange, exchange_name: exchange_name.clone(),
@@ -96,7 +96,7 @@ fn discover_venues_for_base(cfg: &AppConfig, base: &str) -> Vec<SymbolVenue> {
     for (exchange_name, symbols) in &cfg.perp {
         for sym in symbols {
             if sym.starts_with(&prefix) {
-                if let Some(exchange) = Exchange::from_str(exchange_name) {
+                if let Ok(exchange) = exchange_name.parse::<Exchange>() {
                     if let Some(&id) = REGISTRY.lookup(sym, &InstrumentType::Perp) {
                         venues.push(SymbolVenue {
                             exchange, exchange_name: exchange_name.clone(),
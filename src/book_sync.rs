@@ -0,0 +1,243 @@
+//! REST snapshot plus buffered WebSocket diff synchronization.
+//!
+//! Diff-depth streams (Binance `@depth`, and others modelled on it) only
+//! carry changes, so a local book starts from a REST snapshot. Diffs that
+//! arrive before the snapshot are buffered; once it lands, those already
+//! covered by its `last_update_id` are dropped, the first kept diff must
+//! straddle it, and every later diff must continue the chain from the one
+//! before. `BookSync` holds that state machine without doing any I/O: the
+//! feed fetches the snapshot and hands frames in. On a broken chain it goes
+//! back to buffering and the feed fetches a new snapshot.
+
+use crate::orderbook::OrderBook;
+use std::collections::VecDeque;
+
+/// Diffs buffered while waiting for a snapshot before the oldest are
+/// dropped.
+const DEFAULT_MAX_BUFFERED: usize = 1024;
+
+/// One diff-depth event. Sizes of 0 remove the level.
+#[derive(Debug, Clone, Default)]
+pub struct DepthDiff {
+    /// First update id in the event (Binance `U`).
+    pub first_update_id: u64,
+    /// Final update id in the event (Binance `u`).
+    pub final_update_id: u64,
+    /// Final update id of the previous event, for venues that send it
+    /// (Binance futures `pu`). When set it is checked instead of
+    /// `first_update_id`, from the second diff after a snapshot on.
+    pub prev_final_update_id: Option<u64>,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// A REST depth snapshot (Binance `GET /api/v3/depth`).
+#[derive(Debug, Clone, Default)]
+pub struct DepthSnapshot {
+    pub last_update_id: u64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOutcome {
+    /// Held until a snapshot arrives.
+    Buffered,
+    /// Applied to the book.
+    Applied,
+    /// Already covered by the snapshot or an earlier diff; ignored.
+    Stale,
+    /// The chain broke: the book was cleared and diffs are being buffered
+    /// again. Fetch a new snapshot.
+    Gap { expected: u64, got: u64 },
+}
+
+pub struct BookSync {
+    book: OrderBook,
+    /// Final update id applied last; None until a snapshot is loaded.
+    last_update_id: Option<u64>,
+    /// No diff applied since the snapshot: the next must straddle it, and
+    /// its `pu` still points before it.
+    awaiting_first: bool,
+    buffer: VecDeque<DepthDiff>,
+    max_buffered: usize,
+}
+
+impl BookSync {
+    pub fn new() -> Self {
+        Self::with_max_buffered(DEFAULT_MAX_BUFFERED)
+    }
+
+    pub fn with_max_buffered(max_buffered: usize) -> Self {
+        Self { book: OrderBook::new(), last_update_id: None, awaiting_first: false, buffer: VecDeque::new(), max_buffered: max_buffered.max(1) }
+    }
+
+    /// The local book; empty until synced.
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Whether a snapshot is loaded and every diff since has chained.
+    pub fn is_synced(&self) -> bool {
+        self.last_update_id.is_some()
+    }
+
+    pub fn last_update_id(&self) -> Option<u64> {
+        self.last_update_id
+    }
+
+    /// Feed one diff from the stream.
+    pub fn on_diff(&mut self, diff: DepthDiff) -> SyncOutcome {
+        let Some(last) = self.last_update_id else {
+            if self.buffer.len() == self.max_buffered {
+                self.buffer.pop_front();
+            }
+            self.buffer.push_back(diff);
+            return SyncOutcome::Buffered;
+        };
+        if diff.final_update_id <= last {
+            return SyncOutcome::Stale;
+        }
+        let chained = match diff.prev_final_update_id {
+            Some(prev) if !self.awaiting_first => prev == last,
+            _ => diff.first_update_id <= last + 1,
+        };
+        if !chained {
+            let got = match diff.prev_final_update_id {
+                Some(prev) if !self.awaiting_first => prev + 1,
+                _ => diff.first_update_id,
+            };
+            self.reset();
+            self.buffer.push_back(diff);
+            return SyncOutcome::Gap { expected: last + 1, got };
+        }
+        self.apply(&diff);
+        SyncOutcome::Applied
+    }
+
+    /// Load `snapshot` and replay the buffered diffs it doesn't cover.
+    /// `Gap` if the oldest diff still buffered starts after the snapshot,
+    /// i.e. the snapshot is too old: fetch another.
+    pub fn on_snapshot(&mut self, snapshot: DepthSnapshot) -> SyncOutcome {
+        let buffered = std::mem::take(&mut self.buffer);
+        self.book.clear();
+        self.book.update_bids_f64(&snapshot.bids);
+        self.book.update_asks_f64(&snapshot.asks);
+        let last = snapshot.last_update_id;
+        let mut pending = buffered.into_iter().filter(|d| d.final_update_id > last).peekable();
+        // The first diff kept must cover last + 1. Its `pu` points before
+        // the snapshot, so only `first_update_id` is checked.
+        if let Some(first) = pending.peek() {
+            if first.first_update_id > last + 1 {
+                let got = first.first_update_id;
+                self.book.clear();
+                self.buffer = pending.collect();
+                return SyncOutcome::Gap { expected: last + 1, got };
+            }
+        }
+        self.last_update_id = Some(last);
+        self.book.last_update_seq = Some(last);
+        self.awaiting_first = true;
+        if let Some(first) = pending.next() {
+            self.apply(&first);
+        }
+        while let Some(diff) = pending.next() {
+            if let gap @ SyncOutcome::Gap { .. } = self.on_diff(diff) {
+                self.buffer.extend(pending);
+                return gap;
+            }
+        }
+        SyncOutcome::Applied
+    }
+
+    /// Drop the book and buffered diffs; the next snapshot starts over.
+    /// Call on reconnect.
+    pub fn reset(&mut self) {
+        self.book.clear();
+        self.buffer.clear();
+        self.last_update_id = None;
+        self.awaiting_first = false;
+    }
+
+    fn apply(&mut self, diff: &DepthDiff) {
+        self.book.update_bids_f64(&diff.bids);
+        self.book.update_asks_f64(&diff.asks);
+        self.last_update_id = Some(diff.final_update_id);
+        self.book.last_update_seq = self.last_update_id;
+        self.awaiting_first = false;
+    }
+}
+
+impl Default for BookSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(first: u64, last: u64, bids: &[(f64, f64)]) -> DepthDiff {
+        DepthDiff { first_update_id: first, final_update_id: last, bids: bids.to_vec(), ..Default::default() }
+    }
+
+    fn snapshot(last_update_id: u64) -> DepthSnapshot {
+        DepthSnapshot { last_update_id, bids: vec![(100.0, 1.0)], asks: vec![(101.0, 1.0)] }
+    }
+
+    #[test]
+    fn test_buffers_until_snapshot_then_replays() {
+        let mut sync = BookSync::new();
+        assert_eq!(sync.on_diff(diff(90, 95, &[(99.0, 5.0)])), SyncOutcome::Buffered);
+        assert_eq!(sync.on_diff(diff(96, 102, &[(100.0, 2.0)])), SyncOutcome::Buffered);
+        assert_eq!(sync.on_diff(diff(103, 104, &[(100.5, 1.0)])), SyncOutcome::Buffered);
+        assert!(!sync.is_synced());
+
+        // 90..95 is covered by the snapshot; 96..102 straddles 101.
+        assert_eq!(sync.on_snapshot(snapshot(100)), SyncOutcome::Applied);
+        assert_eq!(sync.last_update_id(), Some(104));
+        assert_eq!(sync.book().last_update_seq, Some(104));
+        assert_eq!(sync.book().best_bid(), Some((100.5, 1.0)));
+        assert_eq!(sync.book().bid_at(1), Some((100.0, 2.0)));
+        assert_eq!(sync.book().bid_at(2), None);
+
+        assert_eq!(sync.on_diff(diff(100, 104, &[])), SyncOutcome::Stale);
+        assert_eq!(sync.on_diff(diff(105, 106, &[(100.5, 0.0)])), SyncOutcome::Applied);
+        assert_eq!(sync.book().best_bid(), Some((100.0, 2.0)));
+    }
+
+    #[test]
+    fn test_gaps_go_back_to_buffering() {
+        let mut sync = BookSync::new();
+        sync.on_diff(diff(105, 110, &[]));
+        // Snapshot older than anything buffered.
+        assert_eq!(sync.on_snapshot(snapshot(100)), SyncOutcome::Gap { expected: 101, got: 105 });
+        assert!(!sync.is_synced());
+        assert_eq!(sync.on_snapshot(snapshot(107)), SyncOutcome::Applied);
+        assert_eq!(sync.last_update_id(), Some(110));
+
+        assert_eq!(sync.on_diff(diff(115, 120, &[])), SyncOutcome::Gap { expected: 111, got: 115 });
+        assert!(!sync.is_synced());
+        assert!(sync.book().best_bid().is_none());
+        // The diff that broke the chain is kept for the next snapshot.
+        assert_eq!(sync.on_snapshot(snapshot(116)), SyncOutcome::Applied);
+        assert_eq!(sync.last_update_id(), Some(120));
+    }
+
+    #[test]
+    fn test_prev_final_update_id_chains() {
+        let mut sync = BookSync::new();
+        sync.on_snapshot(snapshot(100));
+        let next = |first, last, pu| DepthDiff { prev_final_update_id: Some(pu), ..diff(first, last, &[]) };
+        // The first diff after the snapshot straddles it; its `pu` is older.
+        assert_eq!(sync.on_diff(next(98, 103, 97)), SyncOutcome::Applied);
+        assert_eq!(sync.on_diff(next(104, 108, 103)), SyncOutcome::Applied);
+        assert_eq!(sync.on_diff(next(109, 112, 107)), SyncOutcome::Gap { expected: 109, got: 108 });
+
+        // A first diff starting past the snapshot is still a gap.
+        let mut sync = BookSync::new();
+        sync.on_snapshot(snapshot(100));
+        assert_eq!(sync.on_diff(next(105, 110, 104)), SyncOutcome::Gap { expected: 101, got: 105 });
+    }
+}
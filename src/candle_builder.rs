@@ -0,0 +1,404 @@
+//! Tick-to-bar OHLCV builder.
+//!
+//! Builds candles per (venue, symbol, interval) from the live quote mids or
+//! trade prints and writes closed candles into a `CandleStore`.
+//!
+//! Close semantics: a candle closes when the first tick of a later bucket
+//! arrives, or when the wall clock passes the bucket end plus
+//! `close_grace_ms`, whichever is first. Ticks for an already-closed bucket
+//! are dropped and counted. Buckets with no ticks are emitted flat at the
+//! previous close with `ticks == 0`, up to `max_gap_fill` in a row; longer
+//! gaps (feed outages) are left as holes.
+
+use crate::candle_data::{Candle, CandleInterval, CandleStore};
+use crate::market_data::{AllMarketData, Exchange};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+use crate::trade_data::AllTradeData;
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use log::warn;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+// ── Config ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CandleSource {
+    /// Mid of each quote update; volume is zero.
+    Quotes,
+    /// Trade prints; volume is the traded quantity.
+    Trades,
+}
+
+fn default_source() -> CandleSource {
+    CandleSource::Quotes
+}
+fn default_intervals() -> Vec<String> {
+    vec!["1s".into(), "1m".into(), "5m".into()]
+}
+fn default_close_grace_ms() -> i64 {
+    500
+}
+fn default_max_gap_fill() -> usize {
+    300
+}
+fn default_capacity() -> usize {
+    1024
+}
+fn default_poll_interval_ms() -> u64 {
+    100
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CandleConfig {
+    #[serde(default = "default_source")]
+    pub source: CandleSource,
+    /// Any of "1s", "1m", "5m", "15m", "1h".
+    #[serde(default = "default_intervals")]
+    pub intervals: Vec<String>,
+    #[serde(default = "default_close_grace_ms")]
+    pub close_grace_ms: i64,
+    #[serde(default = "default_max_gap_fill")]
+    pub max_gap_fill: usize,
+    /// Closed candles kept per series (power of two).
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Venue → config-style symbols ("PERP_BTC_USDT", "SPOT_ETH_USDC").
+    #[serde(default)]
+    pub symbols: HashMap<String, Vec<String>>,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            source: default_source(),
+            intervals: default_intervals(),
+            close_grace_ms: default_close_grace_ms(),
+            max_gap_fill: default_max_gap_fill(),
+            capacity: default_capacity(),
+            poll_interval_ms: default_poll_interval_ms(),
+            symbols: HashMap::new(),
+        }
+    }
+}
+
+impl CandleConfig {
+    pub fn parsed_intervals(&self) -> Result<Vec<CandleInterval>> {
+        self.intervals
+            .iter()
+            .map(|s| CandleInterval::from_str(s).ok_or_else(|| anyhow!("unknown candle interval '{}'", s)))
+            .collect()
+    }
+
+    /// Resolve `symbols` to (venue, id) pairs, skipping unknown entries.
+    pub fn series(&self) -> Vec<(Exchange, SymbolId)> {
+        let mut out = Vec::new();
+        for (venue, syms) in &self.symbols {
+            let Some(exchange) = venue.parse::<Exchange>().ok() else {
+                warn!("candles: unknown venue '{}', skipping", venue);
+                continue;
+            };
+            for sym in syms {
+                if let Some(id) = REGISTRY.resolve(sym) {
+                    out.push((exchange, id));
+                }
+            }
+        }
+        out
+    }
+}
+
+// ── OhlcvBuilder ──────────────────────────────────────────────────────
+
+/// Single-series candle builder. Closed candles are appended to `out`,
+/// oldest first.
+pub struct OhlcvBuilder {
+    interval_ms: i64,
+    close_grace_ms: i64,
+    max_gap_fill: usize,
+    partial: Option<Candle>,
+    /// Open time of the bucket after the last emitted candle.
+    next_open_ms: Option<i64>,
+    last_close: f64,
+    empty_run: usize,
+    late_ticks: u64,
+}
+
+impl OhlcvBuilder {
+    pub fn new(interval: CandleInterval, close_grace_ms: i64, max_gap_fill: usize) -> Self {
+        Self {
+            interval_ms: interval.ms(),
+            close_grace_ms,
+            max_gap_fill,
+            partial: None,
+            next_open_ms: None,
+            last_close: 0.0,
+            empty_run: 0,
+            late_ticks: 0,
+        }
+    }
+
+    fn bucket(&self, ts_ms: i64) -> i64 {
+        ts_ms.div_euclid(self.interval_ms) * self.interval_ms
+    }
+
+    pub fn on_tick(&mut self, price: f64, qty: f64, ts_ms: i64, out: &mut Vec<Candle>) {
+        let b = self.bucket(ts_ms);
+        if let Some(p) = self.partial.as_mut() {
+            if b == p.open_time_ms {
+                p.high = p.high.max(price);
+                p.low = p.low.min(price);
+                p.close = price;
+                p.volume += qty;
+                p.ticks += 1;
+                return;
+            }
+            if b < p.open_time_ms {
+                self.late_ticks += 1;
+                return;
+            }
+            self.close_partial(out);
+        } else if self.next_open_ms.is_some_and(|n| b < n) {
+            self.late_ticks += 1;
+            return;
+        }
+        self.fill_gap(b, out);
+        self.empty_run = 0;
+        self.partial = Some(Candle {
+            open_time_ms: b,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: qty,
+            ticks: 1,
+        });
+    }
+
+    /// Close buckets that ended more than `close_grace_ms` before `now_ms`.
+    pub fn on_clock(&mut self, now_ms: i64, out: &mut Vec<Candle>) {
+        let cutoff = now_ms - self.close_grace_ms;
+        if let Some(p) = self.partial {
+            if p.open_time_ms + self.interval_ms > cutoff {
+                return;
+            }
+            self.close_partial(out);
+        }
+        if self.next_open_ms.is_some() {
+            // Fill every bucket that has fully elapsed.
+            let until = self.bucket(cutoff);
+            self.fill_gap(until, out);
+        }
+    }
+
+    fn close_partial(&mut self, out: &mut Vec<Candle>) {
+        if let Some(p) = self.partial.take() {
+            self.last_close = p.close;
+            self.next_open_ms = Some(p.open_time_ms + self.interval_ms);
+            out.push(p);
+        }
+    }
+
+    /// Emit flat candles for empty buckets in [next_open, until).
+    fn fill_gap(&mut self, until_ms: i64, out: &mut Vec<Candle>) {
+        let Some(mut t) = self.next_open_ms else { return };
+        while t < until_ms {
+            if self.empty_run >= self.max_gap_fill {
+                t = until_ms;
+                break;
+            }
+            let px = self.last_close;
+            out.push(Candle {
+                open_time_ms: t,
+                open: px,
+                high: px,
+                low: px,
+                close: px,
+                volume: 0.0,
+                ticks: 0,
+            });
+            self.empty_run += 1;
+            t += self.interval_ms;
+        }
+        self.next_open_ms = Some(t);
+    }
+
+    /// In-progress candle, if any ticks arrived for the current bucket.
+    pub fn partial(&self) -> Option<Candle> {
+        self.partial
+    }
+
+    /// Ticks dropped because their bucket had already closed.
+    pub fn late_ticks(&self) -> u64 {
+        self.late_ticks
+    }
+}
+
+// ── Service ───────────────────────────────────────────────────────────
+
+struct Series {
+    exchange: Exchange,
+    symbol_id: SymbolId,
+    builders: Vec<(CandleInterval, OhlcvBuilder)>,
+    prev_pos: u64,
+}
+
+/// Poll the quote (or trade) ring buffers for `series` and maintain
+/// candles in `store` until shutdown. `trade_data` is required for
+/// `CandleSource::Trades`.
+pub async fn run_candle_builder(
+    cfg: CandleConfig,
+    series: Vec<(Exchange, SymbolId)>,
+    market_data: Arc<AllMarketData>,
+    trade_data: Option<Arc<AllTradeData>>,
+    store: Arc<CandleStore>,
+    shutdown: Arc<Notify>,
+) -> Result<()> {
+    let intervals = cfg.parsed_intervals()?;
+    if cfg.source == CandleSource::Trades && trade_data.is_none() {
+        return Err(anyhow!("candle source 'trades' needs trade data"));
+    }
+    let mut series: Vec<Series> = series
+        .into_iter()
+        .map(|(exchange, symbol_id)| Series {
+            exchange,
+            symbol_id,
+            builders: intervals
+                .iter()
+                .map(|iv| (*iv, OhlcvBuilder::new(*iv, cfg.close_grace_ms, cfg.max_gap_fill)))
+                .collect(),
+            prev_pos: 0,
+        })
+        .collect();
+
+    let mut interval = time::interval(Duration::from_millis(cfg.poll_interval_ms.max(1)));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut ticks: Vec<(f64, f64, i64)> = Vec::new();
+    let mut closed: Vec<Candle> = Vec::new();
+
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = interval.tick() => {}
+        }
+        let now_ms = Utc::now().timestamp_millis();
+
+        for s in series.iter_mut() {
+            ticks.clear();
+            match (cfg.source, trade_data.as_ref()) {
+                (CandleSource::Trades, Some(td)) => {
+                    if let Some(buf) = td.get_collection(&s.exchange).get_buffer(&s.symbol_id) {
+                        let cur = buf.write_pos();
+                        for pos in s.prev_pos.max(cur.saturating_sub(5000))..cur {
+                            if let Some(t) = buf.read_at(pos) {
+                                let ts = t.exchange_ts.map(|t| t.timestamp_millis()).unwrap_or(now_ms);
+                                ticks.push((t.price, t.qty, ts));
+                            }
+                        }
+                        s.prev_pos = cur;
+                    }
+                }
+                _ => {
+                    if let Some(buf) = market_data.get_collection(&s.exchange).get_buffer(&s.symbol_id) {
+                        let cur = buf.write_pos();
+                        for pos in s.prev_pos.max(cur.saturating_sub(5000))..cur {
+                            if let Some(md) = buf.read_at(pos) {
+                                if let Some(mid) = md.midquote().filter(|m| *m > 0.0) {
+                                    let ts = md.exchange_ts.map(|t| t.timestamp_millis()).unwrap_or(now_ms);
+                                    ticks.push((mid, 0.0, ts));
+                                }
+                            }
+                        }
+                        s.prev_pos = cur;
+                    }
+                }
+            }
+
+            for (iv, builder) in s.builders.iter_mut() {
+                closed.clear();
+                for &(px, qty, ts) in &ticks {
+                    builder.on_tick(px, qty, ts, &mut closed);
+                }
+                builder.on_clock(now_ms, &mut closed);
+                for c in &closed {
+                    store.push(&s.exchange, &s.symbol_id, *iv, *c);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closes(out: &[Candle]) -> Vec<(i64, f64, u32)> {
+        out.iter().map(|c| (c.open_time_ms, c.close, c.ticks)).collect()
+    }
+
+    #[test]
+    fn test_closes_on_next_bucket_tick() {
+        let mut b = OhlcvBuilder::new(CandleInterval::S1, 500, 10);
+        let mut out = Vec::new();
+        b.on_tick(100.0, 1.0, 100, &mut out);
+        b.on_tick(102.0, 2.0, 400, &mut out);
+        b.on_tick(99.0, 1.0, 900, &mut out);
+        assert!(out.is_empty());
+        b.on_tick(101.0, 1.0, 1_200, &mut out);
+        assert_eq!(out.len(), 1);
+        let c = out[0];
+        assert_eq!((c.open, c.high, c.low, c.close, c.volume, c.ticks), (100.0, 102.0, 99.0, 99.0, 4.0, 3));
+        assert_eq!(b.partial().unwrap().open, 101.0);
+    }
+
+    #[test]
+    fn test_clock_close_and_late_tick() {
+        let mut b = OhlcvBuilder::new(CandleInterval::S1, 500, 10);
+        let mut out = Vec::new();
+        b.on_tick(100.0, 0.0, 200, &mut out);
+        b.on_clock(1_400, &mut out); // within grace
+        assert!(out.is_empty());
+        b.on_clock(1_500, &mut out);
+        assert_eq!(closes(&out), vec![(0, 100.0, 1)]);
+        // Bucket 0 is closed; a straggler is dropped.
+        b.on_tick(50.0, 0.0, 999, &mut out);
+        assert_eq!(b.late_ticks(), 1);
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn test_gap_fill_and_cap() {
+        let mut b = OhlcvBuilder::new(CandleInterval::S1, 0, 2);
+        let mut out = Vec::new();
+        b.on_tick(100.0, 0.0, 0, &mut out);
+        b.on_tick(105.0, 0.0, 3_000, &mut out);
+        // bucket 0 closed, 1s and 2s flat-filled
+        assert_eq!(closes(&out), vec![(0, 100.0, 1), (1_000, 100.0, 0), (2_000, 100.0, 0)]);
+
+        out.clear();
+        // 10s gap with cap 2: closes 3s, fills 4s and 5s, then holes until 13s.
+        b.on_tick(110.0, 0.0, 13_000, &mut out);
+        assert_eq!(closes(&out), vec![(3_000, 105.0, 1), (4_000, 105.0, 0), (5_000, 105.0, 0)]);
+        assert_eq!(b.partial().unwrap().open_time_ms, 13_000);
+    }
+
+    #[test]
+    fn test_clock_fills_idle_buckets() {
+        let mut b = OhlcvBuilder::new(CandleInterval::S1, 0, 100);
+        let mut out = Vec::new();
+        b.on_tick(100.0, 0.0, 0, &mut out);
+        b.on_clock(3_500, &mut out);
+        assert_eq!(closes(&out), vec![(0, 100.0, 1), (1_000, 100.0, 0), (2_000, 100.0, 0)]);
+        out.clear();
+        b.on_tick(101.0, 0.0, 3_600, &mut out);
+        assert!(out.is_empty());
+        assert_eq!(b.partial().unwrap().open_time_ms, 3_000);
+    }
+}
@@ -0,0 +1,252 @@
+//! OHLCV candles per venue, symbol and interval.
+//!
+//! `CandleStore` holds closed candles only; a candle is immutable once
+//! pushed. Writers are the tick-to-bar builder (`candle_builder`) and
+//! venue kline feeds, one writer per (venue, symbol, interval) series.
+
+use crate::market_data::{DataSink, Exchange, FeedItem};
+use crate::ring_buffer::RingBuffer;
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    S1,
+    #[default]
+    M1,
+    M5,
+    M15,
+    H1,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 5] = [
+        CandleInterval::S1,
+        CandleInterval::M1,
+        CandleInterval::M5,
+        CandleInterval::M15,
+        CandleInterval::H1,
+    ];
+
+    pub fn ms(&self) -> i64 {
+        match self {
+            CandleInterval::S1 => 1_000,
+            CandleInterval::M1 => 60_000,
+            CandleInterval::M5 => 300_000,
+            CandleInterval::M15 => 900_000,
+            CandleInterval::H1 => 3_600_000,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::S1 => "1s",
+            CandleInterval::M1 => "1m",
+            CandleInterval::M5 => "5m",
+            CandleInterval::M15 => "15m",
+            CandleInterval::H1 => "1h",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "1s" => Some(CandleInterval::S1),
+            "1m" => Some(CandleInterval::M1),
+            "5m" => Some(CandleInterval::M5),
+            "15m" => Some(CandleInterval::M15),
+            "1h" => Some(CandleInterval::H1),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Candle {
+    pub open_time_ms: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Base-unit volume. Zero for quote-built candles.
+    pub volume: f64,
+    /// Ticks aggregated into this candle. 0 marks a gap-filled candle
+    /// (flat at the previous close).
+    pub ticks: u32,
+}
+
+impl Candle {
+    pub fn is_gap_fill(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+const N_INTERVALS: usize = CandleInterval::ALL.len();
+
+pub struct CandleStore {
+    slots: Box<[OnceLock<Box<RingBuffer<Candle>>>]>,
+    capacity: usize,
+    /// Symbols per venue.
+    n_symbols: usize,
+}
+
+impl CandleStore {
+    /// `capacity` candles are kept per series (power of two).
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity.is_power_of_two(), "capacity must be a power of 2");
+        let n_symbols = symbol_capacity();
+        let n = Exchange::ALL.len() * n_symbols * N_INTERVALS;
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            slots: slots.into_boxed_slice(),
+            capacity,
+            n_symbols,
+        }
+    }
+
+    fn index(&self, exchange: &Exchange, id: &SymbolId, interval: CandleInterval) -> usize {
+        let iv = CandleInterval::ALL.iter().position(|i| *i == interval).unwrap();
+        ((*exchange as usize) * self.n_symbols + *id) * N_INTERVALS + iv
+    }
+
+    pub fn push(&self, exchange: &Exchange, id: &SymbolId, interval: CandleInterval, candle: Candle) {
+        let ring = self.slots[self.index(exchange, id, interval)]
+            .get_or_init(|| Box::new(RingBuffer::with_capacity(self.capacity)));
+        ring.push(candle);
+    }
+
+    /// Most recent closed candle.
+    pub fn latest(&self, exchange: &Exchange, id: &SymbolId, interval: CandleInterval) -> Option<Candle> {
+        self.slots[self.index(exchange, id, interval)].get()?.latest()
+    }
+
+    /// Up to `n` most recent closed candles, oldest first.
+    pub fn last_n(&self, exchange: &Exchange, id: &SymbolId, interval: CandleInterval, n: usize) -> Vec<Candle> {
+        let Some(ring) = self.slots[self.index(exchange, id, interval)].get() else {
+            return Vec::new();
+        };
+        let mut out = Vec::with_capacity(n.min(ring.capacity()));
+        ring.scan_last_n(n, |c| out.push(*c));
+        out.reverse();
+        out
+    }
+
+    pub fn write_count(&self, exchange: &Exchange, id: &SymbolId, interval: CandleInterval) -> u64 {
+        self.slots[self.index(exchange, id, interval)]
+            .get()
+            .map(|r| r.write_count())
+            .unwrap_or(0)
+    }
+}
+
+/// One closed bar from a venue kline stream.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Kline {
+    pub interval: CandleInterval,
+    pub candle: Candle,
+}
+
+impl FeedItem for Kline {
+    // Bars of different intervals share a symbol, so per-symbol timestamp
+    // dedup would drop them; closed bars carry their own open time.
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+    fn set_feed_latency_ns(&mut self, _ns: u64) {}
+}
+
+/// Writes a venue's klines into its series of a shared `CandleStore`.
+pub struct KlineSink {
+    pub store: Arc<CandleStore>,
+    pub exchange: Exchange,
+}
+
+impl DataSink<Kline> for KlineSink {
+    fn push(&self, id: &SymbolId, item: Kline) {
+        self.store.push(&self.exchange, id, item.interval, item.candle);
+    }
+}
+
+fn default_kline_intervals() -> Vec<String> {
+    vec!["1m".into()]
+}
+fn default_kline_capacity() -> usize {
+    1024
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KlineConfig {
+    /// Any of "1m", "5m", "15m", "1h" the venue streams.
+    #[serde(default = "default_kline_intervals")]
+    pub intervals: Vec<String>,
+    /// Closed candles kept per series (power of two).
+    #[serde(default = "default_kline_capacity")]
+    pub capacity: usize,
+    /// Venue → symbols ("BTC_USDT"): Binance and Bybit perps, Coinbase spot.
+    #[serde(default)]
+    pub symbols: HashMap<String, Vec<String>>,
+}
+
+impl KlineConfig {
+    pub fn parsed_intervals(&self) -> Result<Vec<CandleInterval>> {
+        self.intervals
+            .iter()
+            .map(|s| CandleInterval::from_str(s).ok_or_else(|| anyhow!("unknown kline interval '{}'", s)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_are_independent() {
+        let store = CandleStore::new(8);
+        let c = |t: i64, px: f64| Candle {
+            open_time_ms: t,
+            open: px,
+            high: px,
+            low: px,
+            close: px,
+            volume: 0.0,
+            ticks: 1,
+        };
+        for i in 0..10 {
+            store.push(&Exchange::Binance, &7, CandleInterval::S1, c(i * 1000, i as f64));
+        }
+        store.push(&Exchange::Bybit, &7, CandleInterval::S1, c(0, 50.0));
+        store.push(&Exchange::Binance, &7, CandleInterval::M1, c(0, 60.0));
+
+        let last = store.last_n(&Exchange::Binance, &7, CandleInterval::S1, 3);
+        assert_eq!(last.iter().map(|c| c.open_time_ms).collect::<Vec<_>>(), vec![7000, 8000, 9000]);
+        // Capacity 8: only the newest 8 survive.
+        assert_eq!(store.last_n(&Exchange::Binance, &7, CandleInterval::S1, 100).len(), 8);
+        assert_eq!(store.latest(&Exchange::Bybit, &7, CandleInterval::S1).unwrap().close, 50.0);
+        assert_eq!(store.latest(&Exchange::Binance, &7, CandleInterval::M1).unwrap().close, 60.0);
+        assert!(store.latest(&Exchange::Binance, &8, CandleInterval::S1).is_none());
+        assert_eq!(CandleInterval::from_str("5m"), Some(CandleInterval::M5));
+    }
+
+    #[test]
+    fn test_kline_sink_and_config() {
+        let store = Arc::new(CandleStore::new(8));
+        let sink = KlineSink { store: Arc::clone(&store), exchange: Exchange::Bybit };
+        let candle = Candle { open_time_ms: 3_600_000, close: 42.0, ticks: 1, ..Default::default() };
+        sink.push(&3, Kline { interval: CandleInterval::H1, candle });
+        assert_eq!(store.latest(&Exchange::Bybit, &3, CandleInterval::H1), Some(candle));
+        assert!(store.latest(&Exchange::Bybit, &3, CandleInterval::M15).is_none());
+
+        let cfg: KlineConfig = serde_yaml::from_str("intervals: [\"15m\", \"1h\"]\n").unwrap();
+        assert_eq!(cfg.parsed_intervals().unwrap(), vec![CandleInterval::M15, CandleInterval::H1]);
+        assert_eq!(cfg.capacity, 1024);
+        let bad: KlineConfig = serde_yaml::from_str("intervals: [\"4h\"]\n").unwrap();
+        assert!(bad.parsed_intervals().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Expected round-trip cost of a hedged carry position across two venues.
+//!
+//! The position is entered by buying on one venue and selling on the other,
+//! held, then exited by crossing back on both, so it pays four taker fills
+//! (`get_fees`, with any `fees` config applied). Each perp leg also pays
+//! funding over the holding period at its venue's latest rate, assumed to
+//! stay where it is; positive rates are paid by longs to shorts. Spot legs
+//! pay no funding.
+
+use crate::exchanges::get_fees;
+use crate::execution::fee_symbol;
+use crate::market_data::{AllMarketData, Exchange, InstrumentType};
+use crate::symbol_registry::SymbolId;
+use std::time::Duration;
+
+/// Funding interval assumed when the venue doesn't report one.
+const DEFAULT_FUNDING_INTERVAL_H: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CarryLeg {
+    pub exchange: Exchange,
+    pub itype: InstrumentType,
+}
+
+/// Costs in quote units of the notional; negative funding is received.
+#[derive(Debug, Clone, Copy)]
+pub struct CarryCost {
+    pub notional: f64,
+    pub holding: Duration,
+    /// Taker fees for entering and exiting both legs.
+    pub fees: f64,
+    pub long_funding: f64,
+    pub short_funding: f64,
+    /// Latest interval rates used, None for spot legs.
+    pub long_funding_rate: Option<f64>,
+    pub short_funding_rate: Option<f64>,
+}
+
+impl CarryCost {
+    pub fn funding(&self) -> f64 {
+        self.long_funding + self.short_funding
+    }
+
+    pub fn total(&self) -> f64 {
+        self.fees + self.funding()
+    }
+
+    /// `total` in bps of the notional.
+    pub fn total_bps(&self) -> f64 {
+        if self.notional > 0.0 { self.total() / self.notional * 10_000.0 } else { 0.0 }
+    }
+}
+
+/// Cost of holding `notional` of `id` long on `long` and short on `short`
+/// for `holding`. None if a venue has no fee schedule or a perp leg has no
+/// funding rate yet.
+pub fn carry_cost(
+    data: &AllMarketData,
+    id: SymbolId,
+    long: CarryLeg,
+    short: CarryLeg,
+    notional: f64,
+    holding: Duration,
+) -> Option<CarryCost> {
+    let symbol = fee_symbol(id);
+    let taker_bps = |leg: &CarryLeg| -> Option<f64> {
+        let fees = get_fees(&leg.exchange)?;
+        Some(match leg.itype {
+            InstrumentType::Spot => fees.get_spot_fees(&symbol).taker_fees_bps,
+            _ => fees.get_perp_fees(&symbol).taker_fees_bps,
+        })
+    };
+    let fees = 2.0 * notional * (taker_bps(&long)? + taker_bps(&short)?) / 10_000.0;
+
+    let hours = holding.as_secs_f64() / 3600.0;
+    // Funding paid per unit of long exposure over `holding`, and the rate.
+    let funding = |leg: &CarryLeg| -> Option<(f64, Option<f64>)> {
+        if matches!(leg.itype, InstrumentType::Spot) {
+            return Some((0.0, None));
+        }
+        let stats = data.deriv_stats.get(&leg.exchange)?.latest(&id)?;
+        let rate = stats.funding_rate?;
+        let interval_h = stats.funding_interval_h.filter(|h| *h > 0.0).unwrap_or(DEFAULT_FUNDING_INTERVAL_H);
+        Some((rate * hours / interval_h, Some(rate)))
+    };
+    let (long_per_unit, long_funding_rate) = funding(&long)?;
+    let (short_per_unit, short_funding_rate) = funding(&short)?;
+
+    Some(CarryCost {
+        notional,
+        holding,
+        fees,
+        long_funding: notional * long_per_unit,
+        short_funding: -notional * short_per_unit,
+        long_funding_rate,
+        short_funding_rate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "binance", feature = "hyperliquid"))]
+    fn test_carry_cost_breakdown() {
+        use crate::deriv_stats::DerivStats;
+
+        let data = AllMarketData::new();
+        let leg = |exchange, itype| CarryLeg { exchange, itype };
+        let long = leg(Exchange::Binance, InstrumentType::Spot);
+        let short = leg(Exchange::Hyperliquid, InstrumentType::Perp);
+        let day = Duration::from_secs(24 * 3600);
+        // No funding rate for the perp leg yet.
+        assert!(carry_cost(&data, 0, long, short, 10_000.0, day).is_none());
+
+        let stats = DerivStats { funding_rate: Some(0.0001), funding_interval_h: Some(1.0), ..Default::default() };
+        data.deriv_stats.hyperliquid.push(&0, stats);
+        let cost = carry_cost(&data, 0, long, short, 10_000.0, day).unwrap();
+        // Binance spot 10 bps + Hyperliquid perp 3.5 bps, in and out.
+        assert!((cost.fees - 27.0).abs() < 1e-9);
+        // The short receives 1 bp an hour for 24 hours.
+        assert_eq!(cost.long_funding, 0.0);
+        assert!((cost.short_funding + 24.0).abs() < 1e-9);
+        assert_eq!((cost.long_funding_rate, cost.short_funding_rate), (None, Some(0.0001)));
+        assert!((cost.total() - 3.0).abs() < 1e-9);
+        assert!((cost.total_bps() - 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,420 @@
+//! Edge → collector push mode.
+//!
+//! An edge instance streams new ticks from its `AllMarketData` to a central
+//! collector over gRPC (`proto/collector.proto`), optionally over TLS. The
+//! collector merges every connected edge into its own `AllMarketData`, so
+//! consumers read one snapshot regardless of where each venue was captured.
+//!
+//! Each push stream opens with a `Hello` carrying the edge's protocol
+//! version; the collector refuses versions it doesn't speak rather than
+//! misreading their ticks.
+//!
+//! Symbols travel as registry canonical names ("PERP-BTC-USDT") rather than
+//! SymbolIds, so edges and the collector may load different symbol configs.
+//! All inbound ticks are written by a single merge task, preserving the
+//! single-writer guarantee of the ring buffers.
+
+use crate::exchanges::connection::calculate_backoff;
+use crate::market_data::{AllMarketData, Exchange, InstrumentType, MarketData};
+use crate::symbol_registry::{REGISTRY, SymbolId, symbol_capacity};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, mpsc};
+use tokio::time::MissedTickBehavior;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status, Streaming};
+
+pub mod proto {
+    tonic::include_proto!("collector.v1");
+}
+
+use proto::collector_client::CollectorClient;
+use proto::collector_server::{Collector, CollectorServer};
+use proto::edge_message::Body;
+use proto::{EdgeMessage, Hello, PushSummary, Tick, TickBatch};
+
+/// Version of `proto/collector.proto` this build speaks. Bump it for
+/// changes an older peer would misread.
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Oldest edge version the collector still accepts.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectorConfig {
+    /// "edge" pushes local ticks to `addr`; "collector" listens on `addr`.
+    pub mode: String,
+    pub addr: String,
+    /// Edge only: how often changed symbols are pushed (latest tick per interval).
+    #[serde(default = "default_push_interval_ms")]
+    pub push_interval_ms: u64,
+    /// Edge only: name shown in the collector's logs. Defaults to "edge".
+    #[serde(default)]
+    pub edge_id: Option<String>,
+    /// Plaintext when absent.
+    #[serde(default)]
+    pub tls: Option<CollectorTls>,
+}
+
+fn default_push_interval_ms() -> u64 {
+    1
+}
+
+/// PEM file paths. The collector needs `cert` and `key`, and with `ca`
+/// also requires edges to present a certificate signed by it. An edge
+/// verifies the collector against `ca` (system roots when unset) and sends
+/// `cert`/`key` as its client certificate when given.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CollectorTls {
+    #[serde(default)]
+    pub ca: Option<String>,
+    #[serde(default)]
+    pub cert: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Edge only: name expected in the collector's certificate. Defaults
+    /// to the host part of `addr`.
+    #[serde(default)]
+    pub domain: Option<String>,
+}
+
+impl CollectorTls {
+    fn identity(&self) -> Result<Option<Identity>> {
+        match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => {
+                let cert = std::fs::read(cert).with_context(|| format!("failed to read {cert}"))?;
+                let key = std::fs::read(key).with_context(|| format!("failed to read {key}"))?;
+                Ok(Some(Identity::from_pem(cert, key)))
+            }
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("collector tls needs both cert and key"),
+        }
+    }
+
+    fn ca(&self) -> Result<Option<Certificate>> {
+        self.ca
+            .as_ref()
+            .map(|ca| std::fs::read(ca).map(Certificate::from_pem).with_context(|| format!("failed to read {ca}")))
+            .transpose()
+    }
+
+    fn server_config(&self) -> Result<ServerTlsConfig> {
+        let identity = self.identity()?.ok_or_else(|| anyhow!("collector tls needs cert and key"))?;
+        let mut tls = ServerTlsConfig::new().identity(identity);
+        if let Some(ca) = self.ca()? {
+            tls = tls.client_ca_root(ca);
+        }
+        Ok(tls)
+    }
+
+    fn client_config(&self, addr: &str) -> Result<ClientTlsConfig> {
+        let domain = match &self.domain {
+            Some(d) => d.clone(),
+            None => addr.rsplit_once(':').map_or(addr, |(host, _)| host).to_string(),
+        };
+        let mut tls = match self.ca()? {
+            Some(ca) => ClientTlsConfig::new().ca_certificate(ca),
+            None => ClientTlsConfig::new().with_native_roots(),
+        }
+        .domain_name(domain);
+        if let Some(identity) = self.identity()? {
+            tls = tls.identity(identity);
+        }
+        Ok(tls)
+    }
+}
+
+// ── Wire format ──────────────────────────────────────────────────────
+
+fn ts_ns(ts: Option<DateTime<Utc>>) -> Option<i64> {
+    ts.and_then(|t| t.timestamp_nanos_opt())
+}
+
+fn ns_ts(ns: Option<i64>) -> Option<DateTime<Utc>> {
+    let ns = ns?;
+    DateTime::from_timestamp(ns.div_euclid(1_000_000_000), ns.rem_euclid(1_000_000_000) as u32)
+}
+
+/// The wire form of `md`.
+pub fn to_tick(exchange: &Exchange, canonical: &str, md: &MarketData) -> Tick {
+    Tick {
+        exchange: exchange.as_str().to_string(),
+        symbol: canonical.to_string(),
+        bid: md.bid,
+        ask: md.ask,
+        bid_qty: md.bid_qty,
+        ask_qty: md.ask_qty,
+        exchange_ts_ns: ts_ns(md.exchange_ts_raw),
+        received_ts_ns: ts_ns(md.received_ts),
+        update_id: md.update_id,
+    }
+}
+
+/// Venue and quote carried by `tick`; the symbol stays in `tick.symbol`.
+pub fn from_tick(tick: &Tick) -> Result<(Exchange, MarketData)> {
+    let exchange = tick.exchange.parse::<Exchange>()?;
+    let md = MarketData {
+        bid: tick.bid,
+        ask: tick.ask,
+        bid_qty: tick.bid_qty,
+        ask_qty: tick.ask_qty,
+        exchange_ts_raw: ns_ts(tick.exchange_ts_ns),
+        received_ts: ns_ts(tick.received_ts_ns),
+        update_id: tick.update_id,
+        ..Default::default()
+    };
+    Ok((exchange, md))
+}
+
+/// Resolve a canonical name ("SPOT-ETH-USDC") to a local SymbolId.
+pub(crate) fn lookup_canonical(canonical: &str) -> Option<SymbolId> {
+    let itype = if canonical.starts_with("SPOT-") {
+        InstrumentType::Spot
+    } else if canonical.starts_with("PERP-") {
+        InstrumentType::Perp
+    } else {
+        return None;
+    };
+    REGISTRY.lookup(canonical, &itype).copied()
+}
+
+// ── Edge ─────────────────────────────────────────────────────────────
+
+/// Push local ticks to the collector at `cfg.addr`, reconnecting with backoff.
+pub async fn run_edge(market_data: Arc<AllMarketData>, cfg: CollectorConfig, shutdown: Arc<Notify>) -> Result<()> {
+    let push_interval = Duration::from_millis(cfg.push_interval_ms.max(1));
+    let edge_id = cfg.edge_id.clone().unwrap_or_else(|| "edge".to_string());
+    let endpoint = match &cfg.tls {
+        Some(tls) => Endpoint::from_shared(format!("https://{}", cfg.addr))?.tls_config(tls.client_config(&cfg.addr)?)?,
+        None => Endpoint::from_shared(format!("http://{}", cfg.addr))?,
+    };
+    let addr = cfg.addr;
+    let mut retry_count: u32 = 0;
+
+    loop {
+        let connect = tokio::select! {
+            _ = shutdown.notified() => break,
+            res = endpoint.connect() => res,
+        };
+
+        match connect {
+            Ok(channel) => {
+                info!("Edge connected to collector {}", addr);
+                retry_count = 0;
+                tokio::select! {
+                    _ = shutdown.notified() => break,
+                    res = push_ticks(&market_data, channel, &edge_id, push_interval) => {
+                        if let Err(e) = res {
+                            warn!("Edge push to {} failed: {}", addr, e);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                retry_count += 1;
+                error!("Edge failed to connect to collector {}: {}", addr, e);
+            }
+        }
+
+        let backoff = calculate_backoff(retry_count, Duration::from_secs(1), Duration::from_secs(30));
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+    }
+
+    info!("Stopped edge push to {}", addr);
+    Ok(())
+}
+
+/// Run one push stream until either side ends it.
+async fn push_ticks(market_data: &AllMarketData, channel: Channel, edge_id: &str, push_interval: Duration) -> Result<()> {
+    let (tx, rx) = mpsc::channel::<EdgeMessage>(1_024);
+    let hello = Hello { protocol_version: PROTOCOL_VERSION, edge_id: edge_id.to_string() };
+    tx.send(EdgeMessage { body: Some(Body::Hello(hello)) }).await?;
+    let outbound = futures_util::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|msg| (msg, rx)) });
+
+    let mut client = CollectorClient::new(channel);
+    tokio::select! {
+        res = client.push(outbound) => {
+            let summary = res?.into_inner();
+            info!("Collector closed the push stream after {} ticks", summary.ticks);
+            Ok(())
+        }
+        res = batch_ticks(market_data, tx, push_interval) => res,
+    }
+}
+
+/// Every `push_interval`, send the latest tick of each symbol written
+/// since the last pass. Ends when the stream is dropped.
+async fn batch_ticks(market_data: &AllMarketData, tx: mpsc::Sender<EdgeMessage>, push_interval: Duration) -> Result<()> {
+    let symbols: Vec<(SymbolId, String)> = REGISTRY.iter().map(|(id, s)| (id, s.to_string())).collect();
+    let n_symbols = symbol_capacity();
+    let n_exchanges = market_data.iter().count();
+    // Starts at zero so the first pass sends everything already buffered.
+    let mut last_counts = vec![0u64; n_exchanges * n_symbols];
+
+    let mut ticker = tokio::time::interval(push_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+        let mut ticks = Vec::new();
+
+        for (ex_idx, (exchange, coll)) in market_data.iter().enumerate() {
+            for (id, canonical) in &symbols {
+                let count = coll.write_count(id);
+                let last = &mut last_counts[ex_idx * n_symbols + id];
+                if count == *last {
+                    continue;
+                }
+                *last = count;
+                if let Some(md) = coll.latest(id) {
+                    ticks.push(to_tick(&exchange, canonical, &md));
+                }
+            }
+        }
+
+        if !ticks.is_empty() {
+            tx.send(EdgeMessage { body: Some(Body::Ticks(TickBatch { ticks })) })
+                .await
+                .map_err(|_| anyhow!("push stream closed"))?;
+        }
+    }
+}
+
+// ── Collector ────────────────────────────────────────────────────────
+
+struct CollectorService {
+    tx: mpsc::Sender<(Exchange, SymbolId, MarketData)>,
+}
+
+#[tonic::async_trait]
+impl Collector for CollectorService {
+    async fn push(&self, request: Request<Streaming<EdgeMessage>>) -> std::result::Result<Response<PushSummary>, Status> {
+        let peer = request.remote_addr().map_or_else(|| "unknown".to_string(), |a| a.to_string());
+        let mut stream = request.into_inner();
+
+        let hello = match stream.message().await? {
+            Some(EdgeMessage { body: Some(Body::Hello(hello)) }) => hello,
+            _ => return Err(Status::invalid_argument("push stream must open with a Hello")),
+        };
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&hello.protocol_version) {
+            warn!("Edge {} ({}) speaks protocol {}, refusing", hello.edge_id, peer, hello.protocol_version);
+            return Err(Status::failed_precondition(format!(
+                "protocol version {} unsupported, collector accepts {}..={}",
+                hello.protocol_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+            )));
+        }
+        info!("Collector accepted edge {} ({})", hello.edge_id, peer);
+
+        let mut warned_symbols: HashSet<String> = HashSet::new();
+        // Edges running a newer build may send venues this one lacks.
+        let mut warned_exchanges: HashSet<String> = HashSet::new();
+        let mut merged: u64 = 0;
+        while let Some(msg) = stream.message().await? {
+            let Some(Body::Ticks(batch)) = msg.body else {
+                continue;
+            };
+            for tick in &batch.ticks {
+                let Some(id) = lookup_canonical(&tick.symbol) else {
+                    if warned_symbols.insert(tick.symbol.clone()) {
+                        warn!("collector: symbol '{}' not in registry, dropping ticks", tick.symbol);
+                    }
+                    continue;
+                };
+                let Ok((exchange, mut md)) = from_tick(tick) else {
+                    if warned_exchanges.insert(tick.exchange.clone()) {
+                        warn!("collector: unknown exchange '{}', dropping ticks", tick.exchange);
+                    }
+                    continue;
+                };
+                md.received_instant = Some(Instant::now());
+                self.tx
+                    .send((exchange, id, md))
+                    .await
+                    .map_err(|_| Status::unavailable("collector merge task stopped"))?;
+                merged += 1;
+            }
+        }
+        info!("Edge {} ({}) disconnected after {} ticks", hello.edge_id, peer, merged);
+        Ok(Response::new(PushSummary { ticks: merged }))
+    }
+}
+
+/// Serve edges on `cfg.addr` and merge their ticks into `market_data`.
+pub async fn run_collector(market_data: Arc<AllMarketData>, cfg: CollectorConfig, shutdown: Arc<Notify>) -> Result<()> {
+    let addr: SocketAddr = cfg.addr.parse().with_context(|| format!("invalid collector addr {}", cfg.addr))?;
+    let mut server = Server::builder();
+    if let Some(tls) = &cfg.tls {
+        server = server.tls_config(tls.server_config()?)?;
+    }
+
+    let (tx, rx) = mpsc::channel::<(Exchange, SymbolId, MarketData)>(65_536);
+    let merge = tokio::spawn(merge_ticks(market_data, rx));
+
+    info!("Collector listening on {}", addr);
+    let served = server
+        .add_service(CollectorServer::new(CollectorService { tx }))
+        .serve_with_shutdown(addr, async move { shutdown.notified().await })
+        .await
+        .with_context(|| format!("collector on {addr} failed"));
+
+    merge.abort();
+    info!("Stopped collector on {}", addr);
+    served
+}
+
+async fn merge_ticks(
+    market_data: Arc<AllMarketData>,
+    mut rx: mpsc::Receiver<(Exchange, SymbolId, MarketData)>,
+) {
+    while let Some((exchange, id, md)) = rx.recv().await {
+        market_data.get_collection(&exchange).push(&id, md);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_tick_roundtrip() {
+        let md = MarketData {
+            bid: Some(100.5),
+            ask: Some(100.75),
+            bid_qty: Some(2.0),
+            ask_qty: None,
+            exchange_ts_raw: DateTime::from_timestamp(1_700_000_000, 123_456_789),
+            received_ts: DateTime::from_timestamp(1_700_000_000, 223_456_789),
+            update_id: Some(42),
+            ..Default::default()
+        };
+        let tick = to_tick(&Exchange::Binance, "PERP-BTC-USDT", &md);
+        let bytes = tick.encode_to_vec();
+        let tick = Tick::decode(bytes.as_slice()).unwrap();
+
+        let (exchange, out) = from_tick(&tick).unwrap();
+        assert!(exchange == Exchange::Binance);
+        assert_eq!(tick.symbol, "PERP-BTC-USDT");
+        assert_eq!(out.bid, Some(100.5));
+        assert_eq!(out.ask, Some(100.75));
+        assert_eq!(out.bid_qty, Some(2.0));
+        assert_eq!(out.ask_qty, None);
+        assert_eq!(out.exchange_ts_raw, md.exchange_ts_raw);
+        assert_eq!(out.received_ts, md.received_ts);
+        assert_eq!(out.update_id, Some(42));
+    }
+
+    #[test]
+    fn test_unknown_exchange_rejected() {
+        let tick = Tick { exchange: "nope".into(), symbol: "SPOT-ETH-USDT".into(), ..Default::default() };
+        assert!(from_tick(&tick).is_err());
+    }
+}
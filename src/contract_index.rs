@@ -0,0 +1,89 @@
+//! Name ↔ id index for instruments kept outside the symbol registry.
+//!
+//! Option chains, dated futures and reference indices appear as feeds see
+//! them, so their collections assign ids on first sight instead. Ids count
+//! up from 0, are never reused and index the collection's slots; `CAP`
+//! bounds them to the slots allocated.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Default)]
+pub struct ContractIndex<const CAP: usize> {
+    inner: RwLock<Names>,
+}
+
+#[derive(Default)]
+struct Names {
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl<const CAP: usize> ContractIndex<CAP> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<usize> {
+        self.inner.read().unwrap().ids.get(name).copied()
+    }
+
+    /// Return the id for `name`, assigning one if new. None once full.
+    pub fn get_or_insert(&self, name: &str) -> Option<usize> {
+        if let Some(id) = self.lookup(name) {
+            return Some(id);
+        }
+        let mut inner = self.inner.write().unwrap();
+        if let Some(&id) = inner.ids.get(name) {
+            return Some(id);
+        }
+        if inner.names.len() >= CAP {
+            return None;
+        }
+        let id = inner.names.len();
+        inner.names.push(name.to_string());
+        inner.ids.insert(name.to_string(), id);
+        Some(id)
+    }
+
+    pub fn name(&self, id: usize) -> Option<String> {
+        self.inner.read().unwrap().names.get(id).cloned()
+    }
+
+    /// Every name, in id order.
+    pub fn names(&self) -> Vec<String> {
+        self.inner.read().unwrap().names.clone()
+    }
+
+    /// All known contracts as (id, name).
+    pub fn all(&self) -> Vec<(usize, String)> {
+        self.inner.read().unwrap().names.iter().cloned().enumerate().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assigns_stable_ids() {
+        let idx = ContractIndex::<2>::new();
+        let a = idx.get_or_insert("BTC-250628-60000-C").unwrap();
+        let b = idx.get_or_insert("BTC-250628-60000-P").unwrap();
+        assert_eq!((a, b), (0, 1));
+        assert_eq!(idx.get_or_insert("BTC-250628-60000-C"), Some(a));
+        assert_eq!(idx.lookup("BTC-250628-60000-P"), Some(b));
+        assert_eq!(idx.name(b).as_deref(), Some("BTC-250628-60000-P"));
+        assert_eq!(idx.get_or_insert("ETH-250628-3000-C"), None);
+        assert_eq!(idx.lookup("ETH-250628-3000-C"), None);
+        assert_eq!(idx.names(), vec!["BTC-250628-60000-C", "BTC-250628-60000-P"]);
+    }
+}
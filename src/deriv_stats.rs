@@ -0,0 +1,220 @@
+//! Derivatives stats per perp: funding, open interest, mark/oracle/index
+//! prices.
+//!
+//! These update far less often than quotes (seconds, not milliseconds) and
+//! venues publish different subsets, so every field is optional and a push
+//! carries whatever the venue sent in that message.
+
+use crate::event_bus::MarketEvent;
+use crate::market_data::{DataSink, Exchange, FeedItem};
+use crate::ring_buffer::RingBuffer;
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DerivStats {
+    /// Rate for the current funding interval as a decimal (0.0001 = 1 bp).
+    pub funding_rate: Option<f64>,
+    /// Funding interval length in hours (8 on most CEXes, 1 on Hyperliquid).
+    pub funding_interval_h: Option<f64>,
+    pub next_funding_ts: Option<DateTime<Utc>>,
+    /// Open interest in base units.
+    pub open_interest: Option<f64>,
+    pub mark_price: Option<f64>,
+    pub oracle_price: Option<f64>,
+    pub index_price: Option<f64>,
+    /// Venue-reported premium, (mark - oracle) / oracle.
+    pub premium: Option<f64>,
+    /// Premium index the venue averages into funding, (impact price -
+    /// index) / index (Binance, Bybit).
+    pub premium_index: Option<f64>,
+    pub exchange_ts_raw: Option<DateTime<Utc>>,
+    pub exchange_ts: Option<DateTime<Utc>>,
+    pub received_ts: Option<DateTime<Utc>>,
+    pub received_instant: Option<Instant>,
+    pub feed_latency_ns: u64,
+}
+
+impl DerivStats {
+    /// Funding rate annualised from the interval rate (simple, not compounded).
+    pub fn annualized_funding(&self) -> Option<f64> {
+        let rate = self.funding_rate?;
+        let interval_h = self.funding_interval_h?;
+        if interval_h <= 0.0 {
+            return None;
+        }
+        Some(rate * (24.0 * 365.0) / interval_h)
+    }
+}
+
+impl FeedItem for DerivStats {
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        self.exchange_ts_raw
+    }
+    fn set_feed_latency_ns(&mut self, ns: u64) {
+        self.feed_latency_ns = ns;
+    }
+    fn to_event(&self, exchange: Exchange, id: SymbolId) -> Option<MarketEvent> {
+        self.funding_rate.map(|_| MarketEvent::Funding { exchange, id, data: *self })
+    }
+}
+
+pub struct DerivStatsCollection {
+    slots: Box<[OnceLock<Box<RingBuffer<DerivStats>>>]>,
+}
+
+impl DerivStatsCollection {
+    pub fn new() -> Self {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    pub fn push(&self, id: &SymbolId, mut item: DerivStats) {
+        if item.exchange_ts.is_none() {
+            item.exchange_ts = item.exchange_ts_raw;
+        }
+        let ring = self.slots[*id].get_or_init(|| Box::new(RingBuffer::with_capacity(64)));
+        ring.push(item);
+    }
+
+    pub fn latest(&self, id: &SymbolId) -> Option<DerivStats> {
+        self.slots[*id].get()?.latest()
+    }
+
+    pub fn write_count(&self, id: &SymbolId) -> u64 {
+        self.slots[*id].get().map(|r| r.write_count()).unwrap_or(0)
+    }
+
+    pub fn get_buffer(&self, id: &SymbolId) -> Option<&RingBuffer<DerivStats>> {
+        self.slots[*id].get().map(|b| b.as_ref())
+    }
+
+    /// Newest value of one field across the buffered pushes, with that
+    /// push's exchange timestamp. Venues fed by several sources (a mark
+    /// price stream plus an open interest poller) rarely carry every field
+    /// in the latest push.
+    pub fn latest_field(
+        &self,
+        id: &SymbolId,
+        field: impl Fn(&DerivStats) -> Option<f64>,
+    ) -> Option<(f64, Option<DateTime<Utc>>)> {
+        let ring = self.get_buffer(id)?;
+        let mut found = None;
+        ring.scan_last_n(ring.capacity(), |ds| {
+            if found.is_none() {
+                found = field(ds).map(|v| (v, ds.exchange_ts));
+            }
+        });
+        found
+    }
+}
+
+impl Default for DerivStatsCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSink<DerivStats> for DerivStatsCollection {
+    fn push(&self, id: &SymbolId, item: DerivStats) {
+        DerivStatsCollection::push(self, id, item);
+    }
+}
+
+impl DataSink<DerivStats> for Arc<DerivStatsCollection> {
+    fn push(&self, id: &SymbolId, item: DerivStats) {
+        DerivStatsCollection::push(self, id, item);
+    }
+}
+
+/// Derivatives stats collections per venue.
+pub struct AllDerivStats {
+    pub hyperliquid: Arc<DerivStatsCollection>,
+    pub binance: Arc<DerivStatsCollection>,
+    pub bybit: Arc<DerivStatsCollection>,
+    pub okx: Arc<DerivStatsCollection>,
+    pub mexc: Arc<DerivStatsCollection>,
+}
+
+impl AllDerivStats {
+    pub fn new() -> Self {
+        Self {
+            hyperliquid: Arc::new(DerivStatsCollection::new()),
+            binance: Arc::new(DerivStatsCollection::new()),
+            bybit: Arc::new(DerivStatsCollection::new()),
+            okx: Arc::new(DerivStatsCollection::new()),
+            mexc: Arc::new(DerivStatsCollection::new()),
+        }
+    }
+
+    /// None for venues without a stats feed.
+    pub fn get(&self, exchange: &Exchange) -> Option<&Arc<DerivStatsCollection>> {
+        match exchange {
+            Exchange::Hyperliquid => Some(&self.hyperliquid),
+            Exchange::Binance => Some(&self.binance),
+            Exchange::Bybit => Some(&self.bybit),
+            Exchange::Okx => Some(&self.okx),
+            Exchange::Mexc => Some(&self.mexc),
+            _ => None,
+        }
+    }
+}
+
+impl Default for AllDerivStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annualized_funding() {
+        let hourly = DerivStats {
+            funding_rate: Some(0.0000125),
+            funding_interval_h: Some(1.0),
+            ..Default::default()
+        };
+        assert!((hourly.annualized_funding().unwrap() - 0.1095).abs() < 1e-9);
+
+        let eight_h = DerivStats {
+            funding_rate: Some(0.0001),
+            funding_interval_h: Some(8.0),
+            ..Default::default()
+        };
+        assert!((eight_h.annualized_funding().unwrap() - 0.1095).abs() < 1e-9);
+
+        assert!(DerivStats::default().annualized_funding().is_none());
+    }
+
+    #[test]
+    fn test_push_latest() {
+        let coll = DerivStatsCollection::new();
+        assert!(coll.latest(&3).is_none());
+        coll.push(&3, DerivStats { open_interest: Some(1250.0), ..Default::default() });
+        assert_eq!(coll.latest(&3).unwrap().open_interest, Some(1250.0));
+        assert_eq!(coll.write_count(&3), 1);
+    }
+
+    #[test]
+    fn test_latest_field_skips_other_sources() {
+        let coll = DerivStatsCollection::new();
+        assert!(coll.latest_field(&5, |ds| ds.mark_price).is_none());
+        coll.push(&5, DerivStats { mark_price: Some(100.0), ..Default::default() });
+        coll.push(&5, DerivStats { mark_price: Some(101.0), ..Default::default() });
+        coll.push(&5, DerivStats { open_interest: Some(7.5), ..Default::default() });
+        assert_eq!(coll.latest_field(&5, |ds| ds.mark_price).map(|(v, _)| v), Some(101.0));
+        assert_eq!(coll.latest_field(&5, |ds| ds.open_interest).map(|(v, _)| v), Some(7.5));
+        assert!(coll.latest_field(&5, |ds| ds.funding_rate).is_none());
+    }
+}
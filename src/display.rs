@@ -1,6 +1,6 @@
 use crate::analytics::{Analytics, AnalyticsScratch, DisplayAnalytics};
 use crate::market_data::{AllMarketData, Exchange, MarketDataCollection};
-use crate::symbol_registry::{MAX_SYMBOLS, SymbolId, REGISTRY};
+use crate::symbol_registry::{SymbolId, REGISTRY, symbol_capacity};
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::{HashMap, VecDeque};
@@ -363,7 +363,7 @@ pub fn write_market_collection(
     let has_analytics = show_analytics;
 
     let prev_len = seen.len();
-    for id in 0..MAX_SYMBOLS {
+    for id in 0..symbol_capacity() {
         if collection.latest(&id).is_some() && !seen.contains(&id) {
             seen.push(id);
         }
@@ -0,0 +1,185 @@
+//! Typed firehose of everything the websocket feeds write.
+//!
+//! With `event_bus` configured, `listen_with_reconnect` publishes each item
+//! it stores as a `MarketEvent`, alongside the usual collection write, and
+//! reports connection changes as `Status` events. Consumers `subscribe()`
+//! for a tokio broadcast receiver instead of polling per-venue collections.
+//! A receiver that falls more than `capacity` events behind gets
+//! `RecvError::Lagged` and skips ahead; feeds never wait on consumers.
+
+use crate::deriv_stats::DerivStats;
+use crate::market_data::{BookDelta, BookSnapshot, Exchange, MarketData};
+use crate::symbol_registry::SymbolId;
+use crate::trade_data::TradeData;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+fn default_capacity() -> usize {
+    4096
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventBusConfig {
+    /// Events buffered per receiver before it lags.
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    /// Also publish every level change of the depth-based feeds' books.
+    #[serde(default)]
+    pub book_deltas: bool,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self { capacity: default_capacity(), book_deltas: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// Connected and subscribed.
+    Connected,
+    /// Socket dropped; the feed will reconnect.
+    Disconnected,
+    /// Circuit breaker tripped; the feed's symbols were invalidated.
+    CircuitOpen,
+    /// Too many failed connects in a row; invalidated and cooling down
+    /// like `CircuitOpen`.
+    Degraded,
+    /// The feed task exited.
+    Stopped,
+}
+
+impl FeedStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedStatus::Connected => "connected",
+            FeedStatus::Disconnected => "disconnected",
+            FeedStatus::CircuitOpen => "circuit_open",
+            FeedStatus::Degraded => "degraded",
+            FeedStatus::Stopped => "stopped",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum MarketEvent {
+    Bbo { exchange: Exchange, id: SymbolId, data: MarketData },
+    Trade { exchange: Exchange, id: SymbolId, data: TradeData },
+    /// Derivatives stats carrying a funding rate.
+    Funding { exchange: Exchange, id: SymbolId, data: DerivStats },
+    /// Top-N book from venues with `depth_levels` set.
+    Depth { exchange: Exchange, id: SymbolId, data: Box<BookSnapshot> },
+    /// A level change in a depth-based feed's book, with `book_deltas` set.
+    BookDelta { exchange: Exchange, id: SymbolId, data: BookDelta },
+    /// The feed cleared its book for `id`, e.g. on reconnect; the deltas
+    /// that follow rebuild it from the venue's snapshot.
+    BookReset { exchange: Exchange, id: SymbolId },
+    /// `feed` is the feed name, e.g. "binance_spot".
+    Status { exchange: Option<Exchange>, feed: String, status: FeedStatus },
+}
+
+impl MarketEvent {
+    pub fn exchange(&self) -> Option<Exchange> {
+        match self {
+            MarketEvent::Bbo { exchange, .. }
+            | MarketEvent::Trade { exchange, .. }
+            | MarketEvent::Funding { exchange, .. }
+            | MarketEvent::Depth { exchange, .. }
+            | MarketEvent::BookDelta { exchange, .. }
+            | MarketEvent::BookReset { exchange, .. } => Some(*exchange),
+            MarketEvent::Status { exchange, .. } => *exchange,
+        }
+    }
+
+    /// The quote of a `Bbo` event, for consumers that only react to ticks.
+    pub fn as_bbo(&self) -> Option<(Exchange, SymbolId, MarketData)> {
+        match self {
+            MarketEvent::Bbo { exchange, id, data } => Some((*exchange, *id, *data)),
+            _ => None,
+        }
+    }
+
+    /// None for `Status`.
+    pub fn symbol_id(&self) -> Option<SymbolId> {
+        match self {
+            MarketEvent::Bbo { id, .. }
+            | MarketEvent::Trade { id, .. }
+            | MarketEvent::Funding { id, .. }
+            | MarketEvent::Depth { id, .. }
+            | MarketEvent::BookDelta { id, .. }
+            | MarketEvent::BookReset { id, .. } => Some(*id),
+            MarketEvent::Status { .. } => None,
+        }
+    }
+}
+
+static BUS: OnceLock<broadcast::Sender<MarketEvent>> = OnceLock::new();
+static BOOK_DELTAS: OnceLock<bool> = OnceLock::new();
+
+/// Enable the bus for feeds started from now on. Only the first call
+/// takes effect.
+pub fn install(cfg: &EventBusConfig) {
+    BUS.get_or_init(|| {
+        let _ = BOOK_DELTAS.set(cfg.book_deltas);
+        broadcast::channel(cfg.capacity.max(1)).0
+    });
+}
+
+pub fn is_active() -> bool {
+    BUS.get().is_some()
+}
+
+/// Whether feeds should publish `BookDelta` and `BookReset` events.
+pub fn book_deltas_active() -> bool {
+    BOOK_DELTAS.get().copied().unwrap_or(false)
+}
+
+/// A receiver for every event published from now on. None unless the bus
+/// is installed.
+pub fn subscribe() -> Option<broadcast::Receiver<MarketEvent>> {
+    BUS.get().map(|tx| tx.subscribe())
+}
+
+/// Send `event` to current subscribers; dropped if there are none.
+pub fn publish(event: MarketEvent) {
+    if let Some(tx) = BUS.get() {
+        let _ = tx.send(event);
+    }
+}
+
+/// Publish a connection change for `feed`.
+pub fn publish_status(feed: &str, status: FeedStatus) {
+    if !is_active() {
+        return;
+    }
+    let exchange = feed.split('_').next().unwrap_or(feed).parse::<Exchange>().ok();
+    publish(MarketEvent::Status { exchange, feed: feed.to_string(), status });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_see_events_after_install() {
+        install(&EventBusConfig { capacity: 8, book_deltas: true });
+        assert!(book_deltas_active());
+        let mut rx = subscribe().unwrap();
+        publish(MarketEvent::Bbo { exchange: Exchange::Binance, id: 3, data: MarketData { bid: Some(1.0), ..Default::default() } });
+        publish_status("bybit_perp", FeedStatus::Connected);
+
+        let (exchange, id, data) = rx.try_recv().unwrap().as_bbo().unwrap();
+        assert_eq!((exchange, id, data.bid), (Exchange::Binance, 3, Some(1.0)));
+        let status = rx.try_recv().unwrap();
+        assert!(status.as_bbo().is_none());
+        assert_eq!(status.exchange(), Some(Exchange::Bybit));
+        assert!(status.symbol_id().is_none());
+        assert!(matches!(status, MarketEvent::Status { status: FeedStatus::Connected, .. }));
+        assert!(rx.try_recv().is_err());
+
+        publish(MarketEvent::BookReset { exchange: Exchange::Lighter, id: 5 });
+        let reset = rx.try_recv().unwrap();
+        assert_eq!((reset.exchange(), reset.symbol_id()), (Some(Exchange::Lighter), Some(5)));
+    }
+}
@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FeeSchedule {
     pub taker_fees_bps: f64,
     pub maker_fees_bps: f64,
@@ -17,13 +18,28 @@ impl FeeSchedule{
 }
 
 
+/// One venue's `fees` config entry. Unset defaults keep the connector's
+/// schedule; overrides are keyed like "BTC_USDT".
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VenueFeesConfig {
+    #[serde(default)]
+    pub spot: Option<FeeSchedule>,
+    #[serde(default)]
+    pub perp: Option<FeeSchedule>,
+    #[serde(default)]
+    pub spot_overrides: HashMap<String, FeeSchedule>,
+    #[serde(default)]
+    pub perp_overrides: HashMap<String, FeeSchedule>,
+}
+
+#[derive(Debug, Clone)]
 pub struct ExchangeFees 
 {
     spot_default: FeeSchedule,
     perp_default: FeeSchedule,
 
-    spot_overrides: HashMap<&'static str, FeeSchedule>,
-    perp_overrides: HashMap<&'static str, FeeSchedule>,
+    spot_overrides: HashMap<String, FeeSchedule>,
+    perp_overrides: HashMap<String, FeeSchedule>,
 
 }
 
@@ -37,13 +53,36 @@ impl ExchangeFees {
         }
     }
     
-    pub fn set_spot_override(mut self, symbol: &'static str, fees: FeeSchedule) -> Self {
-        self.spot_overrides.insert(symbol, fees);
+    /// Schedule built from `cfg` alone, for venues without a connector
+    /// default. None unless it sets both `spot` and `perp`.
+    pub fn from_config(cfg: &VenueFeesConfig) -> Option<Self> {
+        Some(Self::new(cfg.spot?, cfg.perp?).with_config(cfg))
+    }
+
+    /// `self` with `cfg`'s defaults and overrides applied on top.
+    pub fn with_config(mut self, cfg: &VenueFeesConfig) -> Self {
+        if let Some(spot) = cfg.spot {
+            self.spot_default = spot;
+        }
+        if let Some(perp) = cfg.perp {
+            self.perp_default = perp;
+        }
+        for (symbol, fees) in &cfg.spot_overrides {
+            self = self.set_spot_override(symbol.clone(), *fees);
+        }
+        for (symbol, fees) in &cfg.perp_overrides {
+            self = self.set_perp_override(symbol.clone(), *fees);
+        }
+        self
+    }
+
+    pub fn set_spot_override(mut self, symbol: impl Into<String>, fees: FeeSchedule) -> Self {
+        self.spot_overrides.insert(symbol.into(), fees);
         self
     }
     
-    pub fn set_perp_override(mut self, symbol: &'static str, fees: FeeSchedule) -> Self {
-        self.perp_overrides.insert(symbol, fees);
+    pub fn set_perp_override(mut self, symbol: impl Into<String>, fees: FeeSchedule) -> Self {
+        self.perp_overrides.insert(symbol.into(), fees);
         self
     }
     
@@ -54,4 +93,27 @@ impl ExchangeFees {
     pub fn get_perp_fees(&self, symbol: &str) -> &FeeSchedule {
         self.perp_overrides.get(symbol).unwrap_or(&self.perp_default)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_overrides_connector_defaults() {
+        let cfg: VenueFeesConfig = serde_json::from_str(
+            r#"{"perp": {"taker_fees_bps": 3.5, "maker_fees_bps": 0.0},
+                "spot_overrides": {"BTC_USDT": {"taker_fees_bps": 0.0, "maker_fees_bps": 0.0}}}"#,
+        )
+        .unwrap();
+        let fees = ExchangeFees::new(FeeSchedule::new(10.0, 10.0), FeeSchedule::new(5.0, 2.0)).with_config(&cfg);
+        assert_eq!(fees.get_spot_fees("BTC_USDT").taker_fees_bps, 0.0);
+        assert_eq!(fees.get_spot_fees("ETH_USDT").taker_fees_bps, 10.0);
+        assert_eq!(fees.get_perp_fees("BTC_USDT").taker_fees_bps, 3.5);
+        assert_eq!(fees.get_perp_fees("BTC_USDT").maker_fees_bps, 0.0);
+
+        assert!(ExchangeFees::from_config(&cfg).is_none());
+        let full = VenueFeesConfig { spot: Some(FeeSchedule::new(1.0, 1.0)), ..cfg };
+        assert_eq!(ExchangeFees::from_config(&full).unwrap().get_spot_fees("SOL_USDT").taker_fees_bps, 1.0);
+    }
+}
@@ -225,7 +225,7 @@ impl ExchangeFeed for ApexFeed {
                     },
                 )])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 
@@ -1,16 +1,26 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
 use log::warn;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::app_config::BinanceBookMode;
+use crate::candle_data::{Candle, CandleInterval, CandleStore, Kline, KlineSink};
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, SequenceStatus, SequenceTracker, WireMessage, listen_sharded,
+    listen_with_reconnect,
 };
+use crate::exchanges::rest_poll::poll_deriv_stats;
+use crate::futures_data::{FuturesContract, FuturesData, FuturesDataCollection};
+use crate::liquidation_data::{LiquidationCollection, LiquidationEvent};
 use crate::mappers::{BinanceMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::market_data::{Exchange, InstrumentType, MarketData, MarketDataCollection};
+use crate::orderbook::{IMBALANCE_LEVELS, SyncBook};
+use crate::options_data::{OptionContracts, OptionData, OptionDataCollection};
+use crate::symbol_registry::SymbolId;
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
 
 pub fn get_fees() -> ExchangeFees {
@@ -42,35 +52,150 @@ struct BinanceBookTickerData {
     event_time: Option<u64>,
 }
 
+/// Partial book depth frame. Spot sends `{lastUpdateId, bids, asks}` with
+/// no symbol; futures sends a `depthUpdate` with `s`, `E`, `u`, `pu`, `b`,
+/// `a`. Either way it is a complete top-N book, so `pu` is not chained.
+#[derive(Debug, Deserialize)]
+struct BinanceDepth {
+    stream: String,
+    data: BinanceDepthData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceDepthData {
+    #[serde(alias = "lastUpdateId")]
+    u: u64,
+    #[serde(alias = "b")]
+    bids: Vec<(String, String)>,
+    #[serde(alias = "a")]
+    asks: Vec<(String, String)>,
+    #[serde(rename = "E", default)]
+    event_time: Option<u64>,
+}
+
+/// (price, size) levels; unparsable prices are skipped.
+fn depth_levels(levels: &[(String, String)]) -> Vec<(f64, f64)> {
+    levels
+        .iter()
+        .filter_map(|(px, qty)| Some((px.parse::<f64>().ok()?, qty.parse::<f64>().unwrap_or(0.0))))
+        .collect()
+}
+
 /// Binance feed implemented using the generic connection abstraction.
 struct BinanceFeed {
     /// "wss://stream.binance.com:9443/stream" for spot
     /// "wss://fstream.binance.com/public/stream" for perp (bookTicker is /public)
     base_url: &'static str,
+    /// Tried in order when `base_url` can't be reached, e.g. from regions
+    /// where the main hostname is blocked.
+    fallback_urls: &'static [&'static str],
     itype: InstrumentType,
     mapper: BinanceMapper,
     /// Dedup by update ID (spot has no event_time, so connection-loop
     /// timestamp dedup is a no-op; we use the `u` field instead).
-    last_update_id: std::sync::Mutex<HashMap<String, u64>>,
+    sequences: SequenceTracker,
+    /// `Some(levels)` subscribes to `@depth{levels}@100ms` instead of
+    /// `@bookTicker`.
+    depth: Option<u8>,
+    /// Per-symbol partial books in depth mode, keyed by native symbol
+    /// (single writer: one WS task).
+    books: HashMap<String, SyncBook>,
 }
 
 impl BinanceFeed {
     fn new_spot() -> Self {
         Self {
             base_url: "wss://stream.binance.com:9443/stream",
+            fallback_urls: &["wss://stream.binance.com:443/stream", "wss://data-stream.binance.vision/stream"],
             itype: InstrumentType::Spot,
             mapper: BinanceMapper,
-            last_update_id: std::sync::Mutex::new(HashMap::new()),
+            sequences: SequenceTracker::monotonic(),
+            depth: None,
+            books: HashMap::new(),
         }
     }
 
     fn new_perp() -> Self {
         Self {
             base_url: "wss://fstream.binance.com/public/stream",
+            fallback_urls: &[],
             itype: InstrumentType::Perp,
             mapper: BinanceMapper,
-            last_update_id: std::sync::Mutex::new(HashMap::new()),
+            sequences: SequenceTracker::monotonic(),
+            depth: None,
+            books: HashMap::new(),
+        }
+    }
+
+    /// Switch to partial book depth for `symbols` (5, 10 or 20 levels).
+    fn with_depth(mut self, symbols: &[&str], levels: u8) -> Result<Self> {
+        if ![5, 10, 20].contains(&levels) {
+            bail!("Binance depth levels must be 5, 10 or 20, got {}", levels);
+        }
+        for sym in symbols {
+            let native = self.mapper.denormalize(sym, self.itype)?.to_uppercase();
+            self.books.insert(native, SyncBook::new());
         }
+        self.depth = Some(levels);
+        Ok(self)
+    }
+
+    fn with_mode(self, symbols: &[&str], mode: BinanceBookMode, levels: u8) -> Result<Self> {
+        match mode {
+            BinanceBookMode::BookTicker => Ok(self),
+            BinanceBookMode::Depth => self.with_depth(symbols, levels),
+        }
+    }
+
+    fn parse_depth(
+        &self,
+        text: &str,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let msg = serde_json::from_str::<BinanceDepth>(text)?;
+        // "btcusdt@depth5@100ms" -> "BTCUSDT"
+        let symbol = msg.stream.split('@').next().unwrap_or_default().to_uppercase();
+        let Some(book_cell) = self.books.get(&symbol) else {
+            return Ok(vec![]);
+        };
+        // A missed frame is harmless, as the next one replaces the book;
+        // only drop ones older than what it already shows.
+        if self.sequences.check(&symbol, msg.data.u) == SequenceStatus::Stale {
+            return Ok(vec![]);
+        }
+
+        // SAFETY: single writer — one WS task per feed.
+        let book = unsafe { book_cell.get_mut() };
+        // Each frame is a full top-N snapshot.
+        book.clear();
+        book.update_bids_f64(&depth_levels(&msg.data.bids));
+        book.update_asks_f64(&depth_levels(&msg.data.asks));
+
+        let (Some((bid, bid_qty)), Some((ask, ask_qty))) = (book.best_bid(), book.best_ask()) else {
+            return Ok(vec![]);
+        };
+        if bid >= ask {
+            warn!("Invalid depth for {}: bid={} >= ask={}", symbol, bid, ask);
+            return Ok(vec![]);
+        }
+
+        let market_data = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(bid_qty),
+            ask_qty: Some(ask_qty),
+            exchange_ts_raw: msg
+                .data
+                .event_time
+                .and_then(|ms| DateTime::from_timestamp_millis(ms as i64)),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            update_id: Some(msg.data.u),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
+            ..Default::default()
+        };
+        Ok(vec![(symbol, market_data)])
     }
 }
 
@@ -92,7 +217,10 @@ impl ExchangeFeed for BinanceFeed {
                     .denormalize(s, self.itype)
                     .unwrap()
                     .to_lowercase();
-                format!("{}@bookTicker", native)
+                match self.depth {
+                    Some(levels) => format!("{}@depth{}@100ms", native, levels),
+                    None => format!("{}@bookTicker", native),
+                }
             })
             .collect();
 
@@ -101,6 +229,19 @@ impl ExchangeFeed for BinanceFeed {
         Ok(format!("{}?streams={}", self.base_url, streams_str))
     }
 
+    /// A new session, possibly on a fallback host, starts the `u` check
+    /// over.
+    fn on_connected(&self) {
+        self.sequences.reset();
+    }
+
+    fn build_urls(&self, symbols: &[&str]) -> Result<Vec<String>> {
+        let primary = self.build_url(symbols)?;
+        let query = primary[self.base_url.len()..].to_string();
+        let fallbacks = self.fallback_urls.iter().map(|base| format!("{}{}", base, query));
+        Ok(std::iter::once(primary).chain(fallbacks).collect())
+    }
+
     fn parse_message(
         &self,
         msg: WireMessage<'_>,
@@ -110,17 +251,14 @@ impl ExchangeFeed for BinanceFeed {
         // Some exchanges send non-data frames; Binance combined stream sends JSON objects
         // Return Ok(None) on parse failure? Here we propagate error so caller can log.
         match msg {
+            WireMessage::Text(text) if self.depth.is_some() => {
+                self.parse_depth(text, received_ts, received_instant)
+            }
             WireMessage::Text(text) => {
                 let msg = serde_json::from_str::<BinanceBookTicker>(text)?;
 
-                // Dedup by update ID (monotonically increasing per symbol)
-                {
-                    let mut map = self.last_update_id.lock().unwrap();
-                    let last = map.entry(msg.data.symbol.clone()).or_insert(0);
-                    if msg.data.u <= *last {
-                        return Ok(vec![]);
-                    }
-                    *last = msg.data.u;
+                if self.sequences.check(&msg.data.symbol, msg.data.u) == SequenceStatus::Stale {
+                    return Ok(vec![]);
                 }
 
                 let bid = msg.data.bid_price.parse::<f64>().ok();
@@ -157,21 +295,28 @@ impl ExchangeFeed for BinanceFeed {
 
                 Ok(vec![(msg.data.symbol, market_data)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
 
+/// Binance refuses combined-stream connections beyond this many streams.
+const MAX_STREAMS_PER_CONNECTION: usize = 1024;
+
+/// Spot BBO from `@bookTicker` or, with `BinanceBookMode::Depth`, from
+/// `@depth{depth_levels}@100ms` partial books.
 pub async fn listen_spot_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: BinanceBookMode,
+    depth_levels: u8,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(BinanceFeed::new_spot());
-    listen_with_reconnect(
+    listen_sharded(
         data,
         symbols,
-        feed,
+        MAX_STREAMS_PER_CONNECTION,
+        |chunk| BinanceFeed::new_spot().with_mode(chunk, mode, depth_levels),
         "binance_spot",
         ConnectionConfig::default(),
         shutdown,
@@ -179,16 +324,19 @@ pub async fn listen_spot_bbo(
     .await
 }
 
+/// Perp BBO; `mode` and `depth_levels` as for `listen_spot_bbo`.
 pub async fn listen_perp_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: BinanceBookMode,
+    depth_levels: u8,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(BinanceFeed::new_perp());
-    listen_with_reconnect(
+    listen_sharded(
         data,
         symbols,
-        feed,
+        MAX_STREAMS_PER_CONNECTION,
+        |chunk| BinanceFeed::new_perp().with_mode(chunk, mode, depth_levels),
         "binance_perp",
         ConnectionConfig::default(),
         shutdown,
@@ -294,7 +442,7 @@ impl ExchangeFeed for BinanceTradeFeed {
                 };
                 Ok(vec![(msg.data.symbol, trade)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -315,3 +463,732 @@ pub async fn listen_perp_trades(
     )
     .await
 }
+
+// --- Liquidation Feed ---
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct BinanceForceOrder {
+    stream: String,
+    data: BinanceForceOrderData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceForceOrderData {
+    #[serde(rename = "o")]
+    order: BinanceForceOrderDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceForceOrderDetail {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "S")]
+    side: String,
+    /// Average fill price; `p` is the order's limit price.
+    #[serde(rename = "ap")]
+    avg_price: String,
+    /// Filled quantity so far.
+    #[serde(rename = "z")]
+    filled_qty: String,
+    /// Trade time (ms)
+    #[serde(rename = "T")]
+    trade_time: u64,
+}
+
+struct BinanceLiquidationFeed {
+    itype: InstrumentType,
+    mapper: BinanceMapper,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BinanceLiquidationFeed {
+    type Item = LiquidationEvent;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        let streams = symbols
+            .iter()
+            .map(|s| Ok(format!("{}@forceOrder", self.mapper.denormalize(s, self.itype)?.to_lowercase())))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(format!("wss://fstream.binance.com/market/stream?streams={}", streams.join("/")))
+    }
+
+    fn timestamp_dedup(&self) -> bool {
+        false
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, LiquidationEvent)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let o = serde_json::from_str::<BinanceForceOrder>(text)?.data.order;
+        let side = match o.side.as_str() {
+            "BUY" => TradeSide::Buy,
+            "SELL" => TradeSide::Sell,
+            _ => TradeSide::Unknown,
+        };
+        let event = LiquidationEvent {
+            price: o.avg_price.parse::<f64>()?,
+            qty: o.filled_qty.parse::<f64>()?,
+            side,
+            exchange_ts_raw: DateTime::from_timestamp_millis(o.trade_time as i64),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(o.symbol, event)])
+    }
+}
+
+/// Perp liquidation orders from `@forceOrder`. Binance pushes at most the
+/// latest liquidation per symbol each second.
+pub async fn listen_perp_liquidations(
+    data: Arc<LiquidationCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BinanceLiquidationFeed { itype: InstrumentType::Perp, mapper: BinanceMapper });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "binance_perp_liquidations",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Kline Feed ---
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct BinanceKline {
+    stream: String,
+    data: BinanceKlineData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceKlineData {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "k")]
+    kline: BinanceKlineBar,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceKlineBar {
+    /// Open time (ms)
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o")]
+    open: String,
+    #[serde(rename = "h")]
+    high: String,
+    #[serde(rename = "l")]
+    low: String,
+    #[serde(rename = "c")]
+    close: String,
+    /// Base asset volume
+    #[serde(rename = "v")]
+    volume: String,
+    /// Number of trades
+    #[serde(rename = "n")]
+    trades: u32,
+    /// Bar closed
+    #[serde(rename = "x")]
+    closed: bool,
+}
+
+struct BinanceKlineFeed {
+    itype: InstrumentType,
+    mapper: BinanceMapper,
+    intervals: Vec<CandleInterval>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BinanceKlineFeed {
+    type Item = Kline;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        let mut streams = Vec::with_capacity(symbols.len() * self.intervals.len());
+        for s in symbols {
+            let native = self.mapper.denormalize(s, self.itype)?.to_lowercase();
+            for iv in &self.intervals {
+                streams.push(format!("{}@kline_{}", native, iv.as_str()));
+            }
+        }
+        Ok(format!("wss://fstream.binance.com/market/stream?streams={}", streams.join("/")))
+    }
+
+    fn timestamp_dedup(&self) -> bool {
+        false
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        _received_ts: DateTime<Utc>,
+        _received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, Kline)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let d = serde_json::from_str::<BinanceKline>(text)?.data;
+        let k = d.kline;
+        // Open bars update every 250ms; only the final update is stored.
+        if !k.closed {
+            return Ok(vec![]);
+        }
+        let Some(interval) = CandleInterval::from_str(&k.interval) else {
+            return Ok(vec![]);
+        };
+        let candle = Candle {
+            open_time_ms: k.open_time,
+            open: k.open.parse::<f64>()?,
+            high: k.high.parse::<f64>()?,
+            low: k.low.parse::<f64>()?,
+            close: k.close.parse::<f64>()?,
+            volume: k.volume.parse::<f64>()?,
+            ticks: k.trades,
+        };
+        Ok(vec![(d.symbol, Kline { interval, candle })])
+    }
+}
+
+/// Closed perp klines from `@kline_<interval>` for each of `intervals`,
+/// written into `store` under Binance. 1s is spot-only and skipped.
+pub async fn listen_perp_klines(
+    store: Arc<CandleStore>,
+    symbols: &[&str],
+    intervals: &[CandleInterval],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let (supported, skipped): (Vec<_>, Vec<_>) = intervals.iter().copied().partition(|iv| *iv != CandleInterval::S1);
+    for iv in skipped {
+        warn!("Binance perp klines do not stream {}, skipping", iv.as_str());
+    }
+    if supported.is_empty() {
+        bail!("Binance perp klines: no supported interval among {:?}", intervals);
+    }
+    let feed = Arc::new(BinanceKlineFeed {
+        itype: InstrumentType::Perp,
+        mapper: BinanceMapper,
+        intervals: supported,
+    });
+    let sink = Arc::new(KlineSink { store, exchange: Exchange::Binance });
+    listen_with_reconnect(
+        sink,
+        symbols,
+        feed,
+        "binance_perp_klines",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Mark Price Feed ---
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct BinanceMarkPrice {
+    stream: String,
+    data: BinanceMarkPriceData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceMarkPriceData {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "p")]
+    mark_price: String,
+    #[serde(rename = "i")]
+    index_price: String,
+    #[serde(rename = "r")]
+    funding_rate: String,
+    /// Next funding time (ms)
+    #[serde(rename = "T")]
+    next_funding_time: i64,
+    /// Event time (ms)
+    #[serde(rename = "E")]
+    event_time: i64,
+}
+
+struct BinanceMarkPriceFeed {
+    itype: InstrumentType,
+    mapper: BinanceMapper,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BinanceMarkPriceFeed {
+    type Item = DerivStats;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        let streams = symbols
+            .iter()
+            .map(|s| Ok(format!("{}@markPrice@1s", self.mapper.denormalize(s, self.itype)?.to_lowercase())))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(format!("wss://fstream.binance.com/market/stream?streams={}", streams.join("/")))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, DerivStats)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let d = serde_json::from_str::<BinanceMarkPrice>(text)?.data;
+        let stats = DerivStats {
+            mark_price: d.mark_price.parse::<f64>().ok(),
+            index_price: d.index_price.parse::<f64>().ok(),
+            // Empty on delivery contracts.
+            funding_rate: d.funding_rate.parse::<f64>().ok(),
+            next_funding_ts: DateTime::from_timestamp_millis(d.next_funding_time).filter(|_| d.next_funding_time > 0),
+            exchange_ts_raw: DateTime::from_timestamp_millis(d.event_time),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(d.symbol, stats)])
+    }
+}
+
+/// Perp mark and index price plus the current funding rate from
+/// `@markPrice@1s`.
+pub async fn listen_perp_mark_price(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BinanceMarkPriceFeed { itype: InstrumentType::Perp, mapper: BinanceMapper });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "binance_perp_mark",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Open Interest (REST) ---
+
+const OPEN_INTEREST_URL: &str = "https://fapi.binance.com/fapi/v1/openInterest";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceOpenInterest {
+    open_interest: String,
+    time: i64,
+}
+
+async fn fetch_open_interest(client: reqwest::Client, native: String) -> Result<DerivStats> {
+    let resp = client
+        .get(OPEN_INTEREST_URL)
+        .query(&[("symbol", native.as_str())])
+        .send()
+        .await
+        .with_context(|| format!("GET {OPEN_INTEREST_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {OPEN_INTEREST_URL} -> {status}; body: {body}");
+    }
+    let oi: BinanceOpenInterest = serde_json::from_str(&body).context("decode openInterest JSON")?;
+    Ok(DerivStats {
+        open_interest: oi.open_interest.parse::<f64>().ok(),
+        exchange_ts_raw: DateTime::from_timestamp_millis(oi.time),
+        ..Default::default()
+    })
+}
+
+/// Perp open interest (base units) from `/fapi/v1/openInterest`, polled
+/// every `interval`.
+pub async fn poll_open_interest(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    interval: std::time::Duration,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    poll_deriv_stats(data, symbols, BinanceMapper, interval, "binance_oi", shutdown, fetch_open_interest).await
+}
+
+// --- Premium Index (REST) ---
+
+const PREMIUM_INDEX_URL: &str = "https://fapi.binance.com/fapi/v1/premiumIndexKlines";
+
+async fn fetch_premium_index(client: reqwest::Client, native: String) -> Result<DerivStats> {
+    let resp = client
+        .get(PREMIUM_INDEX_URL)
+        .query(&[("symbol", native.as_str()), ("interval", "1m"), ("limit", "1")])
+        .send()
+        .await
+        .with_context(|| format!("GET {PREMIUM_INDEX_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {PREMIUM_INDEX_URL} -> {status}; body: {body}");
+    }
+    // Kline rows: [openTime, open, high, low, close, ...]
+    let rows: Vec<Vec<serde_json::Value>> = serde_json::from_str(&body).context("decode premiumIndexKlines JSON")?;
+    let Some(row) = rows.last().filter(|r| r.len() > 4) else {
+        bail!("Binance premium index: no bar for {}", native);
+    };
+    Ok(DerivStats {
+        premium_index: row[4].as_str().and_then(|s| s.parse::<f64>().ok()),
+        // Open of the current bar; the close is its latest value.
+        exchange_ts_raw: row[0].as_i64().and_then(DateTime::from_timestamp_millis),
+        ..Default::default()
+    })
+}
+
+/// Perp premium index from the close of the current 1m
+/// `/fapi/v1/premiumIndexKlines` bar, polled every `interval`.
+pub async fn poll_premium_index(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    interval: std::time::Duration,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    poll_deriv_stats(data, symbols, BinanceMapper, interval, "binance_premium", shutdown, fetch_premium_index).await
+}
+
+// --- Dated Futures BBO Feed ---
+
+const EXCHANGE_INFO_URL: &str = "https://fapi.binance.com/fapi/v1/exchangeInfo";
+
+#[derive(Debug, Deserialize)]
+struct BinanceExchangeInfo {
+    symbols: Vec<BinanceContractInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceContractInfo {
+    symbol: String,
+    /// "PERPETUAL", "CURRENT_QUARTER", "NEXT_QUARTER", ...
+    contract_type: String,
+    status: String,
+}
+
+/// Trading USDM dated contracts as config symbols ("BTC_USDT_260327").
+async fn fetch_dated_futures(client: &reqwest::Client) -> Result<Vec<String>> {
+    let resp = client
+        .get(EXCHANGE_INFO_URL)
+        .send()
+        .await
+        .with_context(|| format!("GET {EXCHANGE_INFO_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {EXCHANGE_INFO_URL} -> {status}; body: {body}");
+    }
+    let info: BinanceExchangeInfo = serde_json::from_str(&body).context("decode exchangeInfo JSON")?;
+    let mapper = BinanceMapper;
+    Ok(info
+        .symbols
+        .into_iter()
+        .filter(|s| s.contract_type != "PERPETUAL" && s.status == "TRADING")
+        .filter_map(|s| mapper.normalize(&s.symbol, InstrumentType::Futures).ok())
+        .filter_map(|n| FuturesContract::parse(&n).map(|c| c.name()))
+        .collect())
+}
+
+/// Expand config entries into contract symbols. "BTC_USDT_260327" passes
+/// through; a bare pair ("BTC_USDT") becomes every listed expiry on it.
+async fn resolve_dated_futures(entries: &[&str]) -> Result<Vec<String>> {
+    let mut listed: Option<Vec<String>> = None;
+    let mut names = Vec::new();
+    for entry in entries {
+        if let Some(contract) = FuturesContract::parse(entry) {
+            names.push(contract.name());
+            continue;
+        }
+        if listed.is_none() {
+            listed = Some(fetch_dated_futures(&reqwest::Client::new()).await?);
+        }
+        let prefix = format!("{}_", entry.to_uppercase());
+        let before = names.len();
+        names.extend(listed.iter().flatten().filter(|n| n.starts_with(&prefix)).cloned());
+        if names.len() == before {
+            warn!("Binance: no listed dated futures match '{}'", entry);
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+struct BinanceFuturesFeed {
+    itype: InstrumentType,
+    mapper: BinanceMapper,
+    /// Native ("BTCUSDT_260327") -> config symbol ("BTC_USDT_260327").
+    native_to_sym: HashMap<String, String>,
+    futures: Arc<FuturesDataCollection>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BinanceFuturesFeed {
+    type Item = FuturesData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        let streams = symbols
+            .iter()
+            .map(|s| Ok(format!("{}@bookTicker", self.mapper.denormalize(s, self.itype)?.to_lowercase())))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(format!("wss://fstream.binance.com/public/stream?streams={}", streams.join("/")))
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.futures.get_or_insert(symbol)
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, FuturesData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let d = serde_json::from_str::<BinanceBookTicker>(text)?.data;
+        let Some(sym) = self.native_to_sym.get(&d.symbol) else {
+            return Ok(vec![]);
+        };
+        let data = FuturesData {
+            bid: parse_px(&d.bid_price),
+            ask: parse_px(&d.ask_price),
+            bid_qty: parse_opt(&d.bid_quantity),
+            ask_qty: parse_opt(&d.ask_quantity),
+            exchange_ts_raw: d.event_time.and_then(|ms| DateTime::from_timestamp_millis(ms as i64)),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(sym.clone(), data)])
+    }
+}
+
+/// USDM dated futures BBO from `@bookTicker`. `entries` are contract
+/// symbols ("BTC_USDT_260327") or pairs ("BTC_USDT") for every listed
+/// expiry, expanded against `exchangeInfo` once at startup. Binance
+/// bookTicker carries no index; pass a spot or perp reference to
+/// `FuturesDataCollection::term_structure`.
+pub async fn listen_dated_futures_bbo(
+    data: Arc<FuturesDataCollection>,
+    entries: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let names = resolve_dated_futures(entries).await?;
+    if names.is_empty() {
+        bail!("no Binance dated futures resolved from {:?}", entries);
+    }
+    let itype = InstrumentType::Futures;
+    let mapper = BinanceMapper;
+    let mut native_to_sym = HashMap::new();
+    for name in &names {
+        native_to_sym.insert(mapper.denormalize(name, itype)?, name.clone());
+    }
+    let refs: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    let feed = Arc::new(BinanceFuturesFeed {
+        itype,
+        mapper,
+        native_to_sym,
+        futures: Arc::clone(&data),
+    });
+    listen_with_reconnect(
+        data,
+        &refs,
+        feed,
+        "binance_futures",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Options Ticker Feed (EAPI) ---
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct BinanceOptionStream {
+    stream: String,
+    data: OneOrMany<BinanceOptionTicker>,
+}
+
+/// Per-contract streams push one object; `UNDERLYING@ticker@EXPIRY` pushes an array.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceOptionTicker {
+    #[serde(rename = "E")]
+    event_time: u64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "bo", default)]
+    best_bid: String,
+    #[serde(rename = "ao", default)]
+    best_ask: String,
+    #[serde(rename = "bq", default)]
+    bid_qty: String,
+    #[serde(rename = "aq", default)]
+    ask_qty: String,
+    #[serde(rename = "b", default)]
+    bid_iv: String,
+    #[serde(rename = "a", default)]
+    ask_iv: String,
+    #[serde(rename = "vo", default)]
+    mark_iv: String,
+    #[serde(rename = "mp", default)]
+    mark_price: String,
+    #[serde(rename = "d", default)]
+    delta: String,
+    #[serde(rename = "g", default)]
+    gamma: String,
+    #[serde(rename = "t", default)]
+    theta: String,
+    #[serde(rename = "v", default)]
+    vega: String,
+}
+
+/// Empty or non-numeric → None. Binance sends "0" for an empty book side.
+fn parse_opt(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok()
+}
+
+fn parse_px(s: &str) -> Option<f64> {
+    parse_opt(s).filter(|v| *v > 0.0)
+}
+
+struct BinanceOptionsFeed {
+    itype: InstrumentType,
+    contracts: Arc<OptionContracts>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BinanceOptionsFeed {
+    type Item = OptionData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    /// Accepts contract names ("BTC-250628-60000-C") or whole expiries
+    /// ("BTC@250628").
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        let streams: Vec<String> = symbols
+            .iter()
+            .map(|s| match s.split_once('@') {
+                Some((underlying, expiry)) => {
+                    format!("{}@ticker@{}", underlying.to_uppercase(), expiry)
+                }
+                None => format!("{}@ticker", s.to_uppercase()),
+            })
+            .collect();
+        Ok(format!(
+            "wss://nbstream.binance.com/eoptions/stream?streams={}",
+            streams.join("/")
+        ))
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.contracts.get_or_insert(symbol)
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, OptionData)>> {
+        match msg {
+            WireMessage::Text(text) => {
+                let msg = serde_json::from_str::<BinanceOptionStream>(text)?;
+                let tickers = match msg.data {
+                    OneOrMany::One(t) => vec![t],
+                    OneOrMany::Many(v) => v,
+                };
+                Ok(tickers
+                    .into_iter()
+                    .map(|t| {
+                        let exchange_ts = DateTime::from_timestamp_millis(t.event_time as i64);
+                        let data = OptionData {
+                            bid: parse_px(&t.best_bid),
+                            ask: parse_px(&t.best_ask),
+                            bid_qty: parse_opt(&t.bid_qty),
+                            ask_qty: parse_opt(&t.ask_qty),
+                            mark_price: parse_opt(&t.mark_price),
+                            mark_iv: parse_opt(&t.mark_iv),
+                            bid_iv: parse_opt(&t.bid_iv),
+                            ask_iv: parse_opt(&t.ask_iv),
+                            delta: parse_opt(&t.delta),
+                            gamma: parse_opt(&t.gamma),
+                            theta: parse_opt(&t.theta),
+                            vega: parse_opt(&t.vega),
+                            exchange_ts_raw: exchange_ts,
+                            received_ts: Some(received_ts),
+                            received_instant: Some(received_instant),
+                            ..Default::default()
+                        };
+                        (t.symbol, data)
+                    })
+                    .collect())
+            }
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+pub async fn listen_options_ticker(
+    data: Arc<OptionDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BinanceOptionsFeed {
+        itype: InstrumentType::Option,
+        contracts: Arc::clone(data.contracts()),
+    });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "binance_options",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -1,6 +1,6 @@
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, Decompression, ExchangeFeed, WireMessage, listen_with_reconnect,
 };
 use crate::mappers::{BingxMapper, SymbolMapper};
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
@@ -11,7 +11,6 @@ use futures_util::stream::SplitSink;
 use log::{debug, error};
 use serde::Deserialize;
 use serde_json::json;
-use std::io::Read as IoRead;
 use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
@@ -69,11 +68,12 @@ struct BingxMessage {
     data: Option<serde_json::Value>,
 }
 
-fn decompress_gzip(data: &[u8]) -> Result<String> {
-    let mut decoder = flate2::read::GzDecoder::new(data);
-    let mut text = String::new();
-    decoder.read_to_string(&mut text)?;
-    Ok(text)
+/// Pushes, server pings included, arrive as gzip-compressed binary frames.
+fn connection_config() -> ConnectionConfig {
+    ConnectionConfig {
+        decompression: Decompression::Gzip,
+        ..ConnectionConfig::default()
+    }
 }
 
 fn parse_str_or_num(v: &Option<serde_json::Value>) -> Option<f64> {
@@ -198,22 +198,10 @@ impl ExchangeFeed for BingxFeed {
         received_instant: std::time::Instant,
     ) -> Result<Vec<(String, MarketData)>> {
         match msg {
+            // Inflated pings parse to nothing and are answered in `process_other`.
             WireMessage::Text(text) => parse_bingx_text(text, received_ts, received_instant),
-            WireMessage::Binary(data) => {
-                match decompress_gzip(data) {
-                    Ok(text) => {
-                        if text == "Ping" || text == "ping" {
-                            self.got_ping.store(true, std::sync::atomic::Ordering::Relaxed);
-                            return Ok(vec![]);
-                        }
-                        parse_bingx_text(&text, received_ts, received_instant)
-                    }
-                    Err(e) => {
-                        error!("BingX gzip decompress error: {}", e);
-                        Ok(vec![])
-                    }
-                }
-            }
+            // Only frames that failed to inflate get here.
+            _ => Ok(vec![]),
         }
     }
 
@@ -236,7 +224,7 @@ pub async fn listen_spot_bbo(
         symbols,
         feed,
         "bingx_spot",
-        ConnectionConfig::default(),
+        connection_config(),
         shutdown,
     )
     .await
@@ -253,7 +241,7 @@ pub async fn listen_perp_bbo(
         symbols,
         feed,
         "bingx_perp",
-        ConnectionConfig::default(),
+        connection_config(),
         shutdown,
     )
     .await
@@ -0,0 +1,226 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::mappers::{BitfinexMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const WS_URL: &str = "wss://api-pub.bitfinex.com/ws/2";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(20.0, 10.0), FeeSchedule::new(6.5, 2.0))
+}
+
+struct BitfinexFeed {
+    itype: InstrumentType,
+    mapper: BitfinexMapper,
+    /// Native symbol ("tBTCUSD") → config symbol.
+    native_to_sym: HashMap<String, String>,
+    /// chanId → config symbol, learned from `subscribed` events. Data
+    /// frames carry only the chanId.
+    channels: Mutex<HashMap<u64, String>>,
+}
+
+impl BitfinexFeed {
+    fn new(symbols: &[&str], itype: InstrumentType) -> Result<Self> {
+        let mapper = BitfinexMapper;
+        let mut native_to_sym = HashMap::new();
+        for sym in symbols {
+            native_to_sym.insert(mapper.denormalize(sym, itype)?, sym.to_string());
+        }
+        Ok(Self {
+            itype,
+            mapper,
+            native_to_sym,
+            channels: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn channel_symbol(&self, chan_id: u64) -> Option<String> {
+        self.channels.lock().unwrap().get(&chan_id).cloned()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitfinexEvent {
+    event: String,
+    #[serde(default)]
+    chan_id: Option<u64>,
+    #[serde(default)]
+    symbol: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BitfinexFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for symbol in symbols {
+            let native = self.mapper.denormalize(symbol, self.itype)?;
+            let msg = json!({ "event": "subscribe", "channel": "ticker", "symbol": native });
+            write
+                .send(Message::Text(msg.to_string().into()))
+                .await
+                .with_context(|| format!("Failed to subscribe Bitfinex ticker {native}"))?;
+        }
+        Ok(())
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    /// Unsubscribe goes by chanId; the mapping is dropped on the ack.
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let chan_ids: Vec<u64> = {
+            let channels = self.channels.lock().unwrap();
+            channels
+                .iter()
+                .filter(|(_, sym)| symbols.contains(&sym.as_str()))
+                .map(|(&id, _)| id)
+                .collect()
+        };
+        for chan_id in chan_ids {
+            let msg = json!({ "event": "unsubscribe", "chanId": chan_id });
+            write
+                .send(Message::Text(msg.to_string().into()))
+                .await
+                .with_context(|| format!("Failed to unsubscribe Bitfinex channel {chan_id}"))?;
+        }
+        Ok(())
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"event":"ping","cid":1}"#.into()))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+
+        if text.starts_with('{') {
+            let event: BitfinexEvent = serde_json::from_str(text)?;
+            match event.event.as_str() {
+                "subscribed" => {
+                    let (Some(chan_id), Some(native)) = (event.chan_id, event.symbol) else {
+                        return Ok(vec![]);
+                    };
+                    match self.native_to_sym.get(&native) {
+                        Some(sym) => {
+                            self.channels.lock().unwrap().insert(chan_id, sym.clone());
+                        }
+                        None => warn!("Bitfinex subscribed to unexpected symbol {}", native),
+                    }
+                }
+                "unsubscribed" => {
+                    if let Some(chan_id) = event.chan_id {
+                        self.channels.lock().unwrap().remove(&chan_id);
+                    }
+                }
+                // The greeting on a fresh connection: earlier chanIds are gone.
+                "info" if text.contains("\"version\"") => self.channels.lock().unwrap().clear(),
+                "error" => bail!("Bitfinex error: {}", text),
+                // other info (e.g. maintenance codes), pong
+                _ => debug!("Bitfinex event: {}", text),
+            }
+            return Ok(vec![]);
+        }
+
+        // [chanId, [BID, BID_SIZE, ASK, ASK_SIZE, ...]] or [chanId, "hb"]
+        let (chan_id, payload): (u64, serde_json::Value) = serde_json::from_str(text)?;
+        let Some(fields) = payload.as_array() else {
+            return Ok(vec![]);
+        };
+        let Some(symbol) = self.channel_symbol(chan_id) else {
+            debug!("Bitfinex data for unknown channel {}", chan_id);
+            return Ok(vec![]);
+        };
+        let field = |i: usize| fields.get(i).and_then(|v| v.as_f64());
+        let (Some(bid), Some(ask)) = (field(0), field(2)) else {
+            return Ok(vec![]);
+        };
+
+        // Ticker frames carry no exchange timestamp.
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: field(1),
+            ask_qty: field(3),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(symbol, md)])
+    }
+}
+
+/// Spot BBO from the v2 `ticker` channel. `symbols` are config symbols
+/// like "BTC_USD" (tBTCUSD) or "BTC_USDT" (tBTCUST).
+pub async fn listen_spot_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BitfinexFeed::new(symbols, InstrumentType::Spot)?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "bitfinex_spot",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+/// Perpetual BBO; "BTC_USDT" subscribes to tBTCF0:USTF0.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BitfinexFeed::new(symbols, InstrumentType::Perp)?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "bitfinex_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -1,11 +1,14 @@
+use crate::candle_data::{Candle, CandleInterval, CandleStore, Kline, KlineSink};
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
 use crate::mappers::{BybitMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::market_data::{Exchange, InstrumentType, MarketData, MarketDataCollection};
+use crate::options_data::{OptionContracts, OptionData, OptionDataCollection};
+use crate::symbol_registry::SymbolId;
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
-use futures_util::SinkExt;
 use futures_util::stream::SplitSink;
-use log::{debug, error};
+use log::{debug, error, warn};
 use serde::Deserialize;
 use serde_json::json;
 use std::sync::Arc;
@@ -14,18 +17,24 @@ use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, ResyncRequests, SequenceStatus, SequenceTracker, WireMessage, listen_with_reconnect,
+    send_subscribe_batches,
 };
+use crate::exchanges::rest_poll::poll_deriv_stats;
+use crate::liquidation_data::{LiquidationCollection, LiquidationEvent};
 
 pub fn get_fees() -> ExchangeFees {
     ExchangeFees::new(FeeSchedule::new(10.0, 10.0), FeeSchedule::new(5.5, 2.0))
 }
 
-#[derive(Clone)]
 struct BybitFeed {
     url: &'static str,
     itype: InstrumentType,
     mapper: BybitMapper,
+    /// Order book `u` per symbol: deltas step it by one, snapshots start
+    /// it over.
+    sequences: SequenceTracker,
+    resync: ResyncRequests,
 }
 
 impl BybitFeed {
@@ -34,6 +43,8 @@ impl BybitFeed {
             url: "wss://stream.bybit.com/v5/public/spot",
             itype: InstrumentType::Spot,
             mapper: BybitMapper,
+            sequences: SequenceTracker::contiguous(),
+            resync: ResyncRequests::new(),
         }
     }
     fn new_perp() -> Self {
@@ -41,6 +52,30 @@ impl BybitFeed {
             url: "wss://stream.bybit.com/v5/public/linear",
             itype: InstrumentType::Perp,
             mapper: BybitMapper,
+            sequences: SequenceTracker::contiguous(),
+            resync: ResyncRequests::new(),
+        }
+    }
+
+    /// Whether an order book message may be applied: snapshots always,
+    /// deltas only in `u` order.
+    fn in_sequence(&self, response: &BybitResponse) -> bool {
+        let symbol = &response.data.symbol;
+        let Some(u) = response.data.update_id else {
+            return true;
+        };
+        if response.msg_type == "snapshot" {
+            self.sequences.restart(symbol, u);
+            return true;
+        }
+        match self.sequences.check(symbol, u) {
+            SequenceStatus::InOrder => true,
+            SequenceStatus::Stale => false,
+            SequenceStatus::Gap { expected, got } => {
+                warn!("Bybit {} orderbook gap: expected u={}, got {}", symbol, expected, got);
+                self.resync.request(symbol);
+                false
+            }
         }
     }
 }
@@ -58,6 +93,14 @@ impl ExchangeFeed for BybitFeed {
         Some(Message::Text(r#"{"op":"ping"}"#.into()))
     }
 
+    fn on_connected(&self) {
+        self.sequences.reset();
+    }
+
+    fn take_resync(&self) -> Vec<String> {
+        self.resync.take()
+    }
+
     fn build_url(&self, _symbols: &[&str]) -> Result<String> {
         Ok(self.url.to_string())
     }
@@ -76,16 +119,24 @@ impl ExchangeFeed for BybitFeed {
             })
             .collect();
 
-        // Subscribe to spot tickers
-        let subscribe_msg = json!({
-            "op": "subscribe",
-            "args": args
-        });
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": "subscribe", "args": batch }).to_string().into())).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
 
-        write
-            .send(Message::Text(subscribe_msg.to_string().into()))
-            .await?;
-        Ok(())
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let args: Vec<String> = symbols
+            .iter()
+            .filter_map(|symbol| self.mapper.denormalize(symbol, self.itype).ok())
+            .map(|s| format!("orderbook.1.{}", s))
+            .collect();
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": "unsubscribe", "args": batch }).to_string().into())).await
     }
 
     fn parse_message(
@@ -106,6 +157,9 @@ impl ExchangeFeed for BybitFeed {
                 match serde_json::from_str::<BybitResponse>(&text) {
                     Ok(response) => {
                         if response.topic.starts_with("orderbook.") {
+                            if !self.in_sequence(&response) {
+                                return Ok(vec![]);
+                            }
                             let bid = response
                                 .data
                                 .bids
@@ -139,6 +193,7 @@ impl ExchangeFeed for BybitFeed {
                                 exchange_ts_raw: exchange_ts,
                                 received_ts: Some(received_ts),
                                 received_instant: Some(received_instant),
+                                update_id: response.data.update_id,
                                 ..Default::default()
                             };
                             return Ok(vec![(response.data.symbol, market_data)]);
@@ -154,7 +209,7 @@ impl ExchangeFeed for BybitFeed {
                     }
                 }
             }
-            WireMessage::Binary(_) => {
+            _ => {
                 return Ok(vec![]);
             }
         }
@@ -180,6 +235,8 @@ struct BybitOrderbookData {
     bids: Vec<(String, String)>,
     #[serde(rename = "a")]
     asks: Vec<(String, String)>,
+    #[serde(rename = "u", default)]
+    update_id: Option<u64>,
 }
 
 pub async fn listen_spot_bbo(
@@ -290,15 +347,7 @@ impl ExchangeFeed for BybitTradeFeed {
             })
             .collect();
 
-        let subscribe_msg = json!({
-            "op": "subscribe",
-            "args": args
-        });
-
-        write
-            .send(Message::Text(subscribe_msg.to_string().into()))
-            .await?;
-        Ok(())
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": "subscribe", "args": batch }).to_string().into())).await
     }
 
     fn timestamp_dedup(&self) -> bool {
@@ -364,7 +413,7 @@ impl ExchangeFeed for BybitTradeFeed {
                 }
                 Ok(trades)
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -385,3 +434,747 @@ pub async fn listen_perp_trades(
     )
     .await
 }
+
+// --- Liquidation Feed ---
+
+#[derive(Debug, Deserialize)]
+struct BybitLiquidationResponse {
+    topic: String,
+    data: Vec<BybitLiquidationEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitLiquidationEntry {
+    #[serde(rename = "T")]
+    updated_time: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Side of the liquidated position: "Buy" is a long.
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "v")]
+    size: String,
+    #[serde(rename = "p")]
+    price: String,
+}
+
+struct BybitLiquidationFeed {
+    itype: InstrumentType,
+    mapper: BybitMapper,
+}
+
+impl BybitLiquidationFeed {
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        op: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let args = symbols
+            .iter()
+            .map(|s| Ok(format!("allLiquidation.{}", self.mapper.denormalize(s, self.itype)?)))
+            .collect::<Result<Vec<String>>>()?;
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": op, "args": batch }).to_string().into())).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BybitLiquidationFeed {
+    type Item = LiquidationEvent;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"op":"ping"}"#.into()))
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://stream.bybit.com/v5/public/linear".to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn timestamp_dedup(&self) -> bool {
+        false
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, LiquidationEvent)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"topic\":\"allLiquidation.") {
+            // subscribe acks, pongs
+            return Ok(vec![]);
+        }
+        let response = serde_json::from_str::<BybitLiquidationResponse>(text)?;
+        debug_assert!(response.topic.starts_with("allLiquidation."));
+        let mut events = Vec::with_capacity(response.data.len());
+        for entry in response.data {
+            let (Ok(price), Ok(qty)) = (entry.price.parse::<f64>(), entry.size.parse::<f64>()) else {
+                continue;
+            };
+            // The liquidation order trades against the position.
+            let side = match entry.side.as_str() {
+                "Buy" => TradeSide::Sell,
+                "Sell" => TradeSide::Buy,
+                _ => TradeSide::Unknown,
+            };
+            events.push((
+                entry.symbol,
+                LiquidationEvent {
+                    price,
+                    qty,
+                    side,
+                    exchange_ts_raw: DateTime::from_timestamp_millis(entry.updated_time),
+                    received_ts: Some(received_ts),
+                    received_instant: Some(received_instant),
+                    ..Default::default()
+                },
+            ));
+        }
+        Ok(events)
+    }
+}
+
+/// Linear perp liquidations from the `allLiquidation.{symbol}` topic (the
+/// successor of the throttled `liquidation` topic).
+pub async fn listen_perp_liquidations(
+    data: Arc<LiquidationCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BybitLiquidationFeed { itype: InstrumentType::Perp, mapper: BybitMapper });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "bybit_perp_liquidations",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Kline Feed ---
+
+/// Bybit's interval token in `kline.{interval}.{symbol}`.
+fn kline_interval(interval: CandleInterval) -> Option<&'static str> {
+    match interval {
+        CandleInterval::M1 => Some("1"),
+        CandleInterval::M5 => Some("5"),
+        CandleInterval::M15 => Some("15"),
+        CandleInterval::H1 => Some("60"),
+        CandleInterval::S1 => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitKlineResponse {
+    topic: String,
+    data: Vec<BybitKlineEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitKlineEntry {
+    /// Open time (ms)
+    start: i64,
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+    confirm: bool,
+}
+
+struct BybitKlineFeed {
+    itype: InstrumentType,
+    mapper: BybitMapper,
+    intervals: Vec<CandleInterval>,
+}
+
+impl BybitKlineFeed {
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        op: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let mut args = Vec::with_capacity(symbols.len() * self.intervals.len());
+        for s in symbols {
+            let native = self.mapper.denormalize(s, self.itype)?;
+            for iv in self.intervals.iter().filter_map(|iv| kline_interval(*iv)) {
+                args.push(format!("kline.{}.{}", iv, native));
+            }
+        }
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": op, "args": batch }).to_string().into())).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BybitKlineFeed {
+    type Item = Kline;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"op":"ping"}"#.into()))
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://stream.bybit.com/v5/public/linear".to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn timestamp_dedup(&self) -> bool {
+        false
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        _received_ts: DateTime<Utc>,
+        _received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, Kline)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"topic\":\"kline.") {
+            // subscribe acks, pongs
+            return Ok(vec![]);
+        }
+        let response = serde_json::from_str::<BybitKlineResponse>(text)?;
+        // kline.{interval}.{symbol}
+        let mut parts = response.topic.splitn(3, '.').skip(1);
+        let (Some(iv), Some(symbol)) = (parts.next(), parts.next()) else {
+            return Ok(vec![]);
+        };
+        let Some(interval) = CandleInterval::ALL.into_iter().find(|c| kline_interval(*c) == Some(iv)) else {
+            return Ok(vec![]);
+        };
+        let mut out = Vec::new();
+        for entry in response.data.into_iter().filter(|e| e.confirm) {
+            let volume = entry.volume.parse::<f64>()?;
+            let candle = Candle {
+                open_time_ms: entry.start,
+                open: entry.open.parse::<f64>()?,
+                high: entry.high.parse::<f64>()?,
+                low: entry.low.parse::<f64>()?,
+                close: entry.close.parse::<f64>()?,
+                volume,
+                // No trade count on the wire; a bar without volume is flat.
+                ticks: u32::from(volume > 0.0),
+            };
+            out.push((symbol.to_string(), Kline { interval, candle }));
+        }
+        Ok(out)
+    }
+}
+
+/// Closed linear perp klines from `kline.{interval}.{symbol}` for each of
+/// `intervals`, written into `store` under Bybit. 1s is skipped.
+pub async fn listen_perp_klines(
+    store: Arc<CandleStore>,
+    symbols: &[&str],
+    intervals: &[CandleInterval],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let (supported, skipped): (Vec<_>, Vec<_>) = intervals.iter().copied().partition(|iv| kline_interval(*iv).is_some());
+    for iv in skipped {
+        warn!("Bybit perp klines do not stream {}, skipping", iv.as_str());
+    }
+    if supported.is_empty() {
+        bail!("Bybit perp klines: no supported interval among {:?}", intervals);
+    }
+    let feed = Arc::new(BybitKlineFeed {
+        itype: InstrumentType::Perp,
+        mapper: BybitMapper,
+        intervals: supported,
+    });
+    let sink = Arc::new(KlineSink { store, exchange: Exchange::Bybit });
+    listen_with_reconnect(
+        sink,
+        symbols,
+        feed,
+        "bybit_perp_klines",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Mark Price Feed ---
+
+#[derive(Debug, Deserialize)]
+struct BybitLinearTickerResponse {
+    topic: String,
+    ts: i64,
+    data: BybitLinearTickerData,
+}
+
+/// Snapshot first, then deltas carrying only the fields that changed.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitLinearTickerData {
+    symbol: String,
+    #[serde(default)]
+    mark_price: Option<String>,
+    #[serde(default)]
+    index_price: Option<String>,
+    #[serde(default)]
+    funding_rate: Option<String>,
+    #[serde(default)]
+    next_funding_time: Option<String>,
+    #[serde(default)]
+    open_interest: Option<String>,
+}
+
+fn parse_field(s: &Option<String>) -> Option<f64> {
+    s.as_deref().and_then(|v| v.parse::<f64>().ok())
+}
+
+struct BybitMarkPriceFeed {
+    itype: InstrumentType,
+    mapper: BybitMapper,
+}
+
+impl BybitMarkPriceFeed {
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        op: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let args = symbols
+            .iter()
+            .map(|s| Ok(format!("tickers.{}", self.mapper.denormalize(s, self.itype)?)))
+            .collect::<Result<Vec<String>>>()?;
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": op, "args": batch }).to_string().into())).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BybitMarkPriceFeed {
+    type Item = DerivStats;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"op":"ping"}"#.into()))
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://stream.bybit.com/v5/public/linear".to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, DerivStats)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"topic\":\"tickers.") {
+            // subscribe acks, pongs
+            return Ok(vec![]);
+        }
+        let response = serde_json::from_str::<BybitLinearTickerResponse>(text)?;
+        debug_assert!(response.topic.starts_with("tickers."));
+        let t = response.data;
+        let stats = DerivStats {
+            mark_price: parse_field(&t.mark_price),
+            index_price: parse_field(&t.index_price),
+            funding_rate: parse_field(&t.funding_rate),
+            next_funding_ts: t
+                .next_funding_time
+                .as_deref()
+                .and_then(|v| v.parse::<i64>().ok())
+                .and_then(DateTime::from_timestamp_millis),
+            open_interest: parse_field(&t.open_interest),
+            exchange_ts_raw: DateTime::from_timestamp_millis(response.ts),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        if stats.mark_price.is_none() && stats.funding_rate.is_none() && stats.open_interest.is_none() {
+            // Delta touching only last price / volume.
+            return Ok(vec![]);
+        }
+        Ok(vec![(t.symbol, stats)])
+    }
+}
+
+/// Linear perp mark and index price, funding and open interest from
+/// `tickers.{symbol}`. Deltas only carry changed fields.
+pub async fn listen_perp_mark_price(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BybitMarkPriceFeed { itype: InstrumentType::Perp, mapper: BybitMapper });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "bybit_perp_mark",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Open Interest (REST) ---
+
+const TICKERS_URL: &str = "https://api.bybit.com/v5/market/tickers";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitRestResponse<T> {
+    ret_code: i64,
+    ret_msg: String,
+    #[serde(default)]
+    result: Option<T>,
+    #[serde(default)]
+    time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitTickerList {
+    list: Vec<BybitLinearTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitLinearTicker {
+    open_interest: String,
+}
+
+async fn fetch_open_interest(client: reqwest::Client, native: String) -> Result<DerivStats> {
+    let resp = client
+        .get(TICKERS_URL)
+        .query(&[("category", "linear"), ("symbol", native.as_str())])
+        .send()
+        .await
+        .with_context(|| format!("GET {TICKERS_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {TICKERS_URL} -> {status}; body: {body}");
+    }
+    let parsed: BybitRestResponse<BybitTickerList> =
+        serde_json::from_str(&body).context("decode tickers JSON")?;
+    if parsed.ret_code != 0 {
+        bail!("Bybit tickers {} -> {}: {}", native, parsed.ret_code, parsed.ret_msg);
+    }
+    let Some(ticker) = parsed.result.and_then(|r| r.list.into_iter().next()) else {
+        bail!("Bybit tickers: no entry for {}", native);
+    };
+    Ok(DerivStats {
+        open_interest: ticker.open_interest.parse::<f64>().ok(),
+        exchange_ts_raw: parsed.time.and_then(DateTime::from_timestamp_millis),
+        ..Default::default()
+    })
+}
+
+/// Linear perp open interest (base units) from `/v5/market/tickers`,
+/// polled every `interval`.
+pub async fn poll_open_interest(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    interval: std::time::Duration,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    poll_deriv_stats(data, symbols, BybitMapper, interval, "bybit_oi", shutdown, fetch_open_interest).await
+}
+
+// --- Premium Index (REST) ---
+
+const PREMIUM_INDEX_URL: &str = "https://api.bybit.com/v5/market/premium-index-price-kline";
+
+#[derive(Debug, Deserialize)]
+struct BybitKlineList {
+    /// `[startTime, open, high, low, close]` as strings, newest first.
+    list: Vec<Vec<String>>,
+}
+
+async fn fetch_premium_index(client: reqwest::Client, native: String) -> Result<DerivStats> {
+    let resp = client
+        .get(PREMIUM_INDEX_URL)
+        .query(&[("category", "linear"), ("symbol", native.as_str()), ("interval", "1"), ("limit", "1")])
+        .send()
+        .await
+        .with_context(|| format!("GET {PREMIUM_INDEX_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {PREMIUM_INDEX_URL} -> {status}; body: {body}");
+    }
+    let parsed: BybitRestResponse<BybitKlineList> =
+        serde_json::from_str(&body).context("decode premium-index-price-kline JSON")?;
+    if parsed.ret_code != 0 {
+        bail!("Bybit premium index {} -> {}: {}", native, parsed.ret_code, parsed.ret_msg);
+    }
+    let Some(close) = parsed.result.and_then(|r| r.list.into_iter().next()).and_then(|row| row.get(4).cloned()) else {
+        bail!("Bybit premium index: no bar for {}", native);
+    };
+    Ok(DerivStats {
+        premium_index: close.parse::<f64>().ok(),
+        exchange_ts_raw: parsed.time.and_then(DateTime::from_timestamp_millis),
+        ..Default::default()
+    })
+}
+
+/// Linear perp premium index from the close of the current 1m
+/// `/v5/market/premium-index-price-kline` bar, polled every `interval`.
+pub async fn poll_premium_index(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    interval: std::time::Duration,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    poll_deriv_stats(data, symbols, BybitMapper, interval, "bybit_premium", shutdown, fetch_premium_index).await
+}
+
+// --- Options Ticker Feed ---
+
+
+#[derive(Debug, Deserialize)]
+struct BybitOptionResponse {
+    topic: String,
+    ts: i64,
+    data: BybitOptionTicker,
+}
+
+/// Prices, sizes and IVs (decimals) as strings; empty sides are "0".
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitOptionTicker {
+    symbol: String,
+    #[serde(default)]
+    bid_price: String,
+    #[serde(default)]
+    bid_size: String,
+    #[serde(default)]
+    bid_iv: String,
+    #[serde(default)]
+    ask_price: String,
+    #[serde(default)]
+    ask_size: String,
+    #[serde(default)]
+    ask_iv: String,
+    #[serde(default)]
+    mark_price: String,
+    #[serde(default)]
+    mark_price_iv: String,
+    #[serde(default)]
+    underlying_price: String,
+    #[serde(default)]
+    delta: String,
+    #[serde(default)]
+    gamma: String,
+    #[serde(default)]
+    theta: String,
+    #[serde(default)]
+    vega: String,
+}
+
+fn parse_opt(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok()
+}
+
+fn parse_positive(s: &str) -> Option<f64> {
+    parse_opt(s).filter(|v| *v > 0.0)
+}
+
+struct BybitOptionsFeed {
+    itype: InstrumentType,
+    mapper: BybitMapper,
+    contracts: Arc<OptionContracts>,
+}
+
+impl BybitOptionsFeed {
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        op: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let args = symbols
+            .iter()
+            .map(|s| Ok(format!("tickers.{}", self.mapper.denormalize(s, self.itype)?)))
+            .collect::<Result<Vec<String>>>()?;
+        send_subscribe_batches(Exchange::Bybit, write, &args, |batch| Message::Text(json!({ "op": op, "args": batch }).to_string().into())).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for BybitOptionsFeed {
+    type Item = OptionData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"op":"ping"}"#.into()))
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://stream.bybit.com/v5/public/option".to_string())
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.contracts.get_or_insert(symbol)
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, OptionData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"topic\":\"tickers.") {
+            // subscribe acks, pongs
+            return Ok(vec![]);
+        }
+        let msg = serde_json::from_str::<BybitOptionResponse>(text)?;
+        debug_assert!(msg.topic.starts_with("tickers."));
+        let t = msg.data;
+        let data = OptionData {
+            bid: parse_positive(&t.bid_price),
+            ask: parse_positive(&t.ask_price),
+            bid_qty: parse_opt(&t.bid_size),
+            ask_qty: parse_opt(&t.ask_size),
+            mark_price: parse_opt(&t.mark_price),
+            mark_iv: parse_opt(&t.mark_price_iv),
+            bid_iv: parse_positive(&t.bid_iv),
+            ask_iv: parse_positive(&t.ask_iv),
+            delta: parse_opt(&t.delta),
+            gamma: parse_opt(&t.gamma),
+            theta: parse_opt(&t.theta),
+            vega: parse_opt(&t.vega),
+            underlying_price: parse_opt(&t.underlying_price),
+            exchange_ts_raw: DateTime::from_timestamp_millis(msg.ts),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(t.symbol, data)])
+    }
+}
+
+/// Option quotes, IVs and greeks from `tickers.{symbol}`. `symbols` are
+/// contract names like "BTC-27JUN25-70000-C"; prices are in USDC (or the
+/// suffixed settle currency).
+pub async fn listen_options_ticker(
+    data: Arc<OptionDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(BybitOptionsFeed {
+        itype: InstrumentType::Option,
+        mapper: BybitMapper,
+        contracts: Arc::clone(data.contracts()),
+    });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "bybit_options",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -5,16 +5,19 @@ use futures_util::stream::SplitSink;
 use log::{debug, warn};
 use serde::Deserialize;
 use serde_json::json;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
+use crate::app_config::CoinbaseSpotMode;
+use crate::candle_data::{Candle, CandleInterval, CandleStore, Kline, KlineSink};
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
     ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
 };
 use crate::mappers::{CoinbaseMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::market_data::{Exchange, InstrumentType, MarketData, MarketDataCollection};
 
 pub fn get_fees() -> ExchangeFees {
     ExchangeFees::new(FeeSchedule::new(60.0, 40.0), FeeSchedule::new(60.0, 40.0))
@@ -138,6 +141,7 @@ impl ExchangeFeed for CoinbaseFeed {
                             exchange_ts_raw: exchange_ts,
                             received_ts: Some(received_ts),
                             received_instant: Some(received_instant),
+                            update_id: Some(ticker.sequence),
                             ..Default::default()
                         };
 
@@ -155,29 +159,48 @@ impl ExchangeFeed for CoinbaseFeed {
                     }
                 }
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
 
+/// Spot BBO from the legacy Exchange feed or the Advanced Trade feed,
+/// per `mode`. Both store under the same "BTC-USD" product ids.
 pub async fn listen_spot_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: CoinbaseSpotMode,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(CoinbaseFeed::new_spot());
-    listen_with_reconnect(
-        data,
-        symbols,
-        feed,
-        "coinbase_spot",
-        ConnectionConfig::default(),
-        shutdown,
-    )
-    .await
+    match mode {
+        CoinbaseSpotMode::Legacy => {
+            let feed = Arc::new(CoinbaseFeed::new_spot());
+            listen_with_reconnect(
+                data,
+                symbols,
+                feed,
+                "coinbase_spot",
+                ConnectionConfig::default(),
+                shutdown,
+            )
+            .await
+        }
+        CoinbaseSpotMode::Advanced => {
+            let feed = Arc::new(CoinbaseAdvancedFeed::new_spot());
+            listen_with_reconnect(
+                data,
+                symbols,
+                feed,
+                "coinbase_advanced_spot",
+                ConnectionConfig::default(),
+                shutdown,
+            )
+            .await
+        }
+    }
 }
 
-// --- Coinbase Advanced Trade WebSocket (perps) ---
+// --- Coinbase Advanced Trade WebSocket (spot and perps) ---
 
 #[derive(Debug, Deserialize)]
 struct AdvancedTradeMessage {
@@ -209,6 +232,13 @@ struct CoinbaseAdvancedFeed {
 }
 
 impl CoinbaseAdvancedFeed {
+    fn new_spot() -> Self {
+        Self {
+            itype: InstrumentType::Spot,
+            mapper: CoinbaseMapper,
+        }
+    }
+
     fn new_perp() -> Self {
         Self {
             itype: InstrumentType::Perp,
@@ -266,49 +296,53 @@ impl ExchangeFeed for CoinbaseAdvancedFeed {
                     return Ok(vec![]);
                 }
 
-                for event in &msg.events {
-                    for ticker in &event.tickers {
-                        let bid = ticker.best_bid.parse::<f64>().ok();
-                        let ask = ticker.best_ask.parse::<f64>().ok();
-                        let bid_qty = ticker.best_bid_quantity.parse::<f64>().ok();
-                        let ask_qty = ticker.best_ask_quantity.parse::<f64>().ok();
-
-                        if let (Some(b), Some(a)) = (bid, ask) {
-                            if b >= a {
-                                warn!(
-                                    "Invalid Coinbase AT quote for {}: bid={} >= ask={}",
-                                    ticker.product_id, b, a
-                                );
-                                continue;
-                            }
+                let exchange_ts = DateTime::parse_from_rfc3339(&msg.timestamp)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc));
+
+                let mut out = Vec::new();
+                for ticker in msg.events.iter().flat_map(|e| &e.tickers) {
+                    let bid = ticker.best_bid.parse::<f64>().ok();
+                    let ask = ticker.best_ask.parse::<f64>().ok();
+                    let bid_qty = ticker.best_bid_quantity.parse::<f64>().ok();
+                    let ask_qty = ticker.best_ask_quantity.parse::<f64>().ok();
+
+                    if let (Some(b), Some(a)) = (bid, ask) {
+                        if b >= a {
+                            warn!(
+                                "Invalid Coinbase AT quote for {}: bid={} >= ask={}",
+                                ticker.product_id, b, a
+                            );
+                            continue;
                         }
+                    }
 
-                        let exchange_ts = DateTime::parse_from_rfc3339(&msg.timestamp)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc));
-
+                    let sym = match self.itype {
+                        // Spot product ids ("BTC-USD") are registry aliases.
+                        InstrumentType::Spot => ticker.product_id.clone(),
                         // Convert BTC-PERP-INTX -> BTCUSD for registry lookup
-                        let (base, quote) = self.mapper.parse(&ticker.product_id, self.itype)?;
-                        let sym = format!("{}{}", base, quote);
-
-                        let market_data = MarketData {
-                            bid,
-                            ask,
-                            bid_qty,
-                            ask_qty,
-                            exchange_ts_raw: exchange_ts,
-                            received_ts: Some(received_ts),
-                            received_instant: Some(received_instant),
-                            ..Default::default()
-                        };
-
-                        return Ok(vec![(sym, market_data)]);
-                    }
+                        _ => {
+                            let (base, quote) = self.mapper.parse(&ticker.product_id, self.itype)?;
+                            format!("{}{}", base, quote)
+                        }
+                    };
+
+                    let market_data = MarketData {
+                        bid,
+                        ask,
+                        bid_qty,
+                        ask_qty,
+                        exchange_ts_raw: exchange_ts,
+                        received_ts: Some(received_ts),
+                        received_instant: Some(received_instant),
+                        ..Default::default()
+                    };
+                    out.push((sym, market_data));
                 }
 
-                Ok(vec![])
+                Ok(out)
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -329,3 +363,154 @@ pub async fn listen_perp_bbo(
     )
     .await
 }
+
+// --- Advanced Trade candles ---
+
+#[derive(Debug, Deserialize)]
+struct AdvancedCandleMessage {
+    channel: String,
+    #[serde(default)]
+    events: Vec<AdvancedCandleEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdvancedCandleEvent {
+    #[serde(default)]
+    candles: Vec<AdvancedCandle>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdvancedCandle {
+    /// Open time (epoch seconds)
+    start: String,
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+    product_id: String,
+}
+
+/// The `candles` channel streams the open 5m bar about once a second and
+/// has no close flag: a bar is closed once an update for a later one
+/// arrives.
+struct CoinbaseCandleFeed {
+    itype: InstrumentType,
+    mapper: CoinbaseMapper,
+    /// Open bar per product id.
+    open: Mutex<HashMap<String, Candle>>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for CoinbaseCandleFeed {
+    type Item = Kline;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://advanced-trade-ws.coinbase.com".to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let product_ids: Vec<String> = symbols
+            .iter()
+            .map(|s| self.mapper.denormalize(s, self.itype))
+            .collect::<Result<Vec<_>, _>>()?;
+        // A reconnect starts from the snapshot; a bar left open across the
+        // gap is dropped rather than stored as closed.
+        self.open.lock().unwrap().clear();
+
+        let subscribe_msg = json!({
+            "type": "subscribe",
+            "product_ids": product_ids,
+            "channel": "candles"
+        });
+        write
+            .send(Message::Text(subscribe_msg.to_string().into()))
+            .await?;
+        Ok(())
+    }
+
+    fn timestamp_dedup(&self) -> bool {
+        false
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        _received_ts: DateTime<Utc>,
+        _received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, Kline)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg = serde_json::from_str::<AdvancedCandleMessage>(text)?;
+        if msg.channel != "candles" {
+            return Ok(vec![]);
+        }
+
+        let mut open = self.open.lock().unwrap();
+        let mut out = Vec::new();
+        for c in msg.events.into_iter().flat_map(|e| e.candles) {
+            let volume = c.volume.parse::<f64>()?;
+            let candle = Candle {
+                open_time_ms: c.start.parse::<i64>()? * 1000,
+                open: c.open.parse::<f64>()?,
+                high: c.high.parse::<f64>()?,
+                low: c.low.parse::<f64>()?,
+                close: c.close.parse::<f64>()?,
+                volume,
+                // No trade count on the wire; a bar without volume is flat.
+                ticks: u32::from(volume > 0.0),
+            };
+            match open.get(&c.product_id) {
+                Some(prev) if prev.open_time_ms > candle.open_time_ms => continue,
+                Some(prev) if prev.open_time_ms < candle.open_time_ms => {
+                    let kline = Kline { interval: CandleInterval::M5, candle: *prev };
+                    out.push((c.product_id.clone(), kline));
+                }
+                _ => {}
+            }
+            open.insert(c.product_id, candle);
+        }
+        Ok(out)
+    }
+}
+
+/// Closed spot 5m candles from the Advanced Trade `candles` channel,
+/// written into `store` under Coinbase. The channel streams 5m only, so
+/// other `intervals` are skipped; "5m" must be among them.
+pub async fn listen_spot_klines(
+    store: Arc<CandleStore>,
+    symbols: &[&str],
+    intervals: &[CandleInterval],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    if !intervals.contains(&CandleInterval::M5) {
+        anyhow::bail!("Coinbase candles are 5m only; none of {:?} streamed", intervals);
+    }
+    for iv in intervals.iter().filter(|iv| **iv != CandleInterval::M5) {
+        warn!("Coinbase candles are 5m only, skipping {}", iv.as_str());
+    }
+    let feed = Arc::new(CoinbaseCandleFeed {
+        itype: InstrumentType::Spot,
+        mapper: CoinbaseMapper,
+        open: Mutex::new(HashMap::new()),
+    });
+    let sink = Arc::new(KlineSink { store, exchange: Exchange::Coinbase });
+    listen_with_reconnect(
+        sink,
+        symbols,
+        feed,
+        "coinbase_spot_klines",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -3,15 +3,30 @@ use async_trait::async_trait;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt, stream::SplitSink};
 use log::{debug, error, info, warn};
-use std::sync::Arc;
-use std::time::Duration;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::time::interval;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async_with_config, tungstenite::Message, tungstenite::client::IntoClientRequest, tungstenite::http};
 
-use crate::market_data::{DataSink, FeedItem, InstrumentType};
+use crate::event_bus::{self, FeedStatus, MarketEvent};
+use crate::interest::LazySubscriptions;
+use crate::maintenance::{Downtime, feed_downtime};
+use crate::market_data::{DataSink, Exchange, FeedItem, InstrumentType};
 use crate::symbol_registry::{REGISTRY, SymbolId};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// Called with the feed name and each stored item that has an event form.
+pub type UpdateCallback = Arc<dyn Fn(&str, &MarketEvent) + Send + Sync>;
+/// Called with the feed name on every connection change.
+pub type StatusCallback = Arc<dyn Fn(&str, FeedStatus) + Send + Sync>;
+/// Called with the feed name, each inbound frame as it came off the socket
+/// (before decompression) and its receive time.
+pub type FrameTap = Arc<dyn Fn(&str, &WireMessage<'_>, chrono::DateTime<Utc>) + Send + Sync>;
 
 #[derive(Clone)]
 pub struct ConnectionConfig {
@@ -19,6 +34,28 @@ pub struct ConnectionConfig {
     pub heartbeat_interval: Duration,
     pub message_timeout: Duration,
     pub initial_backoff: Duration,
+    /// Each reconnect backoff is scaled by a random factor in
+    /// `[1 - jitter, 1 + jitter]`, so feeds that dropped together don't
+    /// reconnect in lockstep. 0 disables.
+    pub backoff_jitter: f64,
+    /// None disables the breaker.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Applied to binary frames the feed's own `inflate_binary` passes on.
+    pub decompression: Decompression,
+    /// Tasks that parse frames, so slow parsing can't stall socket reads
+    /// and heartbeats. 0 parses on the socket task. Feeds with
+    /// per-connection book state get at most one, which also stores; with
+    /// more, the socket task stores every item so each slot keeps a single
+    /// writer.
+    pub parse_workers: usize,
+    /// Runs on the feed task right after the collection write, so it must
+    /// not block. Falls back to the hook from `install_update_hook`.
+    pub on_update: Option<UpdateCallback>,
+    /// Falls back to the hook from `install_status_hook`.
+    pub on_status: Option<StatusCallback>,
+    /// Runs on the socket task for every frame, ahead of parsing; must not
+    /// block. Falls back to the tap from `install_frame_tap`.
+    pub on_frame: Option<FrameTap>,
 }
 
 impl Default for ConnectionConfig {
@@ -28,16 +65,446 @@ impl Default for ConnectionConfig {
             heartbeat_interval: Duration::from_secs(10),
             message_timeout: Duration::from_secs(90),
             initial_backoff: Duration::from_secs(1),
+            backoff_jitter: 0.2,
+            circuit_breaker: Some(CircuitBreakerConfig::default()),
+            decompression: Decompression::None,
+            parse_workers: 0,
+            on_update: None,
+            on_status: None,
+            on_frame: None,
         }
     }
 }
 
+/// Connection states by feed name, then shard index (0 when unsharded).
+static FEED_STATUS: Lazy<RwLock<HashMap<String, BTreeMap<usize, FeedStatus>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Name shard `i` of `feed_name` connects as ("binance_spot#1").
+fn shard_name(feed_name: &str, i: usize) -> String {
+    format!("{}#{}", feed_name, i)
+}
+
+/// `feed_name` as its feed and shard index; None for an unsharded name.
+fn split_shard(feed_name: &str) -> (&str, Option<usize>) {
+    match feed_name.rsplit_once('#').and_then(|(feed, i)| Some((feed, i.parse().ok()?))) {
+        Some((feed, i)) => (feed, Some(i)),
+        None => (feed_name, None),
+    }
+}
+
+/// The state of the least healthy connection: a sharded feed is only up
+/// when all its shards are.
+fn least_healthy(statuses: impl Iterator<Item = FeedStatus>) -> Option<FeedStatus> {
+    statuses.max_by_key(|s| match s {
+        FeedStatus::Connected => 0,
+        FeedStatus::Disconnected => 1,
+        FeedStatus::CircuitOpen => 2,
+        FeedStatus::Degraded => 3,
+        FeedStatus::Stopped => 4,
+    })
+}
+
+/// Last connection state `feed_name` reported; None before it first
+/// connects. For a sharded feed, that of its least healthy shard; a shard
+/// name ("binance_spot#1") gives that shard's alone.
+pub fn feed_status(feed_name: &str) -> Option<FeedStatus> {
+    let all = FEED_STATUS.read().unwrap();
+    match split_shard(feed_name) {
+        (feed, Some(i)) => all.get(feed)?.get(&i).copied(),
+        (feed, None) => least_healthy(all.get(feed)?.values().copied()),
+    }
+}
+
+/// Every feed's last connection state, by name, with shards combined as
+/// in `feed_status`.
+pub fn feed_statuses() -> Vec<(String, FeedStatus)> {
+    let all = FEED_STATUS.read().unwrap();
+    let mut out: Vec<_> =
+        all.iter().filter_map(|(k, shards)| Some((k.clone(), least_healthy(shards.values().copied())?))).collect();
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+fn report_status(config: &ConnectionConfig, feed_name: &str, status: FeedStatus) {
+    let (feed, shard) = split_shard(feed_name);
+    FEED_STATUS.write().unwrap().entry(feed.to_string()).or_default().insert(shard.unwrap_or(0), status);
+    if let Some(cb) = &config.on_status {
+        cb(feed_name, status);
+    }
+    event_bus::publish_status(feed_name, status);
+}
+
+/// Hand a stored item to `on_update` and the event bus.
+fn report_update<T: FeedItem>(config: &ConnectionConfig, feed_name: &str, venue: Option<Exchange>, id: SymbolId, item: &T) {
+    let Some(ev) = venue.and_then(|ex| item.to_event(ex, id)) else {
+        return;
+    };
+    if let Some(cb) = &config.on_update {
+        cb(feed_name, &ev);
+    }
+    event_bus::publish(ev);
+}
+
+/// Round-trip time of a feed's WebSocket pings, measured from send to the
+/// Pong echoing its payload. Feeds with their own `heartbeat_message`, and
+/// servers that never answer pings, have none.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PingRtt {
+    pub last: Duration,
+    /// Smoothed like TCP's SRTT: each sample weighs 1/8.
+    pub smoothed: Duration,
+    pub min: Duration,
+    pub samples: u64,
+}
+
+impl PingRtt {
+    fn sample(prev: Option<PingRtt>, rtt: Duration) -> PingRtt {
+        match prev {
+            None => PingRtt { last: rtt, smoothed: rtt, min: rtt, samples: 1 },
+            Some(p) => PingRtt {
+                last: rtt,
+                smoothed: (p.smoothed * 7 + rtt) / 8,
+                min: p.min.min(rtt),
+                samples: p.samples + 1,
+            },
+        }
+    }
+}
+
+static PING_RTT: Lazy<RwLock<HashMap<String, PingRtt>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Shards sample into their feed's entry.
+fn record_ping_rtt(feed_name: &str, rtt: Duration) {
+    let (feed, _) = split_shard(feed_name);
+    let mut all = PING_RTT.write().unwrap();
+    let next = PingRtt::sample(all.get(feed).copied(), rtt);
+    all.insert(feed.to_string(), next);
+}
+
+/// Ping RTT of `feed_name` ("binance_spot") across its connections and
+/// shards.
+pub fn ping_rtt(feed_name: &str) -> Option<PingRtt> {
+    PING_RTT.read().unwrap().get(split_shard(feed_name).0).copied()
+}
+
+/// Every feed with a measured ping RTT, by name.
+pub fn ping_rtts() -> Vec<(String, PingRtt)> {
+    let mut out: Vec<_> = PING_RTT.read().unwrap().iter().map(|(k, v)| (k.clone(), *v)).collect();
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+/// How binary frames are turned into text before `parse_message`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Decompression {
+    /// Binary frames reach the feed untouched.
+    #[default]
+    None,
+    Gzip,
+    /// zlib-wrapped or raw deflate.
+    Deflate,
+    /// Gzip or zlib by their header bytes; anything else stays binary, so
+    /// a venue mixing compressed JSON with protobuf still works.
+    Auto,
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn is_zlib(bytes: &[u8]) -> bool {
+    bytes.len() >= 2 && bytes[0] & 0x0f == 8 && u16::from_be_bytes([bytes[0], bytes[1]]) % 31 == 0
+}
+
+impl Decompression {
+    /// Text of a compressed frame. None when this setting leaves `bytes`
+    /// as binary.
+    pub fn inflate(self, bytes: &[u8]) -> Option<Result<String>> {
+        let codec = match self {
+            Decompression::None => return None,
+            Decompression::Auto if bytes.starts_with(&GZIP_MAGIC) => Decompression::Gzip,
+            Decompression::Auto if is_zlib(bytes) => Decompression::Deflate,
+            Decompression::Auto => return None,
+            codec => codec,
+        };
+        Some(inflate_with(codec, bytes))
+    }
+}
+
+#[cfg(feature = "compression")]
+fn inflate_with(codec: Decompression, bytes: &[u8]) -> Result<String> {
+    use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    let mut text = String::new();
+    match codec {
+        Decompression::Gzip => GzDecoder::new(bytes).read_to_string(&mut text)?,
+        _ if is_zlib(bytes) => ZlibDecoder::new(bytes).read_to_string(&mut text)?,
+        _ => DeflateDecoder::new(bytes).read_to_string(&mut text)?,
+    };
+    Ok(text)
+}
+
+#[cfg(not(feature = "compression"))]
+fn inflate_with(codec: Decompression, _bytes: &[u8]) -> Result<String> {
+    Err(anyhow::anyhow!("{:?} frames need the `compression` feature", codec))
+}
+
+/// Timing fields to override, in ms; unset ones keep the call site's value.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConnectionOverrides {
+    #[serde(default)]
+    pub heartbeat_interval_ms: Option<u64>,
+    #[serde(default)]
+    pub message_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub initial_backoff_ms: Option<u64>,
+    #[serde(default)]
+    pub max_retry_delay_ms: Option<u64>,
+    #[serde(default)]
+    pub parse_workers: Option<usize>,
+}
+
+impl ConnectionOverrides {
+    fn apply(&self, cfg: &mut ConnectionConfig) {
+        let fields = [
+            (self.heartbeat_interval_ms, &mut cfg.heartbeat_interval),
+            (self.message_timeout_ms, &mut cfg.message_timeout),
+            (self.initial_backoff_ms, &mut cfg.initial_backoff),
+            (self.max_retry_delay_ms, &mut cfg.max_retry_delay),
+        ];
+        for (ms, field) in fields {
+            if let Some(ms) = ms {
+                *field = Duration::from_millis(ms);
+            }
+        }
+        if let Some(n) = self.parse_workers {
+            cfg.parse_workers = n;
+        }
+    }
+}
+
+/// `connection` config section: overrides for every feed, then per venue.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConnectionSettings {
+    #[serde(flatten)]
+    pub all: ConnectionOverrides,
+    #[serde(default)]
+    pub exchanges: HashMap<String, ConnectionOverrides>,
+}
+
+impl ConnectionSettings {
+    /// `cfg` with the global overrides and then `feed_name`'s venue's applied.
+    pub fn resolve(&self, feed_name: &str, mut cfg: ConnectionConfig) -> ConnectionConfig {
+        self.all.apply(&mut cfg);
+        let venue = feed_name.split('_').next().unwrap_or(feed_name);
+        if let Some(o) = self.exchanges.get(venue) {
+            o.apply(&mut cfg);
+        }
+        cfg
+    }
+}
+
+static SETTINGS: OnceLock<ConnectionSettings> = OnceLock::new();
+
+/// Apply `settings` to feeds started from now on. Only the first call
+/// takes effect.
+pub fn install_settings(settings: &ConnectionSettings) {
+    let _ = SETTINGS.set(settings.clone());
+}
+
+static FRAME_TAP: OnceLock<FrameTap> = OnceLock::new();
+
+/// Tap every frame of feeds started from now on that don't set their own
+/// `on_frame`. Only the first call takes effect.
+pub fn install_frame_tap(tap: FrameTap) {
+    let _ = FRAME_TAP.set(tap);
+}
+
+static UPDATE_HOOK: OnceLock<UpdateCallback> = OnceLock::new();
+static STATUS_HOOK: OnceLock<StatusCallback> = OnceLock::new();
+
+/// `on_update` for every feed started from now on that doesn't set its
+/// own, the built-in connectors included. Only the first call takes effect.
+pub fn install_update_hook(hook: UpdateCallback) {
+    let _ = UPDATE_HOOK.set(hook);
+}
+
+/// `on_status` counterpart of `install_update_hook`.
+pub fn install_status_hook(hook: StatusCallback) {
+    let _ = STATUS_HOOK.set(hook);
+}
+
+/// Callbacks for feeds that set none of their own.
+#[derive(Default)]
+struct InstalledHooks {
+    on_frame: Option<FrameTap>,
+    on_update: Option<UpdateCallback>,
+    on_status: Option<StatusCallback>,
+}
+
+impl InstalledHooks {
+    fn current() -> Self {
+        Self { on_frame: FRAME_TAP.get().cloned(), on_update: UPDATE_HOOK.get().cloned(), on_status: STATUS_HOOK.get().cloned() }
+    }
+
+    /// `config` with its unset callbacks taken from these.
+    fn fill(self, mut config: ConnectionConfig) -> ConnectionConfig {
+        config.on_frame = config.on_frame.or(self.on_frame);
+        config.on_update = config.on_update.or(self.on_update);
+        config.on_status = config.on_status.or(self.on_status);
+        config
+    }
+}
+
+/// `config` as `listen_with_reconnect` runs it: with the installed
+/// settings applied and the installed hooks filling unset callbacks.
+fn installed_config(feed_name: &str, config: ConnectionConfig) -> ConnectionConfig {
+    let config = match SETTINGS.get() {
+        Some(settings) => settings.resolve(feed_name, config),
+        None => config,
+    };
+    InstalledHooks::current().fill(config)
+}
+
+fn tap_frame(config: &ConnectionConfig, feed_name: &str, msg: &Message, received_ts: chrono::DateTime<Utc>) {
+    let Some(tap) = &config.on_frame else { return };
+    let wire = match msg {
+        Message::Text(text) => WireMessage::Text(text.as_str()),
+        Message::Binary(bytes) => WireMessage::Binary(bytes),
+        Message::Ping(payload) | Message::Pong(payload) => WireMessage::Frame(payload),
+        Message::Frame(frame) => WireMessage::Frame(frame.payload()),
+        Message::Close(frame) => WireMessage::Close(frame.as_ref().map(|f| (u16::from(f.code), f.reason.as_str()))),
+    };
+    tap(feed_name, &wire, received_ts);
+}
+
+/// Thresholds past which a feed is treated as emitting garbage (typically a
+/// venue mid-way through an API migration). A tripped feed is disconnected,
+/// every symbol it wrote is invalidated, and it stays down for `cool_down`.
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerConfig {
+    /// Window over which parse errors and reconnects are counted.
+    pub window: Duration,
+    /// The error rate is only judged once this many messages arrived in the window.
+    pub min_messages: u32,
+    /// Fraction of messages failing to parse that trips the breaker.
+    pub max_parse_error_rate: f64,
+    /// Reconnects within the window that trip the breaker.
+    pub max_reconnects: u32,
+    /// Failed attempts in a row, however spread out, after which the feed
+    /// is marked `Degraded` and cools down. 0 disables.
+    pub max_consecutive_failures: u32,
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(300),
+            min_messages: 100,
+            max_parse_error_rate: 0.5,
+            max_reconnects: 10,
+            max_consecutive_failures: 8,
+            cool_down: Duration::from_secs(300),
+        }
+    }
+}
+
+struct CircuitBreaker {
+    cfg: CircuitBreakerConfig,
+    window_start: Instant,
+    messages: u32,
+    parse_errors: u32,
+    reconnects: VecDeque<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(cfg: CircuitBreakerConfig, now: Instant) -> Self {
+        Self { cfg, window_start: now, messages: 0, parse_errors: 0, reconnects: VecDeque::new() }
+    }
+
+    /// Count one parsed message; true when the parse error rate trips.
+    fn record_message(&mut self, ok: bool, now: Instant) -> bool {
+        if now.duration_since(self.window_start) > self.cfg.window {
+            self.window_start = now;
+            self.messages = 0;
+            self.parse_errors = 0;
+        }
+        self.messages += 1;
+        if !ok {
+            self.parse_errors += 1;
+        }
+        self.messages >= self.cfg.min_messages
+            && self.parse_errors as f64 > self.cfg.max_parse_error_rate * self.messages as f64
+    }
+
+    /// Count one reconnect; true when reconnects in the window trip.
+    fn record_reconnect(&mut self, now: Instant) -> bool {
+        while let Some(&t) = self.reconnects.front() {
+            if now.duration_since(t) <= self.cfg.window {
+                break;
+            }
+            self.reconnects.pop_front();
+        }
+        self.reconnects.push_back(now);
+        self.reconnects.len() as u32 > self.cfg.max_reconnects
+    }
+
+    fn reset(&mut self, now: Instant) {
+        self.window_start = now;
+        self.messages = 0;
+        self.parse_errors = 0;
+        self.reconnects.clear();
+    }
+}
+
+/// Invalidate everything the feed has written, report `status` and sit
+/// out the cool-down. Returns false if shutdown arrived meanwhile.
+async fn open_circuit<T, S: DataSink<T>>(
+    data: &Arc<S>,
+    touched: &FxHashSet<SymbolId>,
+    breaker: &mut CircuitBreaker,
+    feed_name: &str,
+    config: &ConnectionConfig,
+    status: FeedStatus,
+    shutdown: &tokio::sync::Notify,
+) -> bool {
+    for id in touched {
+        data.invalidate(id);
+    }
+    report_status(config, feed_name, status);
+    error!(
+        "{} circuit breaker tripped ({:?}): {} symbols invalidated, pausing for {:?}",
+        feed_name,
+        status,
+        touched.len(),
+        breaker.cfg.cool_down
+    );
+    tokio::select! {
+        _ = tokio::time::sleep(breaker.cfg.cool_down) => {}
+        _ = shutdown.notified() => {
+            info!("Shutdown during circuit cool-down for {}", feed_name);
+            return false;
+        }
+    }
+    breaker.reset(Instant::now());
+    info!("{} circuit breaker closed, resuming", feed_name);
+    true
+}
+
 pub enum ConnectionResult {
     Shutdown,
     Reconnect,
     /// Server sent Retry-After header (e.g. 429). Backoff for at least this long.
     RetryAfter(Duration),
     InvalidConfig,
+    /// Parse error rate tripped the circuit breaker.
+    CircuitOpen,
+    /// Lazy subscription set changed; reconnect now with the new set.
+    Resubscribe,
+    /// A book went out of sync (sequence gap, bad checksum); reconnect for
+    /// fresh snapshots, backing off and counting toward the breaker like
+    /// any other reconnect.
+    Resync,
 }
 
 pub fn calculate_backoff(retry_count: u32, initial: Duration, max: Duration) -> Duration {
@@ -45,9 +512,185 @@ pub fn calculate_backoff(retry_count: u32, initial: Duration, max: Duration) ->
     std::cmp::min(exponential, max)
 }
 
+/// `backoff` scaled by a random factor in `[1 - jitter, 1 + jitter]`.
+pub fn with_jitter(backoff: Duration, jitter: f64) -> Duration {
+    use std::hash::{BuildHasher, Hasher};
+
+    let jitter = jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 {
+        return backoff;
+    }
+    // A fresh RandomState is randomly keyed; no need for a rand dependency.
+    let r = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    let unit = (r >> 11) as f64 / (1u64 << 53) as f64;
+    backoff.mul_f64(1.0 - jitter + 2.0 * jitter * unit)
+}
+
+/// How fast a venue accepts subscribe/unsubscribe messages. Limits are
+/// per IP, so one token bucket is shared by all of a venue's connections.
+#[derive(Debug, Clone, Copy)]
+pub struct SubscribeLimits {
+    /// Topics per message.
+    pub max_args: usize,
+    /// Sustained messages per second.
+    pub per_sec: f64,
+    /// Messages that may go out back to back.
+    pub burst: f64,
+}
+
+pub fn subscribe_limits(exchange: Exchange) -> Option<SubscribeLimits> {
+    match exchange {
+        // Spot rejects requests with more than 10 args.
+        Exchange::Bybit => Some(SubscribeLimits { max_args: 10, per_sec: 5.0, burst: 10.0 }),
+        Exchange::Kraken => Some(SubscribeLimits { max_args: 50, per_sec: 2.0, burst: 5.0 }),
+        _ => None,
+    }
+}
+
+/// Tokens left and last refill, per venue.
+static SUBSCRIBE_BUCKETS: Lazy<Mutex<HashMap<Exchange, (f64, Instant)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+async fn take_subscribe_token(exchange: Exchange, limits: SubscribeLimits) {
+    loop {
+        let wait = {
+            let mut buckets = SUBSCRIBE_BUCKETS.lock().unwrap();
+            let now = Instant::now();
+            let (tokens, last) = buckets.entry(exchange).or_insert((limits.burst, now));
+            *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * limits.per_sec).min(limits.burst);
+            *last = now;
+            if *tokens >= 1.0 {
+                *tokens -= 1.0;
+                None
+            } else {
+                Some(Duration::from_secs_f64((1.0 - *tokens) / limits.per_sec))
+            }
+        };
+        match wait {
+            Some(d) => tokio::time::sleep(d).await,
+            None => return,
+        }
+    }
+}
+
+/// Send `args` built into messages by `message`: one message per
+/// `max_args` batch, each waiting on `exchange`'s token bucket. Venues
+/// without `subscribe_limits` get everything in one message.
+pub async fn send_subscribe_batches<T, M>(
+    exchange: Exchange,
+    write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+    args: &[T],
+    message: M,
+) -> Result<()>
+where
+    T: Sync,
+    M: Fn(&[T]) -> Message + Send,
+{
+    let Some(limits) = subscribe_limits(exchange) else {
+        write.send(message(args)).await?;
+        return Ok(());
+    };
+    for batch in args.chunks(limits.max_args.max(1)) {
+        take_subscribe_token(exchange, limits).await;
+        write.send(message(batch)).await?;
+    }
+    Ok(())
+}
+
+/// Symbols a feed found corrupt while parsing, e.g. on a failed book
+/// checksum or a sequence gap, handed to the connection loop by
+/// `ExchangeFeed::take_resync`.
+#[derive(Default)]
+pub struct ResyncRequests(Mutex<Vec<String>>);
+
+impl ResyncRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request(&self, symbol: &str) {
+        let mut pending = self.0.lock().unwrap();
+        if !pending.iter().any(|s| s == symbol) {
+            pending.push(symbol.to_string());
+        }
+    }
+
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceStatus {
+    InOrder,
+    /// Already seen or older; skip the update.
+    Stale,
+    /// Updates were missed.
+    Gap { expected: u64, got: u64 },
+}
+
+/// Last sequence number per symbol, checked from `parse_message` before an
+/// update touches the book. Stale updates are skipped; a gap goes to
+/// `ResyncRequests` so the feed reconnects for fresh snapshots rather than
+/// serving a book with holes in it. Reset it from `on_connected`, as
+/// sequences restart with the snapshot.
+pub struct SequenceTracker {
+    /// Numbers step by exactly one, so any jump is a gap; otherwise they
+    /// only have to increase.
+    contiguous: bool,
+    last: Mutex<HashMap<String, u64>>,
+}
+
+impl SequenceTracker {
+    pub fn contiguous() -> Self {
+        Self { contiguous: true, last: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn monotonic() -> Self {
+        Self { contiguous: false, last: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn check(&self, symbol: &str, seq: u64) -> SequenceStatus {
+        let mut last = self.last.lock().unwrap();
+        let status = match last.get(symbol) {
+            Some(&prev) if seq <= prev => return SequenceStatus::Stale,
+            Some(&prev) if self.contiguous && seq != prev + 1 => SequenceStatus::Gap { expected: prev + 1, got: seq },
+            _ => SequenceStatus::InOrder,
+        };
+        last.insert(symbol.to_string(), seq);
+        status
+    }
+
+    /// For venues that name the previous update in each one (Binance
+    /// futures `pu`): a gap whenever `prev` isn't the last number seen.
+    pub fn check_linked(&self, symbol: &str, prev: u64, seq: u64) -> SequenceStatus {
+        let mut last = self.last.lock().unwrap();
+        let status = match last.get(symbol) {
+            Some(&last_seq) if seq <= last_seq => return SequenceStatus::Stale,
+            Some(&last_seq) if prev != last_seq => SequenceStatus::Gap { expected: last_seq, got: prev },
+            _ => SequenceStatus::InOrder,
+        };
+        last.insert(symbol.to_string(), seq);
+        status
+    }
+
+    /// Start `symbol` over from a snapshot numbered `seq`.
+    pub fn restart(&self, symbol: &str, seq: u64) {
+        self.last.lock().unwrap().insert(symbol.to_string(), seq);
+    }
+
+    pub fn reset(&self) {
+        self.last.lock().unwrap().clear();
+    }
+}
+
 pub enum WireMessage<'a> {
     Text(&'a str),
     Binary(&'a [u8]),
+    /// Ping, pong or other control payload. Only frame taps see these.
+    Frame(&'a [u8]),
+    /// Close code and reason, if the venue sent them. Only frame taps see
+    /// these.
+    Close(Option<(u16, &'a str)>),
 }
 
 #[async_trait]
@@ -72,6 +715,27 @@ pub trait ExchangeFeed: Send + Sync {
         Ok(())
     }
 
+    /// Decode a binary frame to text for venues that compress every frame
+    /// (e.g. gzip). Decoded frames take the text path, `process_other`
+    /// included. None leaves the frame to `parse_message` as binary.
+    fn inflate_binary(&self, _bytes: &[u8]) -> Option<Result<String>> {
+        None
+    }
+
+    /// Whether `send_subscription` / `send_unsubscription` work on a live,
+    /// already-subscribed socket. Lazy mode reconnects feeds that can't.
+    fn incremental_subscribe(&self) -> bool {
+        false
+    }
+
+    async fn send_unsubscription(
+        &self,
+        _write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        _symbols: &[&str],
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn heartbeat_message(&self) -> Option<Message> {
         return None;
     }
@@ -98,8 +762,35 @@ pub trait ExchangeFeed: Send + Sync {
     /// message (snapshot) rebuilds from scratch.
     fn on_connected(&self) {}
 
+    /// Whether `parse_message` may run on several workers at once, i.e. it
+    /// keeps no book or other state across frames. Items are still stored
+    /// from one task.
+    fn parallel_parse(&self) -> bool {
+        false
+    }
+
+    /// Symbols found corrupt or out of sequence since the last call,
+    /// polled after every parsed frame (usually `ResyncRequests::take`).
+    /// Any entry invalidates the symbol and reconnects the feed.
+    fn take_resync(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Map a symbol returned by `parse_message` to a storage slot.
+    /// Feeds whose instruments are not in the global registry (options)
+    /// override this with their own contract index.
+    fn resolve_symbol(&self, symbol: &str, itype: &InstrumentType) -> Option<SymbolId> {
+        REGISTRY.lookup(symbol, itype).copied()
+    }
+
     fn build_url(&self, symbols: &[&str]) -> Result<String>;
 
+    /// Endpoints in order of preference. A failed connect moves the feed to
+    /// the next one, and it stays on whichever works until that fails too.
+    fn build_urls(&self, symbols: &[&str]) -> Result<Vec<String>> {
+        Ok(vec![self.build_url(symbols)?])
+    }
+
     /// Return:
     /// - Ok(vec![(symbol, item), ...]) for usable update(s)
     /// - Ok(vec![]) to ignore the message (heartbeat, sub ack, etc.)
@@ -112,7 +803,7 @@ pub trait ExchangeFeed: Send + Sync {
     ) -> Result<Vec<(String, Self::Item)>>;
 }
 
-pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
+pub async fn listen_with_reconnect<F: ExchangeFeed + 'static, S: DataSink<F::Item> + 'static>(
     data: Arc<S>,
     symbols: &[&str],
     feed: Arc<F>,
@@ -120,9 +811,57 @@ pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
     config: ConnectionConfig,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
+    let config = installed_config(feed_name, config);
     let mut retry_count: u32 = 0;
+    let mut breaker = config.circuit_breaker.clone().map(|c| CircuitBreaker::new(c, Instant::now()));
+    // Every slot this feed has written, so a trip can invalidate them.
+    let mut touched: FxHashSet<SymbolId> = FxHashSet::default();
+    let itype = *feed.get_itype()?;
+    let lazy = crate::interest::active().and_then(|l| {
+        let exchange = feed_name.split('_').next()?.parse::<Exchange>().ok()?;
+        l.covers(exchange).then_some((l, exchange, symbols))
+    });
+    // Venue tag for event bus items and `on_update`; None keeps both off.
+    let venue = (event_bus::is_active() || config.on_update.is_some())
+        .then(|| feed_name.split('_').next()?.parse::<Exchange>().ok())
+        .flatten();
+    // Index into `build_urls`, advanced on connect failures.
+    let mut endpoint: usize = 0;
 
     loop {
+        // Known downtime: wait it out rather than retrying into it.
+        if let Some(down) = feed_downtime(feed_name, Utc::now()) {
+            let wait = match down {
+                Downtime::Until(end) => (end - Utc::now()).to_std().unwrap_or_default(),
+                Downtime::Reported => Duration::from_secs(60),
+            };
+            info!("{} in maintenance ({:?}), waiting {:?}", feed_name, down, wait);
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown.notified() => {
+                    info!("Shutdown during maintenance wait for {}", feed_name);
+                    break;
+                }
+            }
+            retry_count = 0;
+            continue;
+        }
+
+        let active: Vec<&str> = match lazy {
+            Some((l, exchange, _)) => l.wanted_symbols(exchange, symbols, &itype, Instant::now()),
+            None => symbols.to_vec(),
+        };
+        if let (true, Some((l, exchange, _))) = (active.is_empty(), lazy) {
+            tokio::select! {
+                _ = tokio::time::timeout(l.check_interval(), l.changed(exchange)) => {}
+                _ = shutdown.notified() => {
+                    info!("Shutdown received for idle lazy feed {}", feed_name);
+                    break;
+                }
+            }
+            continue;
+        }
+
         debug!("Connecting feed {} attempt {}", feed_name, retry_count + 1);
         let attempt_start = std::time::Instant::now();
 
@@ -132,13 +871,25 @@ pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
                 break;
             }
 
-            res = connect_and_stream::<F, S>(&data, &feed, feed_name, symbols, &config) => {
+            res = connect_and_stream::<F, S>(&data, &feed, feed_name, &active, &config, &mut breaker, &mut touched, lazy, venue, &mut endpoint) => {
                 // Reset backoff only if the connection was stable for >60s
                 let was_long_lived = attempt_start.elapsed() > Duration::from_secs(60);
 
                 match res {
                     Ok(ConnectionResult::Shutdown | ConnectionResult::InvalidConfig) => break,
 
+                    Ok(ConnectionResult::CircuitOpen) => {
+                        let Some(b) = breaker.as_mut() else { continue };
+                        if !open_circuit::<F::Item, S>(&data, &touched, b, feed_name, &config, FeedStatus::CircuitOpen, &shutdown).await {
+                            break;
+                        }
+                        retry_count = 0;
+                    }
+
+                    Ok(ConnectionResult::Resubscribe) => {
+                        retry_count = 0;
+                    }
+
                     Ok(ConnectionResult::RetryAfter(delay)) => {
                         retry_count = 0; // server told us when, reset exponential
                         warn!("{} rate limited. Waiting {:?} (retry-after)", feed_name, delay);
@@ -151,17 +902,36 @@ pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
                         }
                     }
 
-                    Ok(ConnectionResult::Reconnect) => {
+                    Ok(ConnectionResult::Reconnect | ConnectionResult::Resync) => {
                         if was_long_lived {
                             retry_count = 0;
                         } else {
                             retry_count += 1;
                         }
 
-                        let backoff = calculate_backoff(
-                            retry_count,
-                            config.initial_backoff,
-                            config.max_retry_delay
+                        if feed_downtime(feed_name, Utc::now()).is_some() {
+                            continue;
+                        }
+                        if let Some(b) = breaker.as_mut() {
+                            let tripped = if b.record_reconnect(Instant::now()) {
+                                Some(FeedStatus::CircuitOpen)
+                            } else if b.cfg.max_consecutive_failures > 0 && retry_count >= b.cfg.max_consecutive_failures {
+                                Some(FeedStatus::Degraded)
+                            } else {
+                                None
+                            };
+                            if let Some(status) = tripped {
+                                if !open_circuit::<F::Item, S>(&data, &touched, b, feed_name, &config, status, &shutdown).await {
+                                    break;
+                                }
+                                retry_count = 0;
+                                continue;
+                            }
+                        }
+
+                        let backoff = with_jitter(
+                            calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay),
+                            config.backoff_jitter,
                         );
 
                         warn!("{} disconnected. Reconnecting in {:?}", feed_name, backoff);
@@ -182,10 +952,29 @@ pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
                             retry_count += 1;
                         }
 
-                        let backoff = calculate_backoff(
-                            retry_count,
-                            config.initial_backoff,
-                            config.max_retry_delay
+                        if feed_downtime(feed_name, Utc::now()).is_some() {
+                            continue;
+                        }
+                        if let Some(b) = breaker.as_mut() {
+                            let tripped = if b.record_reconnect(Instant::now()) {
+                                Some(FeedStatus::CircuitOpen)
+                            } else if b.cfg.max_consecutive_failures > 0 && retry_count >= b.cfg.max_consecutive_failures {
+                                Some(FeedStatus::Degraded)
+                            } else {
+                                None
+                            };
+                            if let Some(status) = tripped {
+                                if !open_circuit::<F::Item, S>(&data, &touched, b, feed_name, &config, status, &shutdown).await {
+                                    break;
+                                }
+                                retry_count = 0;
+                                continue;
+                            }
+                        }
+
+                        let backoff = with_jitter(
+                            calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay),
+                            config.backoff_jitter,
                         );
 
                         error!("{} error: {}. Reconnecting in {:?}", feed_name, e, backoff);
@@ -203,25 +992,81 @@ pub async fn listen_with_reconnect<F: ExchangeFeed, S: DataSink<F::Item>>(
         }
     }
 
+    report_status(&config, feed_name, FeedStatus::Stopped);
     info!("Stopped {}", feed_name);
     Ok(())
 }
 
-async fn connect_and_stream<F: ExchangeFeed, S: DataSink<F::Item>>(
+/// Spread `symbols` over as many connections as it takes to keep each at
+/// or under `max_per_connection`, all writing to `data`. Each shard gets
+/// its own feed from `make_feed` and runs as `{feed_name}#{i}`, which
+/// `feed_status` and `ping_rtt` fold back into `feed_name`; a list that
+/// fits one connection runs as plain `listen_with_reconnect`.
+pub async fn listen_sharded<F, S, M>(
+    data: Arc<S>,
+    symbols: &[&str],
+    max_per_connection: usize,
+    make_feed: M,
+    feed_name: &str,
+    config: ConnectionConfig,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()>
+where
+    F: ExchangeFeed + 'static,
+    S: DataSink<F::Item> + 'static,
+    M: Fn(&[&str]) -> Result<F>,
+{
+    let max = max_per_connection.max(1);
+    if symbols.len() <= max {
+        let feed = Arc::new(make_feed(symbols)?);
+        return listen_with_reconnect(data, symbols, feed, feed_name, config, shutdown).await;
+    }
+
+    let mut shards = Vec::new();
+    for (i, chunk) in symbols.chunks(max).enumerate() {
+        let feed = Arc::new(make_feed(chunk)?);
+        let (data, config, shutdown) = (Arc::clone(&data), config.clone(), Arc::clone(&shutdown));
+        shards.push(async move {
+            let name = shard_name(feed_name, i);
+            if let Err(e) = listen_with_reconnect(data, chunk, feed, &name, config, shutdown).await {
+                error!("{} error: {:?}", name, e);
+            }
+        });
+    }
+    info!("{}: {} symbols over {} connections", feed_name, symbols.len(), shards.len());
+    futures_util::future::join_all(shards).await;
+    Ok(())
+}
+
+async fn connect_and_stream<F: ExchangeFeed + 'static, S: DataSink<F::Item> + 'static>(
     data: &Arc<S>,
     feed: &Arc<F>,
     feed_name: &str,
     symbols: &[&str],
     config: &ConnectionConfig,
+    breaker: &mut Option<CircuitBreaker>,
+    touched: &mut FxHashSet<SymbolId>,
+    lazy: Option<(&LazySubscriptions, Exchange, &[&str])>,
+    venue: Option<Exchange>,
+    endpoint: &mut usize,
 ) -> Result<ConnectionResult> {
     let itype = feed.get_itype()?;
-    let url = match feed.build_url(symbols) {
-        Ok(v) => v,
+    let urls = match feed.build_urls(symbols) {
+        Ok(v) if !v.is_empty() => v,
+        Ok(_) => {
+            error!("No connection url for {}", feed_name);
+            return Ok(ConnectionResult::InvalidConfig);
+        }
         Err(e) => {
             error!("Could not build connection url {}: {}", feed_name, e);
             return Ok(ConnectionResult::InvalidConfig);
         }
     };
+    let n_urls = urls.len();
+    let url = urls.into_iter().nth(*endpoint % n_urls).unwrap();
+    if n_urls > 1 {
+        debug!("{} connecting to endpoint {}/{}", feed_name, *endpoint % n_urls + 1, n_urls);
+    }
 
     let mut request = url.into_client_request()?;
     for (key, value) in feed.extra_headers() {
@@ -259,10 +1104,12 @@ async fn connect_and_stream<F: ExchangeFeed, S: DataSink<F::Item>>(
                 }
             }
             error!("Failed to connect to {} {}", feed_name, e);
+            next_endpoint(endpoint, n_urls, feed_name);
             return Ok(ConnectionResult::Reconnect);
         }
         Err(e) => {
             error!("Connect timed out for {} {}", feed_name, e);
+            next_endpoint(endpoint, n_urls, feed_name);
             return Ok(ConnectionResult::Reconnect);
         }
     };
@@ -278,17 +1125,62 @@ async fn connect_and_stream<F: ExchangeFeed, S: DataSink<F::Item>>(
     }
 
     feed.on_connected();
+    report_status(config, feed_name, FeedStatus::Connected);
 
     let mut heartbeat = interval(config.heartbeat_interval);
     heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
     let mut last_message_time = Utc::now();
-    let do_ts_dedup = feed.timestamp_dedup();
-    let mut last_exchange_ts: HashMap<SymbolId, chrono::DateTime<Utc>> = HashMap::new();
-    let mut warned_symbols: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut handler = FrameHandler::new(feed, data, *itype, feed_name, config, venue);
+    let workers = if feed.parallel_parse() { config.parse_workers } else { config.parse_workers.min(1) };
+    let (mut pool, mut outcomes) = if workers > 0 {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let pool = ParsePool::start(workers, &tx, || FrameHandler::new(feed, data, *itype, feed_name, config, venue));
+        (Some(pool), Some(rx))
+    } else {
+        (None, None)
+    };
+    let mut in_order = InOrder::new();
+    let mut subscribed: Vec<&str> = symbols.to_vec();
+    let mut lazy_check = lazy.map(|(l, _, _)| interval(l.check_interval()));
+    // Sequence number carried in the last keepalive ping, and when it left.
+    let mut ping_seq: u64 = 0;
+    let mut ping_sent: Option<(u64, Instant)> = None;
 
     let result = loop {
         tokio::select! {
+            _ = next_lazy_check(&mut lazy_check, lazy) => {
+                let Some((l, exchange, universe)) = lazy else { continue };
+                let wanted = l.wanted_symbols(exchange, universe, itype, Instant::now());
+                if wanted == subscribed {
+                    continue;
+                }
+                let added: Vec<&str> = wanted.iter().filter(|s| !subscribed.contains(s)).copied().collect();
+                let removed: Vec<&str> = subscribed.iter().filter(|s| !wanted.contains(s)).copied().collect();
+                // Dropped symbols stop updating; hide their last quote.
+                for id in removed.iter().filter_map(|s| REGISTRY.lookup(s, itype)) {
+                    data.invalidate(id);
+                }
+                if wanted.is_empty() || !feed.incremental_subscribe() {
+                    info!("{}: lazy set now {} symbol(s), resubscribing", feed_name, wanted.len());
+                    break ConnectionResult::Resubscribe;
+                }
+                if !added.is_empty() {
+                    if let Err(e) = feed.send_subscription(&mut write, &added).await {
+                        error!("Error subscribing to {} on {}: {}", added.join(","), feed_name, e);
+                        break ConnectionResult::Reconnect;
+                    }
+                }
+                if !removed.is_empty() {
+                    if let Err(e) = feed.send_unsubscription(&mut write, &removed).await {
+                        error!("Error unsubscribing from {} on {}: {}", removed.join(","), feed_name, e);
+                        break ConnectionResult::Reconnect;
+                    }
+                }
+                debug!("{}: lazy +{} -{} ({} active)", feed_name, added.len(), removed.len(), wanted.len());
+                subscribed = wanted;
+            }
+
             _ = heartbeat.tick() => {
                 let elapsed = Utc::now() - last_message_time;
                 if elapsed > chrono::Duration::from_std(config.message_timeout)? {
@@ -303,96 +1195,70 @@ async fn connect_and_stream<F: ExchangeFeed, S: DataSink<F::Item>>(
                     }
                 } else {
                 // Keepalive ping (many servers ignore it; some require it)
-                if let Err(e) = write.send(Message::Ping(vec![].into())).await {
+                ping_seq += 1;
+                if let Err(e) = write.send(Message::Ping(ping_seq.to_be_bytes().to_vec().into())).await {
                     error!("Failed to send ping on {}: {}", feed_name, e);
                     break ConnectionResult::Reconnect;
                 }
+                ping_sent = Some((ping_seq, Instant::now()));
                 }
 
             }
 
+            Some((seq, parsed)) = next_outcome(&mut outcomes) => {
+                in_order.push(seq, parsed);
+                let mut ended = None;
+                while let Some(parsed) = in_order.pop() {
+                    let out = match parsed {
+                        WorkerOutput::Stored(out) => out,
+                        WorkerOutput::Parsed(parsed) => handler.finish(parsed),
+                    };
+                    ended = apply_outcome(out, feed, &mut write, breaker, touched).await;
+                    if ended.is_some() {
+                        break;
+                    }
+                }
+                if let Some(res) = ended {
+                    break res;
+                }
+            }
+
             msg = read.next() => {
                 let received_instant = std::time::Instant::now();
                 let received_ts = Utc::now();
                 last_message_time = received_ts;
+                if let Some(Ok(frame)) = &msg {
+                    tap_frame(config, feed_name, frame, received_ts);
+                }
 
                 match msg {
-                    Some(Ok(Message::Text(text))) => {
-                        match feed.parse_message(WireMessage::Text(text.as_str()), received_ts, received_instant) {
-                            Ok(items) if items.is_empty() => {
-                                // intentionally ignored (heartbeats, sub acks, etc.)
-                                if let Err(e) = feed.process_other(&mut write, &text).await {
-                                    error!("Error processing other: {}", e);
-                                    break ConnectionResult::Reconnect;
-                                }
-                            }
-                            Ok(items) => {
-                                let latency_ns = received_instant.elapsed().as_nanos() as u64;
-                                for (sym, mut item) in items {
-                                    if let Some(&id) = REGISTRY.lookup(&sym, &itype) {
-                                        let stale = do_ts_dedup && item.exchange_ts_raw().map_or(false, |ts| {
-                                            last_exchange_ts.get(&id).map_or(false, |&last| ts < last)
-                                        });
-                                        if !stale {
-                                            if do_ts_dedup {
-                                                if let Some(ts) = item.exchange_ts_raw() {
-                                                    last_exchange_ts.insert(id, ts);
-                                                }
-                                            }
-                                            item.set_feed_latency_ns(latency_ns);
-                                            data.push(&id, item);
-                                        }
-                                    } else if warned_symbols.insert(sym.clone()) {
-                                        warn!("{}: symbol '{}' not in registry, dropping ticks", feed_name, sym);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                let preview = if text.len() > 120 { &text[..120] } else { &text };
-                                error!("{} parse error: {}  {}", feed_name, preview, e);
+                    Some(Ok(frame @ (Message::Text(_) | Message::Binary(_)))) => match pool.as_mut() {
+                        Some(pool) => {
+                            if !pool.dispatch(frame, received_ts, received_instant).await {
+                                error!("{} parse worker exited, reconnecting", feed_name);
+                                break ConnectionResult::Reconnect;
                             }
                         }
-                    }
-
-                    Some(Ok(Message::Binary(bytes))) => {
-                        match feed.parse_message(WireMessage::Binary(&bytes), received_ts, received_instant) {
-                            Ok(items) if items.is_empty() => {
-                                // intentionally ignored
-                            }
-                            Ok(items) => {
-                                let latency_ns = received_instant.elapsed().as_nanos() as u64;
-                                for (sym, mut item) in items {
-                                    if let Some(&id) = REGISTRY.lookup(&sym, &itype) {
-                                        let stale = do_ts_dedup && item.exchange_ts_raw().map_or(false, |ts| {
-                                            last_exchange_ts.get(&id).map_or(false, |&last| ts < last)
-                                        });
-                                        if !stale {
-                                            if do_ts_dedup {
-                                                if let Some(ts) = item.exchange_ts_raw() {
-                                                    last_exchange_ts.insert(id, ts);
-                                                }
-                                            }
-                                            item.set_feed_latency_ns(latency_ns);
-                                            data.push(&id, item);
-                                        }
-                                    } else if warned_symbols.insert(sym.clone()) {
-                                        warn!("{}: symbol '{}' not in registry, dropping ticks", feed_name, sym);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("{} parse error (binary): {}", feed_name, e);
+                        None => {
+                            let out = handler.handle(frame, received_ts, received_instant);
+                            if let Some(res) = apply_outcome(out, feed, &mut write, breaker, touched).await {
+                                break res;
                             }
                         }
-                    }
+                    },
 
                     Some(Ok(Message::Ping(payload))) => {
                         // Respond to server ping (helps with some exchanges)
                         let _ = write.send(Message::Pong(payload)).await;
                     }
 
-                    Some(Ok(Message::Pong(_))) => {
-                        // keepalive response; nothing to do
+                    Some(Ok(Message::Pong(payload))) => {
+                        if let Some((seq, sent)) = ping_sent {
+                            if payload[..] == seq.to_be_bytes() {
+                                record_ping_rtt(feed_name, received_instant.duration_since(sent));
+                                ping_sent = None;
+                            }
+                        }
                     }
 
                     Some(Ok(Message::Close(_))) => {
@@ -416,10 +1282,314 @@ async fn connect_and_stream<F: ExchangeFeed, S: DataSink<F::Item>>(
         }
     };
 
+    // Workers may still hold book state; let them finish before the next
+    // connection's `on_connected` resets it.
+    if let Some(pool) = pool {
+        pool.join().await;
+    }
     close_stream(write, read, feed_name).await;
+    report_status(config, feed_name, FeedStatus::Disconnected);
     Ok(result)
 }
 
+/// Per-connection state for turning frames into stored items, owned by the
+/// socket task or by a parse worker.
+struct FrameHandler<F: ExchangeFeed, S> {
+    feed: Arc<F>,
+    data: Arc<S>,
+    itype: InstrumentType,
+    feed_name: String,
+    config: ConnectionConfig,
+    venue: Option<Exchange>,
+    do_ts_dedup: bool,
+    last_exchange_ts: HashMap<SymbolId, chrono::DateTime<Utc>>,
+    warned_symbols: std::collections::HashSet<String>,
+    /// Whether the registry has scaled aliases ("1000PEPE") to restate.
+    rescale: bool,
+}
+
+/// What one frame produced, for the socket task to act on.
+struct FrameOutcome {
+    received_instant: Instant,
+    /// Whether `parse_message` succeeded, for the circuit breaker.
+    parsed: bool,
+    touched: Vec<SymbolId>,
+    /// A text frame without items, for `process_other`.
+    other: Option<String>,
+    resync: bool,
+}
+
+/// A parsed frame whose items are not stored yet.
+struct ParsedFrame<T> {
+    out: FrameOutcome,
+    items: Vec<(String, T)>,
+    latency_ns: u64,
+}
+
+/// What a parse worker sends back: a lone worker stores itself, several
+/// leave storing to the socket task.
+enum WorkerOutput<T> {
+    Stored(FrameOutcome),
+    Parsed(ParsedFrame<T>),
+}
+
+impl<F: ExchangeFeed, S: DataSink<F::Item>> FrameHandler<F, S> {
+    fn new(feed: &Arc<F>, data: &Arc<S>, itype: InstrumentType, feed_name: &str, config: &ConnectionConfig, venue: Option<Exchange>) -> Self {
+        Self {
+            feed: Arc::clone(feed),
+            data: Arc::clone(data),
+            itype,
+            feed_name: feed_name.to_string(),
+            config: config.clone(),
+            venue,
+            do_ts_dedup: feed.timestamp_dedup(),
+            last_exchange_ts: HashMap::new(),
+            warned_symbols: std::collections::HashSet::new(),
+            rescale: REGISTRY.has_multipliers(),
+        }
+    }
+
+    /// Inflate, parse and store one text or binary frame.
+    fn handle(&mut self, frame: Message, received_ts: chrono::DateTime<Utc>, received_instant: Instant) -> FrameOutcome {
+        let parsed = self.parse(frame, received_ts, received_instant);
+        self.finish(parsed)
+    }
+
+    /// Store a parsed frame's items, then act on books the feed flagged.
+    fn finish(&mut self, parsed: ParsedFrame<F::Item>) -> FrameOutcome {
+        let mut out = parsed.out;
+        if !parsed.items.is_empty() {
+            self.store(parsed.items, parsed.latency_ns, &mut out);
+        }
+        for sym in self.feed.take_resync() {
+            warn!("{}: {} book out of sync, resyncing", self.feed_name, sym);
+            if let Some(id) = self.feed.resolve_symbol(&sym, &self.itype) {
+                self.data.invalidate(&id);
+            }
+            out.resync = true;
+        }
+        out
+    }
+
+    /// Inflate and parse one frame without storing its items.
+    fn parse(&mut self, frame: Message, received_ts: chrono::DateTime<Utc>, received_instant: Instant) -> ParsedFrame<F::Item> {
+        let mut items = Vec::new();
+        let mut latency_ns = 0;
+        let mut out = FrameOutcome { received_instant, parsed: true, touched: Vec::new(), other: None, resync: false };
+        let frame = match frame {
+            Message::Binary(bytes) => match self.feed.inflate_binary(&bytes).or_else(|| self.config.decompression.inflate(&bytes)) {
+                Some(Ok(text)) => Message::Text(text.into()),
+                Some(Err(e)) => {
+                    error!("{} failed to inflate binary frame: {}", self.feed_name, e);
+                    Message::Binary(bytes)
+                }
+                None => Message::Binary(bytes),
+            },
+            other => other,
+        };
+        let (parsed, text) = match &frame {
+            Message::Text(text) => (self.feed.parse_message(WireMessage::Text(text.as_str()), received_ts, received_instant), Some(text.as_str())),
+            Message::Binary(bytes) => (self.feed.parse_message(WireMessage::Binary(bytes), received_ts, received_instant), None),
+            _ => return ParsedFrame { out, items, latency_ns },
+        };
+        out.parsed = parsed.is_ok();
+        match parsed {
+            // intentionally ignored (heartbeats, sub acks, etc.)
+            Ok(parsed) if parsed.is_empty() => out.other = text.map(str::to_string),
+            Ok(parsed) => {
+                latency_ns = received_instant.elapsed().as_nanos() as u64;
+                items = parsed;
+            }
+            Err(e) => match text {
+                Some(text) => {
+                    let preview = if text.len() > 120 { &text[..120] } else { text };
+                    error!("{} parse error: {}  {}", self.feed_name, preview, e);
+                }
+                None => error!("{} parse error (binary): {}", self.feed_name, e),
+            },
+        }
+        ParsedFrame { out, items, latency_ns }
+    }
+
+    fn store(&mut self, items: Vec<(String, F::Item)>, latency_ns: u64, out: &mut FrameOutcome) {
+        for (sym, mut item) in items {
+            let Some(id) = self.feed.resolve_symbol(&sym, &self.itype) else {
+                if self.warned_symbols.insert(sym.clone()) {
+                    warn!("{}: symbol '{}' not in registry, dropping ticks", self.feed_name, sym);
+                }
+                continue;
+            };
+            let stale = self.do_ts_dedup
+                && item.exchange_ts_raw().map_or(false, |ts| self.last_exchange_ts.get(&id).map_or(false, |&last| ts < last));
+            if stale {
+                continue;
+            }
+            if self.do_ts_dedup {
+                if let Some(ts) = item.exchange_ts_raw() {
+                    self.last_exchange_ts.insert(id, ts);
+                }
+            }
+            if self.rescale {
+                let multiplier = REGISTRY.multiplier(&sym);
+                if multiplier != 1.0 {
+                    item.rescale(multiplier);
+                }
+            }
+            item.set_feed_latency_ns(latency_ns);
+            out.touched.push(id);
+            self.data.push(&id, item);
+            report_update(&self.config, &self.feed_name, self.venue, id, &item);
+        }
+    }
+}
+
+/// Fold a frame's outcome into the connection; Some ends it.
+async fn apply_outcome<F: ExchangeFeed>(
+    out: FrameOutcome,
+    feed: &Arc<F>,
+    write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+    breaker: &mut Option<CircuitBreaker>,
+    touched: &mut FxHashSet<SymbolId>,
+) -> Option<ConnectionResult> {
+    touched.extend(out.touched);
+    if breaker.as_mut().is_some_and(|b| b.record_message(out.parsed, out.received_instant)) {
+        return Some(ConnectionResult::CircuitOpen);
+    }
+    if let Some(text) = out.other {
+        if let Err(e) = feed.process_other(write, &text).await {
+            error!("Error processing other: {}", e);
+            return Some(ConnectionResult::Reconnect);
+        }
+    }
+    out.resync.then_some(ConnectionResult::Resync)
+}
+
+/// A frame and its arrival index on the connection.
+type Frame = (u64, Message, chrono::DateTime<Utc>, Instant);
+
+/// Parse workers fed raw frames by the socket task (`parse_workers`). A
+/// lone worker stores what it parses; several hand their items back so
+/// that only the socket task writes the collections, which are single
+/// writer per slot, and no two workers race on one symbol. Outcomes come
+/// back tagged with the frame's index for `InOrder`.
+struct ParsePool {
+    senders: Vec<tokio::sync::mpsc::Sender<Frame>>,
+    next: u64,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl ParsePool {
+    fn start<F, S>(
+        workers: usize,
+        outcomes: &tokio::sync::mpsc::UnboundedSender<(u64, WorkerOutput<F::Item>)>,
+        make_handler: impl Fn() -> FrameHandler<F, S>,
+    ) -> Self
+    where
+        F: ExchangeFeed + 'static,
+        S: DataSink<F::Item> + 'static,
+    {
+        let mut pool = ParsePool { senders: Vec::new(), next: 0, tasks: Vec::new() };
+        let store = workers == 1;
+        for _ in 0..workers {
+            let (tx, mut rx) = tokio::sync::mpsc::channel::<Frame>(PARSE_QUEUE_DEPTH);
+            let outcomes = outcomes.clone();
+            let mut handler = make_handler();
+            pool.tasks.push(tokio::spawn(async move {
+                while let Some((seq, frame, received_ts, received_instant)) = rx.recv().await {
+                    let parsed = if store {
+                        WorkerOutput::Stored(handler.handle(frame, received_ts, received_instant))
+                    } else {
+                        WorkerOutput::Parsed(handler.parse(frame, received_ts, received_instant))
+                    };
+                    if outcomes.send((seq, parsed)).is_err() {
+                        break;
+                    }
+                }
+            }));
+            pool.senders.push(tx);
+        }
+        pool
+    }
+
+    /// Hand a frame to the next worker, waiting if its queue is full; false
+    /// if that worker is gone.
+    async fn dispatch(&mut self, frame: Message, received_ts: chrono::DateTime<Utc>, received_instant: Instant) -> bool {
+        let seq = self.next;
+        let tx = &self.senders[(seq % self.senders.len() as u64) as usize];
+        self.next += 1;
+        tx.send((seq, frame, received_ts, received_instant)).await.is_ok()
+    }
+
+    async fn join(self) {
+        drop(self.senders);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+    }
+}
+
+/// Frames queued per parse worker before the socket task waits on it.
+const PARSE_QUEUE_DEPTH: usize = 1024;
+
+/// Parse worker outcomes put back in frame order: workers finish out of
+/// order, and storing a symbol's updates out of order would have the
+/// sequence and timestamp checks drop the newer ones.
+struct InOrder<T> {
+    next: u64,
+    pending: BTreeMap<u64, WorkerOutput<T>>,
+}
+
+impl<T> InOrder<T> {
+    fn new() -> Self {
+        Self { next: 0, pending: BTreeMap::new() }
+    }
+
+    fn push(&mut self, seq: u64, out: WorkerOutput<T>) {
+        self.pending.insert(seq, out);
+    }
+
+    /// The next frame's outcome, once its worker sent it.
+    fn pop(&mut self) -> Option<WorkerOutput<T>> {
+        let out = self.pending.remove(&self.next)?;
+        self.next += 1;
+        Some(out)
+    }
+}
+
+/// Next outcome from the parse workers. Never resolves when parsing inline.
+async fn next_outcome<T>(
+    rx: &mut Option<tokio::sync::mpsc::UnboundedReceiver<(u64, WorkerOutput<T>)>>,
+) -> Option<(u64, WorkerOutput<T>)> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn next_endpoint(endpoint: &mut usize, n_urls: usize, feed_name: &str) {
+    if n_urls > 1 {
+        *endpoint = (*endpoint + 1) % n_urls;
+        warn!("{} failing over to endpoint {}/{}", feed_name, *endpoint + 1, n_urls);
+    }
+}
+
+/// Next lazy-subscription check: the periodic tick (for idle expiry) or new
+/// interest on the venue. Never resolves for non-lazy feeds.
+async fn next_lazy_check(
+    tick: &mut Option<tokio::time::Interval>,
+    lazy: Option<(&LazySubscriptions, Exchange, &[&str])>,
+) {
+    match (tick.as_mut(), lazy) {
+        (Some(tick), Some((l, exchange, _))) => {
+            tokio::select! {
+                _ = tick.tick() => {}
+                _ = l.changed(exchange) => {}
+            }
+        }
+        _ => std::future::pending().await,
+    }
+}
+
 /// Shut down the WebSocket connection without blocking async worker threads.
 ///
 /// Dropping a TLS WebSocket stream calls `SSLClose()` (native-tls / SecureTransport
@@ -443,3 +1613,102 @@ async fn close_stream(
     })
     .await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::MarketData;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_installed_hooks_reach_feeds() {
+        // Hooks built here rather than installed: the installed ones are
+        // process-wide and would reach every other test's feeds.
+        let updates = Arc::new(AtomicUsize::new(0));
+        let statuses = Arc::new(AtomicUsize::new(0));
+        let hooks = || {
+            let (updates, statuses) = (Arc::clone(&updates), Arc::clone(&statuses));
+            InstalledHooks {
+                on_frame: None,
+                on_update: Some(Arc::new(move |feed: &str, ev: &MarketEvent| {
+                    if (feed, ev.symbol_id()) == ("hooktest_spot", Some(3)) {
+                        updates.fetch_add(1, Ordering::SeqCst);
+                    }
+                })),
+                on_status: Some(Arc::new(move |feed: &str, status: FeedStatus| {
+                    if (feed, status) == ("hooktest_spot", FeedStatus::Connected) {
+                        statuses.fetch_add(1, Ordering::SeqCst);
+                    }
+                })),
+            }
+        };
+
+        // What a built-in connector passes: no callbacks of its own.
+        let config = hooks().fill(ConnectionConfig::default());
+        report_status(&config, "hooktest_spot", FeedStatus::Connected);
+        let md = MarketData { bid: Some(1.0), ..Default::default() };
+        report_update(&config, "hooktest_spot", Some(Exchange::Binance), 3, &md);
+        assert_eq!(updates.load(Ordering::SeqCst), 1);
+        assert_eq!(statuses.load(Ordering::SeqCst), 1);
+
+        // A feed's own callback wins over the installed one.
+        let own = ConnectionConfig { on_status: Some(Arc::new(|_: &str, _: FeedStatus| {})), ..ConnectionConfig::default() };
+        report_status(&hooks().fill(own), "hooktest_spot", FeedStatus::Connected);
+        assert_eq!(statuses.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_sharded_status_and_rtt() {
+        let config = ConnectionConfig::default();
+        report_status(&config, &shard_name("shardtest_spot", 0), FeedStatus::Connected);
+        report_status(&config, &shard_name("shardtest_spot", 1), FeedStatus::Connected);
+        assert_eq!(feed_status("shardtest_spot"), Some(FeedStatus::Connected));
+        report_status(&config, &shard_name("shardtest_spot", 1), FeedStatus::Disconnected);
+        assert_eq!(feed_status("shardtest_spot"), Some(FeedStatus::Disconnected));
+        assert_eq!(feed_status("shardtest_spot#0"), Some(FeedStatus::Connected));
+        assert!(feed_statuses().contains(&("shardtest_spot".to_string(), FeedStatus::Disconnected)));
+
+        record_ping_rtt(&shard_name("shardtest_spot", 0), Duration::from_millis(8));
+        record_ping_rtt(&shard_name("shardtest_spot", 1), Duration::from_millis(4));
+        let rtt = ping_rtt("shardtest_spot").unwrap();
+        assert_eq!((rtt.samples, rtt.min), (2, Duration::from_millis(4)));
+    }
+
+    #[test]
+    fn test_worker_outcomes_in_frame_order() {
+        let outcome = |id: SymbolId| {
+            let out = FrameOutcome { received_instant: Instant::now(), parsed: true, touched: vec![id], other: None, resync: false };
+            WorkerOutput::<MarketData>::Stored(out)
+        };
+        let touched = |out: Option<WorkerOutput<MarketData>>| match out {
+            Some(WorkerOutput::Stored(out)) => out.touched,
+            _ => panic!("expected a stored outcome"),
+        };
+        let mut in_order = InOrder::new();
+        in_order.push(1, outcome(1));
+        in_order.push(2, outcome(2));
+        assert!(in_order.pop().is_none());
+        in_order.push(0, outcome(0));
+        assert_eq!(touched(in_order.pop()), [0]);
+        assert_eq!(touched(in_order.pop()), [1]);
+        assert_eq!(touched(in_order.pop()), [2]);
+        assert!(in_order.pop().is_none());
+    }
+
+    #[test]
+    fn test_sequence_tracker_gaps() {
+        let contiguous = SequenceTracker::contiguous();
+        assert_eq!(contiguous.check("BTC", 5), SequenceStatus::InOrder);
+        assert_eq!(contiguous.check("BTC", 6), SequenceStatus::InOrder);
+        assert_eq!(contiguous.check("BTC", 6), SequenceStatus::Stale);
+        assert_eq!(contiguous.check("BTC", 9), SequenceStatus::Gap { expected: 7, got: 9 });
+        contiguous.restart("BTC", 1);
+        assert_eq!(contiguous.check("BTC", 2), SequenceStatus::InOrder);
+
+        let linked = SequenceTracker::monotonic();
+        assert_eq!(linked.check_linked("BTC", 90, 100), SequenceStatus::InOrder);
+        assert_eq!(linked.check_linked("BTC", 100, 130), SequenceStatus::InOrder);
+        assert_eq!(linked.check_linked("BTC", 120, 125), SequenceStatus::Stale);
+        assert_eq!(linked.check_linked("BTC", 140, 150), SequenceStatus::Gap { expected: 130, got: 140 });
+    }
+}
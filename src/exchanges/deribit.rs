@@ -0,0 +1,758 @@
+use crate::futures_data::{FuturesContract, FuturesData, FuturesDataCollection};
+use crate::index_data::{IndexCollection, IndexData};
+use crate::mappers::{DeribitMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::options_data::{ChainSpec, OptionContract, OptionContracts, OptionData, OptionDataCollection};
+use crate::symbol_registry::SymbolId;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+
+const WS_URL: &str = "wss://www.deribit.com/ws/api/v2";
+const INSTRUMENTS_URL: &str = "https://www.deribit.com/api/v2/public/get_instruments";
+const HEARTBEAT: &str = r#"{"jsonrpc":"2.0","id":0,"method":"public/test"}"#;
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(5.0, 0.0), FeeSchedule::new(5.0, 0.0))
+}
+
+/// JSON-RPC envelope. Subscription pushes have `method == "subscription"`;
+/// everything else (subscribe acks, heartbeat test replies) is ignored.
+#[derive(Debug, Deserialize)]
+struct DeribitMessage<T> {
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Option<DeribitParams<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct DeribitParams<T> {
+    channel: String,
+    data: T,
+}
+
+/// `public/subscribe` or `public/unsubscribe` for `channels`, in batches
+/// of `chunk`.
+async fn send_channels(
+    write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+    method: &str,
+    channels: &[String],
+    chunk: usize,
+) -> Result<()> {
+    for batch in channels.chunks(chunk) {
+        let msg = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": method,
+            "params": { "channels": batch }
+        });
+        write
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .with_context(|| format!("Deribit {method} failed for {batch:?}"))?;
+        debug!("Deribit {}: {:?}", method, batch);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct InstrumentsResponse {
+    result: Vec<InstrumentRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstrumentRow {
+    instrument_name: String,
+}
+
+/// Live instrument names of `kind` ("option", "future") for `currency`
+/// ("BTC"; "USDC" for linear contracts).
+async fn fetch_instruments(client: &Client, currency: &str, kind: &str) -> Result<Vec<String>> {
+    let url = format!("{INSTRUMENTS_URL}?currency={currency}&kind={kind}&expired=false");
+    let resp = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("GET {url} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {url} -> {status}; body: {body}");
+    }
+    let parsed: InstrumentsResponse = serde_json::from_str(&body).context("decode get_instruments JSON")?;
+    Ok(parsed.result.into_iter().map(|r| r.instrument_name).collect())
+}
+
+// --- Perpetual BBO Feed ---
+
+#[derive(Debug, Deserialize)]
+struct DeribitTicker {
+    /// e.g. "BTC-PERPETUAL"
+    instrument_name: String,
+    #[serde(default)]
+    best_bid_price: Option<f64>,
+    #[serde(default)]
+    best_bid_amount: Option<f64>,
+    #[serde(default)]
+    best_ask_price: Option<f64>,
+    #[serde(default)]
+    best_ask_amount: Option<f64>,
+    /// ms
+    timestamp: i64,
+}
+
+struct DeribitPerpFeed {
+    itype: InstrumentType,
+    mapper: DeribitMapper,
+    /// Instrument ("BTC-PERPETUAL") -> config symbol ("BTC_USD").
+    instrument_to_sym: HashMap<String, String>,
+}
+
+impl DeribitPerpFeed {
+    fn new(symbols: &[&str]) -> Result<Self> {
+        let itype = InstrumentType::Perp;
+        let mapper = DeribitMapper;
+        let mut instrument_to_sym = HashMap::new();
+        for sym in symbols {
+            instrument_to_sym.insert(mapper.denormalize(sym, itype)?, sym.to_string());
+        }
+        Ok(Self { itype, mapper, instrument_to_sym })
+    }
+
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        method: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let channels = symbols
+            .iter()
+            .map(|sym| Ok(format!("ticker.{}.100ms", self.mapper.denormalize(sym, self.itype)?)))
+            .collect::<Result<Vec<String>>>()?;
+        send_channels(write, method, &channels, channels.len().max(1)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for DeribitPerpFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "public/subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "public/unsubscribe", symbols).await
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(HEARTBEAT.into()))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg = serde_json::from_str::<DeribitMessage<DeribitTicker>>(text)?;
+        if msg.method.as_deref() != Some("subscription") {
+            return Ok(vec![]);
+        }
+        let Some(params) = msg.params else {
+            warn!("Deribit subscription push without params: {}", text);
+            return Ok(vec![]);
+        };
+        let t = params.data;
+        let Some(symbol) = self.instrument_to_sym.get(&t.instrument_name) else {
+            debug!("Deribit ticker for unknown instrument {}", t.instrument_name);
+            return Ok(vec![]);
+        };
+        // An empty side is reported as a zero price.
+        let (Some(bid), Some(ask)) = (
+            t.best_bid_price.filter(|p| *p > 0.0),
+            t.best_ask_price.filter(|p| *p > 0.0),
+        ) else {
+            return Ok(vec![]);
+        };
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: t.best_bid_amount,
+            ask_qty: t.best_ask_amount,
+            exchange_ts_raw: DateTime::from_timestamp_millis(t.timestamp),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(symbol.clone(), md)])
+    }
+}
+
+/// Perp BBO from the `ticker.{instrument}.100ms` channel. `symbols` are
+/// config symbols: "BTC_USD" for the inverse "BTC-PERPETUAL", "SOL_USDC"
+/// for the linear "SOL_USDC-PERPETUAL". Quantities are in the instrument's
+/// contract units (USD for inverse perps).
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(DeribitPerpFeed::new(symbols)?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "deribit_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Dated Futures Ticker Feed ---
+
+#[derive(Debug, Deserialize)]
+struct DeribitFuturesTicker {
+    /// e.g. "BTC-27MAR26"
+    instrument_name: String,
+    #[serde(default)]
+    best_bid_price: Option<f64>,
+    #[serde(default)]
+    best_bid_amount: Option<f64>,
+    #[serde(default)]
+    best_ask_price: Option<f64>,
+    #[serde(default)]
+    best_ask_amount: Option<f64>,
+    #[serde(default)]
+    mark_price: Option<f64>,
+    #[serde(default)]
+    index_price: Option<f64>,
+    /// ms
+    timestamp: i64,
+}
+
+/// Expand config entries into contract symbols. "BTC_USD_260327" passes
+/// through; a bare pair ("BTC_USD") becomes every listed expiry on it.
+async fn resolve_dated_futures(entries: &[&str]) -> Result<Vec<String>> {
+    let client = Client::new();
+    let mapper = DeribitMapper;
+    let mut names = Vec::new();
+    for entry in entries {
+        if let Some(contract) = FuturesContract::parse(entry) {
+            names.push(contract.name());
+            continue;
+        }
+        let upper = entry.to_uppercase();
+        let Some(base) = upper.strip_suffix("_USD") else {
+            warn!("Deribit: '{}' is neither a dated future nor an inverse pair, skipping", entry);
+            continue;
+        };
+        let before = names.len();
+        // `kind=future` lists the perpetual too; the mapper rejects it.
+        names.extend(
+            fetch_instruments(&client, base, "future")
+                .await?
+                .iter()
+                .filter_map(|n| mapper.normalize(n, InstrumentType::Futures).ok())
+                .filter_map(|n| FuturesContract::parse(&n).map(|c| c.name())),
+        );
+        if names.len() == before {
+            warn!("Deribit: no listed dated futures match '{}'", entry);
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+struct DeribitFuturesFeed {
+    itype: InstrumentType,
+    mapper: DeribitMapper,
+    /// Instrument ("BTC-27MAR26") -> config symbol ("BTC_USD_260327").
+    instrument_to_sym: HashMap<String, String>,
+    futures: Arc<FuturesDataCollection>,
+}
+
+impl DeribitFuturesFeed {
+    fn channels(&self, symbols: &[&str]) -> Result<Vec<String>> {
+        symbols
+            .iter()
+            .map(|sym| Ok(format!("ticker.{}.100ms", self.mapper.denormalize(sym, self.itype)?)))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for DeribitFuturesFeed {
+    type Item = FuturesData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.futures.get_or_insert(symbol)
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let channels = self.channels(symbols)?;
+        send_channels(write, "public/subscribe", &channels, channels.len().max(1)).await
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(HEARTBEAT.into()))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, FuturesData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg = serde_json::from_str::<DeribitMessage<DeribitFuturesTicker>>(text)?;
+        if msg.method.as_deref() != Some("subscription") {
+            return Ok(vec![]);
+        }
+        let Some(params) = msg.params else {
+            warn!("Deribit subscription push without params: {}", text);
+            return Ok(vec![]);
+        };
+        let t = params.data;
+        let Some(sym) = self.instrument_to_sym.get(&t.instrument_name) else {
+            return Ok(vec![]);
+        };
+        let positive = |v: Option<f64>| v.filter(|x| *x > 0.0);
+        let data = FuturesData {
+            bid: positive(t.best_bid_price),
+            ask: positive(t.best_ask_price),
+            bid_qty: t.best_bid_amount,
+            ask_qty: t.best_ask_amount,
+            mark_price: t.mark_price,
+            index_price: t.index_price,
+            exchange_ts_raw: DateTime::from_timestamp_millis(t.timestamp),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(sym.clone(), data)])
+    }
+}
+
+/// Inverse dated futures from `ticker.{instrument}.100ms`, with the
+/// settlement index on every push. `entries` are contract symbols
+/// ("BTC_USD_260327") or pairs ("BTC_USD") for every listed expiry,
+/// expanded against the instrument list once at startup. Quantities are
+/// in USD contracts.
+pub async fn listen_dated_futures_ticker(
+    data: Arc<FuturesDataCollection>,
+    entries: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let names = resolve_dated_futures(entries).await?;
+    if names.is_empty() {
+        bail!("no Deribit dated futures resolved from {:?}", entries);
+    }
+    let itype = InstrumentType::Futures;
+    let mapper = DeribitMapper;
+    let mut instrument_to_sym = HashMap::new();
+    for name in &names {
+        instrument_to_sym.insert(mapper.denormalize(name, itype)?, name.clone());
+    }
+    let refs: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    let feed = Arc::new(DeribitFuturesFeed {
+        itype,
+        mapper,
+        instrument_to_sym,
+        futures: Arc::clone(&data),
+    });
+    listen_with_reconnect(
+        data,
+        &refs,
+        feed,
+        "deribit_futures",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Options Ticker Feed ---
+
+/// Channels per subscribe request for option chains.
+const OPTION_SUBSCRIBE_CHUNK: usize = 200;
+
+/// Expand config entries into contract names. Contract names pass
+/// through; a `ChainSpec` ("BTC@28JUN25", "BTC@28JUN25@50000-70000")
+/// becomes every listed contract it matches.
+async fn resolve_option_instruments(entries: &[&str]) -> Result<Vec<String>> {
+    let client = Client::new();
+    // Settlement currency -> listed names, fetched once per currency.
+    let mut listed: HashMap<String, Vec<String>> = HashMap::new();
+    let mut names = Vec::new();
+    for entry in entries {
+        let Some(spec) = ChainSpec::parse(entry) else {
+            if OptionContract::parse(entry).is_none() {
+                warn!("Deribit: '{}' is neither an option contract nor a chain spec, skipping", entry);
+                continue;
+            }
+            names.push(entry.to_string());
+            continue;
+        };
+        // "SOL_USDC" options are listed under USDC.
+        let currency = spec.underlying.split_once('_').map_or(spec.underlying.as_str(), |(_, q)| q);
+        if !listed.contains_key(currency) {
+            let fetched = fetch_instruments(&client, currency, "option").await?;
+            listed.insert(currency.to_string(), fetched);
+        }
+        let before = names.len();
+        names.extend(
+            listed[currency]
+                .iter()
+                .filter(|n| OptionContract::parse(n).is_some_and(|c| spec.matches(&c)))
+                .cloned(),
+        );
+        if names.len() == before {
+            warn!("Deribit: no listed options match '{}'", entry);
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+#[derive(Debug, Deserialize)]
+struct DeribitOptionTicker {
+    /// e.g. "BTC-28JUN25-60000-C"
+    instrument_name: String,
+    #[serde(default)]
+    best_bid_price: Option<f64>,
+    #[serde(default)]
+    best_bid_amount: Option<f64>,
+    #[serde(default)]
+    best_ask_price: Option<f64>,
+    #[serde(default)]
+    best_ask_amount: Option<f64>,
+    #[serde(default)]
+    mark_price: Option<f64>,
+    /// IVs in percent.
+    #[serde(default)]
+    mark_iv: Option<f64>,
+    #[serde(default)]
+    bid_iv: Option<f64>,
+    #[serde(default)]
+    ask_iv: Option<f64>,
+    #[serde(default)]
+    underlying_price: Option<f64>,
+    #[serde(default)]
+    greeks: DeribitGreeks,
+    /// ms
+    timestamp: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DeribitGreeks {
+    #[serde(default)]
+    delta: Option<f64>,
+    #[serde(default)]
+    gamma: Option<f64>,
+    #[serde(default)]
+    theta: Option<f64>,
+    #[serde(default)]
+    vega: Option<f64>,
+}
+
+struct DeribitOptionsFeed {
+    itype: InstrumentType,
+    contracts: Arc<OptionContracts>,
+}
+
+impl DeribitOptionsFeed {
+    fn channels(symbols: &[&str]) -> Vec<String> {
+        symbols.iter().map(|name| format!("ticker.{name}.100ms")).collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for DeribitOptionsFeed {
+    type Item = OptionData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.contracts.get_or_insert(symbol)
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        send_channels(write, "public/subscribe", &Self::channels(symbols), OPTION_SUBSCRIBE_CHUNK).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        send_channels(write, "public/unsubscribe", &Self::channels(symbols), OPTION_SUBSCRIBE_CHUNK).await
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(HEARTBEAT.into()))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, OptionData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg = serde_json::from_str::<DeribitMessage<DeribitOptionTicker>>(text)?;
+        if msg.method.as_deref() != Some("subscription") {
+            return Ok(vec![]);
+        }
+        let Some(params) = msg.params else {
+            warn!("Deribit subscription push without params: {}", text);
+            return Ok(vec![]);
+        };
+        let t = params.data;
+        // Empty sides (and their IVs) come as zeros.
+        let positive = |v: Option<f64>| v.filter(|x| *x > 0.0);
+        let data = OptionData {
+            bid: positive(t.best_bid_price),
+            ask: positive(t.best_ask_price),
+            bid_qty: t.best_bid_amount,
+            ask_qty: t.best_ask_amount,
+            mark_price: t.mark_price,
+            mark_iv: t.mark_iv.map(|v| v / 100.0),
+            bid_iv: positive(t.bid_iv).map(|v| v / 100.0),
+            ask_iv: positive(t.ask_iv).map(|v| v / 100.0),
+            delta: t.greeks.delta,
+            gamma: t.greeks.gamma,
+            theta: t.greeks.theta,
+            vega: t.greeks.vega,
+            underlying_price: t.underlying_price,
+            exchange_ts_raw: DateTime::from_timestamp_millis(t.timestamp),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(t.instrument_name, data)])
+    }
+}
+
+/// Option quotes, IVs and greeks from `ticker.{instrument}.100ms`.
+/// `entries` are contract names ("BTC-28JUN25-60000-C") or chain specs
+/// ("BTC@28JUN25", "BTC@28JUN25@50000-70000"), expanded against the
+/// instrument list once at startup. Prices are in the settlement currency
+/// (BTC for inverse BTC options); IVs are decimals.
+pub async fn listen_options_ticker(
+    data: Arc<OptionDataCollection>,
+    entries: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let names = resolve_option_instruments(entries).await?;
+    if names.is_empty() {
+        bail!("no Deribit options resolved from {:?}", entries);
+    }
+    let refs: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    let feed = Arc::new(DeribitOptionsFeed {
+        itype: InstrumentType::Option,
+        contracts: Arc::clone(data.contracts()),
+    });
+    listen_with_reconnect(
+        data,
+        &refs,
+        feed,
+        "deribit_options",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+// --- Volatility Index (DVOL) Feed ---
+
+#[derive(Debug, Deserialize)]
+struct DeribitVolIndex {
+    /// e.g. "btc_usd"
+    index_name: String,
+    volatility: f64,
+    /// ms
+    timestamp: i64,
+}
+
+struct DeribitDvolFeed {
+    itype: InstrumentType,
+    indices: Arc<IndexCollection>,
+}
+
+/// "BTC" → "DVOL-BTC" (storage key); "btc_usd" → "DVOL-BTC" (from wire).
+fn dvol_key(currency_or_index: &str) -> String {
+    let ccy = currency_or_index.split('_').next().unwrap_or(currency_or_index);
+    format!("DVOL-{}", ccy.to_uppercase())
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for DeribitDvolFeed {
+    type Item = IndexData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|ccy| format!("deribit_volatility_index.{}_usd", ccy.to_lowercase()))
+            .collect();
+        let subscribe_msg = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "public/subscribe",
+            "params": { "channels": channels }
+        });
+        write
+            .send(Message::Text(subscribe_msg.to_string().into()))
+            .await?;
+        debug!("Deribit DVOL subscribed: {:?}", channels);
+        Ok(())
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(HEARTBEAT.into()))
+    }
+
+    fn resolve_symbol(&self, symbol: &str, _itype: &InstrumentType) -> Option<SymbolId> {
+        self.indices.get_or_insert(symbol)
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, IndexData)>> {
+        match msg {
+            WireMessage::Text(text) => {
+                let msg = serde_json::from_str::<DeribitMessage<DeribitVolIndex>>(text)?;
+                if msg.method.as_deref() != Some("subscription") {
+                    return Ok(vec![]);
+                }
+                let Some(params) = msg.params else {
+                    warn!("Deribit subscription push without params: {}", text);
+                    return Ok(vec![]);
+                };
+                let d = params.data;
+                let exchange_ts = DateTime::from_timestamp_millis(d.timestamp);
+                let data = IndexData {
+                    value: d.volatility,
+                    exchange_ts_raw: exchange_ts,
+                    received_ts: Some(received_ts),
+                    received_instant: Some(received_instant),
+                    ..Default::default()
+                };
+                Ok(vec![(dvol_key(&d.index_name), data)])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+/// Stream DVOL for each currency in `currencies` ("BTC", "ETH").
+pub async fn listen_dvol(
+    data: Arc<IndexCollection>,
+    currencies: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    // Pre-register so readers can resolve names before the first push.
+    for ccy in currencies {
+        data.get_or_insert(&dvol_key(ccy));
+    }
+    let feed = Arc::new(DeribitDvolFeed {
+        itype: InstrumentType::Index,
+        indices: Arc::clone(&data),
+    });
+    listen_with_reconnect(
+        data,
+        currencies,
+        feed,
+        "deribit_dvol",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -0,0 +1,267 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::mappers::{DydxMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::orderbook::{IMBALANCE_LEVELS, SyncBook};
+
+const WS_URL: &str = "wss://indexer.dydx.trade/v4/ws";
+const MARKETS_URL: &str = "https://indexer.dydx.trade/v4/perpetualMarkets";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(5.0, 1.0), FeeSchedule::new(5.0, 1.0))
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketsResponse {
+    markets: HashMap<String, MarketRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketRow {
+    status: String,
+}
+
+/// Tickers the indexer currently lists as ACTIVE.
+async fn fetch_active_tickers(client: &Client) -> Result<Vec<String>> {
+    let resp = client
+        .get(MARKETS_URL)
+        .send()
+        .await
+        .with_context(|| format!("GET {MARKETS_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {MARKETS_URL} -> {status}; body: {body}");
+    }
+    let markets: MarketsResponse = serde_json::from_str(&body).context("decode perpetualMarkets JSON")?;
+    Ok(markets
+        .markets
+        .into_iter()
+        .filter(|(_, m)| m.status == "ACTIVE")
+        .map(|(ticker, _)| ticker)
+        .collect())
+}
+
+struct DydxFeed {
+    /// Per-ticker books (single writer: one WS task).
+    books: HashMap<String, SyncBook>,
+    /// Ticker ("BTC-USD") -> config symbol ("BTC_USD") for the registry.
+    ticker_to_sym: HashMap<String, String>,
+    itype: InstrumentType,
+    mapper: DydxMapper,
+}
+
+impl DydxFeed {
+    /// Map config symbols to tickers, dropping any the indexer doesn't list
+    /// as active. Returns the feed and the config symbols it kept.
+    async fn new_perp(symbols: &[&str]) -> Result<(Self, Vec<String>)> {
+        let active = fetch_active_tickers(&Client::new()).await?;
+        let itype = InstrumentType::Perp;
+        let mapper = DydxMapper;
+        let mut ticker_to_sym = HashMap::new();
+        let mut kept = Vec::new();
+        for sym in symbols {
+            let ticker = mapper.denormalize(sym, itype)?;
+            if !active.contains(&ticker) {
+                warn!("dYdX market '{}' not active on the indexer, skipping", ticker);
+                continue;
+            }
+            ticker_to_sym.insert(ticker, sym.to_string());
+            kept.push(sym.to_string());
+        }
+        let books = ticker_to_sym.keys().map(|t| (t.clone(), SyncBook::new())).collect();
+        Ok((Self { books, ticker_to_sym, itype, mapper }, kept))
+    }
+
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        kind: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for sym in symbols {
+            let ticker = self.mapper.denormalize(sym, self.itype)?;
+            let msg = json!({ "type": kind, "channel": "v4_orderbook", "id": ticker });
+            write
+                .send(Message::Text(msg.to_string().into()))
+                .await
+                .with_context(|| format!("failed to {kind} dYdX v4_orderbook {ticker}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DydxMsg {
+    #[serde(rename = "type")]
+    msg_type: String,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    contents: Option<DydxBookContents>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DydxBookContents {
+    #[serde(default)]
+    bids: Vec<DydxLevel>,
+    #[serde(default)]
+    asks: Vec<DydxLevel>,
+}
+
+/// Snapshots send `{"price", "size"}` objects, updates `[price, size]` pairs.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DydxLevel {
+    Object { price: String, size: String },
+    Pair(String, String),
+}
+
+impl DydxLevel {
+    fn into_update(self) -> (String, f64) {
+        let (price, size) = match self {
+            DydxLevel::Object { price, size } => (price, size),
+            DydxLevel::Pair(price, size) => (price, size),
+        };
+        let size = size.parse::<f64>().unwrap_or(0.0);
+        (price, size)
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for DydxFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("v4_orderbook") {
+            // "connected", unsubscribe acks, etc.
+            return Ok(vec![]);
+        }
+
+        let msg: DydxMsg = serde_json::from_str(text)?;
+        if msg.channel.as_deref() != Some("v4_orderbook") {
+            return Ok(vec![]);
+        }
+        let is_snapshot = match msg.msg_type.as_str() {
+            "subscribed" => true,
+            "channel_data" => false,
+            "error" => bail!("dYdX error: {}", text),
+            _ => return Ok(vec![]),
+        };
+        let Some(ticker) = msg.id else { return Ok(vec![]) };
+        let (Some(book_cell), Some(symbol)) = (self.books.get(&ticker), self.ticker_to_sym.get(&ticker)) else {
+            debug!("dYdX update for unknown ticker {}", ticker);
+            return Ok(vec![]);
+        };
+
+        // SAFETY: single writer — one WS task per feed.
+        let book = unsafe { book_cell.get_mut() };
+        if is_snapshot {
+            book.clear();
+        }
+        let contents = msg.contents.unwrap_or_default();
+        if !contents.bids.is_empty() {
+            book.update_bids(contents.bids.into_iter().map(DydxLevel::into_update).collect());
+        }
+        if !contents.asks.is_empty() {
+            book.update_asks(contents.asks.into_iter().map(DydxLevel::into_update).collect());
+        }
+
+        let (Some((bid, bid_qty)), Some((ask, ask_qty))) = (book.best_bid(), book.best_ask()) else {
+            return Ok(vec![]);
+        };
+        // The indexer book can be briefly crossed while it uncrosses fills.
+        if bid >= ask {
+            return Ok(vec![]);
+        }
+
+        // Orderbook messages carry no exchange timestamp.
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(bid_qty),
+            ask_qty: Some(ask_qty),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
+            ..Default::default()
+        };
+        Ok(vec![(symbol.clone(), md)])
+    }
+}
+
+/// Perp BBO from the indexer `v4_orderbook` channel. `symbols` are config
+/// symbols like "BTC_USD"; markets not active on the indexer are skipped.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let (feed, kept) = DydxFeed::new_perp(symbols).await?;
+    if kept.is_empty() {
+        bail!("no active dYdX markets among {:?}", symbols);
+    }
+    let kept_refs: Vec<&str> = kept.iter().map(|s| s.as_str()).collect();
+    listen_with_reconnect(
+        data,
+        &kept_refs,
+        Arc::new(feed),
+        "dydx_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -0,0 +1,275 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::mappers::{GateioMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const SPOT_WS_URL: &str = "wss://api.gateio.ws/ws/v4/";
+const FUTURES_WS_URL: &str = "wss://fx-ws.gateio.ws/v4/ws/usdt";
+const CONTRACTS_URL: &str = "https://api.gateio.ws/api/v4/futures/usdt/contracts";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(20.0, 20.0), FeeSchedule::new(5.0, 2.0))
+}
+
+#[derive(Debug, Deserialize)]
+struct GateContract {
+    name: String,
+    /// Base units per contract, e.g. "0.0001" for BTC_USDT.
+    quanto_multiplier: String,
+}
+
+/// Contract name → base units per contract for the USDT futures in `contracts`.
+async fn fetch_quanto_multipliers(contracts: &[String]) -> Result<HashMap<String, f64>> {
+    let resp = reqwest::Client::new()
+        .get(CONTRACTS_URL)
+        .send()
+        .await
+        .with_context(|| format!("GET {CONTRACTS_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {CONTRACTS_URL} -> {status}; body: {body}");
+    }
+    let all: Vec<GateContract> = serde_json::from_str(&body).context("decode Gate.io contracts JSON")?;
+    let mut out = HashMap::new();
+    for c in all {
+        if contracts.contains(&c.name) {
+            let mult = c.quanto_multiplier.parse::<f64>().unwrap_or(1.0);
+            info!("Gate.io contract {}: quanto_multiplier={}", c.name, mult);
+            out.insert(c.name, mult);
+        }
+    }
+    Ok(out)
+}
+
+struct GateioFeed {
+    itype: InstrumentType,
+    mapper: GateioMapper,
+    /// "spot" or "futures": channel prefix for book_ticker and ping.
+    market: &'static str,
+    url: &'static str,
+    /// Perp only: contract → base units per contract.
+    multipliers: HashMap<String, f64>,
+}
+
+impl GateioFeed {
+    fn new_spot() -> Self {
+        Self {
+            itype: InstrumentType::Spot,
+            mapper: GateioMapper,
+            market: "spot",
+            url: SPOT_WS_URL,
+            multipliers: HashMap::new(),
+        }
+    }
+
+    async fn new_perp(symbols: &[&str]) -> Result<Self> {
+        let mapper = GateioMapper;
+        let contracts = symbols
+            .iter()
+            .map(|s| mapper.denormalize(s, InstrumentType::Perp))
+            .collect::<Result<Vec<_>>>()?;
+        let multipliers = fetch_quanto_multipliers(&contracts).await?;
+        for c in &contracts {
+            if !multipliers.contains_key(c) {
+                warn!("Gate.io contract {} not listed; sizes left in contracts", c);
+            }
+        }
+        Ok(Self {
+            itype: InstrumentType::Perp,
+            mapper,
+            market: "futures",
+            url: FUTURES_WS_URL,
+            multipliers,
+        })
+    }
+
+    fn channel(&self) -> String {
+        format!("{}.book_ticker", self.market)
+    }
+
+    async fn send_book_ticker_event(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        event: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let payload = symbols
+            .iter()
+            .map(|s| self.mapper.denormalize(s, self.itype))
+            .collect::<Result<Vec<_>>>()?;
+        let msg = json!({
+            "time": Utc::now().timestamp(),
+            "channel": self.channel(),
+            "event": event,
+            "payload": payload
+        });
+        write
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .with_context(|| format!("Failed to {event} Gate.io {}", self.channel()))?;
+        Ok(())
+    }
+}
+
+/// `result` is a `GateBookTicker` on updates and `{"status": ...}` on acks.
+#[derive(Debug, Deserialize)]
+struct GateMessage {
+    channel: String,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+}
+
+/// Prices are strings; sizes are strings on spot and contract counts
+/// (numbers) on futures.
+#[derive(Debug, Deserialize)]
+struct GateBookTicker {
+    /// ms
+    t: i64,
+    s: String,
+    b: String,
+    #[serde(rename = "B")]
+    bid_size: serde_json::Value,
+    a: String,
+    #[serde(rename = "A")]
+    ask_size: serde_json::Value,
+}
+
+fn size(v: &serde_json::Value) -> Option<f64> {
+    match v {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for GateioFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(self.url.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_book_ticker_event(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_book_ticker_event(write, "unsubscribe", symbols).await
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        let ping = json!({ "time": Utc::now().timestamp(), "channel": format!("{}.ping", self.market) });
+        Some(Message::Text(ping.to_string().into()))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg: GateMessage = serde_json::from_str(text)?;
+        if msg.error.as_ref().is_some_and(|e| !e.is_null()) {
+            bail!("Gate.io error: {}", text);
+        }
+        if msg.channel != self.channel() || msg.event != "update" {
+            // pongs, subscribe acks
+            debug!("Gate.io {}: {}", msg.channel, msg.event);
+            return Ok(vec![]);
+        }
+        let Some(result) = msg.result else { return Ok(vec![]) };
+        let t: GateBookTicker = serde_json::from_value(result)?;
+
+        let (Ok(bid), Ok(ask)) = (t.b.parse::<f64>(), t.a.parse::<f64>()) else {
+            // Empty side
+            return Ok(vec![]);
+        };
+        let mult = self.multipliers.get(&t.s).copied().unwrap_or(1.0);
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: size(&t.bid_size).map(|q| q * mult),
+            ask_qty: size(&t.ask_size).map(|q| q * mult),
+            exchange_ts_raw: DateTime::from_timestamp_millis(t.t),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        // "BTC_USDT" is a registry alias as-is.
+        Ok(vec![(t.s, md)])
+    }
+}
+
+pub async fn listen_spot_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(GateioFeed::new_spot());
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "gateio_spot",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
+
+/// USDT-margined futures BBO. Sizes are converted from contracts to base
+/// units with each contract's `quanto_multiplier`.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(GateioFeed::new_perp(symbols).await?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "gateio_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
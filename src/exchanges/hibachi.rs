@@ -4,7 +4,7 @@ use crate::exchanges::connection::{
 };
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
-use crate::orderbook::SyncBook;
+use crate::orderbook::{IMBALANCE_LEVELS, SyncBook};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use futures_util::SinkExt;
@@ -181,8 +181,7 @@ impl ExchangeFeed for HibachiFeed {
 
         let is_snapshot = ob.message_type == "Snapshot";
         if is_snapshot {
-            book.bids.clear();
-            book.asks.clear();
+            book.clear();
         }
 
         if !ob.data.bid.levels.is_empty() {
@@ -228,6 +227,7 @@ impl ExchangeFeed for HibachiFeed {
             exchange_ts_raw: exchange_ts,
             received_ts: Some(received_ts),
             received_instant: Some(received_instant),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
             ..Default::default()
         };
 
@@ -3,7 +3,7 @@ use crate::exchanges::connection::{
     ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
 };
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
-use crate::orderbook::OrderBook;
+use crate::orderbook::{IMBALANCE_LEVELS, OrderBook};
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -237,12 +237,14 @@ impl ExchangeFeed for HotstuffFeed {
                     exchange_ts_raw: exchange_ts,
                     received_ts: Some(received_ts),
                     received_instant: Some(received_instant),
+                    update_id: Some(books.sequence_number),
+                    imbalance: state.book.imbalance(IMBALANCE_LEVELS),
                     ..Default::default()
                 };
 
                 Ok(vec![(registry_sym, market_data)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -400,7 +402,7 @@ impl ExchangeFeed for HotstuffTradeFeed {
 
                 Ok(vec![(registry_sym, trade)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -0,0 +1,300 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, Decompression, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::mappers::{HtxMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const SPOT_WS_URL: &str = "wss://api.huobi.pro/ws";
+const SWAP_WS_URL: &str = "wss://api.hbdm.com/linear-swap-ws";
+const CONTRACT_INFO_URL: &str = "https://api.hbdm.com/linear-swap-api/v1/swap_contract_info";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(20.0, 20.0), FeeSchedule::new(5.0, 2.0))
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractInfoResponse {
+    status: String,
+    #[serde(default)]
+    data: Vec<ContractInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractInfo {
+    contract_code: String,
+    /// Base units per contract.
+    contract_size: f64,
+}
+
+/// Contract code ("BTC-USDT") → base units per contract for `codes`.
+async fn fetch_contract_sizes(codes: &[String]) -> Result<HashMap<String, f64>> {
+    let resp = reqwest::Client::new()
+        .get(CONTRACT_INFO_URL)
+        .send()
+        .await
+        .with_context(|| format!("GET {CONTRACT_INFO_URL} failed"))?;
+    let info: ContractInfoResponse = resp.json().await.context("decode HTX contract info")?;
+    if info.status != "ok" {
+        bail!("HTX contract info returned status {}", info.status);
+    }
+    let mut out = HashMap::new();
+    for c in info.data {
+        if codes.contains(&c.contract_code) {
+            info!("HTX contract {}: contract_size={}", c.contract_code, c.contract_size);
+            out.insert(c.contract_code, c.contract_size);
+        }
+    }
+    Ok(out)
+}
+
+/// Every HTX market-data frame is gzip-compressed JSON.
+fn connection_config() -> ConnectionConfig {
+    ConnectionConfig {
+        decompression: Decompression::Gzip,
+        ..ConnectionConfig::default()
+    }
+}
+
+struct HtxFeed {
+    url: &'static str,
+    itype: InstrumentType,
+    mapper: HtxMapper,
+    /// Swap only: contract code → base units per contract.
+    contract_sizes: HashMap<String, f64>,
+}
+
+impl HtxFeed {
+    fn new_spot() -> Self {
+        Self {
+            url: SPOT_WS_URL,
+            itype: InstrumentType::Spot,
+            mapper: HtxMapper,
+            contract_sizes: HashMap::new(),
+        }
+    }
+
+    async fn new_perp(symbols: &[&str]) -> Result<Self> {
+        let mapper = HtxMapper;
+        let codes = symbols
+            .iter()
+            .map(|s| mapper.denormalize(s, InstrumentType::Perp))
+            .collect::<Result<Vec<_>>>()?;
+        let contract_sizes = fetch_contract_sizes(&codes).await?;
+        for code in &codes {
+            if !contract_sizes.contains_key(code) {
+                warn!("HTX contract {} not listed; sizes left in contracts", code);
+            }
+        }
+        Ok(Self {
+            url: SWAP_WS_URL,
+            itype: InstrumentType::Perp,
+            mapper,
+            contract_sizes,
+        })
+    }
+
+    async fn send_bbo_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        op: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for symbol in symbols {
+            let native = self.mapper.denormalize(symbol, self.itype)?;
+            let msg = json!({ op: format!("market.{}.bbo", native), "id": native });
+            write
+                .send(Message::Text(msg.to_string().into()))
+                .await
+                .with_context(|| format!("Failed to {op} HTX bbo for {native}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Pushes carry `ch` + `tick`; acks carry `status`; server pings `ping`.
+#[derive(Debug, Deserialize)]
+struct HtxMessage {
+    #[serde(default)]
+    ch: Option<String>,
+    #[serde(default)]
+    tick: Option<serde_json::Value>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    ping: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HtxSpotBbo {
+    bid: f64,
+    bid_size: f64,
+    ask: f64,
+    ask_size: f64,
+    /// ms
+    quote_time: i64,
+}
+
+/// Swap levels are `[price, contracts]`.
+#[derive(Debug, Deserialize)]
+struct HtxSwapBbo {
+    #[serde(default)]
+    bid: Option<(f64, f64)>,
+    #[serde(default)]
+    ask: Option<(f64, f64)>,
+    /// ms
+    ts: i64,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for HtxFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(self.url.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_bbo_requests(write, "sub", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_bbo_requests(write, "unsub", symbols).await
+    }
+
+    /// The server pings `{"ping": ts}` every few seconds and drops the
+    /// connection after two unanswered; echo the timestamp back.
+    async fn process_other(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        text: &str,
+    ) -> Result<()> {
+        if !text.contains("\"ping\"") {
+            return Ok(());
+        }
+        if let Some(ts) = serde_json::from_str::<HtxMessage>(text)?.ping {
+            write
+                .send(Message::Text(json!({ "pong": ts }).to_string().into()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        let msg: HtxMessage = serde_json::from_str(text)?;
+        if msg.ping.is_some() {
+            return Ok(vec![]);
+        }
+        if msg.status.as_deref() == Some("error") {
+            bail!("HTX error: {}", text);
+        }
+        let (Some(ch), Some(tick)) = (msg.ch, msg.tick) else {
+            debug!("HTX non-data message: {}", text);
+            return Ok(vec![]);
+        };
+        // "market.btcusdt.bbo" / "market.BTC-USDT.bbo"
+        let Some(native) = ch.strip_prefix("market.").and_then(|c| c.strip_suffix(".bbo")) else {
+            return Ok(vec![]);
+        };
+
+        let (bid, bid_qty, ask, ask_qty, ts_ms) = match self.itype {
+            InstrumentType::Perp => {
+                let t: HtxSwapBbo = serde_json::from_value(tick)?;
+                let (Some((bid, bid_cont)), Some((ask, ask_cont))) = (t.bid, t.ask) else {
+                    return Ok(vec![]);
+                };
+                let size = self.contract_sizes.get(native).copied().unwrap_or(1.0);
+                (bid, bid_cont * size, ask, ask_cont * size, t.ts)
+            }
+            _ => {
+                let t: HtxSpotBbo = serde_json::from_value(tick)?;
+                (t.bid, t.bid_size, t.ask, t.ask_size, t.quote_time)
+            }
+        };
+
+        let (base, quote) = self.mapper.parse(native, self.itype)?;
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(bid_qty),
+            ask_qty: Some(ask_qty),
+            exchange_ts_raw: DateTime::from_timestamp_millis(ts_ms),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(format!("{}_{}", base, quote), md)])
+    }
+}
+
+pub async fn listen_spot_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(HtxFeed::new_spot());
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "htx_spot",
+        connection_config(),
+        shutdown,
+    )
+    .await
+}
+
+/// USDT-margined swap BBO. Sizes are converted from contracts to base
+/// units with each contract's `contract_size`.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(HtxFeed::new_perp(symbols).await?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "htx_perp",
+        connection_config(),
+        shutdown,
+    )
+    .await
+}
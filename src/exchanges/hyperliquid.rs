@@ -2,10 +2,11 @@ use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
     ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
 };
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, Utc};
 use futures_util::SinkExt;
 use futures_util::stream::SplitSink;
 use log::debug;
@@ -85,20 +86,19 @@ impl ExchangeFeed for HyperliquidFeed {
         write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
         symbols: &[&str],
     ) -> Result<()> {
-        for sym in symbols {
-            let coin = Self::coin_from_config(sym);
-            let sub_msg = json!({
-                "method": "subscribe",
-                "subscription": {
-                    "type": "l2Book",
-                    "coin": coin
-                }
-            });
-            write
-                .send(Message::Text(sub_msg.to_string().into()))
-                .await?;
-        }
-        Ok(())
+        send_bbo_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        send_bbo_requests(write, "unsubscribe", symbols).await
     }
 
     fn parse_message(
@@ -116,11 +116,11 @@ impl ExchangeFeed for HyperliquidFeed {
                     return Ok(vec![]);
                 }
 
-                if !text.contains("\"channel\":\"l2Book\"") {
+                if !text.contains("\"channel\":\"bbo\"") {
                     return Ok(vec![]);
                 }
 
-                let response: HyperliquidL2Book = serde_json::from_str(text)?;
+                let response: HyperliquidBbo = serde_json::from_str(text)?;
                 let coin = &response.data.coin;
 
                 let registry_sym = match self.coin_to_symbol.get(coin) {
@@ -131,13 +131,12 @@ impl ExchangeFeed for HyperliquidFeed {
                     }
                 };
 
-                let bids = &response.data.levels[0];
-                let asks = &response.data.levels[1];
-
-                let bid = bids.first().and_then(|l| l.px.parse::<f64>().ok());
-                let ask = asks.first().and_then(|l| l.px.parse::<f64>().ok());
-                let bid_qty = bids.first().and_then(|l| l.sz.parse::<f64>().ok());
-                let ask_qty = asks.first().and_then(|l| l.sz.parse::<f64>().ok());
+                // [bid, ask]; a side is null when that side of the book is empty.
+                let [bid_level, ask_level] = &response.data.bbo;
+                let px = |l: &Option<HyperliquidLevel>| l.as_ref().and_then(|l| l.px.parse::<f64>().ok());
+                let sz = |l: &Option<HyperliquidLevel>| l.as_ref().and_then(|l| l.sz.parse::<f64>().ok());
+                let (bid, ask) = (px(bid_level), px(ask_level));
+                let (bid_qty, ask_qty) = (sz(bid_level), sz(ask_level));
 
                 let exchange_ts =
                     DateTime::from_timestamp_millis(response.data.time as i64)
@@ -156,21 +155,22 @@ impl ExchangeFeed for HyperliquidFeed {
 
                 Ok(vec![(registry_sym, market_data)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
 
+/// `bbo` channel: pushed on every block where the top of book changed.
 #[derive(Debug, Deserialize)]
-struct HyperliquidL2Book {
-    data: HyperliquidL2Data,
+struct HyperliquidBbo {
+    data: HyperliquidBboData,
 }
 
 #[derive(Debug, Deserialize)]
-struct HyperliquidL2Data {
+struct HyperliquidBboData {
     coin: String,
     time: u64,
-    levels: Vec<Vec<HyperliquidLevel>>,
+    bbo: [Option<HyperliquidLevel>; 2],
 }
 
 #[derive(Debug, Deserialize)]
@@ -181,6 +181,24 @@ struct HyperliquidLevel {
     n: u64,
 }
 
+async fn send_bbo_requests(
+    write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+    method: &str,
+    symbols: &[&str],
+) -> Result<()> {
+    for sym in symbols {
+        let msg = json!({
+            "method": method,
+            "subscription": {
+                "type": "bbo",
+                "coin": HyperliquidFeed::coin_from_config(sym)
+            }
+        });
+        write.send(Message::Text(msg.to_string().into())).await?;
+    }
+    Ok(())
+}
+
 pub async fn listen_perp_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
@@ -338,7 +356,7 @@ impl ExchangeFeed for HyperliquidTradeFeed {
 
                 Ok(results)
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -359,3 +377,145 @@ pub async fn listen_perp_trades(
     )
     .await
 }
+
+// ---------------------------------------------------------------------------
+// Asset context feed (funding, OI, oracle)
+// ---------------------------------------------------------------------------
+
+// Wire: {"channel":"activeAssetCtx","data":{"coin":"BTC","ctx":{"funding":"0.0000125",
+//   "openInterest":"1234.5","oraclePx":"77010.0","markPx":"77000.0","premium":"-0.00013",...}}}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidAssetCtxMsg {
+    data: HyperliquidAssetCtxData,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidAssetCtxData {
+    coin: String,
+    ctx: HyperliquidAssetCtx,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyperliquidAssetCtx {
+    funding: Option<String>,
+    open_interest: Option<String>,
+    oracle_px: Option<String>,
+    mark_px: Option<String>,
+    premium: Option<String>,
+}
+
+struct HyperliquidAssetCtxFeed {
+    inner: HyperliquidFeed,
+}
+
+fn parse_opt(v: &Option<String>) -> Option<f64> {
+    v.as_deref().and_then(|s| s.parse::<f64>().ok())
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for HyperliquidAssetCtxFeed {
+    type Item = DerivStats;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.inner.itype)
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        self.inner.heartbeat_message()
+    }
+
+    fn build_url(&self, symbols: &[&str]) -> Result<String> {
+        self.inner.build_url(symbols)
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for sym in symbols {
+            let coin = HyperliquidFeed::coin_from_config(sym);
+            let sub_msg = json!({
+                "method": "subscribe",
+                "subscription": {
+                    "type": "activeAssetCtx",
+                    "coin": coin
+                }
+            });
+            write
+                .send(Message::Text(sub_msg.to_string().into()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, DerivStats)>> {
+        match msg {
+            WireMessage::Text(text) => {
+                if !text.contains("\"channel\":\"activeAssetCtx\"") {
+                    return Ok(vec![]);
+                }
+
+                let response: HyperliquidAssetCtxMsg = serde_json::from_str(text)?;
+                let coin = &response.data.coin;
+                let registry_sym = match self.inner.coin_to_symbol.get(coin) {
+                    Some(s) => s.clone(),
+                    None => {
+                        debug!("Unknown coin from Hyperliquid asset ctx: {}", coin);
+                        return Ok(vec![]);
+                    }
+                };
+
+                // Funding settles hourly on the hour; the message carries no timestamp.
+                let ctx = &response.data.ctx;
+                let next_funding_ts = received_ts
+                    .duration_trunc(chrono::Duration::hours(1))
+                    .ok()
+                    .map(|t| t + chrono::Duration::hours(1));
+
+                let stats = DerivStats {
+                    funding_rate: parse_opt(&ctx.funding),
+                    funding_interval_h: Some(1.0),
+                    next_funding_ts,
+                    open_interest: parse_opt(&ctx.open_interest),
+                    mark_price: parse_opt(&ctx.mark_px),
+                    oracle_price: parse_opt(&ctx.oracle_px),
+                    premium: parse_opt(&ctx.premium),
+                    received_ts: Some(received_ts),
+                    received_instant: Some(received_instant),
+                    ..Default::default()
+                };
+
+                Ok(vec![(registry_sym, stats)])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+/// Stream funding, open interest, mark and oracle prices per coin.
+pub async fn listen_perp_asset_ctx(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(HyperliquidAssetCtxFeed {
+        inner: HyperliquidFeed::new(symbols),
+    });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "hyperliquid_asset_ctx",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -1,18 +1,20 @@
+use crate::app_config::KrakenSpotMode;
 use crate::mappers::{KrakenMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::market_data::{Exchange, InstrumentType, MarketData, MarketDataCollection};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use futures_util::{SinkExt, stream::SplitSink};
+use futures_util::stream::SplitSink;
 use log::{debug, warn};
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect, send_subscribe_batches,
 };
 
 pub fn get_fees() -> ExchangeFees {
@@ -53,18 +55,18 @@ impl ExchangeFeed for KrakenFeed {
             .iter()
             .map(|s| self.mapper.denormalize(s, self.itype))
             .collect::<Result<Vec<_>, _>>()?;
-        let subscribe_msg = json!({
-            "event": "subscribe",
-            "pair": pairs,
-            "subscription": {
-                "name": "spread"
-            }
-        });
-        write
-            .send(Message::Text(subscribe_msg.to_string().into()))
-            .await
-            .context("Failed to subscribe to kraken")?;
-        Ok(())
+        send_subscribe_batches(Exchange::Kraken, write, &pairs, |batch| {
+            let msg = json!({
+                "event": "subscribe",
+                "pair": batch,
+                "subscription": {
+                    "name": "spread"
+                }
+            });
+            Message::Text(msg.to_string().into())
+        })
+        .await
+        .context("Failed to subscribe to kraken")
     }
     fn parse_message(
         &self,
@@ -75,7 +77,6 @@ impl ExchangeFeed for KrakenFeed {
         match self.itype {
             InstrumentType::Spot => {
                 match msg {
-                    WireMessage::Binary(_) => Ok(vec![]),
                     WireMessage::Text(text) => {
                         // Handle subscription confirmation
                         if text.contains("\"event\":\"subscriptionStatus\"") {
@@ -154,6 +155,7 @@ impl ExchangeFeed for KrakenFeed {
                         }
                         return Ok(vec![]);
                     }
+                    _ => Ok(vec![]),
                 }
             }
             _ => Ok(vec![]),
@@ -161,21 +163,188 @@ impl ExchangeFeed for KrakenFeed {
     }
 }
 
+// --- Kraken spot WebSocket v2 ---
+
+#[derive(Debug, Deserialize)]
+struct KrakenV2Message {
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    data: Vec<KrakenV2Ticker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenV2Ticker {
+    /// e.g. "BTC/USD"
+    symbol: String,
+    bid: f64,
+    bid_qty: f64,
+    ask: f64,
+    ask_qty: f64,
+}
+
+/// v2 names BTC as "BTC"; v1 and config symbols use "XBT".
+fn v2_pair(v1_pair: &str) -> String {
+    match v1_pair.split_once('/') {
+        Some((base, quote)) => {
+            let base = if base == "XBT" { "BTC" } else { base };
+            let quote = if quote == "XBT" { "BTC" } else { quote };
+            format!("{base}/{quote}")
+        }
+        None => v1_pair.to_string(),
+    }
+}
+
+struct KrakenV2Feed {
+    itype: InstrumentType,
+    mapper: KrakenMapper,
+    /// v2 pair ("BTC/USD") -> config symbol, for the registry.
+    pair_to_sym: HashMap<String, String>,
+}
+
+impl KrakenV2Feed {
+    fn new_spot(symbols: &[&str]) -> Result<Self> {
+        let itype = InstrumentType::Spot;
+        let mapper = KrakenMapper;
+        let mut pair_to_sym = HashMap::new();
+        for sym in symbols {
+            pair_to_sym.insert(v2_pair(&mapper.denormalize(sym, itype)?), sym.to_string());
+        }
+        Ok(Self { itype, mapper, pair_to_sym })
+    }
+
+    async fn send_requests(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        method: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let pairs = symbols
+            .iter()
+            .map(|s| Ok(v2_pair(&self.mapper.denormalize(s, self.itype)?)))
+            .collect::<Result<Vec<String>>>()?;
+        send_subscribe_batches(Exchange::Kraken, write, &pairs, |batch| {
+            let msg = json!({
+                "method": method,
+                "params": { "channel": "ticker", "symbol": batch, "event_trigger": "bbo" }
+            });
+            Message::Text(msg.to_string().into())
+        })
+        .await
+        .with_context(|| format!("Kraken v2 {method} failed for {pairs:?}"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for KrakenV2Feed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://ws.kraken.com/v2".to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "subscribe", symbols).await
+    }
+
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
+
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_requests(write, "unsubscribe", symbols).await
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"channel\":\"ticker\"") {
+            // heartbeat, status, method acks
+            if text.contains("\"success\":false") {
+                warn!("Kraken v2 request failed: {}", text);
+            }
+            return Ok(vec![]);
+        }
+        let msg = serde_json::from_str::<KrakenV2Message>(text)?;
+        if msg.channel.as_deref() != Some("ticker") {
+            return Ok(vec![]);
+        }
+        // The ticker carries no exchange timestamp.
+        Ok(msg
+            .data
+            .into_iter()
+            .filter_map(|t| {
+                let Some(symbol) = self.pair_to_sym.get(&t.symbol) else {
+                    debug!("Kraken v2 ticker for unknown pair {}", t.symbol);
+                    return None;
+                };
+                let md = MarketData {
+                    bid: Some(t.bid),
+                    ask: Some(t.ask),
+                    bid_qty: Some(t.bid_qty),
+                    ask_qty: Some(t.ask_qty),
+                    received_ts: Some(received_ts),
+                    received_instant: Some(received_instant),
+                    ..Default::default()
+                };
+                Some((symbol.clone(), md))
+            })
+            .collect())
+    }
+}
+
+/// Spot BBO from the v1 `spread` channel or, with `KrakenSpotMode::V2`,
+/// the v2 `ticker` channel triggered on BBO changes.
 pub async fn listen_spot_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: KrakenSpotMode,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(KrakenFeed::new_spot());
-    listen_with_reconnect(
-        data,
-        symbols,
-        feed,
-        "kraken_spot",
-        ConnectionConfig::default(),
-        shutdown,
-    )
-    .await
+    match mode {
+        KrakenSpotMode::V1 => {
+            let feed = Arc::new(KrakenFeed::new_spot());
+            listen_with_reconnect(
+                data,
+                symbols,
+                feed,
+                "kraken_spot",
+                ConnectionConfig::default(),
+                shutdown,
+            )
+            .await
+        }
+        KrakenSpotMode::V2 => {
+            let feed = Arc::new(KrakenV2Feed::new_spot(symbols)?);
+            listen_with_reconnect(
+                data,
+                symbols,
+                feed,
+                "kraken_spot_v2",
+                ConnectionConfig::default(),
+                shutdown,
+            )
+            .await
+        }
+    }
 }
 
 // --- Kraken Futures WebSocket (perps) ---
@@ -204,6 +373,29 @@ impl KrakenFuturesFeed {
             mapper: KrakenMapper,
         }
     }
+
+    async fn send_ticker_event(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        event: &str,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let product_ids: Vec<String> = symbols
+            .iter()
+            .map(|s| self.mapper.denormalize(s, self.itype))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        send_subscribe_batches(Exchange::Kraken, write, &product_ids, |batch| {
+            let msg = json!({
+                "event": event,
+                "feed": "ticker",
+                "product_ids": batch
+            });
+            Message::Text(msg.to_string().into())
+        })
+        .await
+        .with_context(|| format!("Failed to {event} kraken futures tickers"))
+    }
 }
 
 #[async_trait::async_trait]
@@ -223,23 +415,19 @@ impl ExchangeFeed for KrakenFuturesFeed {
         write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
         symbols: &[&str],
     ) -> Result<()> {
-        let product_ids: Vec<String> = symbols
-            .iter()
-            .map(|s| self.mapper.denormalize(s, self.itype))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let subscribe_msg = json!({
-            "event": "subscribe",
-            "feed": "ticker",
-            "product_ids": product_ids
-        });
+        self.send_ticker_event(write, "subscribe", symbols).await
+    }
 
-        write
-            .send(Message::Text(subscribe_msg.to_string().into()))
-            .await
-            .context("Failed to subscribe to kraken futures")?;
+    fn incremental_subscribe(&self) -> bool {
+        true
+    }
 
-        Ok(())
+    async fn send_unsubscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        self.send_ticker_event(write, "unsubscribe", symbols).await
     }
 
     fn parse_message(
@@ -286,13 +474,13 @@ impl ExchangeFeed for KrakenFuturesFeed {
                     ..Default::default()
                 };
 
-                // PI_XBTUSD -> BTCUSD for registry lookup
+                // PI_XBTUSD / PF_XBTUSD -> BTCUSD for registry lookup
                 let (base, quote) = self.mapper.parse(&ticker.product_id, self.itype)?;
                 let sym = format!("{}{}", base, quote);
 
                 Ok(vec![(sym, market_data)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -313,3 +501,24 @@ pub async fn listen_perp_bbo(
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_spread_frame() {
+        let feed = KrakenFeed::new_spot();
+        let text = r#"[0,["5698.40000","5700.00000","1542057299.545897","1.01234567","0.98765432"],"spread","XBT/USD"]"#;
+        let out = feed.parse_message(WireMessage::Text(text), Utc::now(), std::time::Instant::now()).unwrap();
+        assert_eq!(out.len(), 1);
+        let (symbol, md) = &out[0];
+        assert_eq!(symbol, "XBT/USD");
+        assert_eq!((md.bid, md.ask), (Some(5698.4), Some(5700.0)));
+        assert_eq!((md.bid_qty, md.ask_qty), (Some(1.01234567), Some(0.98765432)));
+        assert!(md.exchange_ts_raw.is_some());
+
+        let heartbeat = r#"{"event":"heartbeat"}"#;
+        assert!(feed.parse_message(WireMessage::Text(heartbeat), Utc::now(), std::time::Instant::now()).unwrap().is_empty());
+    }
+}
@@ -87,6 +87,7 @@ struct KucoinSpotTickerData {
     #[serde(rename = "bestAskSize")]
     best_ask_size: Option<String>,
     time: Option<u64>,
+    sequence: Option<String>,
 }
 
 /// Futures ticker data from `/contractMarket/tickerV2:{symbol}`
@@ -102,6 +103,7 @@ struct KucoinFuturesTickerData {
     #[serde(rename = "bestAskSize")]
     best_ask_size: Option<serde_json::Value>,
     ts: Option<u64>,
+    sequence: Option<u64>,
 }
 
 fn parse_f64(v: &Option<serde_json::Value>) -> Option<f64> {
@@ -313,6 +315,7 @@ impl ExchangeFeed for KucoinFeed {
                             exchange_ts_raw: exchange_ts,
                             received_ts: Some(received_ts),
                             received_instant: Some(received_instant),
+                            update_id: ticker.sequence.as_deref().and_then(|s| s.parse::<u64>().ok()),
                             ..Default::default()
                         },
                     )])
@@ -352,6 +355,7 @@ impl ExchangeFeed for KucoinFeed {
                             exchange_ts_raw: exchange_ts,
                             received_ts: Some(received_ts),
                             received_instant: Some(received_instant),
+                            update_id: ticker.sequence,
                             ..Default::default()
                         },
                     )])
@@ -360,7 +364,7 @@ impl ExchangeFeed for KucoinFeed {
                     Ok(vec![])
                 }
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 
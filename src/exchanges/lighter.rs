@@ -8,24 +8,26 @@ use reqwest::header::{ACCEPT, USER_AGENT};
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
 
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, SequenceStatus, SequenceTracker, WireMessage, listen_with_reconnect,
 };
 use crate::mappers::{LighterMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
-use crate::orderbook::SyncBook;
+use crate::market_data::{BookSide, Exchange, InstrumentType, MarketData, MarketDataCollection};
+use crate::orderbook::{
+    BookHandle, DEFAULT_MAX_LEVELS, DEFAULT_TICK_SIZE, DepthPublisher, IMBALANCE_LEVELS, LiveBooks, OrderBook, publish_deltas, publish_reset,
+};
 
 pub fn get_fees() -> ExchangeFees {
     ExchangeFees::new(FeeSchedule::new(0.0, 0.0), FeeSchedule::new(0.0, 0.0))
 }
 
-type Book = SyncBook;
+type Book = BookHandle;
 
 #[derive(Debug, Clone, Deserialize)]
 struct MarketIndexRow {
@@ -56,7 +58,7 @@ async fn fetch_market_indices(client: &Client) -> Result<Vec<MarketIndexRow>> {
 }
 
 struct LighterFeed {
-    /// Per-symbol orderbooks (single-writer: one WS task).
+    /// Per-symbol orderbooks, written by the WS task.
     books: HashMap<String, Book>,
     /// Requested symbol -> market_index (symbols are API symbols like "ETH", "BTC", etc.)
     sym_to_index: HashMap<String, u32>,
@@ -64,12 +66,21 @@ struct LighterFeed {
     index_to_sym: HashMap<u32, String>,
     itype: InstrumentType,
     mapper: LighterMapper,
+    /// Top-N snapshots of the books.
+    depth: Option<DepthPublisher>,
+    /// Order book `offset` per symbol. It only moves forward but skips
+    /// numbers, so a backward step is all that can be told apart.
+    offsets: SequenceTracker,
 }
 
 impl LighterFeed {
     /// Build the feed by loading the dynamic market index mapping from REST.
     /// `symbols` must be API symbols exactly as returned by the markets endpoint (e.g. "ETH", not "ETH-USD").
-    async fn new_perp(normalized_symbols: &[&str]) -> Result<Self> {
+    async fn new_perp(
+        normalized_symbols: &[&str],
+        depth: Option<DepthPublisher>,
+        live_books: Option<Arc<LiveBooks>>,
+    ) -> Result<Self> {
         let client = Client::new();
         let rows = fetch_market_indices(&client).await?;
         let itype = InstrumentType::Perp;
@@ -106,10 +117,21 @@ impl LighterFeed {
             index_to_sym.insert(idx, config_sym.to_string());
         }
 
-        let books = config_symbols
-            .iter()
-            .map(|s| (s.to_string(), SyncBook::new()))
-            .collect();
+        // Venue tick per market, so book keys match its price grid.
+        #[cfg(feature = "analytics")]
+        let ticks = crate::instruments::tick_sizes(Exchange::Lighter, itype, normalized_symbols).await;
+        #[cfg(not(feature = "analytics"))]
+        let ticks: HashMap<String, f64> = HashMap::new();
+
+        let mut books = HashMap::new();
+        for (native, sym) in native_symbols.iter().zip(config_symbols.iter()) {
+            let tick = ticks.get(native).copied().unwrap_or(DEFAULT_TICK_SIZE);
+            let book: Book = Arc::new(Mutex::new(OrderBook::builder().tick_size(tick).max_levels(DEFAULT_MAX_LEVELS).build()));
+            if let Some(live) = &live_books {
+                live.register(Exchange::Lighter, sym, &itype, &book);
+            }
+            books.insert(sym.clone(), book);
+        }
 
         Ok(Self {
             books,
@@ -117,6 +139,8 @@ impl LighterFeed {
             index_to_sym,
             itype,
             mapper,
+            depth,
+            offsets: SequenceTracker::monotonic(),
         })
     }
 }
@@ -153,6 +177,14 @@ struct PriceLevel {
     size: String,
 }
 
+impl PriceLevel {
+    /// (price, size); None for an unparsable price, size 0 (a removal) for
+    /// an unparsable size.
+    fn level(&self) -> Option<(f64, f64)> {
+        Some((self.price.parse().ok()?, self.size.parse().unwrap_or(0.0)))
+    }
+}
+
 fn parse_market_index(channel: &str) -> Option<u32> {
     channel.strip_prefix("order_book:")?.parse().ok()
 }
@@ -169,6 +201,15 @@ impl ExchangeFeed for LighterFeed {
         false // incremental depth feed
     }
 
+    /// The first message after subscribing is a full snapshot.
+    fn on_connected(&self) {
+        for (symbol, book) in &self.books {
+            book.lock().unwrap().clear();
+            publish_reset(Exchange::Lighter, symbol, &self.itype);
+        }
+        self.offsets.reset();
+    }
+
     fn build_url(&self, _symbols: &[&str]) -> Result<String> {
         Ok("wss://mainnet.zklighter.elliot.ai/stream".to_string())
     }
@@ -276,26 +317,35 @@ impl ExchangeFeed for LighterFeed {
             return Ok(vec![]);
         };
 
-        // SAFETY: single writer — one WS task per feed.
-        let book = unsafe { book_cell.get_mut() };
+        // Replayed or reordered updates must not touch the book.
+        if self.offsets.check(symbol, ob.offset) == SequenceStatus::Stale {
+            return Ok(vec![]);
+        }
+
+        let mut book = book_cell.lock().unwrap();
 
+        // Each side's levels are parsed once, for the book and the deltas.
+        let mut levels = Vec::with_capacity(ob.order_book.bids.len().max(ob.order_book.asks.len()));
         if !ob.order_book.bids.is_empty() {
-            book.update_bids(
-                ob.order_book
-                    .bids
-                    .iter()
-                    .map(|l| (l.price.clone(), l.size.parse::<f64>().unwrap_or(0.0)))
-                    .collect(),
-            );
+            levels.extend(ob.order_book.bids.iter().filter_map(PriceLevel::level));
+            book.update_bids_f64(&levels);
+            publish_deltas(Exchange::Lighter, symbol, &self.itype, BookSide::Bid, levels.iter().copied());
         }
         if !ob.order_book.asks.is_empty() {
-            book.update_asks(
-                ob.order_book
-                    .asks
-                    .iter()
-                    .map(|l| (l.price.clone(), l.size.parse::<f64>().unwrap_or(0.0)))
-                    .collect(),
-            );
+            levels.clear();
+            levels.extend(ob.order_book.asks.iter().filter_map(PriceLevel::level));
+            book.update_asks_f64(&levels);
+            publish_deltas(Exchange::Lighter, symbol, &self.itype, BookSide::Ask, levels.iter().copied());
+        }
+
+        let exchange_ts = ob
+            .order_book
+            .timestamp
+            .and_then(|ms| DateTime::from_timestamp_millis(ms as i64));
+        book.mark_update(Some(ob.offset), Some(exchange_ts.unwrap_or(received_ts)));
+
+        if let Some(publisher) = &self.depth {
+            publisher.publish(symbol, &self.itype, &book);
         }
 
         let (bid, bid_qty) = book
@@ -319,11 +369,6 @@ impl ExchangeFeed for LighterFeed {
             }
         }
 
-        let exchange_ts = ob
-            .order_book
-            .timestamp
-            .and_then(|ms| DateTime::from_timestamp_millis(ms as i64));
-
         let md = MarketData {
             bid,
             ask,
@@ -332,6 +377,8 @@ impl ExchangeFeed for LighterFeed {
             exchange_ts_raw: exchange_ts,
             received_ts: Some(received_ts),
             received_instant: Some(received_instant),
+            update_id: Some(ob.offset),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
             ..Default::default()
         };
 
@@ -341,12 +388,16 @@ impl ExchangeFeed for LighterFeed {
 
 /// Public entry point (perp “BBO” derived from order book best levels)
 /// IMPORTANT: `symbols` must be API symbols exactly as returned by the markets endpoint (e.g. ["ETH", "BTC"]).
+/// `depth`, if given, receives the books' top levels and `live_books` a
+/// handle to each.
 pub async fn listen_perp_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    depth: Option<DepthPublisher>,
+    live_books: Option<Arc<LiveBooks>>,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(LighterFeed::new_perp(symbols).await?);
+    let feed = Arc::new(LighterFeed::new_perp(symbols, depth, live_books).await?);
 
     listen_with_reconnect(
         data,
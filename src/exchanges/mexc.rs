@@ -1,21 +1,23 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, stream::SplitSink};
-use log::error;
+use log::{error, warn};
 use prost::Message as ProstMessage;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
+use crate::app_config::MexcPerpMode;
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
 use crate::exchanges::connection::{
     ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
 };
 use crate::mappers::{MexcMapper, SymbolMapper};
-use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
-use crate::orderbook::SyncBook;
+use crate::market_data::{BookSide, Exchange, InstrumentType, MarketData, MarketDataCollection};
+use crate::orderbook::{BookHandle, DEFAULT_MAX_LEVELS, DEFAULT_TICK_SIZE, DepthPublisher, IMBALANCE_LEVELS, LiveBooks, OrderBook, publish_deltas};
 
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 
@@ -77,23 +79,50 @@ struct MexcFuturesDepthData {
     version: i64,
 }
 
-// Convert futures depth levels into your OrderBook::update_* format: Vec<(String, f64)>
-// - price as String (your OrderBook parses it)
-// - size = quantity (2nd element)
-fn depth_levels_to_updates(levels: &[[f64; 3]]) -> Vec<(String, f64)> {
-    levels
-        .iter()
-        .map(|lvl| (lvl[0].to_string(), lvl[1]))
-        .collect()
+/// `[price, size, order count]` rows as (price, size).
+fn depth_levels(levels: &[[f64; 3]]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    levels.iter().map(|lvl| (lvl[0], lvl[1]))
 }
 
-type Book = SyncBook;
+// ---- Futures perps ticker ----
+// {
+//   "channel":"push.ticker",
+//   "data":{"symbol":"BTC_USDT","bid1":6865,"ask1":6866,"lastPrice":6865.5,"timestamp":1587442022003,...},
+//   "symbol":"BTC_USDT",
+//   "ts":1587442022003
+// }
+#[derive(Debug, Deserialize)]
+struct MexcFuturesTickerMsg {
+    data: MexcFuturesTickerData,
+}
+
+#[derive(Debug, Deserialize)]
+struct MexcFuturesTickerData {
+    symbol: String,
+    #[serde(default)]
+    bid1: Option<f64>,
+    #[serde(default)]
+    ask1: Option<f64>,
+    #[serde(default)]
+    timestamp: Option<i64>,
+}
+
+type Book = BookHandle;
+
+/// Book on the venue's `tick`, or the default tick where it isn't known.
+fn new_book(tick: Option<f64>) -> Book {
+    let tick = tick.unwrap_or(DEFAULT_TICK_SIZE);
+    Arc::new(Mutex::new(OrderBook::builder().tick_size(tick).max_levels(DEFAULT_MAX_LEVELS).build()))
+}
 
 struct MexcFeed {
     // Used for perps depth -> BBO derivation
     books: HashMap<String, Book>,
     itype: InstrumentType,
     mapper: MexcMapper,
+    perp_mode: MexcPerpMode,
+    /// Top-N snapshots of the perp depth books.
+    depth: Option<DepthPublisher>,
 }
 
 impl MexcFeed {
@@ -105,7 +134,7 @@ impl MexcFeed {
         for normalized in symbols {
             // Denormalize to get native symbol for book key
             if let Ok(native) = mapper.denormalize(normalized, itype) {
-                books.insert(native, SyncBook::new());
+                books.insert(native, new_book(None));
             }
         }
 
@@ -113,24 +142,46 @@ impl MexcFeed {
             itype: itype,
             books: books,
             mapper: mapper,
+            perp_mode: MexcPerpMode::default(),
+            depth: None,
         }
     }
-    fn new_perp(symbols: &[&str]) -> Self {
+    /// `ticks` holds the venue tick per native symbol, for the depth books.
+    fn new_perp(
+        symbols: &[&str],
+        ticks: &HashMap<String, f64>,
+        perp_mode: MexcPerpMode,
+        depth: Option<DepthPublisher>,
+        live_books: Option<Arc<LiveBooks>>,
+    ) -> Self {
         let mut books = HashMap::new();
         let mapper = MexcMapper;
         let itype = InstrumentType::Perp;
 
-        for normalized in symbols {
-            // Denormalize to get native symbol for book key
-            if let Ok(native) = mapper.denormalize(normalized, itype) {
-                books.insert(native, SyncBook::new());
+        // Ticker mode reads BBO straight off push.ticker; no books.
+        if perp_mode == MexcPerpMode::Depth {
+            for normalized in symbols {
+                // Denormalize to get native symbol for book key
+                if let Ok(native) = mapper.denormalize(normalized, itype) {
+                    let book = new_book(ticks.get(&native).copied());
+                    if let Some(live) = &live_books {
+                        live.register(Exchange::Mexc, &native, &itype, &book);
+                    }
+                    books.insert(native, book);
+                }
             }
         }
 
+        if depth.is_some() && perp_mode == MexcPerpMode::Ticker {
+            warn!("MEXC perp ticker mode builds no books; depth is not published");
+        }
+
         Self {
             itype: itype,
             books,
             mapper: mapper,
+            perp_mode,
+            depth,
         }
     }
 }
@@ -144,9 +195,9 @@ impl ExchangeFeed for MexcFeed {
     }
 
     fn timestamp_dedup(&self) -> bool {
-        // Perp uses incremental depth — must not skip messages.
-        // Spot has no exchange timestamp anyway.
-        false
+        // Perp depth is incremental — must not skip messages; tickers are
+        // full BBOs and can be deduped. Spot has no exchange timestamp anyway.
+        matches!(self.itype, InstrumentType::Perp) && self.perp_mode == MexcPerpMode::Ticker
     }
 
     fn build_url(&self, _symbols: &[&str]) -> Result<String> {
@@ -203,18 +254,22 @@ impl ExchangeFeed for MexcFeed {
                 // MEXC futures depth can be "zipped push by default" per update log; request uncompressed to
                 // keep parsing simple. :contentReference[oaicite:7]{index=7}
                 for s in symbols {
-                    let sub = json!({
-                        "method": "sub.depth",
-                        "param": {
-                            "symbol": self.mapper.denormalize(s, InstrumentType::Perp).unwrap(),
-                            "compress": false
-                        }
-                    });
+                    let native = self.mapper.denormalize(s, InstrumentType::Perp)?;
+                    let sub = match self.perp_mode {
+                        MexcPerpMode::Depth => json!({
+                            "method": "sub.depth",
+                            "param": { "symbol": native, "compress": false }
+                        }),
+                        MexcPerpMode::Ticker => json!({
+                            "method": "sub.ticker",
+                            "param": { "symbol": native }
+                        }),
+                    };
                     write
                         .send(Message::Text(sub.to_string().into()))
                         .await
                         .with_context(|| {
-                            format!("Failed to subscribe MEXC perp depth for {}", s)
+                            format!("Failed to subscribe MEXC perp {:?} for {}", self.perp_mode, s)
                         })?;
                 }
 
@@ -261,7 +316,7 @@ impl ExchangeFeed for MexcFeed {
                 let WireMessage::Text(text) = msg else {
                     // If you still receive binary here, it's likely compressed. You can either:
                     // 1) keep compress=false as above, or
-                    // 2) add decompression logic with flate2.
+                    // 2) set `ConnectionConfig::decompression` to `Gzip`.
                     return Ok(vec![]);
                 };
                 // Quick parse just for channel
@@ -273,6 +328,9 @@ impl ExchangeFeed for MexcFeed {
                 let Some(channel) = v.get("channel").and_then(|c| c.as_str()) else {
                     return Ok(vec![]);
                 };
+                if channel == "push.ticker" && self.perp_mode == MexcPerpMode::Ticker {
+                    return parse_perp_ticker(text, received_ts, received_instant);
+                }
                 if channel != "push.depth" && channel != "push.depth.step" {
                     return Ok(vec![]);
                 }
@@ -293,11 +351,20 @@ impl ExchangeFeed for MexcFeed {
                     None => return Ok(vec![]),
                 };
 
-                // SAFETY: single writer — one WS task per feed.
-                let book = unsafe { book_cell.get_mut() };
+                let mut book = book_cell.lock().unwrap();
 
-                book.update_bids(depth_levels_to_updates(&depth.data.bids));
-                book.update_asks(depth_levels_to_updates(&depth.data.asks));
+                book.update_bids_iter(depth_levels(&depth.data.bids));
+                book.update_asks_iter(depth_levels(&depth.data.asks));
+                for (side, levels) in [(BookSide::Bid, &depth.data.bids), (BookSide::Ask, &depth.data.asks)] {
+                    publish_deltas(Exchange::Mexc, &depth.symbol, &self.itype, side, depth_levels(levels));
+                }
+                let exchange_ts = depth
+                    .ts
+                    .and_then(|ms| DateTime::from_timestamp_millis(ms));
+                book.mark_update(u64::try_from(depth.data.version).ok(), Some(exchange_ts.unwrap_or(received_ts)));
+                if let Some(publisher) = &self.depth {
+                    publisher.publish(&depth.symbol, &self.itype, &book);
+                }
 
                 // Derive BBO
                 let (bid, bid_qty) = match book.best_bid() {
@@ -315,10 +382,6 @@ impl ExchangeFeed for MexcFeed {
                     return Ok(vec![]);
                 }
 
-                let exchange_ts = depth
-                    .ts
-                    .and_then(|ms| DateTime::from_timestamp_millis(ms));
-
                 let md = MarketData {
                     bid: Some(bid),
                     ask: Some(ask),
@@ -327,6 +390,8 @@ impl ExchangeFeed for MexcFeed {
                     exchange_ts_raw: exchange_ts,
                     received_ts: Some(received_ts),
                     received_instant: Some(received_instant),
+                    update_id: book.last_update_seq,
+                    imbalance: book.imbalance(IMBALANCE_LEVELS),
                     ..Default::default()
                 };
 
@@ -339,6 +404,35 @@ impl ExchangeFeed for MexcFeed {
     }
 }
 
+fn parse_perp_ticker(
+    text: &str,
+    received_ts: DateTime<Utc>,
+    received_instant: std::time::Instant,
+) -> Result<Vec<(String, MarketData)>> {
+    let ticker = serde_json::from_str::<MexcFuturesTickerMsg>(text)
+        .with_context(|| format!("Mexc couldn't parse ticker {}", text))?
+        .data;
+    let (Some(bid), Some(ask)) = (
+        ticker.bid1.filter(|p| *p > 0.0),
+        ticker.ask1.filter(|p| *p > 0.0),
+    ) else {
+        return Ok(vec![]);
+    };
+    if bid >= ask {
+        return Ok(vec![]);
+    }
+    // The ticker carries prices only, no top-of-book sizes.
+    let md = MarketData {
+        bid: Some(bid),
+        ask: Some(ask),
+        exchange_ts_raw: ticker.timestamp.and_then(DateTime::from_timestamp_millis),
+        received_ts: Some(received_ts),
+        received_instant: Some(received_instant),
+        ..Default::default()
+    };
+    Ok(vec![(ticker.symbol, md)])
+}
+
 pub async fn listen_spot_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
@@ -356,12 +450,25 @@ pub async fn listen_spot_bbo(
     .await
 }
 
+/// Perp BBO from `sub.depth` books (with sizes) or, in ticker mode, from
+/// `push.ticker` (prices only, much lighter). In depth mode, `depth`
+/// also receives the books' top levels and `live_books` a handle to each.
 pub async fn listen_perp_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: MexcPerpMode,
+    depth: Option<DepthPublisher>,
+    live_books: Option<Arc<LiveBooks>>,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(MexcFeed::new_perp(symbols));
+    #[cfg(feature = "analytics")]
+    let ticks = match mode {
+        MexcPerpMode::Depth => crate::instruments::tick_sizes(Exchange::Mexc, InstrumentType::Perp, symbols).await,
+        MexcPerpMode::Ticker => HashMap::new(),
+    };
+    #[cfg(not(feature = "analytics"))]
+    let ticks = HashMap::new();
+    let feed = Arc::new(MexcFeed::new_perp(symbols, &ticks, mode, depth, live_books));
     listen_with_reconnect(
         data,
         symbols,
@@ -372,3 +479,114 @@ pub async fn listen_perp_bbo(
     )
     .await
 }
+
+// ---- Futures mark price (push.ticker) ----
+// push.ticker also carries "fairPrice" (mark), "indexPrice" and "fundingRate".
+
+#[derive(Debug, Deserialize)]
+struct MexcFuturesStatsMsg {
+    channel: String,
+    data: MexcFuturesStatsData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcFuturesStatsData {
+    symbol: String,
+    #[serde(default)]
+    fair_price: Option<f64>,
+    #[serde(default)]
+    index_price: Option<f64>,
+    #[serde(default)]
+    funding_rate: Option<f64>,
+    #[serde(default)]
+    timestamp: Option<i64>,
+}
+
+struct MexcMarkPriceFeed {
+    itype: InstrumentType,
+    mapper: MexcMapper,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for MexcMarkPriceFeed {
+    type Item = DerivStats;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok("wss://contract.mexc.com/edge".to_string())
+    }
+
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"method":"ping"}"#.into()))
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for s in symbols {
+            let native = self.mapper.denormalize(s, self.itype)?;
+            let sub = json!({ "method": "sub.ticker", "param": { "symbol": native } });
+            write
+                .send(Message::Text(sub.to_string().into()))
+                .await
+                .with_context(|| format!("Failed to subscribe MEXC perp ticker for {}", s))?;
+        }
+        Ok(())
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, DerivStats)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("push.ticker") {
+            // rs.sub.ticker acks, pongs
+            return Ok(vec![]);
+        }
+        let msg = serde_json::from_str::<MexcFuturesStatsMsg>(text)
+            .with_context(|| format!("Mexc couldn't parse ticker {}", text))?;
+        if msg.channel != "push.ticker" {
+            return Ok(vec![]);
+        }
+        let t = msg.data;
+        let stats = DerivStats {
+            mark_price: t.fair_price.filter(|p| *p > 0.0),
+            index_price: t.index_price.filter(|p| *p > 0.0),
+            funding_rate: t.funding_rate,
+            exchange_ts_raw: t.timestamp.and_then(DateTime::from_timestamp_millis),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+        Ok(vec![(t.symbol, stats)])
+    }
+}
+
+/// Perp fair (mark) and index price plus the funding rate from
+/// `push.ticker`.
+pub async fn listen_perp_mark_price(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(MexcMarkPriceFeed { itype: InstrumentType::Perp, mapper: MexcMapper });
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "mexc_perp_mark",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -1,18 +1,189 @@
+#[cfg(feature = "binance")]
 pub mod binance;
+#[cfg(feature = "mexc")]
 pub mod mexc;
+#[cfg(feature = "coinbase")]
 pub mod coinbase;
+#[cfg(feature = "bybit")]
 pub mod bybit;
+#[cfg(feature = "kraken")]
 pub mod kraken;
+#[cfg(feature = "lighter")]
 pub mod lighter;
+#[cfg(feature = "extended")]
 pub mod extended;
+#[cfg(feature = "nado")]
 pub mod nado;
+#[cfg(feature = "okx")]
 pub mod okx;
+#[cfg(feature = "kucoin")]
 pub mod kucoin;
+#[cfg(feature = "bingx")]
 pub mod bingx;
+#[cfg(feature = "apex")]
 pub mod apex;
+#[cfg(feature = "hibachi")]
 pub mod hibachi;
+#[cfg(feature = "hotstuff")]
 pub mod hotstuff;
+#[cfg(feature = "hyperliquid")]
 pub mod hyperliquid;
+#[cfg(feature = "risex")]
 pub mod risex;
+#[cfg(feature = "zeroone")]
 pub mod zeroone;
-pub mod connection;
\ No newline at end of file
+#[cfg(feature = "deribit")]
+pub mod deribit;
+#[cfg(feature = "pyth")]
+pub mod pyth;
+#[cfg(feature = "dydx")]
+pub mod dydx;
+#[cfg(feature = "gateio")]
+pub mod gateio;
+#[cfg(feature = "htx")]
+pub mod htx;
+#[cfg(feature = "bitfinex")]
+pub mod bitfinex;
+#[cfg(feature = "upbit")]
+pub mod upbit;
+#[cfg(feature = "phemex")]
+pub mod phemex;
+#[cfg(feature = "vertex")]
+pub mod vertex;
+pub mod connection;
+#[cfg(any(feature = "binance", feature = "bybit", feature = "okx"))]
+pub mod rest_poll;
+
+use crate::exchange_fees::{ExchangeFees, VenueFeesConfig};
+use crate::market_data::Exchange;
+use log::warn;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Whether the connector for `exchange` was compiled in (see Cargo features).
+pub fn is_compiled(exchange: &Exchange) -> bool {
+    match exchange {
+        Exchange::Binance => cfg!(feature = "binance"),
+        Exchange::Mexc => cfg!(feature = "mexc"),
+        Exchange::Coinbase => cfg!(feature = "coinbase"),
+        Exchange::Bybit => cfg!(feature = "bybit"),
+        Exchange::Kraken => cfg!(feature = "kraken"),
+        Exchange::Lighter => cfg!(feature = "lighter"),
+        Exchange::Extended => cfg!(feature = "extended"),
+        Exchange::Nado => cfg!(feature = "nado"),
+        Exchange::Okx => cfg!(feature = "okx"),
+        Exchange::Kucoin => cfg!(feature = "kucoin"),
+        Exchange::Bingx => cfg!(feature = "bingx"),
+        Exchange::Apex => cfg!(feature = "apex"),
+        Exchange::Hibachi => cfg!(feature = "hibachi"),
+        Exchange::Hotstuff => cfg!(feature = "hotstuff"),
+        Exchange::Hyperliquid => cfg!(feature = "hyperliquid"),
+        Exchange::RiseX => cfg!(feature = "risex"),
+        Exchange::ZeroOne => cfg!(feature = "zeroone"),
+        Exchange::Deribit => cfg!(feature = "deribit"),
+        Exchange::Pyth => cfg!(feature = "pyth"),
+        Exchange::Dydx => cfg!(feature = "dydx"),
+        Exchange::Gateio => cfg!(feature = "gateio"),
+        Exchange::Htx => cfg!(feature = "htx"),
+        Exchange::Bitfinex => cfg!(feature = "bitfinex"),
+        Exchange::Upbit => cfg!(feature = "upbit"),
+        Exchange::Phemex => cfg!(feature = "phemex"),
+        Exchange::Vertex => cfg!(feature = "vertex"),
+        // Configured under `onchain` / HFT engines, not as WS venues.
+        Exchange::Aerodrome | Exchange::Uniswap | Exchange::Bulk | Exchange::Chainlink => false,
+    }
+}
+
+static FEES: OnceLock<HashMap<Exchange, ExchangeFees>> = OnceLock::new();
+
+/// Apply the `fees` config section, keyed by venue name, to later
+/// `get_fees` calls. Only the first call takes effect.
+pub fn install_fees(cfg: &HashMap<String, VenueFeesConfig>) {
+    FEES.get_or_init(|| {
+        let mut fees = HashMap::new();
+        for (name, venue_cfg) in cfg {
+            let Ok(exchange) = name.parse::<Exchange>() else {
+                warn!("fees: unknown venue '{}'; skipping", name);
+                continue;
+            };
+            let built = match default_fees(&exchange) {
+                Some(defaults) => defaults.with_config(venue_cfg),
+                None => match ExchangeFees::from_config(venue_cfg) {
+                    Some(built) => built,
+                    None => {
+                        warn!("fees: '{}' has no default schedule; set both spot and perp", name);
+                        continue;
+                    }
+                },
+            };
+            fees.insert(exchange, built);
+        }
+        fees
+    });
+}
+
+/// Fee schedule of a venue: the connector's default with any installed
+/// `fees` config applied. None for venues without either.
+pub fn get_fees(exchange: &Exchange) -> Option<ExchangeFees> {
+    FEES.get().and_then(|fees| fees.get(exchange).cloned()).or_else(|| default_fees(exchange))
+}
+
+/// Default fee schedule of a venue's connector. None for venues without
+/// one (DEX pools, oracles) or whose feature is not compiled in.
+fn default_fees(exchange: &Exchange) -> Option<ExchangeFees> {
+    match exchange {
+        #[cfg(feature = "binance")]
+        Exchange::Binance => Some(binance::get_fees()),
+        #[cfg(feature = "mexc")]
+        Exchange::Mexc => Some(mexc::get_fees()),
+        #[cfg(feature = "coinbase")]
+        Exchange::Coinbase => Some(coinbase::get_fees()),
+        #[cfg(feature = "bybit")]
+        Exchange::Bybit => Some(bybit::get_fees()),
+        #[cfg(feature = "kraken")]
+        Exchange::Kraken => Some(kraken::get_fees()),
+        #[cfg(feature = "lighter")]
+        Exchange::Lighter => Some(lighter::get_fees()),
+        #[cfg(feature = "extended")]
+        Exchange::Extended => Some(extended::get_fees()),
+        #[cfg(feature = "nado")]
+        Exchange::Nado => Some(nado::get_fees()),
+        #[cfg(feature = "okx")]
+        Exchange::Okx => Some(okx::get_fees()),
+        #[cfg(feature = "kucoin")]
+        Exchange::Kucoin => Some(kucoin::get_fees()),
+        #[cfg(feature = "bingx")]
+        Exchange::Bingx => Some(bingx::get_fees()),
+        #[cfg(feature = "apex")]
+        Exchange::Apex => Some(apex::get_fees()),
+        #[cfg(feature = "hibachi")]
+        Exchange::Hibachi => Some(hibachi::get_fees()),
+        #[cfg(feature = "hotstuff")]
+        Exchange::Hotstuff => Some(hotstuff::get_fees()),
+        #[cfg(feature = "hyperliquid")]
+        Exchange::Hyperliquid => Some(hyperliquid::get_fees()),
+        #[cfg(feature = "risex")]
+        Exchange::RiseX => Some(risex::get_fees()),
+        #[cfg(feature = "zeroone")]
+        Exchange::ZeroOne => Some(zeroone::get_fees()),
+        #[cfg(feature = "pyth")]
+        Exchange::Pyth => Some(pyth::get_fees()),
+        #[cfg(feature = "dydx")]
+        Exchange::Dydx => Some(dydx::get_fees()),
+        #[cfg(feature = "deribit")]
+        Exchange::Deribit => Some(deribit::get_fees()),
+        #[cfg(feature = "gateio")]
+        Exchange::Gateio => Some(gateio::get_fees()),
+        #[cfg(feature = "htx")]
+        Exchange::Htx => Some(htx::get_fees()),
+        #[cfg(feature = "bitfinex")]
+        Exchange::Bitfinex => Some(bitfinex::get_fees()),
+        #[cfg(feature = "upbit")]
+        Exchange::Upbit => Some(upbit::get_fees()),
+        #[cfg(feature = "phemex")]
+        Exchange::Phemex => Some(phemex::get_fees()),
+        #[cfg(feature = "vertex")]
+        Exchange::Vertex => Some(vertex::get_fees()),
+        _ => None,
+    }
+}
@@ -12,7 +12,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
-use crate::exchanges::connection::{ConnectionConfig, calculate_backoff};
+use crate::exchanges::connection::{ConnectionConfig, calculate_backoff, with_jitter};
 use crate::mappers::{NadoMapper, SymbolMapper};
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
 use crate::symbol_registry::REGISTRY;
@@ -326,10 +326,9 @@ pub async fn listen_perp_bbo(
                             retry_count += 1;
                         }
 
-                        let backoff = calculate_backoff(
-                            retry_count,
-                            config.initial_backoff,
-                            config.max_retry_delay,
+                        let backoff = with_jitter(
+                            calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay),
+                            config.backoff_jitter,
                         );
                         error!("{} disconnected. Reconnecting in {:?}", FEED_NAME, backoff);
 
@@ -348,10 +347,9 @@ pub async fn listen_perp_bbo(
                             retry_count += 1;
                         }
 
-                        let backoff = calculate_backoff(
-                            retry_count,
-                            config.initial_backoff,
-                            config.max_retry_delay,
+                        let backoff = with_jitter(
+                            calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay),
+                            config.backoff_jitter,
                         );
                         error!("{} error: {}. Reconnecting in {:?}", FEED_NAME, e, backoff);
 
@@ -572,7 +570,7 @@ pub async fn listen_perp_trades(
                     Ok(true) => break,
                     Ok(false) => {
                         if was_long_lived { retry_count = 0; } else { retry_count += 1; }
-                        let backoff = calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay);
+                        let backoff = with_jitter(calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay), config.backoff_jitter);
                         error!("{} disconnected. Reconnecting in {:?}", TRADE_FEED_NAME, backoff);
                         tokio::select! {
                             _ = tokio::time::sleep(backoff) => {}
@@ -584,7 +582,7 @@ pub async fn listen_perp_trades(
                     }
                     Err(e) => {
                         if was_long_lived { retry_count = 0; } else { retry_count += 1; }
-                        let backoff = calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay);
+                        let backoff = with_jitter(calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay), config.backoff_jitter);
                         error!("{} error: {}. Reconnecting in {:?}", TRADE_FEED_NAME, e, backoff);
                         tokio::select! {
                             _ = tokio::time::sleep(backoff) => {}
@@ -1,18 +1,22 @@
+use crate::app_config::OkxBookMode;
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, ResyncRequests, WireMessage, listen_with_reconnect,
 };
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
+use crate::exchanges::rest_poll::poll_deriv_stats;
 use crate::mappers::{OkxMapper, SymbolMapper};
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
-use anyhow::Result;
+use crate::orderbook::{DEFAULT_MAX_LEVELS, IMBALANCE_LEVELS, OrderBook, RawLevels};
+use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
 use futures_util::SinkExt;
 use futures_util::stream::SplitSink;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
@@ -20,12 +24,43 @@ pub fn get_fees() -> ExchangeFees {
     ExchangeFees::new(FeeSchedule::new(10.0, 8.0), FeeSchedule::new(5.0, 2.0))
 }
 
-#[derive(Clone)]
+/// Levels per side the `books` checksum covers.
+const CHECKSUM_LEVELS: usize = 25;
+
+/// A `books` channel book. OKX checksums its own price and size strings,
+/// so they are kept beside the f64 book.
+struct OkxBook {
+    book: OrderBook,
+    raw: RawLevels,
+    /// Set by the channel's snapshot; updates are dropped until then and
+    /// after a failed checksum.
+    synced: bool,
+}
+
+impl OkxBook {
+    fn new() -> Self {
+        Self {
+            book: OrderBook::builder().max_levels(DEFAULT_MAX_LEVELS).build(),
+            raw: RawLevels::new(),
+            synced: false,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.book.clear();
+        self.raw.clear();
+        self.synced = false;
+    }
+}
+
 struct OkxFeed {
     itype: InstrumentType,
     mapper: OkxMapper,
     /// instId → (canonical symbol, ctVal). Precomputed at startup for perp.
     perp_symbol_map: HashMap<String, (String, f64)>,
+    /// Per-instId books in `books` mode; empty for `bbo-tbt`.
+    books: HashMap<String, Mutex<OkxBook>>,
+    resync: ResyncRequests,
 }
 
 impl OkxFeed {
@@ -34,6 +69,8 @@ impl OkxFeed {
             itype: InstrumentType::Spot,
             mapper: OkxMapper,
             perp_symbol_map: HashMap::new(),
+            books: HashMap::new(),
+            resync: ResyncRequests::new(),
         }
     }
     fn new_perp(symbols: &[&str]) -> Result<Self> {
@@ -51,8 +88,185 @@ impl OkxFeed {
             itype: InstrumentType::Perp,
             mapper,
             perp_symbol_map,
+            books: HashMap::new(),
+            resync: ResyncRequests::new(),
         })
     }
+
+    fn with_mode(mut self, symbols: &[&str], mode: OkxBookMode) -> Result<Self> {
+        if mode == OkxBookMode::Books {
+            for sym in symbols {
+                let native = self.mapper.denormalize(sym, self.itype)?;
+                self.books.insert(native, Mutex::new(OkxBook::new()));
+            }
+        }
+        Ok(self)
+    }
+
+    fn channel(&self) -> &'static str {
+        if self.books.is_empty() { "bbo-tbt" } else { "books" }
+    }
+
+    /// Canonical symbol and contract value for an instId.
+    fn resolve(&self, inst_id: &str) -> (String, Option<f64>) {
+        match self.perp_symbol_map.get(inst_id) {
+            Some((sym, ct_val)) => (sym.clone(), Some(*ct_val)),
+            // Spot or unknown — strip "-SWAP" suffix for compat
+            None => (inst_id.strip_suffix("-SWAP").unwrap_or(inst_id).to_string(), None),
+        }
+    }
+
+    fn parse_bbo(
+        &self,
+        response: OkxBookResponse,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let entry = match response.data.first() {
+            Some(d) => d,
+            None => return Ok(vec![]),
+        };
+
+        let bid = entry
+            .bids
+            .first()
+            .and_then(|v| v.first())
+            .and_then(|p| p.parse::<f64>().ok());
+        let ask = entry
+            .asks
+            .first()
+            .and_then(|v| v.first())
+            .and_then(|p| p.parse::<f64>().ok());
+        let mut bid_qty = entry
+            .bids
+            .first()
+            .and_then(|v| v.get(1))
+            .and_then(|q| q.parse::<f64>().ok());
+        let mut ask_qty = entry
+            .asks
+            .first()
+            .and_then(|v| v.get(1))
+            .and_then(|q| q.parse::<f64>().ok());
+
+        // For perp: lookup canonical symbol + apply contract value
+        let (symbol, ct_val) = self.resolve(&response.arg.inst_id);
+        if let Some(ct_val) = ct_val {
+            bid_qty = bid_qty.map(|q| q * ct_val);
+            ask_qty = ask_qty.map(|q| q * ct_val);
+        }
+
+        let exchange_ts = entry
+            .ts
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis);
+
+        let market_data = MarketData {
+            bid,
+            ask,
+            bid_qty,
+            ask_qty,
+            exchange_ts_raw: exchange_ts,
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            update_id: entry.seq_id,
+            ..Default::default()
+        };
+
+        Ok(vec![(symbol, market_data)])
+    }
+
+    /// Apply a `books` snapshot or update and check it against the pushed
+    /// checksum; a mismatch drops the book and asks for a resync.
+    fn parse_books(
+        &self,
+        response: OkxBookResponse,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let Some(cell) = self.books.get(&response.arg.inst_id) else {
+            return Ok(vec![]);
+        };
+        let Some(entry) = response.data.first() else {
+            return Ok(vec![]);
+        };
+        let (symbol, ct_val) = self.resolve(&response.arg.inst_id);
+        let mut state = cell.lock().unwrap();
+        if response.action.as_deref() == Some("snapshot") {
+            state.clear();
+            state.synced = true;
+        } else if !state.synced {
+            return Ok(vec![]);
+        }
+
+        state.raw.update_bids(raw_levels(&entry.bids));
+        state.raw.update_asks(raw_levels(&entry.asks));
+        state.raw.truncate(DEFAULT_MAX_LEVELS);
+        if let Some(expected) = entry.checksum {
+            if !state.raw.validate_checksum(expected as u32, checksum_payload) {
+                warn!("OKX {} book checksum mismatch", response.arg.inst_id);
+                state.clear();
+                self.resync.request(&symbol);
+                return Ok(vec![]);
+            }
+        }
+        state.book.update_bids_iter(parsed_levels(&entry.bids));
+        state.book.update_asks_iter(parsed_levels(&entry.asks));
+
+        let book = &state.book;
+        let (Some((bid, bid_qty)), Some((ask, ask_qty))) = (book.best_bid(), book.best_ask()) else {
+            return Ok(vec![]);
+        };
+        if bid >= ask {
+            warn!("Invalid book for {}: bid={} >= ask={}", symbol, bid, ask);
+            return Ok(vec![]);
+        }
+        let ct_val = ct_val.unwrap_or(1.0);
+
+        let market_data = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(bid_qty * ct_val),
+            ask_qty: Some(ask_qty * ct_val),
+            exchange_ts_raw: entry.ts.parse::<i64>().ok().and_then(DateTime::from_timestamp_millis),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            update_id: entry.seq_id,
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
+            ..Default::default()
+        };
+        Ok(vec![(symbol, market_data)])
+    }
+}
+
+/// `[price, size, _, orders]` levels as (price, size) strings.
+fn raw_levels(levels: &[Vec<String>]) -> impl Iterator<Item = (&str, &str)> {
+    levels
+        .iter()
+        .filter_map(|l| Some((l.first()?.as_str(), l.get(1)?.as_str())))
+}
+
+fn parsed_levels(levels: &[Vec<String>]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    raw_levels(levels).filter_map(|(p, q)| Some((p.parse().ok()?, q.parse().unwrap_or(0.0))))
+}
+
+/// OKX checksum input: the top 25 levels interleaved as
+/// `bid:size:ask:size:...`, best first; once one side runs out the other
+/// continues alone.
+fn checksum_payload(raw: &RawLevels) -> String {
+    let (mut bids, mut asks) = (raw.bids().take(CHECKSUM_LEVELS), raw.asks().take(CHECKSUM_LEVELS));
+    let mut parts = Vec::with_capacity(CHECKSUM_LEVELS * 4);
+    loop {
+        let (bid, ask) = (bids.next(), asks.next());
+        if bid.is_none() && ask.is_none() {
+            break;
+        }
+        for (price, size) in [bid, ask].into_iter().flatten() {
+            parts.push(price);
+            parts.push(size);
+        }
+    }
+    parts.join(":")
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,17 +276,27 @@ struct OkxArg {
     inst_id: String,
 }
 
+/// `bbo-tbt` and `books` pushes share a shape; `books` adds `action` and
+/// `checksum`.
 #[derive(Debug, Deserialize)]
-struct OkxBboData {
+struct OkxBookData {
     asks: Vec<Vec<String>>,
     bids: Vec<Vec<String>>,
     ts: String,
+    #[serde(rename = "seqId", default)]
+    seq_id: Option<u64>,
+    /// Signed CRC32 of the top 25 levels after this push.
+    #[serde(default)]
+    checksum: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OkxBboResponse {
+struct OkxBookResponse {
     arg: OkxArg,
-    data: Vec<OkxBboData>,
+    /// "snapshot" or "update" on `books`.
+    #[serde(default)]
+    action: Option<String>,
+    data: Vec<OkxBookData>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +360,17 @@ impl ExchangeFeed for OkxFeed {
         Ok("wss://ws.okx.com:8443/ws/v5/public".to_string())
     }
 
+    /// `books` starts every subscription with a snapshot.
+    fn on_connected(&self) {
+        for book in self.books.values() {
+            book.lock().unwrap().clear();
+        }
+    }
+
+    fn take_resync(&self) -> Vec<String> {
+        self.resync.take()
+    }
+
     async fn send_subscription(
         &self,
         write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
@@ -146,7 +381,7 @@ impl ExchangeFeed for OkxFeed {
             .map(|symbol| {
                 let inst_id = self.mapper.denormalize(symbol, self.itype).unwrap();
                 json!({
-                    "channel": "bbo-tbt",
+                    "channel": self.channel(),
                     "instId": inst_id
                 })
             })
@@ -182,77 +417,19 @@ impl ExchangeFeed for OkxFeed {
                     return Ok(vec![]);
                 }
 
-                match serde_json::from_str::<OkxBboResponse>(text) {
-                    Ok(response) => {
-                        if response.arg.channel != "bbo-tbt" {
-                            return Ok(vec![]);
-                        }
-
-                        let entry = match response.data.first() {
-                            Some(d) => d,
-                            None => return Ok(vec![]),
-                        };
-
-                        let bid = entry
-                            .bids
-                            .first()
-                            .and_then(|v| v.first())
-                            .and_then(|p| p.parse::<f64>().ok());
-                        let ask = entry
-                            .asks
-                            .first()
-                            .and_then(|v| v.first())
-                            .and_then(|p| p.parse::<f64>().ok());
-                        let mut bid_qty = entry
-                            .bids
-                            .first()
-                            .and_then(|v| v.get(1))
-                            .and_then(|q| q.parse::<f64>().ok());
-                        let mut ask_qty = entry
-                            .asks
-                            .first()
-                            .and_then(|v| v.get(1))
-                            .and_then(|q| q.parse::<f64>().ok());
-
-                        // For perp: lookup canonical symbol + apply contract value
-                        let symbol = if let Some((sym, ct_val)) = self.perp_symbol_map.get(&response.arg.inst_id) {
-                            bid_qty = bid_qty.map(|q| q * ct_val);
-                            ask_qty = ask_qty.map(|q| q * ct_val);
-                            sym.clone()
-                        } else {
-                            // Spot or unknown — strip "-SWAP" suffix for compat
-                            response.arg.inst_id
-                                .strip_suffix("-SWAP")
-                                .unwrap_or(&response.arg.inst_id)
-                                .to_string()
-                        };
-
-                        let exchange_ts = entry
-                            .ts
-                            .parse::<i64>()
-                            .ok()
-                            .and_then(DateTime::from_timestamp_millis);
-
-                        let market_data = MarketData {
-                            bid,
-                            ask,
-                            bid_qty,
-                            ask_qty,
-                            exchange_ts_raw: exchange_ts,
-                            received_ts: Some(received_ts),
-                            received_instant: Some(received_instant),
-                            ..Default::default()
-                        };
-
-                        Ok(vec![(symbol, market_data)])
-                    }
+                match serde_json::from_str::<OkxBookResponse>(text) {
+                    Ok(response) => match response.arg.channel.as_str() {
+                        "bbo-tbt" => self.parse_bbo(response, received_ts, received_instant),
+                        "books" => self.parse_books(response, received_ts, received_instant),
+                        _ => Ok(vec![]),
+                    },
                     Err(e) => {
                         error!("Got error parsing OKX message: {} \n {}", e, text);
                         Ok(vec![])
                     }
                 }
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 
@@ -261,12 +438,15 @@ impl ExchangeFeed for OkxFeed {
     }
 }
 
+/// Spot BBO from `bbo-tbt` or, with `OkxBookMode::Books`, from checksummed
+/// `books` order books.
 pub async fn listen_spot_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: OkxBookMode,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(OkxFeed::new_spot());
+    let feed = Arc::new(OkxFeed::new_spot().with_mode(symbols, mode)?);
     listen_with_reconnect(
         data,
         symbols,
@@ -281,9 +461,10 @@ pub async fn listen_spot_bbo(
 pub async fn listen_perp_bbo(
     data: Arc<MarketDataCollection>,
     symbols: &[&str],
+    mode: OkxBookMode,
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    let feed = Arc::new(OkxFeed::new_perp(symbols)?);
+    let feed = Arc::new(OkxFeed::new_perp(symbols)?.with_mode(symbols, mode)?);
     listen_with_reconnect(
         data,
         symbols,
@@ -294,3 +475,103 @@ pub async fn listen_perp_bbo(
     )
     .await
 }
+
+// --- Open Interest (REST) ---
+
+const OPEN_INTEREST_URL: &str = "https://www.okx.com/api/v5/public/open-interest";
+
+#[derive(Debug, Deserialize)]
+struct OkxOpenInterestResponse {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    data: Vec<OkxOpenInterest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxOpenInterest {
+    /// Open interest in base currency (`oi` is in contracts).
+    oi_ccy: String,
+    ts: String,
+}
+
+async fn fetch_open_interest(client: reqwest::Client, native: String) -> Result<DerivStats> {
+    let resp = client
+        .get(OPEN_INTEREST_URL)
+        .query(&[("instType", "SWAP"), ("instId", native.as_str())])
+        .send()
+        .await
+        .with_context(|| format!("GET {OPEN_INTEREST_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {OPEN_INTEREST_URL} -> {status}; body: {body}");
+    }
+    let parsed: OkxOpenInterestResponse = serde_json::from_str(&body).context("decode open-interest JSON")?;
+    if parsed.code != "0" {
+        bail!("OKX open-interest {} -> {}: {}", native, parsed.code, parsed.msg);
+    }
+    let Some(oi) = parsed.data.into_iter().next() else {
+        bail!("OKX open-interest: no entry for {}", native);
+    };
+    Ok(DerivStats {
+        open_interest: oi.oi_ccy.parse::<f64>().ok(),
+        exchange_ts_raw: oi.ts.parse::<i64>().ok().and_then(DateTime::from_timestamp_millis),
+        ..Default::default()
+    })
+}
+
+/// Swap open interest (base units) from `/api/v5/public/open-interest`,
+/// polled every `interval`.
+pub async fn poll_open_interest(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    interval: std::time::Duration,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    poll_deriv_stats(data, symbols, OkxMapper, interval, "okx_oi", shutdown, fetch_open_interest).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::crc32;
+
+    fn push(action: &str, bids: &str, asks: &str, checksum: u32) -> String {
+        format!(
+            r#"{{"arg":{{"channel":"books","instId":"BTC-USDT"}},"action":"{}","data":[{{"asks":{},"bids":{},"ts":"1597026383085","checksum":{},"seqId":1}}]}}"#,
+            action, asks, bids, checksum as i32
+        )
+    }
+
+    #[test]
+    fn test_books_checksum() {
+        let feed = OkxFeed::new_spot().with_mode(&["BTC_USDT"], OkxBookMode::Books).unwrap();
+        let parse = |text: &str| feed.parse_message(WireMessage::Text(text), Utc::now(), std::time::Instant::now()).unwrap();
+
+        // Trailing zeros are part of the checksummed strings.
+        let snapshot = push(
+            "snapshot",
+            r#"[["100.0","2.50","0","1"],["99.5","1","0","1"]]"#,
+            r#"[["101.0","3","0","2"]]"#,
+            crc32(b"100.0:2.50:101.0:3:99.5:1"),
+        );
+        let out = parse(&snapshot);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0, "BTC-USDT");
+        assert_eq!((out[0].1.bid, out[0].1.ask, out[0].1.bid_qty), (Some(100.0), Some(101.0), Some(2.5)));
+
+        let update = push("update", "[]", r#"[["101.0","0","0","0"],["100.5","4","0","1"]]"#, crc32(b"100.0:2.50:100.5:4:99.5:1"));
+        assert_eq!(parse(&update)[0].1.ask, Some(100.5));
+        assert!(feed.take_resync().is_empty());
+
+        let bad = push("update", r#"[["100.0","3","0","1"]]"#, "[]", 0);
+        assert!(parse(&bad).is_empty());
+        assert_eq!(feed.take_resync(), vec!["BTC-USDT".to_string()]);
+        // Nothing applies until the next snapshot.
+        assert!(parse(&update).is_empty());
+        assert_eq!(parse(&snapshot).len(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::mappers::{PhemexMapper, SymbolMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::orderbook::{IMBALANCE_LEVELS, SyncBook};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, info};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const WS_URL: &str = "wss://ws.phemex.com";
+const PRODUCTS_URL: &str = "https://api.phemex.com/public/products";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(10.0, 10.0), FeeSchedule::new(6.0, 1.0))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProductsResponse {
+    code: i64,
+    data: ProductsData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProductsData {
+    products: Vec<Product>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Product {
+    symbol: String,
+    #[serde(rename = "type")]
+    product_type: String,
+    /// Wire prices ("priceEp") are the price times 10^priceScale.
+    #[serde(default)]
+    price_scale: Option<u32>,
+}
+
+/// Contract symbol → price scale for every scaled-price perpetual.
+async fn fetch_price_scales(client: &Client) -> Result<HashMap<String, u32>> {
+    let resp = client
+        .get(PRODUCTS_URL)
+        .send()
+        .await
+        .with_context(|| format!("GET {PRODUCTS_URL} failed"))?;
+    let products: ProductsResponse = resp.json().await.context("decode Phemex products")?;
+    if products.code != 0 {
+        bail!("Phemex products returned code {}", products.code);
+    }
+    Ok(products
+        .data
+        .products
+        .into_iter()
+        .filter(|p| p.product_type == "Perpetual")
+        .filter_map(|p| Some((p.symbol, p.price_scale?)))
+        .collect())
+}
+
+struct PhemexFeed {
+    itype: InstrumentType,
+    mapper: PhemexMapper,
+    /// Contract ("BTCUSD") → (config symbol, 10^priceScale).
+    contracts: HashMap<String, (String, f64)>,
+    /// Per-contract books (single writer: one WS task).
+    books: HashMap<String, SyncBook>,
+}
+
+impl PhemexFeed {
+    async fn new_perp(symbols: &[&str]) -> Result<Self> {
+        let scales = fetch_price_scales(&Client::new()).await?;
+        let itype = InstrumentType::Perp;
+        let mapper = PhemexMapper;
+        let mut contracts = HashMap::new();
+        for sym in symbols {
+            let native = mapper.denormalize(sym, itype)?;
+            let scale = *scales
+                .get(&native)
+                .ok_or_else(|| anyhow!("Phemex contract '{native}' not found among scaled-price perpetuals"))?;
+            info!("Phemex contract {}: priceScale={}", native, scale);
+            contracts.insert(native, (sym.to_string(), 10f64.powi(scale as i32)));
+        }
+        // One tick is one unit of the scaled price.
+        let books = contracts.iter().map(|(c, (_, scale))| (c.clone(), SyncBook::with_tick_size(1.0 / scale))).collect();
+        Ok(Self { itype, mapper, contracts, books })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PhemexBookMsg {
+    book: PhemexBook,
+    symbol: String,
+    /// ns
+    timestamp: i64,
+    #[serde(rename = "type")]
+    msg_type: String,
+}
+
+/// Levels are `[priceEp, size]`; size 0 removes the level.
+#[derive(Debug, Deserialize)]
+struct PhemexBook {
+    #[serde(default)]
+    asks: Vec<(f64, f64)>,
+    #[serde(default)]
+    bids: Vec<(f64, f64)>,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for PhemexFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        for (i, symbol) in symbols.iter().enumerate() {
+            let native = self.mapper.denormalize(symbol, self.itype)?;
+            let msg = json!({ "id": i + 1, "method": "orderbook.subscribe", "params": [native] });
+            write
+                .send(Message::Text(msg.to_string().into()))
+                .await
+                .with_context(|| format!("Failed to subscribe Phemex orderbook {native}"))?;
+        }
+        Ok(())
+    }
+
+    /// The server drops connections without a `server.ping` every 30s.
+    fn heartbeat_message(&self) -> Option<Message> {
+        Some(Message::Text(
+            r#"{"id":0,"method":"server.ping","params":[]}"#.into(),
+        ))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if !text.contains("\"book\"") {
+            // pongs and subscribe acks: {"error":null,"id":1,"result":...}
+            if text.contains("\"error\":{") {
+                bail!("Phemex error: {}", text);
+            }
+            return Ok(vec![]);
+        }
+
+        let msg: PhemexBookMsg = serde_json::from_str(text)?;
+        let (Some(book_cell), Some((symbol, scale))) = (self.books.get(&msg.symbol), self.contracts.get(&msg.symbol))
+        else {
+            debug!("Phemex book for unknown contract {}", msg.symbol);
+            return Ok(vec![]);
+        };
+
+        // SAFETY: single writer — one WS task per feed.
+        let book = unsafe { book_cell.get_mut() };
+        if msg.msg_type == "snapshot" {
+            book.clear();
+        }
+        let unscale = |levels: Vec<(f64, f64)>| -> Vec<(f64, f64)> {
+            levels.into_iter().map(|(ep, size)| (ep / scale, size)).collect()
+        };
+        book.update_bids_f64(&unscale(msg.book.bids));
+        book.update_asks_f64(&unscale(msg.book.asks));
+
+        let (Some((bid, bid_qty)), Some((ask, ask_qty))) = (book.best_bid(), book.best_ask()) else {
+            return Ok(vec![]);
+        };
+        let md = MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(bid_qty),
+            ask_qty: Some(ask_qty),
+            exchange_ts_raw: Some(DateTime::from_timestamp_nanos(msg.timestamp)),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
+            ..Default::default()
+        };
+        Ok(vec![(symbol.clone(), md)])
+    }
+}
+
+/// Perp BBO from the `orderbook` channel of scaled-price contracts
+/// ("BTC_USD" → BTCUSD). Prices are divided by 10^priceScale from the
+/// products endpoint; sizes stay in contracts.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(PhemexFeed::new_perp(symbols).await?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "phemex_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
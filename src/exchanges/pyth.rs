@@ -0,0 +1,195 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const WS_URL: &str = "wss://hermes.pyth.network/ws";
+
+/// Price feed ids for common pairs, so config can just say "BTC_USD".
+/// Any other feed is configured as "SYMBOL:FEED_ID".
+const KNOWN_FEEDS: &[(&str, &str)] = &[
+    ("BTC_USD", "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43"),
+    ("ETH_USD", "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace"),
+    ("SOL_USD", "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d"),
+];
+
+pub fn get_fees() -> ExchangeFees {
+    // Oracle, not a trading venue.
+    ExchangeFees::new(FeeSchedule::new(0.0, 0.0), FeeSchedule::new(0.0, 0.0))
+}
+
+// --- Hermes message structures ---
+
+#[derive(Debug, Deserialize)]
+struct HermesMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    #[serde(default)]
+    price_feed: Option<HermesPriceFeed>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HermesPriceFeed {
+    id: String,
+    price: HermesPrice,
+}
+
+/// Fixed-point price: value = price * 10^expo.
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    /// Unix seconds.
+    publish_time: i64,
+}
+
+// --- Feed implementation ---
+
+struct PythFeed {
+    itype: InstrumentType,
+    /// Lowercase hex feed id (no 0x) → registry symbol ("BTC_USD")
+    id_to_symbol: HashMap<String, String>,
+}
+
+/// Split a config entry into (symbol, feed id). Accepts "BTC_USD" for
+/// pairs in `KNOWN_FEEDS`, or "BTC_USD:0xe62d..." for anything else.
+fn parse_entry(entry: &str) -> Option<(String, String)> {
+    let (symbol, id) = match entry.split_once(':') {
+        Some((sym, id)) => (sym.to_uppercase(), id.to_string()),
+        None => {
+            let symbol = entry.to_uppercase();
+            let (_, id) = KNOWN_FEEDS.iter().find(|(s, _)| *s == symbol)?;
+            (symbol, id.to_string())
+        }
+    };
+    let id = id.trim_start_matches("0x").to_lowercase();
+    Some((symbol, id))
+}
+
+impl PythFeed {
+    fn new(symbols: &[&str], itype: InstrumentType) -> Result<Self> {
+        let mut id_to_symbol = HashMap::new();
+        for entry in symbols {
+            let (symbol, id) = parse_entry(entry).ok_or_else(|| {
+                anyhow!("Pyth: no feed id for '{}'; use SYMBOL:FEED_ID", entry)
+            })?;
+            id_to_symbol.insert(id, symbol);
+        }
+        Ok(Self { itype, id_to_symbol })
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for PythFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        _symbols: &[&str],
+    ) -> Result<()> {
+        let ids: Vec<&String> = self.id_to_symbol.keys().collect();
+        let sub_msg = json!({
+            "type": "subscribe",
+            "ids": ids,
+        });
+        write
+            .send(Message::Text(sub_msg.to_string().into()))
+            .await?;
+        debug!("Pyth subscribed to {} feeds", ids.len());
+        Ok(())
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        match msg {
+            WireMessage::Text(text) => {
+                let msg: HermesMessage = serde_json::from_str(text)?;
+                match msg.msg_type.as_str() {
+                    "price_update" => {}
+                    "response" => {
+                        if msg.status.as_deref() != Some("success") {
+                            warn!("Pyth subscription error: {:?}", msg.error);
+                        }
+                        return Ok(vec![]);
+                    }
+                    _ => return Ok(vec![]),
+                }
+                let Some(feed) = msg.price_feed else {
+                    return Ok(vec![]);
+                };
+                let id = feed.id.trim_start_matches("0x");
+                let Some(symbol) = self.id_to_symbol.get(id) else {
+                    debug!("Unknown Pyth feed id: {}", id);
+                    return Ok(vec![]);
+                };
+
+                let scale = 10f64.powi(feed.price.expo);
+                let price = feed.price.price.parse::<f64>()? * scale;
+                let conf = feed.price.conf.parse::<f64>()? * scale;
+                let exchange_ts = DateTime::from_timestamp(feed.price.publish_time, 0);
+
+                // The confidence interval stands in for the spread, so the
+                // midquote is the aggregate price.
+                let market_data = MarketData {
+                    bid: Some(price - conf),
+                    ask: Some(price + conf),
+                    exchange_ts_raw: exchange_ts,
+                    received_ts: Some(received_ts),
+                    received_instant: Some(received_instant),
+                    ..Default::default()
+                };
+                Ok(vec![(symbol.clone(), market_data)])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+/// Stream Pyth aggregate prices. Entries are "BTC_USD" for known pairs or
+/// "SYMBOL:FEED_ID"; updates land under the spot symbol.
+pub async fn listen_spot_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = Arc::new(PythFeed::new(symbols, InstrumentType::Spot)?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "pyth_spot",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
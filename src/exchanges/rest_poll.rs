@@ -0,0 +1,69 @@
+//! Timer-driven REST polling for stats venues only publish over HTTP
+//! (open interest on Binance, Bybit and OKX).
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use reqwest::Client;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
+use crate::mappers::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::REGISTRY;
+
+/// Every `interval`, fetch each perp in `symbols` (config symbols such as
+/// "BTC_USDT") with `fetch(client, native)` and push the result. A failed
+/// request is logged and retried on the next tick.
+pub async fn poll_deriv_stats<M, F, Fut>(
+    data: Arc<DerivStatsCollection>,
+    symbols: &[&str],
+    mapper: M,
+    interval: Duration,
+    name: &str,
+    shutdown: Arc<tokio::sync::Notify>,
+    fetch: F,
+) -> Result<()>
+where
+    M: SymbolMapper,
+    F: Fn(Client, String) -> Fut,
+    Fut: Future<Output = Result<DerivStats>>,
+{
+    let itype = InstrumentType::Perp;
+    let mut targets = Vec::with_capacity(symbols.len());
+    for &sym in symbols {
+        let Some(&id) = REGISTRY.lookup(sym, &itype) else {
+            warn!("{}: symbol '{}' not in registry, skipping", name, sym);
+            continue;
+        };
+        targets.push((id, mapper.denormalize(sym, itype)?));
+    }
+    if targets.is_empty() {
+        anyhow::bail!("{}: no known symbols among {:?}", name, symbols);
+    }
+
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    info!("Started {} for {} symbols every {:?}", name, targets.len(), interval);
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = ticker.tick() => {}
+        }
+        for (id, native) in &targets {
+            match fetch(client.clone(), native.clone()).await {
+                Ok(mut stats) => {
+                    stats.received_ts = Some(Utc::now());
+                    stats.received_instant = Some(Instant::now());
+                    data.push(id, stats);
+                }
+                Err(e) => warn!("{} {} failed: {:#}", name, native, e),
+            }
+        }
+    }
+    info!("Stopped {}", name);
+    Ok(())
+}
@@ -4,7 +4,7 @@ use crate::exchanges::connection::{
 };
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
-use crate::orderbook::SyncBook;
+use crate::orderbook::{IMBALANCE_LEVELS, SyncBook};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use futures_util::SinkExt;
@@ -259,8 +259,7 @@ impl ExchangeFeed for RiseXBboFeed {
 
         let is_snapshot = ob.r#type == "snapshot";
         if is_snapshot {
-            book.bids.clear();
-            book.asks.clear();
+            book.clear();
         }
 
         if !data.bids.is_empty() {
@@ -297,6 +296,7 @@ impl ExchangeFeed for RiseXBboFeed {
             exchange_ts,
             received_ts: Some(received_ts),
             received_instant: Some(received_instant),
+            imbalance: book.imbalance(IMBALANCE_LEVELS),
             ..Default::default()
         };
 
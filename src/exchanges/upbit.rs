@@ -0,0 +1,190 @@
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+};
+use crate::fx::QuoteConversion;
+use crate::mappers::{SymbolMapper, UpbitMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use log::{debug, info};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+const WS_URL: &str = "wss://api.upbit.com/websocket/v1";
+
+pub fn get_fees() -> ExchangeFees {
+    // KRW market; Upbit has no derivatives.
+    ExchangeFees::new(FeeSchedule::new(5.0, 5.0), FeeSchedule::new(5.0, 5.0))
+}
+
+struct UpbitFeed {
+    itype: InstrumentType,
+    mapper: UpbitMapper,
+    conversion: Option<QuoteConversion>,
+    /// Market code of a `via` rate market that is not itself configured:
+    /// subscribed and observed, but not stored.
+    rate_only: Option<String>,
+}
+
+impl UpbitFeed {
+    fn new(symbols: &[&str], conversion: Option<QuoteConversion>) -> Result<Self> {
+        let mapper = UpbitMapper;
+        let itype = InstrumentType::Spot;
+        let mut codes = Vec::new();
+        for sym in symbols {
+            codes.push(mapper.denormalize(sym, itype)?);
+        }
+        let rate_only = conversion
+            .as_ref()
+            .and_then(|c| c.via_market())
+            .map(|(base, quote)| format!("{}-{}", quote, base))
+            .filter(|code| !codes.contains(code));
+        Ok(Self { itype, mapper, conversion, rate_only })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UpbitOrderbook {
+    #[serde(rename = "type")]
+    msg_type: String,
+    /// e.g. "KRW-BTC"
+    code: String,
+    /// ms
+    timestamp: i64,
+    orderbook_units: Vec<UpbitUnit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpbitUnit {
+    ask_price: f64,
+    bid_price: f64,
+    ask_size: f64,
+    bid_size: f64,
+}
+
+#[async_trait::async_trait]
+impl ExchangeFeed for UpbitFeed {
+    type Item = MarketData;
+
+    fn get_itype(&self) -> Result<&InstrumentType> {
+        Ok(&self.itype)
+    }
+
+    fn build_url(&self, _symbols: &[&str]) -> Result<String> {
+        Ok(WS_URL.to_string())
+    }
+
+    /// One request carries every code; a new request replaces the set, so
+    /// the feed does not (un)subscribe incrementally.
+    async fn send_subscription(
+        &self,
+        write: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        symbols: &[&str],
+    ) -> Result<()> {
+        let mut codes = symbols
+            .iter()
+            .map(|s| self.mapper.denormalize(s, self.itype))
+            .collect::<Result<Vec<_>>>()?;
+        codes.extend(self.rate_only.iter().cloned());
+        let msg = json!([
+            { "ticket": "crypto-feeds" },
+            { "type": "orderbook", "codes": codes },
+            { "format": "DEFAULT" }
+        ]);
+        write
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .context("Failed to subscribe to Upbit orderbook")?;
+        debug!("Upbit subscribed: {:?}", codes);
+        Ok(())
+    }
+
+    /// Upbit sends JSON in binary frames.
+    fn inflate_binary(&self, bytes: &[u8]) -> Option<Result<String>> {
+        Some(String::from_utf8(bytes.to_vec()).map_err(Into::into))
+    }
+
+    fn parse_message(
+        &self,
+        msg: WireMessage<'_>,
+        received_ts: DateTime<Utc>,
+        received_instant: std::time::Instant,
+    ) -> Result<Vec<(String, MarketData)>> {
+        let WireMessage::Text(text) = msg else {
+            return Ok(vec![]);
+        };
+        if text.contains("\"error\"") {
+            bail!("Upbit error: {}", text);
+        }
+        if !text.contains("\"orderbook_units\"") {
+            // {"status":"UP"} and the like
+            return Ok(vec![]);
+        }
+        let book: UpbitOrderbook = serde_json::from_str(text)?;
+        if book.msg_type != "orderbook" {
+            return Ok(vec![]);
+        }
+        let Some(top) = book.orderbook_units.first() else {
+            return Ok(vec![]);
+        };
+        let (base, quote) = self.mapper.parse(&book.code, self.itype)?;
+
+        let mut md = MarketData {
+            bid: Some(top.bid_price),
+            ask: Some(top.ask_price),
+            bid_qty: Some(top.bid_size),
+            ask_qty: Some(top.ask_size),
+            exchange_ts_raw: DateTime::from_timestamp_millis(book.timestamp),
+            received_ts: Some(received_ts),
+            received_instant: Some(received_instant),
+            ..Default::default()
+        };
+
+        let Some(conv) = &self.conversion else {
+            return Ok(vec![(format!("{}_{}", base, quote), md)]);
+        };
+        conv.observe(&base, &quote, (top.bid_price + top.ask_price) / 2.0);
+        if self.rate_only.as_deref() == Some(book.code.as_str()) {
+            return Ok(vec![]);
+        }
+        if !conv.applies_to(&quote) {
+            return Ok(vec![(format!("{}_{}", base, quote), md)]);
+        }
+        if !conv.convert(&mut md) {
+            // No rate yet.
+            return Ok(vec![]);
+        }
+        Ok(vec![(format!("{}_{}", base, conv.to_ccy()), md)])
+    }
+}
+
+/// KRW spot BBO from the `orderbook` stream. `symbols` are config symbols
+/// like "BTC_KRW". With a `conversion`, KRW prices are rescaled and stored
+/// under the converted pair ("BTC_USD"); ticks arriving before a rate is
+/// known are dropped.
+pub async fn listen_spot_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    conversion: Option<QuoteConversion>,
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    if let Some(c) = &conversion {
+        info!("Upbit: converting {} quotes to {}", c.from_ccy(), c.to_ccy());
+    }
+    let feed = Arc::new(UpbitFeed::new(symbols, conversion)?);
+    listen_with_reconnect(
+        data,
+        symbols,
+        feed,
+        "upbit_spot",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
+}
@@ -0,0 +1,313 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error, info, warn};
+use nado_ws::Message;
+use reqwest::Client;
+use reqwest::header::ACCEPT_ENCODING;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::exchange_fees::{ExchangeFees, FeeSchedule};
+use crate::exchanges::connection::{ConnectionConfig, calculate_backoff, with_jitter};
+use crate::mappers::{SymbolMapper, VertexMapper};
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+
+const X18: f64 = 1e18;
+const FEED_NAME: &str = "vertex_perp";
+const WS_URL: &str = "wss://gateway.prod.vertexprotocol.com/v1/subscribe";
+const WS_ORIGIN: &str = "https://app.vertexprotocol.com";
+const SYMBOLS_URL: &str = "https://gateway.prod.vertexprotocol.com/v1/query?type=symbols&product_type=perp";
+
+pub fn get_fees() -> ExchangeFees {
+    ExchangeFees::new(FeeSchedule::new(2.0, 0.0), FeeSchedule::new(2.0, 0.0))
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolsResponse {
+    status: String,
+    #[serde(default)]
+    data: Option<SymbolsData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolsData {
+    /// Keyed by symbol ("BTC-PERP").
+    symbols: HashMap<String, SymbolRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolRow {
+    product_id: u32,
+}
+
+/// Native symbol ("BTC-PERP") -> product id, from the gateway query endpoint.
+async fn fetch_product_ids(client: &Client) -> Result<HashMap<String, u32>> {
+    let resp = client
+        .get(SYMBOLS_URL)
+        .header(ACCEPT_ENCODING, "gzip")
+        .send()
+        .await
+        .with_context(|| format!("GET {SYMBOLS_URL} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {SYMBOLS_URL} -> {status}; body: {body}");
+    }
+    let parsed: SymbolsResponse = serde_json::from_str(&body).context("decode symbols JSON")?;
+    let Some(data) = parsed.data.filter(|_| parsed.status == "success") else {
+        bail!("Vertex symbols query -> {}; body: {body}", parsed.status);
+    };
+    Ok(data.symbols.into_iter().map(|(sym, row)| (sym, row.product_id)).collect())
+}
+
+struct VertexFeed {
+    /// Product id -> config symbol ("BTC_USDC") for the registry.
+    id_to_config: HashMap<u32, String>,
+    itype: InstrumentType,
+}
+
+impl VertexFeed {
+    /// Symbols the gateway doesn't list are skipped with a warning.
+    async fn new_perp(symbols: &[&str]) -> Result<Self> {
+        let products = fetch_product_ids(&Client::new()).await?;
+        let itype = InstrumentType::Perp;
+        let mapper = VertexMapper;
+
+        let mut id_to_config = HashMap::new();
+        for &sym in symbols {
+            let native = mapper.denormalize(sym, itype)?;
+            match products.get(&native) {
+                Some(&pid) => {
+                    id_to_config.insert(pid, sym.to_string());
+                }
+                None => warn!("Vertex: symbol '{}' (native '{}') not listed, skipping", sym, native),
+            }
+        }
+        if id_to_config.is_empty() {
+            bail!("Vertex: no listed products among {:?}", symbols);
+        }
+        Ok(Self { id_to_config, itype })
+    }
+}
+
+// Wire: {"type":"best_bid_offer","timestamp":"1676151190656903000","product_id":2,
+//        "bid_price":"25000000000000000000000","bid_qty":"1000000000000000000",
+//        "ask_price":"25001000000000000000000","ask_qty":"1000000000000000000"}
+#[derive(Debug, Deserialize)]
+struct BboEvent {
+    #[serde(default)]
+    r#type: Option<String>,
+    #[serde(default)]
+    product_id: Option<u32>,
+    #[serde(default)]
+    bid_price: Option<String>,
+    #[serde(default)]
+    bid_qty: Option<String>,
+    #[serde(default)]
+    ask_price: Option<String>,
+    #[serde(default)]
+    ask_qty: Option<String>,
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+fn parse_x18(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().map(|v| v / X18)
+}
+
+fn parse_bbo(
+    feed: &VertexFeed,
+    text: &str,
+    received_ts: DateTime<Utc>,
+    received_instant: std::time::Instant,
+) -> Option<(String, MarketData)> {
+    if !text.contains("best_bid_offer") {
+        return None;
+    }
+    let evt: BboEvent = serde_json::from_str(text).ok()?;
+    if evt.r#type.as_deref() != Some("best_bid_offer") {
+        return None;
+    }
+    let config_sym = feed.id_to_config.get(&evt.product_id?)?;
+
+    let bid = evt.bid_price.as_deref().and_then(parse_x18)?;
+    let ask = evt.ask_price.as_deref().and_then(parse_x18)?;
+    if bid <= 0.0 || ask <= 0.0 || bid >= ask {
+        return None;
+    }
+    let exchange_ts = evt
+        .timestamp
+        .as_deref()
+        .and_then(|s| s.parse::<i64>().ok())
+        .map(DateTime::from_timestamp_nanos);
+
+    let md = MarketData {
+        bid: Some(bid),
+        ask: Some(ask),
+        bid_qty: evt.bid_qty.as_deref().and_then(parse_x18),
+        ask_qty: evt.ask_qty.as_deref().and_then(parse_x18),
+        exchange_ts_raw: exchange_ts,
+        received_ts: Some(received_ts),
+        received_instant: Some(received_instant),
+        ..Default::default()
+    };
+    Some((config_sym.clone(), md))
+}
+
+async fn connect_and_stream(
+    data: &Arc<MarketDataCollection>,
+    feed: &VertexFeed,
+    config: &ConnectionConfig,
+) -> Result<()> {
+    let ws_stream = tokio::time::timeout(config.message_timeout, nado_ws::connect_with_origin(WS_URL, WS_ORIGIN))
+        .await
+        .with_context(|| format!("connect timed out for {FEED_NAME}"))??;
+    info!("Connected to {}", FEED_NAME);
+
+    let (mut write, mut read) = ws_stream.split();
+
+    for (i, (&pid, sym)) in feed.id_to_config.iter().enumerate() {
+        let msg = json!({
+            "method": "subscribe",
+            "stream": { "type": "best_bid_offer", "product_id": pid },
+            "id": i + 1,
+        });
+        debug!("Vertex sub: {}", msg);
+        if let Err(e) = write.send(Message::Text(msg.to_string().into())).await {
+            close_vertex(write, read).await;
+            bail!("failed to subscribe to Vertex {} (product {}): {}", sym, pid, e);
+        }
+    }
+
+    let mut heartbeat = tokio::time::interval(config.heartbeat_interval);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_message_time = Utc::now();
+    let mut last_exchange_ts: HashMap<SymbolId, DateTime<Utc>> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if Utc::now() - last_message_time > chrono::Duration::from_std(config.message_timeout)? {
+                    warn!("No messages for {:?} on {}, reconnecting", config.message_timeout, FEED_NAME);
+                    break;
+                }
+                if let Err(e) = write.send(Message::Ping(vec![].into())).await {
+                    error!("Failed heartbeat on {}: {}", FEED_NAME, e);
+                    break;
+                }
+            }
+
+            msg = read.next() => {
+                let received_ts = Utc::now();
+                last_message_time = received_ts;
+
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let received_instant = std::time::Instant::now();
+                        let Some((sym, md)) = parse_bbo(feed, text.as_str(), received_ts, received_instant) else {
+                            continue;
+                        };
+                        let Some(&id) = REGISTRY.lookup(&sym, &feed.itype) else { continue };
+                        if let Some(ts) = md.exchange_ts_raw {
+                            if last_exchange_ts.get(&id).is_some_and(|&last| ts < last) {
+                                continue;
+                            }
+                            last_exchange_ts.insert(id, ts);
+                        }
+                        data.push(&id, md);
+                    }
+                    Some(Ok(Message::Ping(payload))) => {
+                        let _ = write.send(Message::Pong(payload)).await;
+                    }
+                    Some(Ok(Message::Close(_))) => {
+                        warn!("{} socket closed.", FEED_NAME);
+                        break;
+                    }
+                    Some(Err(e)) => {
+                        error!("{} socket error: {}", FEED_NAME, e);
+                        break;
+                    }
+                    None => {
+                        warn!("{} stream ended.", FEED_NAME);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    close_vertex(write, read).await;
+    Ok(())
+}
+
+async fn close_vertex(
+    write: futures_util::stream::SplitSink<nado_ws::WsStream, Message>,
+    read: futures_util::stream::SplitStream<nado_ws::WsStream>,
+) {
+    // Drop off the async workers, as in nado.rs: TLS shutdown on a dead
+    // socket can block.
+    let _ = tokio::task::spawn_blocking(move || {
+        drop(read);
+        drop(write);
+    })
+    .await;
+}
+
+/// Perp BBO from the gateway `best_bid_offer` stream. `symbols` are config
+/// symbols like "BTC_USDC"; product ids come from the symbols query.
+///
+/// The subscribe gateway requires permessage-deflate, so this connects
+/// through `nado_ws` with its own reconnect loop rather than
+/// `listen_with_reconnect`.
+pub async fn listen_perp_bbo(
+    data: Arc<MarketDataCollection>,
+    symbols: &[&str],
+    shutdown: Arc<tokio::sync::Notify>,
+) -> Result<()> {
+    let feed = VertexFeed::new_perp(symbols).await?;
+
+    let mut config = ConnectionConfig::default();
+    config.heartbeat_interval = Duration::from_secs(15);
+
+    let mut retry_count: u32 = 0;
+    loop {
+        debug!("Connecting feed {} attempt {}", FEED_NAME, retry_count + 1);
+        let attempt_start = std::time::Instant::now();
+
+        tokio::select! {
+            _ = shutdown.notified() => {
+                info!("Shutdown received for feed {}", FEED_NAME);
+                break;
+            }
+            res = connect_and_stream(&data, &feed, &config) => {
+                // Reset backoff only if the connection was stable for >60s
+                if attempt_start.elapsed() > Duration::from_secs(60) {
+                    retry_count = 0;
+                } else {
+                    retry_count += 1;
+                }
+                let backoff = with_jitter(calculate_backoff(retry_count, config.initial_backoff, config.max_retry_delay), config.backoff_jitter);
+                match res {
+                    Ok(()) => error!("{} disconnected. Reconnecting in {:?}", FEED_NAME, backoff),
+                    Err(e) => error!("{} error: {:#}. Reconnecting in {:?}", FEED_NAME, e, backoff),
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = shutdown.notified() => {
+                        info!("Shutdown during backoff for {}", FEED_NAME);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    info!("Stopped {}", FEED_NAME);
+    Ok(())
+}
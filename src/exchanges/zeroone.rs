@@ -1,9 +1,9 @@
 use crate::exchange_fees::{ExchangeFees, FeeSchedule};
 use crate::exchanges::connection::{
-    ConnectionConfig, ExchangeFeed, WireMessage, listen_with_reconnect,
+    ConnectionConfig, ExchangeFeed, WireMessage, listen_sharded,
 };
 use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
-use crate::orderbook::OrderBook;
+use crate::orderbook::{IMBALANCE_LEVELS, OrderBook};
 use crate::trade_data::{TradeData, TradeDataCollection, TradeSide};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -26,7 +26,6 @@ struct ZeroOneDeltaMsg {
 #[derive(Debug, Deserialize)]
 struct ZeroOneDelta {
     market_symbol: String,
-    #[allow(dead_code)]
     update_id: u64,
     bids: Vec<(f64, f64)>,
     asks: Vec<(f64, f64)>,
@@ -162,12 +161,14 @@ impl ExchangeFeed for ZeroOneBboFeed {
                     ask_qty: ask.map(|(_, q)| q),
                     received_ts: Some(received_ts),
                     received_instant: Some(received_instant),
+                    update_id: Some(delta.update_id),
+                    imbalance: book.imbalance(IMBALANCE_LEVELS),
                     ..Default::default()
                 };
 
                 Ok(vec![(registry_sym, market_data)])
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -181,35 +182,16 @@ pub async fn listen_perp_bbo(
     symbols: &[&str],
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    if symbols.len() <= MAX_STREAMS_PER_WS {
-        let feed = Arc::new(ZeroOneBboFeed::new(symbols, InstrumentType::Perp));
-        return listen_with_reconnect(
-            data, symbols, feed, "zeroone_perp",
-            ConnectionConfig::default(), shutdown,
-        ).await;
-    }
-
-    let mut handles = Vec::new();
-    for (i, chunk) in symbols.chunks(MAX_STREAMS_PER_WS).enumerate() {
-        let owned: Vec<String> = chunk.iter().map(|s| s.to_string()).collect();
-        let feed = Arc::new(ZeroOneBboFeed::new(chunk, InstrumentType::Perp));
-        let data = Arc::clone(&data);
-        let shutdown = Arc::clone(&shutdown);
-        let feed_name = format!("zeroone_perp_{}", i);
-        handles.push(tokio::spawn(async move {
-            let refs: Vec<&str> = owned.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = listen_with_reconnect(
-                data, &refs, feed, &feed_name,
-                ConnectionConfig::default(), shutdown,
-            ).await {
-                log::error!("{} error: {:?}", feed_name, e);
-            }
-        }));
-    }
-    for h in handles {
-        let _ = h.await;
-    }
-    Ok(())
+    listen_sharded(
+        data,
+        symbols,
+        MAX_STREAMS_PER_WS,
+        |chunk| Ok(ZeroOneBboFeed::new(chunk, InstrumentType::Perp)),
+        "zeroone_perp",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
 }
 
 // --- Trade Feed ---
@@ -301,7 +283,7 @@ impl ExchangeFeed for ZeroOneTradeFeed {
 
                 Ok(results)
             }
-            WireMessage::Binary(_) => Ok(vec![]),
+            _ => Ok(vec![]),
         }
     }
 }
@@ -311,33 +293,14 @@ pub async fn listen_perp_trades(
     symbols: &[&str],
     shutdown: Arc<tokio::sync::Notify>,
 ) -> Result<()> {
-    if symbols.len() <= MAX_STREAMS_PER_WS {
-        let feed = Arc::new(ZeroOneTradeFeed::new(symbols, InstrumentType::Perp));
-        return listen_with_reconnect(
-            data, symbols, feed, "zeroone_perp_trades",
-            ConnectionConfig::default(), shutdown,
-        ).await;
-    }
-
-    let mut handles = Vec::new();
-    for (i, chunk) in symbols.chunks(MAX_STREAMS_PER_WS).enumerate() {
-        let owned: Vec<String> = chunk.iter().map(|s| s.to_string()).collect();
-        let feed = Arc::new(ZeroOneTradeFeed::new(chunk, InstrumentType::Perp));
-        let data = Arc::clone(&data);
-        let shutdown = Arc::clone(&shutdown);
-        let feed_name = format!("zeroone_perp_trades_{}", i);
-        handles.push(tokio::spawn(async move {
-            let refs: Vec<&str> = owned.iter().map(|s| s.as_str()).collect();
-            if let Err(e) = listen_with_reconnect(
-                data, &refs, feed, &feed_name,
-                ConnectionConfig::default(), shutdown,
-            ).await {
-                log::error!("{} error: {:?}", feed_name, e);
-            }
-        }));
-    }
-    for h in handles {
-        let _ = h.await;
-    }
-    Ok(())
+    listen_sharded(
+        data,
+        symbols,
+        MAX_STREAMS_PER_WS,
+        |chunk| Ok(ZeroOneTradeFeed::new(chunk, InstrumentType::Perp)),
+        "zeroone_perp_trades",
+        ConnectionConfig::default(),
+        shutdown,
+    )
+    .await
 }
@@ -0,0 +1,168 @@
+//! Venue selection for taking liquidity.
+//!
+//! Venues are ranked by all-in expected price: the touch on the crossed
+//! side, plus the venue's taker fee, plus a penalty that grows with quote
+//! age (an old quote is more likely to have moved). Depth is top of book:
+//! a venue whose displayed size is below the requested quantity ranks
+//! behind every venue that covers it.
+
+use crate::exchanges::get_fees;
+use crate::market_data::{AllMarketData, Exchange, InstrumentType};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+use crate::trade_data::TradeSide;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct VenueSelectConfig {
+    /// Quotes older than this are not considered.
+    pub max_age: Duration,
+    /// Price penalty per second of quote age, in bps.
+    pub stale_penalty_bps_per_s: f64,
+}
+
+impl Default for VenueSelectConfig {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(2),
+            stale_penalty_bps_per_s: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionEstimate {
+    pub exchange: Exchange,
+    /// Touch on the crossed side (ask for buys, bid for sells).
+    pub price: f64,
+    pub taker_fee_bps: f64,
+    pub age: Duration,
+    /// Price after fee and staleness penalty. For buys lower is better,
+    /// for sells higher is better.
+    pub all_in_price: f64,
+    /// Displayed top-of-book size covers the requested quantity. False
+    /// when the venue does not publish sizes.
+    pub covers_qty: bool,
+}
+
+/// Every eligible venue for crossing `side` with `qty` on `id`, best first.
+/// Oracles and venues without a compiled fee schedule are skipped.
+pub fn rank_venues(
+    data: &AllMarketData,
+    id: SymbolId,
+    itype: &InstrumentType,
+    side: TradeSide,
+    qty: f64,
+    cfg: &VenueSelectConfig,
+    now: Instant,
+) -> Vec<ExecutionEstimate> {
+    let fee_symbol = fee_symbol(id);
+    let mut out: Vec<ExecutionEstimate> = data
+        .iter()
+        .filter(|(exchange, _)| !exchange.is_oracle())
+        .filter_map(|(exchange, coll)| {
+            let md = coll.latest(&id)?;
+            let age = now.saturating_duration_since(md.received_instant?);
+            if age > cfg.max_age {
+                return None;
+            }
+            let (price, size) = match side {
+                TradeSide::Buy => (md.ask?, md.ask_qty),
+                TradeSide::Sell => (md.bid?, md.bid_qty),
+                TradeSide::Unknown => return None,
+            };
+            let fees = get_fees(&exchange)?;
+            let taker_fee_bps = match itype {
+                InstrumentType::Spot => fees.get_spot_fees(&fee_symbol).taker_fees_bps,
+                _ => fees.get_perp_fees(&fee_symbol).taker_fees_bps,
+            };
+            let cost_bps = taker_fee_bps + cfg.stale_penalty_bps_per_s * age.as_secs_f64();
+            let all_in_price = match side {
+                TradeSide::Buy => price * (1.0 + cost_bps / 10_000.0),
+                _ => price * (1.0 - cost_bps / 10_000.0),
+            };
+            Some(ExecutionEstimate {
+                exchange,
+                price,
+                taker_fee_bps,
+                age,
+                all_in_price,
+                covers_qty: size.is_some_and(|s| s >= qty),
+            })
+        })
+        .collect();
+
+    out.sort_by(|a, b| {
+        b.covers_qty.cmp(&a.covers_qty).then_with(|| {
+            let by_price = a.all_in_price.partial_cmp(&b.all_in_price).unwrap_or(Ordering::Equal);
+            if side == TradeSide::Sell { by_price.reverse() } else { by_price }
+        })
+    });
+    out
+}
+
+/// Best venue to cross `side` with `qty` on a config-style symbol
+/// ("PERP_BTC_USDT", "SPOT_ETH_USDC").
+pub fn best_venue(
+    data: &AllMarketData,
+    symbol: &str,
+    side: TradeSide,
+    qty: f64,
+    cfg: &VenueSelectConfig,
+) -> Option<ExecutionEstimate> {
+    let id = REGISTRY.resolve(symbol)?;
+    let itype = if symbol.starts_with("SPOT_") {
+        InstrumentType::Spot
+    } else {
+        InstrumentType::Perp
+    };
+    rank_venues(data, id, &itype, side, qty, cfg, Instant::now())
+        .into_iter()
+        .next()
+}
+
+/// Key for fee overrides: "BTC_USDT" from the canonical "PERP-BTC-USDT".
+pub(crate) fn fee_symbol(id: SymbolId) -> String {
+    REGISTRY.canonical(id).map(|c| c.pair()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::MarketData;
+
+    #[test]
+    #[cfg(all(feature = "binance", feature = "bybit", feature = "hyperliquid"))]
+    fn test_rank_prefers_cheapest_covering_venue() {
+        let data = AllMarketData::new();
+        let now = Instant::now();
+        let quote = |bid: f64, ask: f64, qty: f64| MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            bid_qty: Some(qty),
+            ask_qty: Some(qty),
+            received_instant: Some(now),
+            ..Default::default()
+        };
+        // Same ask everywhere: the cheaper perp taker fee wins
+        // (Hyperliquid 3.5 bps vs Binance 5 bps).
+        data.binance.push(&0, quote(99.0, 100.0, 10.0));
+        data.hyperliquid.push(&0, quote(99.0, 100.0, 10.0));
+        // Best raw ask but too little size shown.
+        data.bybit.push(&0, quote(99.5, 99.9, 0.1));
+        // Oracles are never execution venues.
+        data.pyth.push(&0, quote(99.9, 99.91, 100.0));
+
+        let cfg = VenueSelectConfig::default();
+        let ranked = rank_venues(&data, 0, &InstrumentType::Perp, TradeSide::Buy, 1.0, &cfg, now);
+        let order: Vec<Exchange> = ranked.iter().map(|e| e.exchange).collect();
+        assert_eq!(order.first(), Some(&Exchange::Hyperliquid));
+        assert_eq!(order.last(), Some(&Exchange::Bybit));
+        assert!(!order.contains(&Exchange::Pyth));
+        assert!((ranked[0].all_in_price - 100.035).abs() < 1e-9);
+
+        // Too old to be considered at all.
+        let later = now + Duration::from_secs(5);
+        assert!(rank_venues(&data, 0, &InstrumentType::Perp, TradeSide::Sell, 1.0, &cfg, later).is_empty());
+    }
+}
@@ -67,9 +67,9 @@ fn build_feed_targets(cfg: &AppConfig, market_data: &AllMarketData) -> Vec<FeedT
         ] {
             if let Some(pools) = dex_cfg {
                 let name_static: &'static str = Box::leak(dex_name.to_string().into_boxed_str());
-                let _exchange = match Exchange::from_str(dex_name) {
-                    Some(e) => e,
-                    None => continue,
+                let _exchange = match dex_name.parse::<Exchange>() {
+                    Ok(e) => e,
+                    Err(_) => continue,
                 };
                 for pool in &pools.validated_pools(dex_name) {
                     if let Some(&id) = REGISTRY.lookup(&pool.symbol, &InstrumentType::Spot) {
@@ -0,0 +1,156 @@
+//! Feed lifecycle for embedding the crate in another application.
+//!
+//! `FeedManager` starts the configured feeds on either a runtime it owns
+//! or one the caller already runs (`with_handle`), so an application keeps
+//! control of its executor and thread budget. Loaders only spawn tasks, so
+//! starting is safe from inside the caller's async context.
+
+use crate::app_config::{
+    AppConfig, load_deriv_stats, load_futures, load_klines, load_liquidations, load_maintenance, load_options, load_perp, load_spot,
+    load_stale_sweeper,
+};
+use crate::candle_data::CandleStore;
+#[cfg(feature = "analytics")]
+use crate::instruments::InstrumentStore;
+use crate::market_data::AllMarketData;
+use anyhow::Result;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Where feed tasks run.
+pub enum FeedRuntime {
+    Owned(Runtime),
+    Shared(Handle),
+}
+
+impl FeedRuntime {
+    /// A multi-threaded runtime owned by the manager. `worker_threads`
+    /// defaults to tokio's (one per core).
+    pub fn owned(worker_threads: Option<usize>) -> std::io::Result<Self> {
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all().thread_name("crypto-feeds");
+        if let Some(n) = worker_threads {
+            builder.worker_threads(n.max(1));
+        }
+        Ok(FeedRuntime::Owned(builder.build()?))
+    }
+
+    pub fn handle(&self) -> &Handle {
+        match self {
+            FeedRuntime::Owned(rt) => rt.handle(),
+            FeedRuntime::Shared(handle) => handle,
+        }
+    }
+
+    /// Drive `fut` to completion from synchronous code. Panics if called
+    /// from inside an async task, like `Runtime::block_on`.
+    pub fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        match self {
+            FeedRuntime::Owned(rt) => rt.block_on(fut),
+            FeedRuntime::Shared(handle) => handle.block_on(fut),
+        }
+    }
+
+    pub fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle().spawn(fut)
+    }
+}
+
+pub struct FeedManager {
+    runtime: FeedRuntime,
+    market_data: Arc<AllMarketData>,
+    shutdown: Arc<Notify>,
+    handles: Vec<JoinHandle<()>>,
+    klines: Option<Arc<CandleStore>>,
+    #[cfg(feature = "analytics")]
+    instruments: Option<Arc<InstrumentStore>>,
+}
+
+impl FeedManager {
+    /// Manager with its own runtime. Drop it outside async code, as with
+    /// any owned tokio runtime.
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_runtime(FeedRuntime::owned(None)?))
+    }
+
+    /// Manager spawning onto the caller's runtime, e.g. `Handle::current()`.
+    pub fn with_handle(handle: Handle) -> Self {
+        Self::with_runtime(FeedRuntime::Shared(handle))
+    }
+
+    pub fn with_runtime(runtime: FeedRuntime) -> Self {
+        Self {
+            runtime,
+            market_data: Arc::new(AllMarketData::new()),
+            shutdown: Arc::new(Notify::new()),
+            handles: Vec::new(),
+            klines: None,
+            #[cfg(feature = "analytics")]
+            instruments: None,
+        }
+    }
+
+    pub fn runtime(&self) -> &FeedRuntime {
+        &self.runtime
+    }
+
+    pub fn market_data(&self) -> &Arc<AllMarketData> {
+        &self.market_data
+    }
+
+    pub fn shutdown_signal(&self) -> &Arc<Notify> {
+        &self.shutdown
+    }
+
+    /// Closed venue klines, once `start` ran with a `klines` section.
+    pub fn klines(&self) -> Option<&Arc<CandleStore>> {
+        self.klines.as_ref()
+    }
+
+    /// Instrument metadata, once `start` ran with `instruments: true`.
+    #[cfg(feature = "analytics")]
+    pub fn instruments(&self) -> Option<&Arc<InstrumentStore>> {
+        self.instruments.as_ref()
+    }
+
+    /// Start maintenance tracking, the spot, perp, options, dated futures,
+    /// derivatives stats, liquidation and kline feeds, the instrument
+    /// metadata fetch and the stale sweeper from `cfg`.
+    pub fn start(&mut self, cfg: &AppConfig) -> Result<()> {
+        let _guard = self.runtime.handle().enter();
+        load_maintenance(&mut self.handles, cfg, &self.shutdown)?;
+        load_spot(&mut self.handles, cfg, &self.market_data, &self.shutdown)?;
+        load_perp(&mut self.handles, cfg, &self.market_data, &self.shutdown)?;
+        load_options(&mut self.handles, cfg, &self.market_data.options, &self.shutdown)?;
+        load_futures(&mut self.handles, cfg, &self.market_data.futures, &self.shutdown)?;
+        load_deriv_stats(&mut self.handles, cfg, &self.market_data.deriv_stats, &self.shutdown)?;
+        load_liquidations(&mut self.handles, cfg, &self.market_data.liquidations, &self.shutdown)?;
+        if let Some(store) = load_klines(&mut self.handles, cfg, &self.shutdown)? {
+            self.klines = Some(store);
+        }
+        #[cfg(feature = "analytics")]
+        {
+            self.instruments = crate::app_config::load_instruments(&mut self.handles, cfg, &self.shutdown);
+        }
+        load_stale_sweeper(&mut self.handles, cfg, &self.market_data, &self.shutdown)?;
+        Ok(())
+    }
+
+    /// Signal every feed to stop. Tasks on a shared runtime finish on their
+    /// own; an owned runtime is torn down when the manager is dropped.
+    pub fn shutdown(&self) {
+        self.shutdown.notify_waiters();
+    }
+
+    /// Number of spawned tasks still running.
+    pub fn running(&self) -> usize {
+        self.handles.iter().filter(|h| !h.is_finished()).count()
+    }
+}
@@ -0,0 +1,439 @@
+//! FIX 4.4 market data acceptor.
+//!
+//! Serves top-of-book from `AllMarketData` to FIX clients. Supported flow:
+//! Logon (A) → MarketDataRequest (V) → MarketDataSnapshotFullRefresh (W),
+//! then MarketDataIncrementalRefresh (X) whenever a subscribed symbol ticks.
+//! Heartbeat (0), TestRequest (1) and Logout (5) are handled; there is no
+//! resend/persistence — a reconnecting client logs on again with seq 1.
+//!
+//! Instruments are addressed by Symbol (55) = canonical name
+//! ("PERP-BTC-USDT") and SecurityExchange (207) = venue ("binance").
+
+use crate::collector::lookup_canonical;
+use crate::market_data::{AllMarketData, Exchange, MarketData};
+use crate::symbol_registry::SymbolId;
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+use tokio::time::MissedTickBehavior;
+
+const SOH: u8 = 0x01;
+const BEGIN_STRING: &str = "FIX.4.4";
+/// Drop clients whose unparsed input grows past this.
+const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FixConfig {
+    pub addr: String,
+    #[serde(default = "default_sender_comp_id")]
+    pub sender_comp_id: String,
+    /// How often subscriptions are checked for new ticks.
+    #[serde(default = "default_publish_interval_ms")]
+    pub publish_interval_ms: u64,
+}
+
+fn default_sender_comp_id() -> String {
+    "CRYPTOFEEDS".to_string()
+}
+
+fn default_publish_interval_ms() -> u64 {
+    10
+}
+
+// ── Encoding ─────────────────────────────────────────────────────────
+
+/// Body fields of an outbound message; header and trailer added by `finish`.
+pub struct FixBody {
+    buf: Vec<u8>,
+}
+
+impl FixBody {
+    pub fn new() -> Self {
+        Self { buf: Vec::with_capacity(256) }
+    }
+
+    pub fn field(&mut self, tag: u32, value: impl std::fmt::Display) -> &mut Self {
+        use std::io::Write;
+        let _ = write!(self.buf, "{}={}", tag, value);
+        self.buf.push(SOH);
+        self
+    }
+
+    /// Wrap the body with BeginString/BodyLength/header and CheckSum.
+    pub fn finish(&self, msg_type: &str, sender: &str, target: &str, seq: u64) -> Vec<u8> {
+        let sending_time = Utc::now().format("%Y%m%d-%H:%M:%S%.3f");
+        let mut inner = FixBody::new();
+        inner
+            .field(35, msg_type)
+            .field(49, sender)
+            .field(56, target)
+            .field(34, seq)
+            .field(52, sending_time);
+        inner.buf.extend_from_slice(&self.buf);
+
+        let mut out = FixBody::new();
+        out.field(8, BEGIN_STRING).field(9, inner.buf.len());
+        out.buf.extend_from_slice(&inner.buf);
+        let checksum = out.buf.iter().fold(0u32, |acc, b| acc + *b as u32) % 256;
+        out.field(10, format!("{:03}", checksum));
+        out.buf
+    }
+}
+
+impl Default for FixBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ── Decoding ─────────────────────────────────────────────────────────
+
+/// Split one complete message off the front of `buf`, if present.
+pub fn take_message(buf: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let start = buf.windows(5).position(|w| w == b"8=FIX")?;
+    let trailer = buf[start..].windows(4).position(|w| w == b"\x0110=")? + start;
+    let end = buf[trailer + 1..].iter().position(|&b| b == SOH)? + trailer + 2;
+    let msg = buf[start..end].to_vec();
+    buf.drain(..end);
+    Some(msg)
+}
+
+/// Parse `tag=value` pairs in order. Repeating groups stay flattened.
+pub fn parse_fields(msg: &[u8]) -> Vec<(u32, &str)> {
+    msg.split(|&b| b == SOH)
+        .filter_map(|f| {
+            let s = std::str::from_utf8(f).ok()?;
+            let (tag, value) = s.split_once('=')?;
+            Some((tag.parse().ok()?, value))
+        })
+        .collect()
+}
+
+fn get<'a>(fields: &[(u32, &'a str)], tag: u32) -> Option<&'a str> {
+    fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v)
+}
+
+// ── Market data messages ─────────────────────────────────────────────
+
+/// Append bid/offer MD entries. Incrementals repeat symbol/venue per entry.
+fn push_entries(
+    body: &mut FixBody,
+    md: &MarketData,
+    incremental: Option<(&str, &Exchange)>,
+) -> usize {
+    let mut n = 0;
+    for (entry_type, px, qty) in [("0", md.bid, md.bid_qty), ("1", md.ask, md.ask_qty)] {
+        let Some(px) = px else { continue };
+        if let Some((symbol, exchange)) = incremental {
+            body.field(279, "1").field(269, entry_type).field(55, symbol).field(207, exchange.as_str());
+        } else {
+            body.field(269, entry_type);
+        }
+        body.field(270, px);
+        if let Some(qty) = qty {
+            body.field(271, qty);
+        }
+        n += 1;
+    }
+    n
+}
+
+/// Body of a MarketDataSnapshotFullRefresh (W).
+pub fn snapshot_body(md_req_id: &str, symbol: &str, exchange: &Exchange, md: &MarketData) -> FixBody {
+    let mut entries = FixBody::new();
+    let n = push_entries(&mut entries, md, None);
+    let mut body = FixBody::new();
+    body.field(262, md_req_id)
+        .field(55, symbol)
+        .field(207, exchange.as_str())
+        .field(268, n);
+    body.buf.extend_from_slice(&entries.buf);
+    body
+}
+
+/// Body of a MarketDataIncrementalRefresh (X).
+pub fn incremental_body(md_req_id: &str, symbol: &str, exchange: &Exchange, md: &MarketData) -> FixBody {
+    let mut entries = FixBody::new();
+    let n = push_entries(&mut entries, md, Some((symbol, exchange)));
+    let mut body = FixBody::new();
+    body.field(262, md_req_id).field(268, n);
+    body.buf.extend_from_slice(&entries.buf);
+    body
+}
+
+// ── Acceptor ─────────────────────────────────────────────────────────
+
+struct Subscription {
+    md_req_id: String,
+    exchange: Exchange,
+    id: SymbolId,
+    symbol: String,
+    last_count: u64,
+}
+
+struct Session {
+    stream: TcpStream,
+    sender: String,
+    target: String,
+    out_seq: u64,
+    last_sent: Instant,
+    heartbeat: Duration,
+}
+
+impl Session {
+    async fn send(&mut self, msg_type: &str, body: &FixBody) -> Result<()> {
+        self.out_seq += 1;
+        let msg = body.finish(msg_type, &self.sender, &self.target, self.out_seq);
+        self.stream.write_all(&msg).await?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+}
+
+/// Accept FIX sessions on `cfg.addr` until shutdown.
+pub async fn run_fix_acceptor(
+    market_data: Arc<AllMarketData>,
+    cfg: FixConfig,
+    shutdown: Arc<Notify>,
+) -> Result<()> {
+    let listener = TcpListener::bind(&cfg.addr)
+        .await
+        .with_context(|| format!("failed to bind FIX acceptor on {}", cfg.addr))?;
+    info!("FIX acceptor listening on {}", cfg.addr);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            res = listener.accept() => match res {
+                Ok((stream, peer)) => {
+                    info!("FIX client connected from {}", peer);
+                    let data = Arc::clone(&market_data);
+                    let cfg = cfg.clone();
+                    let shutdown = shutdown.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = run_session(stream, data, cfg, shutdown).await {
+                            warn!("FIX session {} ended: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => error!("FIX accept failed: {}", e),
+            }
+        }
+    }
+
+    info!("Stopped FIX acceptor on {}", cfg.addr);
+    Ok(())
+}
+
+async fn run_session(
+    stream: TcpStream,
+    market_data: Arc<AllMarketData>,
+    cfg: FixConfig,
+    shutdown: Arc<Notify>,
+) -> Result<()> {
+    stream.set_nodelay(true)?;
+    let mut session = Session {
+        stream,
+        sender: cfg.sender_comp_id.clone(),
+        target: String::new(),
+        out_seq: 0,
+        last_sent: Instant::now(),
+        heartbeat: Duration::from_secs(30),
+    };
+    let mut logged_on = false;
+    let mut subs: Vec<Subscription> = Vec::new();
+    let mut pending = Vec::with_capacity(4096);
+    let mut read_buf = [0u8; 4096];
+
+    let mut ticker = tokio::time::interval(Duration::from_millis(cfg.publish_interval_ms.max(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => {
+                if logged_on {
+                    let _ = session.send("5", &FixBody::new()).await;
+                }
+                return Ok(());
+            }
+            n = session.stream.read(&mut read_buf) => {
+                let n = n?;
+                if n == 0 {
+                    return Ok(());
+                }
+                pending.extend_from_slice(&read_buf[..n]);
+                if pending.len() > MAX_PENDING_BYTES {
+                    bail!("client sent {} bytes without a complete message", pending.len());
+                }
+                while let Some(msg) = take_message(&mut pending) {
+                    let fields = parse_fields(&msg);
+                    let msg_type = get(&fields, 35).unwrap_or("");
+                    if !logged_on && msg_type != "A" {
+                        bail!("expected Logon, got MsgType {}", msg_type);
+                    }
+                    match msg_type {
+                        "A" => {
+                            session.target = get(&fields, 49).unwrap_or("CLIENT").to_string();
+                            if let Some(hb) = get(&fields, 108).and_then(|v| v.parse::<u64>().ok()) {
+                                session.heartbeat = Duration::from_secs(hb.max(1));
+                            }
+                            let mut body = FixBody::new();
+                            body.field(98, 0).field(108, session.heartbeat.as_secs());
+                            session.send("A", &body).await?;
+                            logged_on = true;
+                            info!("FIX logon from {}", session.target);
+                        }
+                        "1" => {
+                            let mut body = FixBody::new();
+                            if let Some(id) = get(&fields, 112) {
+                                body.field(112, id);
+                            }
+                            session.send("0", &body).await?;
+                        }
+                        "5" => {
+                            session.send("5", &FixBody::new()).await?;
+                            return Ok(());
+                        }
+                        "V" => handle_md_request(&mut session, &fields, &market_data, &mut subs).await?,
+                        _ => {}
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                if logged_on {
+                    publish_incrementals(&mut session, &market_data, &mut subs).await?;
+                    if session.last_sent.elapsed() >= session.heartbeat {
+                        session.send("0", &FixBody::new()).await?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn handle_md_request(
+    session: &mut Session,
+    fields: &[(u32, &str)],
+    market_data: &AllMarketData,
+    subs: &mut Vec<Subscription>,
+) -> Result<()> {
+    let md_req_id = get(fields, 262).unwrap_or("").to_string();
+    let sub_type = get(fields, 263).unwrap_or("0");
+
+    if sub_type == "2" {
+        subs.retain(|s| s.md_req_id != md_req_id);
+        return Ok(());
+    }
+
+    // Symbols and venues come as parallel 55/207 pairs inside NoRelatedSym (146).
+    let symbols: Vec<&str> = fields.iter().filter(|(t, _)| *t == 55).map(|(_, v)| *v).collect();
+    let venues: Vec<&str> = fields.iter().filter(|(t, _)| *t == 207).map(|(_, v)| *v).collect();
+
+    for (i, symbol) in symbols.iter().enumerate() {
+        let venue = venues.get(i).copied().unwrap_or("");
+        let resolved = venue.parse::<Exchange>().ok().zip(lookup_canonical(symbol));
+        let Some((exchange, id)) = resolved else {
+            let mut body = FixBody::new();
+            body.field(262, &md_req_id).field(281, 0).field(58, format!("unknown instrument {venue}:{symbol}"));
+            session.send("Y", &body).await?;
+            continue;
+        };
+
+        let coll = market_data.get_collection(&exchange);
+        let md = coll.latest(&id).unwrap_or_default();
+        session.send("W", &snapshot_body(&md_req_id, symbol, &exchange, &md)).await?;
+
+        if sub_type == "1" {
+            subs.push(Subscription {
+                md_req_id: md_req_id.clone(),
+                exchange,
+                id,
+                symbol: symbol.to_string(),
+                last_count: coll.write_count(&id),
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn publish_incrementals(
+    session: &mut Session,
+    market_data: &AllMarketData,
+    subs: &mut [Subscription],
+) -> Result<()> {
+    for sub in subs.iter_mut() {
+        let coll = market_data.get_collection(&sub.exchange);
+        let count = coll.write_count(&sub.id);
+        if count == sub.last_count {
+            continue;
+        }
+        sub.last_count = count;
+        if let Some(md) = coll.latest(&sub.id) {
+            session
+                .send("X", &incremental_body(&sub.md_req_id, &sub.symbol, &sub.exchange, &md))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_length_and_checksum() {
+        let mut body = FixBody::new();
+        body.field(262, "req1");
+        let msg = body.finish("0", "SRV", "CLI", 7);
+
+        let fields = parse_fields(&msg);
+        assert_eq!(fields[0], (8, BEGIN_STRING));
+        assert_eq!(get(&fields, 35), Some("0"));
+        assert_eq!(get(&fields, 34), Some("7"));
+        assert_eq!(get(&fields, 262), Some("req1"));
+
+        // BodyLength counts bytes after the 9= field up to the checksum field.
+        let body_start = msg.windows(3).position(|w| w == b"35=").unwrap();
+        let trailer = msg.windows(4).position(|w| w == b"\x0110=").unwrap() + 1;
+        assert_eq!(get(&fields, 9).unwrap().parse::<usize>().unwrap(), trailer - body_start);
+
+        let sum = msg[..trailer].iter().fold(0u32, |a, b| a + *b as u32) % 256;
+        assert_eq!(get(&fields, 10), Some(format!("{:03}", sum).as_str()));
+    }
+
+    #[test]
+    fn test_take_message_splits_stream() {
+        let a = FixBody::new().finish("0", "S", "C", 1);
+        let b = FixBody::new().finish("1", "S", "C", 2);
+        let mut buf = [a.clone(), b.clone()].concat();
+        buf.extend_from_slice(b"8=FIX.4.4\x019=5");
+
+        assert_eq!(take_message(&mut buf), Some(a));
+        assert_eq!(take_message(&mut buf), Some(b));
+        assert_eq!(take_message(&mut buf), None);
+        assert_eq!(buf, b"8=FIX.4.4\x019=5");
+    }
+
+    #[test]
+    fn test_snapshot_entries() {
+        let md = MarketData {
+            bid: Some(100.0),
+            ask: Some(101.0),
+            bid_qty: Some(2.0),
+            ask_qty: Some(3.0),
+            ..Default::default()
+        };
+        let body = snapshot_body("r", "PERP-BTC-USDT", &Exchange::Binance, &md);
+        let fields = parse_fields(&body.buf);
+        assert_eq!(get(&fields, 268), Some("2"));
+        let prices: Vec<&str> = fields.iter().filter(|(t, _)| *t == 270).map(|(_, v)| *v).collect();
+        assert_eq!(prices, vec!["100", "101"]);
+    }
+}
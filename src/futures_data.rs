@@ -0,0 +1,294 @@
+//! Dated futures quotes and the basis term structure.
+//!
+//! Quarterly and other dated contracts list and expire on a schedule, so
+//! like options they live outside the symbol registry: each
+//! `FuturesDataCollection` names contracts as feeds first see them. Names
+//! are config symbols `BASE_QUOTE_YYMMDD` ("BTC_USDT_260327"); mappers turn
+//! them into venue names ("BTCUSDT_260327" on Binance, "BTC-27MAR26" on
+//! Deribit). `term_structure` annualizes each expiry's basis against a
+//! reference price.
+
+use crate::contract_index::ContractIndex;
+use crate::market_data::{DataSink, Exchange, FeedItem};
+use crate::options_data::parse_expiry;
+use crate::ring_buffer::RingBuffer;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+pub type FuturesId = usize;
+
+pub const MAX_FUTURES_CONTRACTS: usize = 256;
+
+/// Binance and Deribit both settle dated contracts at 08:00 UTC.
+const EXPIRY_HOUR_UTC: u32 = 8;
+
+/// Parsed dated futures config symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesContract {
+    pub base: String,
+    pub quote: String,
+    pub expiry: NaiveDate,
+}
+
+impl FuturesContract {
+    /// Parse `BASE_QUOTE_YYMMDD`, optionally `FUT_`-prefixed as mappers
+    /// normalize it ("FUT_BTC_USDT_260327").
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let symbol = symbol.strip_prefix("FUT_").unwrap_or(symbol);
+        let mut parts = symbol.split('_');
+        let base = parts.next().filter(|b| !b.is_empty())?.to_uppercase();
+        let quote = parts.next().filter(|q| !q.is_empty())?.to_uppercase();
+        let expiry = parse_expiry(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { base, quote, expiry })
+    }
+
+    /// Config symbol, "BTC_USDT_260327".
+    pub fn name(&self) -> String {
+        format!("{}_{}_{}", self.base, self.quote, self.expiry_code())
+    }
+
+    /// Expiry as `YYMMDD`.
+    pub fn expiry_code(&self) -> String {
+        self.expiry.format("%y%m%d").to_string()
+    }
+
+    pub fn expiry_ts(&self) -> DateTime<Utc> {
+        self.expiry.and_hms_opt(EXPIRY_HOUR_UTC, 0, 0).unwrap().and_utc()
+    }
+
+    /// Days to settlement from `now`; negative once expired.
+    pub fn days_to_expiry(&self, now: DateTime<Utc>) -> f64 {
+        (self.expiry_ts() - now).num_seconds() as f64 / 86_400.0
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FuturesData {
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub bid_qty: Option<f64>,
+    pub ask_qty: Option<f64>,
+    pub mark_price: Option<f64>,
+    /// Venue index the contract settles against, where the feed carries it.
+    pub index_price: Option<f64>,
+    pub exchange_ts_raw: Option<DateTime<Utc>>,
+    pub exchange_ts: Option<DateTime<Utc>>,
+    pub received_ts: Option<DateTime<Utc>>,
+    pub received_instant: Option<Instant>,
+    pub feed_latency_ns: u64,
+}
+
+impl FuturesData {
+    pub fn midquote(&self) -> Option<f64> {
+        Some((self.bid? + self.ask?) / 2.0)
+    }
+}
+
+impl FeedItem for FuturesData {
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        self.exchange_ts_raw
+    }
+    fn set_feed_latency_ns(&mut self, ns: u64) {
+        self.feed_latency_ns = ns;
+    }
+}
+
+/// Simple annualized basis, `(future / reference - 1) * 365 / days`.
+pub fn annualized_basis(future: f64, reference: f64, days: f64) -> Option<f64> {
+    if future <= 0.0 || reference <= 0.0 || days <= 0.0 {
+        return None;
+    }
+    Some((future / reference - 1.0) * 365.0 / days)
+}
+
+pub struct FuturesDataCollection {
+    names: ContractIndex<MAX_FUTURES_CONTRACTS>,
+    slots: Box<[OnceLock<Box<RingBuffer<FuturesData>>>]>,
+}
+
+impl FuturesDataCollection {
+    pub fn new() -> Self {
+        let mut slots = Vec::with_capacity(MAX_FUTURES_CONTRACTS);
+        for _ in 0..MAX_FUTURES_CONTRACTS {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            names: ContractIndex::new(),
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<FuturesId> {
+        self.names.lookup(name)
+    }
+
+    /// Return the id for `name`, assigning one if new. None once full.
+    pub fn get_or_insert(&self, name: &str) -> Option<FuturesId> {
+        self.names.get_or_insert(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.names.names()
+    }
+
+    pub fn push(&self, id: &FuturesId, mut item: FuturesData) {
+        if item.exchange_ts.is_none() {
+            item.exchange_ts = item.exchange_ts_raw;
+        }
+        let ring = self.slots[*id].get_or_init(|| Box::new(RingBuffer::with_capacity(64)));
+        ring.push(item);
+    }
+
+    pub fn latest(&self, id: &FuturesId) -> Option<FuturesData> {
+        self.slots[*id].get()?.latest()
+    }
+
+    pub fn latest_by_name(&self, name: &str) -> Option<FuturesData> {
+        self.latest(&self.lookup(name)?)
+    }
+
+    /// Basis per live expiry on `base`, nearest first. Each contract's
+    /// mid (mark price if one side is empty) is compared with `reference`
+    /// or, when that is None, with the contract's own `index_price`;
+    /// contracts with neither are left out.
+    pub fn term_structure(&self, base: &str, reference: Option<f64>, now: DateTime<Utc>) -> TermStructure {
+        let base = base.to_uppercase();
+        let mut points: Vec<TermPoint> = self
+            .names()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, name)| {
+                let contract = FuturesContract::parse(&name).filter(|c| c.base == base)?;
+                let quote = self.latest(&id)?;
+                let price = quote.midquote().or(quote.mark_price)?;
+                let reference = reference.or(quote.index_price)?;
+                let days = contract.days_to_expiry(now);
+                Some(TermPoint {
+                    basis: price / reference - 1.0,
+                    annualized_basis: annualized_basis(price, reference, days)?,
+                    symbol: name,
+                    expiry: contract.expiry,
+                    days,
+                    price,
+                    reference,
+                })
+            })
+            .collect();
+        points.sort_by(|a, b| a.expiry.cmp(&b.expiry));
+        TermStructure { base, points }
+    }
+}
+
+impl Default for FuturesDataCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSink<FuturesData> for FuturesDataCollection {
+    fn push(&self, id: &FuturesId, item: FuturesData) {
+        FuturesDataCollection::push(self, id, item);
+    }
+}
+
+impl DataSink<FuturesData> for Arc<FuturesDataCollection> {
+    fn push(&self, id: &FuturesId, item: FuturesData) {
+        FuturesDataCollection::push(self, id, item);
+    }
+}
+
+/// One expiry of a term structure.
+#[derive(Debug, Clone)]
+pub struct TermPoint {
+    pub symbol: String,
+    pub expiry: NaiveDate,
+    pub days: f64,
+    pub price: f64,
+    pub reference: f64,
+    /// `price / reference - 1`.
+    pub basis: f64,
+    pub annualized_basis: f64,
+}
+
+/// Snapshot of one base asset's dated futures. Build it again to refresh.
+#[derive(Debug, Clone, Default)]
+pub struct TermStructure {
+    pub base: String,
+    pub points: Vec<TermPoint>,
+}
+
+/// Dated futures collections per venue.
+pub struct AllFuturesData {
+    pub binance: Arc<FuturesDataCollection>,
+    pub deribit: Arc<FuturesDataCollection>,
+}
+
+impl AllFuturesData {
+    pub fn new() -> Self {
+        Self {
+            binance: Arc::new(FuturesDataCollection::new()),
+            deribit: Arc::new(FuturesDataCollection::new()),
+        }
+    }
+
+    /// None for venues without a dated futures feed.
+    pub fn get(&self, exchange: &Exchange) -> Option<&Arc<FuturesDataCollection>> {
+        match exchange {
+            Exchange::Binance => Some(&self.binance),
+            Exchange::Deribit => Some(&self.deribit),
+            _ => None,
+        }
+    }
+}
+
+impl Default for AllFuturesData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_symbols() {
+        let c = FuturesContract::parse("FUT_BTC_USDT_260327").unwrap();
+        assert_eq!(c.base, "BTC");
+        assert_eq!(c.quote, "USDT");
+        assert_eq!(c.expiry, NaiveDate::from_ymd_opt(2026, 3, 27).unwrap());
+        assert_eq!(c.name(), "BTC_USDT_260327");
+        assert_eq!(FuturesContract::parse("eth_usd_27MAR26").unwrap().name(), "ETH_USD_260327");
+        assert!(FuturesContract::parse("BTC_USDT").is_none());
+        assert!(FuturesContract::parse("BTC_USDT_260327_X").is_none());
+    }
+
+    #[test]
+    fn test_term_structure_annualizes_basis() {
+        let coll = FuturesDataCollection::new();
+        let now = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(8, 0, 0).unwrap().and_utc();
+        // 90 and 180 days out.
+        let near = coll.get_or_insert("BTC_USD_260401").unwrap();
+        let far = coll.get_or_insert("BTC_USD_260630").unwrap();
+        let other = coll.get_or_insert("ETH_USD_260401").unwrap();
+        coll.push(&far, FuturesData { bid: Some(101.9), ask: Some(102.1), index_price: Some(100.0), ..Default::default() });
+        coll.push(&near, FuturesData { mark_price: Some(101.0), index_price: Some(100.0), ..Default::default() });
+        coll.push(&other, FuturesData { bid: Some(10.0), ask: Some(10.2), ..Default::default() });
+
+        let ts = coll.term_structure("btc", None, now);
+        assert_eq!(ts.points.len(), 2);
+        assert_eq!(ts.points[0].symbol, "BTC_USD_260401");
+        assert_eq!(ts.points[0].days, 90.0);
+        assert!((ts.points[0].annualized_basis - 0.01 * 365.0 / 90.0).abs() < 1e-12);
+        assert!((ts.points[1].basis - 0.02).abs() < 1e-12);
+
+        // An explicit reference overrides the venue index; ETH has no index.
+        let ts = coll.term_structure("ETH", Some(10.0), now);
+        assert!((ts.points[0].basis - 0.01).abs() < 1e-12);
+        assert!(coll.term_structure("ETH", None, now).points.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+//! Quote-currency conversion for venues quoting in a local fiat (Upbit KRW).
+//!
+//! A `QuoteConversion` rescales a tick's prices from the venue's quote into
+//! another currency before the tick is stored, and stores it under the
+//! converted pair (KRW-BTC lands under BTC_USD). The rate is fixed, supplied
+//! by the application (`custom`), or read off the venue's own stablecoin
+//! market (`via`: USD per KRW = 1 / mid of USDT_KRW, taking USDT at par).
+
+use crate::market_data::MarketData;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Current units of the target currency per unit of the venue's quote.
+/// None while no rate is known; ticks are then dropped.
+pub type RateFn = Arc<dyn Fn() -> Option<f64> + Send + Sync>;
+
+fn default_to() -> String {
+    "USD".into()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteConversionConfig {
+    #[serde(default = "default_to")]
+    pub to: String,
+    /// Fixed units of `to` per unit of the venue's quote.
+    #[serde(default)]
+    pub rate: Option<f64>,
+    /// Base of a market on the same venue that sets the rate, e.g. "USDT".
+    /// Used when `rate` is not given.
+    #[serde(default)]
+    pub via: Option<String>,
+}
+
+enum RateSource {
+    Fixed(f64),
+    Custom(RateFn),
+    /// Inverse of the latest `<base>_<from>` mid, as f64 bits; 0 = none yet.
+    Via { base: String, bits: AtomicU64 },
+}
+
+pub struct QuoteConversion {
+    from: String,
+    to: String,
+    source: RateSource,
+}
+
+impl QuoteConversion {
+    pub fn fixed(from: &str, to: &str, rate: f64) -> Self {
+        Self::with_source(from, to, RateSource::Fixed(rate))
+    }
+
+    pub fn custom(from: &str, to: &str, rate: RateFn) -> Self {
+        Self::with_source(from, to, RateSource::Custom(rate))
+    }
+
+    /// Rate from the venue's `<base>_<from>` market, fed through `observe`.
+    pub fn via(from: &str, to: &str, base: &str) -> Self {
+        Self::with_source(
+            from,
+            to,
+            RateSource::Via { base: base.to_uppercase(), bits: AtomicU64::new(0) },
+        )
+    }
+
+    /// None if the config names neither a `rate` nor a `via` market.
+    pub fn from_config(cfg: &QuoteConversionConfig, from: &str) -> Option<Self> {
+        match (cfg.rate, &cfg.via) {
+            (Some(rate), _) => Some(Self::fixed(from, &cfg.to, rate)),
+            (None, Some(base)) => Some(Self::via(from, &cfg.to, base)),
+            (None, None) => None,
+        }
+    }
+
+    fn with_source(from: &str, to: &str, source: RateSource) -> Self {
+        Self { from: from.to_uppercase(), to: to.to_uppercase(), source }
+    }
+
+    pub fn from_ccy(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to_ccy(&self) -> &str {
+        &self.to
+    }
+
+    /// The (base, quote) market a `via` rate is read from.
+    pub fn via_market(&self) -> Option<(&str, &str)> {
+        match &self.source {
+            RateSource::Via { base, .. } => Some((base, &self.from)),
+            _ => None,
+        }
+    }
+
+    /// Feed every unconverted tick's mid; updates a `via` rate.
+    pub fn observe(&self, base: &str, quote: &str, mid: f64) {
+        if let RateSource::Via { base: via, bits } = &self.source {
+            if mid > 0.0 && base == via && quote == self.from {
+                bits.store((1.0 / mid).to_bits(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn rate(&self) -> Option<f64> {
+        match &self.source {
+            RateSource::Fixed(rate) => Some(*rate),
+            RateSource::Custom(f) => f(),
+            RateSource::Via { bits, .. } => {
+                let rate = f64::from_bits(bits.load(Ordering::Relaxed));
+                (rate > 0.0).then_some(rate)
+            }
+        }
+        .filter(|r| r.is_finite() && *r > 0.0)
+    }
+
+    /// Whether ticks quoted in `quote` are converted.
+    pub fn applies_to(&self, quote: &str) -> bool {
+        quote == self.from
+    }
+
+    /// Rescale bid/ask into the target currency. False (tick untouched)
+    /// while no rate is known.
+    pub fn convert(&self, md: &mut MarketData) -> bool {
+        let Some(rate) = self.rate() else { return false };
+        md.bid = md.bid.map(|p| p * rate);
+        md.ask = md.ask.map(|p| p * rate);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_via_rate_from_stablecoin_market() {
+        let conv = QuoteConversion::via("KRW", "USD", "usdt");
+        assert_eq!(conv.via_market(), Some(("USDT", "KRW")));
+        let mut md = MarketData { bid: Some(140_000_000.0), ask: Some(140_100_000.0), ..Default::default() };
+        assert!(!conv.convert(&mut md));
+        assert_eq!(md.bid, Some(140_000_000.0));
+
+        conv.observe("BTC", "KRW", 140_050_000.0);
+        conv.observe("USDT", "KRW", 1_400.0);
+        assert!(conv.convert(&mut md));
+        assert!((md.bid.unwrap() - 100_000.0).abs() < 1e-6);
+        assert!((md.ask.unwrap() - 100_071.428_571).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let cfg: QuoteConversionConfig = serde_yaml::from_str("rate: 0.00071\n").unwrap();
+        let conv = QuoteConversion::from_config(&cfg, "KRW").unwrap();
+        assert_eq!(conv.to_ccy(), "USD");
+        assert_eq!(conv.rate(), Some(0.00071));
+        assert!(conv.applies_to("KRW") && !conv.applies_to("USDT"));
+
+        let cfg: QuoteConversionConfig = serde_yaml::from_str("to: USDT\n").unwrap();
+        assert!(QuoteConversion::from_config(&cfg, "KRW").is_none());
+
+        let custom = QuoteConversion::custom("KRW", "USD", Arc::new(|| None));
+        assert_eq!(custom.rate(), None);
+    }
+}
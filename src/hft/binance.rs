@@ -11,7 +11,7 @@
 /// ```
 use crate::hft::{HftFeed, TickScratch, extract_str_after, extract_u64_after, parse_f64};
 use crate::market_data::{InstrumentType, MarketData};
-use crate::symbol_registry::{MAX_SYMBOLS, REGISTRY, SymbolId};
+use crate::symbol_registry::{REGISTRY, SymbolId, symbol_capacity};
 use chrono::DateTime;
 use rustc_hash::FxHashMap;
 use std::time::Instant;
@@ -33,7 +33,7 @@ pub struct BinanceHftFeed {
     /// Native Binance symbol (e.g. "BTCUSDT") → SymbolId. Read-only after init.
     pub(crate) symbol_to_id: FxHashMap<String, SymbolId>,
     /// Pre-allocated dedup state: last update ID per SymbolId.
-    pub(crate) last_update_id: [u64; MAX_SYMBOLS],
+    pub(crate) last_update_id: Box<[u64]>,
     /// Subscription stream names, built at startup.
     pub(crate) _streams: Vec<String>,
     /// Full WS URL, built at startup.
@@ -80,7 +80,7 @@ impl BinanceHftFeed {
         Self {
             _itype: itype,
             symbol_to_id,
-            last_update_id: [0u64; MAX_SYMBOLS],
+            last_update_id: vec![0u64; symbol_capacity()].into_boxed_slice(),
             _streams: streams,
             url,
             sub_messages: vec![], // Binance combined stream subscribes via URL, not messages
@@ -96,7 +96,7 @@ impl BinanceHftFeed {
         Self {
             _itype: itype,
             symbol_to_id,
-            last_update_id: [0u64; MAX_SYMBOLS],
+            last_update_id: vec![0u64; symbol_capacity()].into_boxed_slice(),
             _streams: vec![],
             url: String::new(),
             sub_messages: vec![],
@@ -105,7 +105,7 @@ impl BinanceHftFeed {
 
     /// Reset dedup state for all symbols. Used for testing/reconnect.
     pub fn reset_dedup(&mut self) {
-        self.last_update_id = [0u64; MAX_SYMBOLS];
+        self.last_update_id.fill(0);
     }
 
     /// Reset dedup state for a single symbol. Used for testing.
@@ -188,6 +188,7 @@ impl BinanceHftFeed {
                 received_ts: None,
                 received_instant: Some(received_instant),
                 update_id: Some(update_id),
+                imbalance: None,
                 feed_latency_ns: 0,
             },
         );
@@ -296,7 +297,7 @@ mod tests {
         BinanceHftFeed {
             _itype: InstrumentType::Perp,
             symbol_to_id: test_lookup(),
-            last_update_id: [0u64; MAX_SYMBOLS],
+            last_update_id: vec![0u64; symbol_capacity()].into_boxed_slice(),
             _streams: vec![],
             url: String::new(),
             sub_messages: vec![],
@@ -316,7 +317,7 @@ mod tests {
             let ref_ask_qty: f64 = reference.data.ask_quantity.parse().unwrap();
 
             // Reset dedup so each message is accepted
-            feed.last_update_id = [0u64; MAX_SYMBOLS];
+            feed.last_update_id.fill(0);
 
             // Our scanner (zero-alloc)
             let mut scratch = TickScratch::<MarketData>::new();
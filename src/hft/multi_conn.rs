@@ -9,7 +9,7 @@
 use crate::hft::engine::{HftEngine, HftEngineConfig};
 use crate::hft::HftFeed;
 use crate::market_data::{DataSink, MarketData};
-use crate::symbol_registry::{MAX_SYMBOLS, SymbolId};
+use crate::symbol_registry::{SymbolId, symbol_capacity};
 use log::{debug, info, warn};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -19,14 +19,14 @@ const MAX_CONNS: usize = 8;
 
 /// Per-symbol cross-connection dedup state + per-connection win tracking.
 struct SharedState {
-    last_update_id: [AtomicU64; MAX_SYMBOLS],
+    last_update_id: Box<[AtomicU64]>,
     wins: [AtomicU64; MAX_CONNS],
 }
 
 impl SharedState {
     fn new() -> Self {
         Self {
-            last_update_id: std::array::from_fn(|_| AtomicU64::new(0)),
+            last_update_id: (0..symbol_capacity()).map(|_| AtomicU64::new(0)).collect(),
             wins: std::array::from_fn(|_| AtomicU64::new(0)),
         }
     }
@@ -53,6 +53,10 @@ impl<S: DataSink<MarketData>> DataSink<MarketData> for DedupSink<S> {
         }
         self.inner.push(id, item);
     }
+
+    fn invalidate(&self, id: &SymbolId) {
+        self.inner.invalidate(id);
+    }
 }
 
 pub struct MultiConnConfig {
@@ -0,0 +1,351 @@
+//! Historical perp funding rates from Binance, Bybit and OKX.
+//!
+//! `fetch_funding_history` takes a config symbol ("BTC_USDT"), maps it
+//! with the venue's `SymbolMapper` as the live feeds do, and pages back
+//! through the venue endpoint until `start` is covered. Depth differs by
+//! venue: Binance and Bybit keep funding since listing, OKX about three
+//! months.
+
+use crate::mappers::{BinanceMapper, BybitMapper, OkxMapper, SymbolMapper};
+use crate::market_data::{Exchange, InstrumentType};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use log::debug;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::time::Duration;
+
+const BINANCE_URL: &str = "https://fapi.binance.com/fapi/v1/fundingRate";
+const BYBIT_URL: &str = "https://api.bybit.com/v5/market/funding/history";
+const OKX_URL: &str = "https://www.okx.com/api/v5/public/funding-rate-history";
+
+const BINANCE_PAGE: usize = 1000;
+const BYBIT_PAGE: usize = 200;
+const OKX_PAGE: usize = 100;
+
+/// Retries of a rate-limited (429) request before giving up.
+const MAX_RETRIES: u32 = 5;
+
+/// One settled funding payment.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FundingRate {
+    pub funding_ts: DateTime<Utc>,
+    /// Rate for the interval settled at `funding_ts`, as a decimal
+    /// (0.0001 = 1 bp). Positive means longs pay shorts.
+    pub rate: f64,
+}
+
+/// Settled funding for one perp, ascending by time.
+#[derive(Debug, Clone)]
+pub struct FundingSeries {
+    pub exchange: Exchange,
+    /// Config symbol, "BTC_USDT".
+    pub symbol: String,
+    pub rates: Vec<FundingRate>,
+}
+
+impl FundingSeries {
+    /// Sum of rates: what a short collected per unit notional.
+    pub fn cumulative(&self) -> f64 {
+        self.rates.iter().map(|r| r.rate).sum()
+    }
+
+    pub fn mean_rate(&self) -> Option<f64> {
+        (!self.rates.is_empty()).then(|| self.cumulative() / self.rates.len() as f64)
+    }
+
+    /// Mean spacing between settlements.
+    pub fn interval(&self) -> Option<Duration> {
+        let (first, last) = (self.rates.first()?, self.rates.last()?);
+        let n = self.rates.len() as i64 - 1;
+        if n < 1 {
+            return None;
+        }
+        let ms = (last.funding_ts - first.funding_ts).num_milliseconds() / n;
+        (ms >= 0).then(|| Duration::from_millis(ms as u64))
+    }
+
+    /// Mean rate annualised by the mean interval (simple, not compounded),
+    /// comparable with `DerivStats::annualized_funding`.
+    pub fn annualized(&self) -> Option<f64> {
+        let secs = self.interval()?.as_secs_f64();
+        if secs <= 0.0 {
+            return None;
+        }
+        Some(self.mean_rate()? * (365.0 * 86_400.0) / secs)
+    }
+
+    /// Settlements in `[start, end)`.
+    pub fn between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> &[FundingRate] {
+        let lo = self.rates.partition_point(|r| r.funding_ts < start);
+        let hi = self.rates.partition_point(|r| r.funding_ts < end);
+        &self.rates[lo..hi.max(lo)]
+    }
+}
+
+/// Settled funding for perp `symbol` ("BTC_USDT") in `[start, end)`.
+pub async fn fetch_funding_history(
+    exchange: Exchange,
+    symbol: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<FundingSeries> {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let (start_ms, end_ms) = (start.timestamp_millis(), end.timestamp_millis());
+    let itype = InstrumentType::Perp;
+    let mut rates = match exchange {
+        Exchange::Binance => fetch_binance(&client, &BinanceMapper.denormalize(symbol, itype)?, start_ms, end_ms).await?,
+        Exchange::Bybit => fetch_bybit(&client, &BybitMapper.denormalize(symbol, itype)?, start_ms, end_ms).await?,
+        Exchange::Okx => fetch_okx(&client, &OkxMapper.denormalize(symbol, itype)?, start_ms, end_ms).await?,
+        other => bail!("no funding history for {:?}", other),
+    };
+    rates.retain(|r| r.funding_ts >= start && r.funding_ts < end);
+    rates.sort_by_key(|r| r.funding_ts);
+    rates.dedup_by_key(|r| r.funding_ts);
+    Ok(FundingSeries { exchange, symbol: symbol.to_string(), rates })
+}
+
+/// GET `url` with `query`, waiting out 429s.
+async fn get_body(client: &Client, url: &str, query: &[(&str, String)]) -> Result<String> {
+    let mut retries = 0;
+    loop {
+        let resp = client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("GET {url} failed"))?;
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS && retries < MAX_RETRIES {
+            retries += 1;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            continue;
+        }
+        let body = resp.text().await.context("read response body")?;
+        if !status.is_success() {
+            bail!("GET {url} -> {status}; body: {body}");
+        }
+        return Ok(body);
+    }
+}
+
+fn ts_ms(ms: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(ms).with_context(|| format!("bad funding time {ms}"))
+}
+
+// --- Binance ---
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceFunding {
+    funding_time: i64,
+    funding_rate: String,
+}
+
+fn parse_binance(body: &str) -> Result<Vec<FundingRate>> {
+    let rows: Vec<BinanceFunding> = serde_json::from_str(body).context("decode fundingRate JSON")?;
+    rows.into_iter()
+        .map(|r| Ok(FundingRate { funding_ts: ts_ms(r.funding_time)?, rate: r.funding_rate.parse()? }))
+        .collect()
+}
+
+/// Oldest first; pages forward from `start_ms`.
+async fn fetch_binance(client: &Client, native: &str, start_ms: i64, end_ms: i64) -> Result<Vec<FundingRate>> {
+    let mut out = Vec::new();
+    let mut cursor = start_ms;
+    while cursor < end_ms {
+        let query = [
+            ("symbol", native.to_string()),
+            ("startTime", cursor.to_string()),
+            ("endTime", (end_ms - 1).to_string()),
+            ("limit", BINANCE_PAGE.to_string()),
+        ];
+        let page = parse_binance(&get_body(client, BINANCE_URL, &query).await?)?;
+        debug!("Binance funding {}: {} rows from {}", native, page.len(), cursor);
+        let Some(last) = page.last() else { break };
+        cursor = last.funding_ts.timestamp_millis() + 1;
+        let full = page.len() >= BINANCE_PAGE;
+        out.extend(page);
+        if !full {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+// --- Bybit ---
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse {
+    ret_code: i64,
+    #[serde(default)]
+    ret_msg: String,
+    result: Option<BybitResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitResult {
+    list: Vec<BybitFunding>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitFunding {
+    funding_rate: String,
+    /// ms, as a string
+    funding_rate_timestamp: String,
+}
+
+fn parse_bybit(body: &str) -> Result<Vec<FundingRate>> {
+    let resp: BybitResponse = serde_json::from_str(body).context("decode funding/history JSON")?;
+    if resp.ret_code != 0 {
+        bail!("Bybit funding history -> {}: {}", resp.ret_code, resp.ret_msg);
+    }
+    resp.result
+        .map_or_else(Vec::new, |r| r.list)
+        .into_iter()
+        .map(|r| Ok(FundingRate { funding_ts: ts_ms(r.funding_rate_timestamp.parse()?)?, rate: r.funding_rate.parse()? }))
+        .collect()
+}
+
+/// Newest first; pages backward from `end_ms`.
+async fn fetch_bybit(client: &Client, native: &str, start_ms: i64, end_ms: i64) -> Result<Vec<FundingRate>> {
+    let mut out = Vec::new();
+    let mut cursor = end_ms - 1;
+    while cursor >= start_ms {
+        let query = [
+            ("category", "linear".to_string()),
+            ("symbol", native.to_string()),
+            ("startTime", start_ms.to_string()),
+            ("endTime", cursor.to_string()),
+            ("limit", BYBIT_PAGE.to_string()),
+        ];
+        let page = parse_bybit(&get_body(client, BYBIT_URL, &query).await?)?;
+        debug!("Bybit funding {}: {} rows to {}", native, page.len(), cursor);
+        let Some(oldest) = page.iter().map(|r| r.funding_ts).min() else { break };
+        cursor = oldest.timestamp_millis() - 1;
+        let full = page.len() >= BYBIT_PAGE;
+        out.extend(page);
+        if !full {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+// --- OKX ---
+
+#[derive(Debug, Deserialize)]
+struct OkxResponse {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    data: Vec<OkxFunding>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxFunding {
+    /// ms, as a string
+    funding_time: String,
+    funding_rate: String,
+    /// Rate actually settled; empty on some older rows.
+    #[serde(default)]
+    realized_rate: String,
+}
+
+fn parse_okx(body: &str) -> Result<Vec<FundingRate>> {
+    let resp: OkxResponse = serde_json::from_str(body).context("decode funding-rate-history JSON")?;
+    if resp.code != "0" {
+        bail!("OKX funding history -> {}: {}", resp.code, resp.msg);
+    }
+    resp.data
+        .into_iter()
+        .map(|r| {
+            let rate = if r.realized_rate.is_empty() { &r.funding_rate } else { &r.realized_rate };
+            Ok(FundingRate { funding_ts: ts_ms(r.funding_time.parse()?)?, rate: rate.parse()? })
+        })
+        .collect()
+}
+
+/// Newest first; `after` pages to rows strictly older than the cursor.
+async fn fetch_okx(client: &Client, native: &str, start_ms: i64, end_ms: i64) -> Result<Vec<FundingRate>> {
+    let mut out = Vec::new();
+    let mut cursor = end_ms;
+    loop {
+        let query = [
+            ("instId", native.to_string()),
+            ("after", cursor.to_string()),
+            ("limit", OKX_PAGE.to_string()),
+        ];
+        let page = parse_okx(&get_body(client, OKX_URL, &query).await?)?;
+        debug!("OKX funding {}: {} rows before {}", native, page.len(), cursor);
+        let Some(oldest) = page.iter().map(|r| r.funding_ts).min() else { break };
+        cursor = oldest.timestamp_millis();
+        let full = page.len() >= OKX_PAGE;
+        out.extend(page);
+        if !full || cursor <= start_ms {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_venue_pages() {
+        let b = parse_binance(
+            r#"[{"symbol":"BTCUSDT","fundingTime":1698768000000,"fundingRate":"0.00010000","markPrice":"34502.1"}]"#,
+        )
+        .unwrap();
+        assert_eq!(b[0].funding_ts.timestamp_millis(), 1_698_768_000_000);
+        assert_eq!(b[0].rate, 0.0001);
+
+        let y = parse_bybit(
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[
+                {"symbol":"BTCUSDT","fundingRate":"-0.000012","fundingRateTimestamp":"1698796800000"},
+                {"symbol":"BTCUSDT","fundingRate":"0.0001","fundingRateTimestamp":"1698768000000"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(y.len(), 2);
+        assert_eq!(y[0].rate, -0.000012);
+        assert!(parse_bybit(r#"{"retCode":10001,"retMsg":"params error","result":null}"#).is_err());
+
+        let o = parse_okx(
+            r#"{"code":"0","msg":"","data":[
+                {"instId":"BTC-USDT-SWAP","fundingRate":"0.0002","realizedRate":"0.00019","fundingTime":"1698796800000"},
+                {"instId":"BTC-USDT-SWAP","fundingRate":"0.0001","realizedRate":"","fundingTime":"1698768000000"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(o[0].rate, 0.00019);
+        assert_eq!(o[1].rate, 0.0001);
+    }
+
+    #[test]
+    fn test_series_stats() {
+        let t0 = 1_698_768_000_000;
+        let eight_h = 8 * 3_600_000;
+        let series = FundingSeries {
+            exchange: Exchange::Binance,
+            symbol: "BTC_USDT".into(),
+            rates: [0.0001, 0.0003, -0.0001]
+                .iter()
+                .enumerate()
+                .map(|(i, &rate)| FundingRate { funding_ts: ts_ms(t0 + i as i64 * eight_h).unwrap(), rate })
+                .collect(),
+        };
+        assert!((series.cumulative() - 0.0003).abs() < 1e-12);
+        assert_eq!(series.interval(), Some(Duration::from_secs(8 * 3600)));
+        assert!((series.annualized().unwrap() - 0.0001 * 3.0 * 365.0).abs() < 1e-9);
+        assert_eq!(series.between(ts_ms(t0 + 1).unwrap(), ts_ms(t0 + 2 * eight_h).unwrap()).len(), 1);
+
+        let empty = FundingSeries { rates: vec![], ..series };
+        assert_eq!(empty.mean_rate(), None);
+        assert_eq!(empty.interval(), None);
+    }
+}
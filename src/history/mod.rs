@@ -0,0 +1,4 @@
+//! REST backfill of venue history, keyed by the same config symbols as
+//! the live feeds.
+
+pub mod funding;
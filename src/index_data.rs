@@ -0,0 +1,143 @@
+//! Reference index values (volatility indices, oracle/index prices).
+//!
+//! Indices are few and not tradable, so they live outside the symbol
+//! registry. Each collection names its indices as they first appear
+//! (e.g. "DVOL-BTC") and stores a ring buffer per name.
+
+use crate::contract_index::ContractIndex;
+use crate::market_data::{DataSink, FeedItem};
+use crate::ring_buffer::RingBuffer;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+pub type IndexId = usize;
+
+pub const MAX_INDICES: usize = 256;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct IndexData {
+    pub value: f64,
+    pub exchange_ts_raw: Option<DateTime<Utc>>,
+    pub exchange_ts: Option<DateTime<Utc>>,
+    pub received_ts: Option<DateTime<Utc>>,
+    pub received_instant: Option<Instant>,
+    pub feed_latency_ns: u64,
+}
+
+impl FeedItem for IndexData {
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        self.exchange_ts_raw
+    }
+    fn set_feed_latency_ns(&mut self, ns: u64) {
+        self.feed_latency_ns = ns;
+    }
+}
+
+pub struct IndexCollection {
+    names: ContractIndex<MAX_INDICES>,
+    slots: Box<[OnceLock<Box<RingBuffer<IndexData>>>]>,
+}
+
+impl IndexCollection {
+    pub fn new() -> Self {
+        let mut slots = Vec::with_capacity(MAX_INDICES);
+        for _ in 0..MAX_INDICES {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            names: ContractIndex::new(),
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<IndexId> {
+        self.names.lookup(name)
+    }
+
+    /// Return the id for `name`, assigning one if new. None once full.
+    pub fn get_or_insert(&self, name: &str) -> Option<IndexId> {
+        self.names.get_or_insert(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.names.names()
+    }
+
+    pub fn push(&self, id: &IndexId, mut item: IndexData) {
+        if item.exchange_ts.is_none() {
+            item.exchange_ts = item.exchange_ts_raw;
+        }
+        let ring = self.slots[*id].get_or_init(|| Box::new(RingBuffer::new()));
+        ring.push(item);
+    }
+
+    pub fn latest(&self, id: &IndexId) -> Option<IndexData> {
+        self.slots[*id].get()?.latest()
+    }
+
+    pub fn latest_by_name(&self, name: &str) -> Option<IndexData> {
+        self.latest(&self.lookup(name)?)
+    }
+
+    pub fn get_buffer(&self, id: &IndexId) -> Option<&RingBuffer<IndexData>> {
+        self.slots[*id].get().map(|b| b.as_ref())
+    }
+}
+
+impl Default for IndexCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSink<IndexData> for IndexCollection {
+    fn push(&self, id: &IndexId, item: IndexData) {
+        IndexCollection::push(self, id, item);
+    }
+}
+
+impl DataSink<IndexData> for Arc<IndexCollection> {
+    fn push(&self, id: &IndexId, item: IndexData) {
+        IndexCollection::push(self, id, item);
+    }
+}
+
+/// Index collections per source.
+pub struct AllIndexData {
+    /// Deribit volatility indices, keyed "DVOL-BTC", "DVOL-ETH", ...
+    pub deribit: Arc<IndexCollection>,
+}
+
+impl AllIndexData {
+    pub fn new() -> Self {
+        Self {
+            deribit: Arc::new(IndexCollection::new()),
+        }
+    }
+}
+
+impl Default for AllIndexData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_slots() {
+        let coll = IndexCollection::new();
+        let btc = coll.get_or_insert("DVOL-BTC").unwrap();
+        let eth = coll.get_or_insert("DVOL-ETH").unwrap();
+        assert_ne!(btc, eth);
+        assert_eq!(coll.get_or_insert("DVOL-BTC"), Some(btc));
+
+        coll.push(&btc, IndexData { value: 52.5, ..Default::default() });
+        assert_eq!(coll.latest_by_name("DVOL-BTC").unwrap().value, 52.5);
+        assert!(coll.latest(&eth).is_none());
+        assert!(coll.latest_by_name("DVOL-SOL").is_none());
+    }
+}
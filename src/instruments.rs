@@ -0,0 +1,609 @@
+//! Instrument metadata from the venues' REST listings.
+//!
+//! `fetch_instruments` reads one venue's listing (Binance `exchangeInfo`,
+//! Bybit `instruments-info`, Coinbase `products`, Lighter `orderBooks`,
+//! MEXC `contract/detail`) and returns tick size, lot size, minimum notional and contract multiplier
+//! for the config symbols asked for, mapped with the venue's `SymbolMapper`
+//! as the live feeds do. With `instruments: true`, `load_instruments` runs
+//! it once at startup for every configured venue it covers and fills an
+//! `InstrumentStore` keyed like the quote collections.
+//!
+//! The same listings give an `AvailabilityMatrix`: which registry symbols
+//! each covered venue lists, and under what native name.
+
+use crate::mappers::get_mapper;
+use crate::market_data::{Exchange, InstrumentType};
+use crate::symbol_registry::{CanonicalSymbol, REGISTRY, SymbolId, SymbolRegistry};
+use anyhow::{Context, Result, bail};
+use log::{debug, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+const BINANCE_SPOT_URL: &str = "https://api.binance.com/api/v3/exchangeInfo";
+const BINANCE_PERP_URL: &str = "https://fapi.binance.com/fapi/v1/exchangeInfo";
+const BYBIT_URL: &str = "https://api.bybit.com/v5/market/instruments-info";
+const COINBASE_URL: &str = "https://api.exchange.coinbase.com/products";
+const LIGHTER_URL: &str = "https://mainnet.zklighter.elliot.ai/api/v1/orderBooks";
+const MEXC_PERP_URL: &str = "https://contract.mexc.com/api/v1/contract/detail";
+
+const BYBIT_PAGE: usize = 1000;
+
+/// Venue and instrument type pairs `fetch_instruments` covers.
+pub const SUPPORTED: &[(Exchange, InstrumentType)] = &[
+    (Exchange::Binance, InstrumentType::Spot),
+    (Exchange::Binance, InstrumentType::Perp),
+    (Exchange::Bybit, InstrumentType::Spot),
+    (Exchange::Bybit, InstrumentType::Perp),
+    (Exchange::Coinbase, InstrumentType::Spot),
+    (Exchange::Lighter, InstrumentType::Perp),
+    (Exchange::Mexc, InstrumentType::Perp),
+];
+
+#[derive(Debug, Clone)]
+pub struct InstrumentInfo {
+    pub exchange: Exchange,
+    pub itype: InstrumentType,
+    /// Config symbol, "BTC_USDT".
+    pub symbol: String,
+    /// Venue symbol, "BTCUSDT".
+    pub native: String,
+    /// Smallest price increment.
+    pub tick_size: f64,
+    /// Smallest quantity increment, in the venue's order size unit.
+    pub lot_size: f64,
+    /// Smallest order value in quote units, where the venue sets one.
+    pub min_notional: Option<f64>,
+    /// Base units per unit of order size; 1 for venues sized in base.
+    pub contract_multiplier: f64,
+}
+
+impl InstrumentInfo {
+    /// `price` rounded down to a tick.
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_down(price, self.tick_size)
+    }
+
+    /// `qty` rounded down to a lot.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        round_down(qty, self.lot_size)
+    }
+}
+
+fn round_down(x: f64, step: f64) -> f64 {
+    if step > 0.0 {
+        // Nudge so 0.3 / 0.1 = 2.9999... still lands on 3 steps.
+        (x / step + 1e-9).floor() * step
+    } else {
+        x
+    }
+}
+
+/// Fetched metadata by venue and registry id.
+#[derive(Default)]
+pub struct InstrumentStore {
+    by_id: RwLock<HashMap<(Exchange, SymbolId), InstrumentInfo>>,
+}
+
+impl InstrumentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `info` under its symbol's registry id; false if the registry
+    /// doesn't know the symbol.
+    pub fn insert(&self, info: InstrumentInfo) -> bool {
+        let Some(&id) = REGISTRY.lookup(&info.symbol, &info.itype) else {
+            return false;
+        };
+        self.by_id.write().unwrap().insert((info.exchange, id), info);
+        true
+    }
+
+    pub fn get(&self, exchange: &Exchange, id: &SymbolId) -> Option<InstrumentInfo> {
+        self.by_id.read().unwrap().get(&(*exchange, *id)).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Registry symbols listed per venue, from one fetch of each venue's
+/// listing.
+#[derive(Debug, Default)]
+pub struct AvailabilityMatrix {
+    /// Venue and type pairs whose listing was read.
+    checked: Vec<(Exchange, InstrumentType)>,
+    /// Registry id -> (venue, native name) for every venue listing it.
+    listed: HashMap<SymbolId, Vec<(Exchange, String)>>,
+}
+
+impl AvailabilityMatrix {
+    /// Venue and type pairs the matrix covers; a venue whose fetch failed
+    /// is missing rather than listing nothing.
+    pub fn checked(&self) -> &[(Exchange, InstrumentType)] {
+        &self.checked
+    }
+
+    /// Venues listing `id`, with their native names.
+    pub fn venues(&self, id: &SymbolId) -> &[(Exchange, String)] {
+        self.listed.get(id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// `exchange`'s name for `id`, if it lists it.
+    pub fn native(&self, exchange: &Exchange, id: &SymbolId) -> Option<&str> {
+        self.venues(id).iter().find(|(e, _)| e == exchange).map(|(_, n)| n.as_str())
+    }
+
+    pub fn is_listed(&self, exchange: &Exchange, id: &SymbolId) -> bool {
+        self.native(exchange, id).is_some()
+    }
+
+    /// Registry ids `exchange` lists, ascending.
+    pub fn symbols_on(&self, exchange: &Exchange) -> Vec<SymbolId> {
+        let mut ids: Vec<SymbolId> = self.listed.iter().filter(|(_, v)| v.iter().any(|(e, _)| e == exchange)).map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Every `itype` symbol of `registry` that `listed` holds, found by
+    /// the mapper's native name or else by an alias the venue uses as is
+    /// ("1000PEPEUSDT").
+    fn add(&mut self, registry: &SymbolRegistry, exchange: Exchange, itype: InstrumentType, listed: &HashMap<String, Spec>) {
+        let mapper = get_mapper(exchange).ok();
+        for (id, name) in registry.iter() {
+            let Ok(sym) = CanonicalSymbol::parse(name) else { continue };
+            if sym.itype.as_str() != itype.as_str() {
+                continue;
+            }
+            let mapped = mapper.as_ref().and_then(|m| m.denormalize(&sym.pair(), itype).ok()).filter(|n| listed.contains_key(n));
+            let native = mapped.or_else(|| registry.aliases_for(id).into_iter().find(|a| listed.contains_key(*a)).map(str::to_string));
+            if let Some(native) = native {
+                self.listed.entry(id).or_default().push((exchange, native));
+            }
+        }
+        self.checked.push((exchange, itype));
+    }
+}
+
+/// Cross-reference `REGISTRY` with the listings of `venues` (e.g.
+/// `SUPPORTED`). Venues whose listing can't be fetched are logged and left
+/// out of `checked()`.
+pub async fn availability(venues: &[(Exchange, InstrumentType)]) -> AvailabilityMatrix {
+    let mut matrix = AvailabilityMatrix::default();
+    for &(exchange, itype) in venues {
+        match fetch_listing(exchange, itype).await {
+            Ok(listed) => matrix.add(&REGISTRY, exchange, itype, &listed),
+            Err(e) => warn!("{} {} listing unavailable: {:?}", exchange, itype.as_str(), e),
+        }
+    }
+    matrix
+}
+
+/// Config `symbols` `exchange` doesn't list, each with the native name
+/// looked for.
+pub async fn unlisted(exchange: Exchange, itype: InstrumentType, symbols: &[String]) -> Result<Vec<(String, String)>> {
+    let listed = fetch_listing(exchange, itype).await?;
+    let mapper = get_mapper(exchange)?;
+    let mut out = Vec::new();
+    for symbol in symbols {
+        let native = mapper.denormalize(symbol, itype)?;
+        if !listed.contains_key(&native) {
+            out.push((symbol.clone(), native));
+        }
+    }
+    Ok(out)
+}
+
+async fn fetch_listing(exchange: Exchange, itype: InstrumentType) -> Result<HashMap<String, Spec>> {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let listed = match (exchange, itype) {
+        (Exchange::Binance, InstrumentType::Spot) => parse_binance(&get_body(&client, BINANCE_SPOT_URL, &[]).await?)?,
+        (Exchange::Binance, InstrumentType::Perp) => parse_binance(&get_body(&client, BINANCE_PERP_URL, &[]).await?)?,
+        (Exchange::Bybit, InstrumentType::Spot) => fetch_bybit(&client, "spot").await?,
+        (Exchange::Bybit, InstrumentType::Perp) => fetch_bybit(&client, "linear").await?,
+        (Exchange::Coinbase, InstrumentType::Spot) => parse_coinbase(&get_body(&client, COINBASE_URL, &[]).await?)?,
+        (Exchange::Lighter, InstrumentType::Perp) => parse_lighter(&get_body(&client, LIGHTER_URL, &[]).await?)?,
+        (Exchange::Mexc, InstrumentType::Perp) => parse_mexc_perp(&get_body(&client, MEXC_PERP_URL, &[]).await?)?,
+        (exchange, itype) => bail!("no instrument listing for {} {}", exchange, itype.as_str()),
+    };
+    debug!("{} {} lists {} instruments", exchange, itype.as_str(), listed.len());
+    Ok(listed)
+}
+
+/// Metadata for config `symbols` on `exchange`. Symbols the venue doesn't
+/// list are skipped with a warning.
+pub async fn fetch_instruments(exchange: Exchange, itype: InstrumentType, symbols: &[String]) -> Result<Vec<InstrumentInfo>> {
+    let listed = fetch_listing(exchange, itype).await?;
+
+    let mapper = get_mapper(exchange)?;
+    let mut out = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let native = mapper.denormalize(symbol, itype)?;
+        match listed.get(&native) {
+            Some(spec) => out.push(InstrumentInfo {
+                exchange,
+                itype,
+                symbol: symbol.clone(),
+                native,
+                tick_size: spec.tick_size,
+                lot_size: spec.lot_size,
+                min_notional: spec.min_notional,
+                contract_multiplier: spec.contract_multiplier,
+            }),
+            None => warn!("{} {} lists no instrument '{}' ({})", exchange, itype.as_str(), native, symbol),
+        }
+    }
+    Ok(out)
+}
+
+/// Tick size per venue symbol of config `symbols`, for feeds sizing their
+/// book keys. Empty if the listing can't be read, leaving the books on
+/// `orderbook::DEFAULT_TICK_SIZE`.
+pub async fn tick_sizes(exchange: Exchange, itype: InstrumentType, symbols: &[&str]) -> HashMap<String, f64> {
+    let symbols: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+    match fetch_instruments(exchange, itype, &symbols).await {
+        Ok(infos) => infos.into_iter().map(|i| (i.native, i.tick_size)).collect(),
+        Err(e) => {
+            warn!("{} {} tick sizes unavailable, books use the default: {:?}", exchange, itype.as_str(), e);
+            HashMap::new()
+        }
+    }
+}
+
+/// One listing row, keyed by venue symbol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spec {
+    tick_size: f64,
+    lot_size: f64,
+    min_notional: Option<f64>,
+    contract_multiplier: f64,
+}
+
+impl Spec {
+    fn new(tick_size: f64, lot_size: f64, min_notional: Option<f64>) -> Self {
+        Self { tick_size, lot_size, min_notional, contract_multiplier: 1.0 }
+    }
+}
+
+async fn get_body(client: &Client, url: &str, query: &[(&str, String)]) -> Result<String> {
+    let resp = client
+        .get(url)
+        .query(query)
+        .header(reqwest::header::USER_AGENT, "crypto-feeds")
+        .send()
+        .await
+        .with_context(|| format!("GET {url} failed"))?;
+    let status = resp.status();
+    let body = resp.text().await.context("read response body")?;
+    if !status.is_success() {
+        bail!("GET {url} -> {status}; body: {body}");
+    }
+    Ok(body)
+}
+
+fn num(s: &Option<String>) -> Option<f64> {
+    s.as_deref()?.parse().ok().filter(|v: &f64| *v > 0.0)
+}
+
+// --- Binance ---
+
+#[derive(Debug, Deserialize)]
+struct BinanceExchangeInfo {
+    symbols: Vec<BinanceSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceSymbol {
+    symbol: String,
+    #[serde(default)]
+    filters: Vec<BinanceFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceFilter {
+    filter_type: String,
+    tick_size: Option<String>,
+    step_size: Option<String>,
+    /// Spot `NOTIONAL` / `MIN_NOTIONAL`.
+    min_notional: Option<String>,
+    /// Futures `MIN_NOTIONAL`.
+    notional: Option<String>,
+}
+
+fn parse_binance(body: &str) -> Result<HashMap<String, Spec>> {
+    let info: BinanceExchangeInfo = serde_json::from_str(body).context("decode exchangeInfo JSON")?;
+    let mut out = HashMap::new();
+    for s in info.symbols {
+        let (mut tick, mut lot, mut min_notional) = (None, None, None);
+        for f in &s.filters {
+            match f.filter_type.as_str() {
+                "PRICE_FILTER" => tick = num(&f.tick_size),
+                "LOT_SIZE" => lot = num(&f.step_size),
+                "NOTIONAL" | "MIN_NOTIONAL" => min_notional = num(&f.min_notional).or(num(&f.notional)),
+                _ => {}
+            }
+        }
+        if let (Some(tick), Some(lot)) = (tick, lot) {
+            out.insert(s.symbol, Spec::new(tick, lot, min_notional));
+        }
+    }
+    Ok(out)
+}
+
+// --- Bybit ---
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<BybitResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResult {
+    list: Vec<BybitInstrument>,
+    #[serde(default)]
+    next_page_cursor: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitInstrument {
+    symbol: String,
+    price_filter: BybitPriceFilter,
+    lot_size_filter: BybitLotSizeFilter,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitPriceFilter {
+    tick_size: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitLotSizeFilter {
+    /// Linear.
+    qty_step: Option<String>,
+    min_notional_value: Option<String>,
+    /// Spot.
+    base_precision: Option<String>,
+    min_order_amt: Option<String>,
+}
+
+/// One page and the cursor of the next; empty when done.
+fn parse_bybit(body: &str) -> Result<(HashMap<String, Spec>, String)> {
+    let resp: BybitResponse = serde_json::from_str(body).context("decode instruments-info JSON")?;
+    if resp.ret_code != 0 {
+        bail!("Bybit instruments-info error {}: {}", resp.ret_code, resp.ret_msg);
+    }
+    let result = resp.result.context("Bybit instruments-info without result")?;
+    let mut out = HashMap::new();
+    for i in result.list {
+        let lot = num(&i.lot_size_filter.qty_step).or(num(&i.lot_size_filter.base_precision));
+        let min_notional = num(&i.lot_size_filter.min_notional_value).or(num(&i.lot_size_filter.min_order_amt));
+        if let (Some(tick), Some(lot)) = (num(&i.price_filter.tick_size), lot) {
+            out.insert(i.symbol, Spec::new(tick, lot, min_notional));
+        }
+    }
+    Ok((out, result.next_page_cursor))
+}
+
+async fn fetch_bybit(client: &Client, category: &str) -> Result<HashMap<String, Spec>> {
+    let mut out = HashMap::new();
+    let mut cursor = String::new();
+    loop {
+        let mut query = vec![("category", category.to_string()), ("limit", BYBIT_PAGE.to_string())];
+        if !cursor.is_empty() {
+            query.push(("cursor", cursor.clone()));
+        }
+        let (page, next) = parse_bybit(&get_body(client, BYBIT_URL, &query).await?)?;
+        out.extend(page);
+        if next.is_empty() || next == cursor {
+            break;
+        }
+        cursor = next;
+    }
+    Ok(out)
+}
+
+// --- Coinbase ---
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseProduct {
+    id: String,
+    quote_increment: Option<String>,
+    base_increment: Option<String>,
+    min_market_funds: Option<String>,
+}
+
+fn parse_coinbase(body: &str) -> Result<HashMap<String, Spec>> {
+    let products: Vec<CoinbaseProduct> = serde_json::from_str(body).context("decode products JSON")?;
+    Ok(products
+        .into_iter()
+        .filter_map(|p| {
+            let spec = Spec::new(num(&p.quote_increment)?, num(&p.base_increment)?, num(&p.min_market_funds));
+            Some((p.id, spec))
+        })
+        .collect())
+}
+
+// --- Lighter ---
+
+#[derive(Debug, Deserialize)]
+struct LighterOrderBooks {
+    order_books: Vec<LighterOrderBook>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LighterOrderBook {
+    symbol: String,
+    supported_price_decimals: u32,
+    supported_size_decimals: u32,
+    min_quote_amount: Option<String>,
+}
+
+fn parse_lighter(body: &str) -> Result<HashMap<String, Spec>> {
+    let books: LighterOrderBooks = serde_json::from_str(body).context("decode orderBooks JSON")?;
+    Ok(books
+        .order_books
+        .into_iter()
+        .map(|b| {
+            let tick = 10f64.powi(-(b.supported_price_decimals as i32));
+            let lot = 10f64.powi(-(b.supported_size_decimals as i32));
+            (b.symbol, Spec::new(tick, lot, num(&b.min_quote_amount)))
+        })
+        .collect())
+}
+
+// --- MEXC ---
+
+#[derive(Debug, Deserialize)]
+struct MexcContractDetail {
+    success: bool,
+    #[serde(default)]
+    data: Vec<MexcContract>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcContract {
+    symbol: String,
+    price_unit: f64,
+    vol_unit: f64,
+    contract_size: f64,
+}
+
+fn parse_mexc_perp(body: &str) -> Result<HashMap<String, Spec>> {
+    let detail: MexcContractDetail = serde_json::from_str(body).context("decode contract/detail JSON")?;
+    if !detail.success {
+        bail!("MEXC contract/detail returned success=false");
+    }
+    Ok(detail
+        .data
+        .into_iter()
+        .map(|c| {
+            let spec = Spec { contract_multiplier: c.contract_size, ..Spec::new(c.price_unit, c.vol_unit, None) };
+            (c.symbol, spec)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_venue_listings() {
+        let b = parse_binance(
+            r#"{"timezone":"UTC","symbols":[{"symbol":"BTCUSDT","status":"TRADING","filters":[
+                {"filterType":"PRICE_FILTER","minPrice":"0.01","maxPrice":"1000000","tickSize":"0.01"},
+                {"filterType":"LOT_SIZE","minQty":"0.00001","maxQty":"9000","stepSize":"0.00001"},
+                {"filterType":"NOTIONAL","minNotional":"5.00000000","applyMinToMarket":true}]},
+                {"symbol":"ETHUSDT","filters":[{"filterType":"PRICE_FILTER","tickSize":"0.01"},
+                {"filterType":"LOT_SIZE","stepSize":"0.001"},{"filterType":"MIN_NOTIONAL","notional":"20"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(b["BTCUSDT"], Spec::new(0.01, 0.00001, Some(5.0)));
+        assert_eq!(b["ETHUSDT"].min_notional, Some(20.0));
+
+        let (y, next) = parse_bybit(
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[
+                {"symbol":"BTCUSDT","priceFilter":{"minPrice":"0.10","tickSize":"0.10"},
+                 "lotSizeFilter":{"maxOrderQty":"1190","minOrderQty":"0.001","qtyStep":"0.001","minNotionalValue":"5"}}],
+                "nextPageCursor":"abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(y["BTCUSDT"], Spec::new(0.1, 0.001, Some(5.0)));
+        assert_eq!(next, "abc");
+        let (s, _) = parse_bybit(
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"spot","list":[
+                {"symbol":"ETHUSDT","priceFilter":{"tickSize":"0.01"},
+                 "lotSizeFilter":{"basePrecision":"0.00001","minOrderAmt":"1"}}],"nextPageCursor":""}}"#,
+        )
+        .unwrap();
+        assert_eq!(s["ETHUSDT"], Spec::new(0.01, 0.00001, Some(1.0)));
+        assert!(parse_bybit(r#"{"retCode":10001,"retMsg":"params error","result":null}"#).is_err());
+
+        let c = parse_coinbase(
+            r#"[{"id":"BTC-USD","base_currency":"BTC","quote_increment":"0.01","base_increment":"0.00000001","min_market_funds":"1"}]"#,
+        )
+        .unwrap();
+        assert_eq!(c["BTC-USD"], Spec::new(0.01, 0.00000001, Some(1.0)));
+
+        let l = parse_lighter(
+            r#"{"code":200,"order_books":[{"symbol":"ETH","market_id":0,"status":"active","min_base_amount":"0.0050",
+                "min_quote_amount":"10.000000","supported_size_decimals":4,"supported_price_decimals":2,"supported_quote_decimals":6}]}"#,
+        )
+        .unwrap();
+        assert_eq!(l["ETH"], Spec::new(0.01, 0.0001, Some(10.0)));
+
+        let m = parse_mexc_perp(
+            r#"{"success":true,"code":0,"data":[{"symbol":"BTC_USDT","displayName":"BTC_USDT PERPETUAL",
+                "contractSize":0.0001,"priceUnit":0.1,"volUnit":1,"minVol":1}]}"#,
+        )
+        .unwrap();
+        assert_eq!(m["BTC_USDT"], Spec { contract_multiplier: 0.0001, ..Spec::new(0.1, 1.0, None) });
+    }
+
+    #[test]
+    fn test_availability_matrix() {
+        let registry = SymbolRegistry::from_bases(&["BTC".to_string(), "PEPE".to_string()]).unwrap();
+        let spec = Spec::new(0.1, 0.001, None);
+        let perps: HashMap<String, Spec> = [("BTCUSDT".to_string(), spec), ("1000PEPEUSDT".to_string(), spec)].into();
+        let spot: HashMap<String, Spec> = [("BTC-USD".to_string(), spec)].into();
+
+        let mut matrix = AvailabilityMatrix::default();
+        matrix.add(&registry, Exchange::Binance, InstrumentType::Perp, &perps);
+        matrix.add(&registry, Exchange::Coinbase, InstrumentType::Spot, &spot);
+        assert_eq!(matrix.checked().len(), 2);
+
+        let btc_perp = *registry.lookup("BTCUSDT", &InstrumentType::Perp).unwrap();
+        let pepe_perp = *registry.lookup("PEPEUSDT", &InstrumentType::Perp).unwrap();
+        let btc_usd = *registry.lookup("BTC-USD", &InstrumentType::Spot).unwrap();
+        assert_eq!(matrix.native(&Exchange::Binance, &btc_perp), Some("BTCUSDT"));
+        assert_eq!(matrix.native(&Exchange::Binance, &pepe_perp), Some("1000PEPEUSDT"));
+        assert_eq!(matrix.venues(&btc_usd), [(Exchange::Coinbase, "BTC-USD".to_string())]);
+        assert!(!matrix.is_listed(&Exchange::Coinbase, &btc_perp));
+        let mut on_binance = vec![btc_perp, pepe_perp];
+        on_binance.sort_unstable();
+        assert_eq!(matrix.symbols_on(&Exchange::Binance), on_binance);
+    }
+
+    #[test]
+    fn test_rounding_and_store() {
+        let info = InstrumentInfo {
+            exchange: Exchange::Binance,
+            itype: InstrumentType::Spot,
+            symbol: "BTC_USDT".into(),
+            native: "BTCUSDT".into(),
+            tick_size: 0.1,
+            lot_size: 0.001,
+            min_notional: Some(5.0),
+            contract_multiplier: 1.0,
+        };
+        assert!((info.round_price(100.37) - 100.3).abs() < 1e-9);
+        assert!((info.round_price(0.3) - 0.3).abs() < 1e-9);
+        assert!((info.round_qty(0.0129) - 0.012).abs() < 1e-12);
+
+        let store = InstrumentStore::new();
+        assert!(store.insert(info.clone()));
+        assert!(!store.insert(InstrumentInfo { symbol: "NOPE_USDT".into(), ..info.clone() }));
+        let id = *REGISTRY.lookup("BTC_USDT", &InstrumentType::Spot).unwrap();
+        let got = store.get(&Exchange::Binance, &id).unwrap();
+        assert_eq!((got.native.as_str(), got.tick_size, got.min_notional), ("BTCUSDT", 0.1, Some(5.0)));
+        assert!(store.get(&Exchange::Bybit, &id).is_none());
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -0,0 +1,207 @@
+//! On-demand subscriptions.
+//!
+//! With `lazy_subscriptions` configured, a feed subscribes only to the
+//! configured symbols a consumer has asked for (`register_interest`, or any
+//! `PyMarketData` read), and drops a symbol again after `idle_timeout_s`
+//! without interest. A feed with nothing wanted stays disconnected. Feeds
+//! that can (un)subscribe on a live socket do so; the rest reconnect with
+//! the new symbol set.
+
+use crate::market_data::{Exchange, InstrumentType};
+use crate::symbol_registry::{REGISTRY, SymbolId, symbol_capacity};
+use log::warn;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+const N_VENUES: usize = Exchange::ALL.len();
+
+fn default_idle_timeout_s() -> u64 {
+    300
+}
+fn default_check_interval_ms() -> u64 {
+    1_000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LazySubscriptionConfig {
+    /// Unsubscribe after this long without interest; 0 keeps symbols forever.
+    #[serde(default = "default_idle_timeout_s")]
+    pub idle_timeout_s: u64,
+    /// How often feeds re-check the wanted set (new interest is applied at once).
+    #[serde(default = "default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Venues run lazily. Empty means every venue.
+    #[serde(default)]
+    pub venues: Vec<String>,
+}
+
+impl Default for LazySubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_s: default_idle_timeout_s(),
+            check_interval_ms: default_check_interval_ms(),
+            venues: Vec::new(),
+        }
+    }
+}
+
+pub struct LazySubscriptions {
+    venues: [bool; N_VENUES],
+    idle_timeout: Duration,
+    check_interval: Duration,
+    epoch: Instant,
+    /// Symbols per venue in `last_ms`.
+    n_symbols: usize,
+    /// Last interest per (venue, symbol), ms since `epoch` plus one; 0 = never.
+    last_ms: Box<[AtomicU64]>,
+    changed: [Notify; N_VENUES],
+}
+
+impl LazySubscriptions {
+    pub fn new(cfg: &LazySubscriptionConfig) -> Self {
+        let mut venues = [cfg.venues.is_empty(); N_VENUES];
+        for v in &cfg.venues {
+            match v.parse::<Exchange>() {
+                Ok(e) => venues[e as usize] = true,
+                Err(_) => warn!("lazy_subscriptions: unknown venue '{}'", v),
+            }
+        }
+        Self {
+            venues,
+            idle_timeout: Duration::from_secs(cfg.idle_timeout_s),
+            check_interval: Duration::from_millis(cfg.check_interval_ms.max(1)),
+            epoch: Instant::now(),
+            n_symbols: symbol_capacity(),
+            last_ms: (0..N_VENUES * symbol_capacity()).map(|_| AtomicU64::new(0)).collect(),
+            changed: std::array::from_fn(|_| Notify::new()),
+        }
+    }
+
+    pub fn covers(&self, exchange: Exchange) -> bool {
+        self.venues[exchange as usize]
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        self.check_interval
+    }
+
+    fn stamp(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.epoch).as_millis() as u64 + 1
+    }
+
+    fn is_live(&self, stamp: u64, now: Instant) -> bool {
+        stamp != 0
+            && (self.idle_timeout.is_zero()
+                || self.stamp(now).saturating_sub(stamp) <= self.idle_timeout.as_millis() as u64)
+    }
+
+    /// The slot for (exchange, id); None for ids past the registry's, which
+    /// would land in the next venue's row.
+    fn slot(&self, exchange: Exchange, id: SymbolId) -> Option<&AtomicU64> {
+        (id < self.n_symbols).then(|| &self.last_ms[exchange as usize * self.n_symbols + id])
+    }
+
+    /// Ignored for ids outside the registry.
+    pub fn touch_at(&self, exchange: Exchange, id: SymbolId, now: Instant) {
+        let Some(slot) = self.slot(exchange, id) else {
+            return;
+        };
+        let prev = slot.swap(self.stamp(now), Ordering::Relaxed);
+        if !self.is_live(prev, now) {
+            self.changed[exchange as usize].notify_waiters();
+        }
+    }
+
+    /// False for ids outside the registry.
+    pub fn wanted_at(&self, exchange: Exchange, id: SymbolId, now: Instant) -> bool {
+        self.slot(exchange, id).is_some_and(|slot| self.is_live(slot.load(Ordering::Relaxed), now))
+    }
+
+    /// The subset of a feed's configured `symbols` to subscribe to now.
+    /// Symbols outside the registry (e.g. options) are always kept.
+    pub fn wanted_symbols<'a>(
+        &self,
+        exchange: Exchange,
+        symbols: &[&'a str],
+        itype: &InstrumentType,
+        now: Instant,
+    ) -> Vec<&'a str> {
+        symbols
+            .iter()
+            .filter(|s| REGISTRY.lookup(s, itype).is_none_or(|&id| self.wanted_at(exchange, id, now)))
+            .copied()
+            .collect()
+    }
+
+    /// Resolves when a symbol on `exchange` gains interest.
+    pub async fn changed(&self, exchange: Exchange) {
+        self.changed[exchange as usize].notified().await
+    }
+}
+
+static LAZY: OnceLock<LazySubscriptions> = OnceLock::new();
+
+/// Enable lazy mode for feeds started from now on. Only the first call
+/// takes effect.
+pub fn install(cfg: &LazySubscriptionConfig) {
+    let _ = LAZY.set(LazySubscriptions::new(cfg));
+}
+
+pub fn active() -> Option<&'static LazySubscriptions> {
+    LAZY.get()
+}
+
+/// Mark (exchange, id) as wanted, subscribing it if it wasn't. No-op
+/// unless lazy mode is installed.
+pub fn register_interest(exchange: Exchange, id: SymbolId) {
+    if let Some(lazy) = LAZY.get() {
+        lazy.touch_at(exchange, id, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interest_expires() {
+        let lazy = LazySubscriptions::new(&LazySubscriptionConfig { idle_timeout_s: 10, ..Default::default() });
+        let t0 = Instant::now();
+        assert!(!lazy.wanted_at(Exchange::Bybit, 4, t0));
+        lazy.touch_at(Exchange::Bybit, 4, t0);
+        assert!(lazy.wanted_at(Exchange::Bybit, 4, t0 + Duration::from_secs(10)));
+        assert!(!lazy.wanted_at(Exchange::Bybit, 4, t0 + Duration::from_secs(11)));
+        assert!(!lazy.wanted_at(Exchange::Okx, 4, t0));
+    }
+
+    #[test]
+    fn test_out_of_range_ids_ignored() {
+        let lazy = LazySubscriptions::new(&LazySubscriptionConfig::default());
+        let now = Instant::now();
+        let n = symbol_capacity();
+        lazy.touch_at(Exchange::ALL[0], n, now);
+        assert!(!lazy.wanted_at(Exchange::ALL[0], n, now));
+        assert!(!lazy.wanted_at(Exchange::ALL[1], 0, now));
+        let last = Exchange::ALL[N_VENUES - 1];
+        lazy.touch_at(last, n + 7, now);
+        assert!(!lazy.wanted_at(last, n + 7, now));
+    }
+
+    #[test]
+    fn test_wanted_symbols() {
+        let lazy = LazySubscriptions::new(&LazySubscriptionConfig { venues: vec!["bybit".into()], ..Default::default() });
+        assert!(lazy.covers(Exchange::Bybit));
+        assert!(!lazy.covers(Exchange::Binance));
+        let now = Instant::now();
+        let eth = *REGISTRY.lookup("ETH_USDT", &InstrumentType::Perp).unwrap();
+        lazy.touch_at(Exchange::Bybit, eth, now);
+        let syms = ["BTC_USDT", "ETH_USDT", "NOT_A_SYMBOL"];
+        assert_eq!(
+            lazy.wanted_symbols(Exchange::Bybit, &syms, &InstrumentType::Perp, now),
+            vec!["ETH_USDT", "NOT_A_SYMBOL"]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Consolidated cross-venue depth.
+//!
+//! Merges per-venue book snapshots for one symbol into a single ladder with
+//! every level tagged by venue. With taker fee adjustment on, levels are
+//! ordered by what crossing them actually costs, so a deep but expensive
+//! venue can sort behind a thinner, cheaper one at the same quoted price.
+
+use crate::exchanges::get_fees;
+use crate::market_data::{BookCollection, BookLevel, BookSnapshot, Exchange, InstrumentType};
+use crate::symbol_registry::SymbolId;
+use crate::trade_data::TradeSide;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy)]
+pub enum FeeAdjust {
+    /// Rank by quoted price.
+    None,
+    /// Rank by price after each venue's default taker fee for this
+    /// instrument type.
+    Taker(InstrumentType),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LadderLevel {
+    pub exchange: Exchange,
+    pub price: f64,
+    pub qty: f64,
+    /// Price after the taker fee (bids lower, asks higher). Equals `price`
+    /// without fee adjustment or when the venue has no fee schedule.
+    pub effective_price: f64,
+}
+
+/// Bids best (highest effective price) first, asks best (lowest) first.
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidatedLadder {
+    pub bids: Vec<LadderLevel>,
+    pub asks: Vec<LadderLevel>,
+}
+
+impl ConsolidatedLadder {
+    pub fn merge<'a>(books: impl IntoIterator<Item = (Exchange, &'a BookSnapshot)>, fees: FeeAdjust) -> Self {
+        let mut ladder = Self::default();
+        for (exchange, book) in books {
+            let fee = match fees {
+                FeeAdjust::None => 0.0,
+                FeeAdjust::Taker(itype) => taker_fee(&exchange, &itype),
+            };
+            ladder.bids.extend(venue_levels(exchange, &book.bids[..book.bid_count as usize], -fee));
+            ladder.asks.extend(venue_levels(exchange, &book.asks[..book.ask_count as usize], fee));
+        }
+        let by_eff = |a: &LadderLevel, b: &LadderLevel| {
+            a.effective_price.partial_cmp(&b.effective_price).unwrap_or(Ordering::Equal)
+        };
+        ladder.bids.sort_by(|a, b| by_eff(b, a).then(b.qty.total_cmp(&a.qty)));
+        ladder.asks.sort_by(|a, b| by_eff(a, b).then(b.qty.total_cmp(&a.qty)));
+        ladder
+    }
+
+    /// Merge the latest snapshot for `id` from each venue's collection.
+    pub fn from_collections(sources: &[(Exchange, &BookCollection)], id: &SymbolId, fees: FeeAdjust) -> Self {
+        let snaps: Vec<(Exchange, BookSnapshot)> = sources
+            .iter()
+            .filter_map(|(exchange, coll)| Some((*exchange, coll.latest(id)?)))
+            .collect();
+        Self::merge(snaps.iter().map(|(e, s)| (*e, s)), fees)
+    }
+
+    pub fn best_bid(&self) -> Option<&LadderLevel> {
+        self.bids.first()
+    }
+
+    pub fn best_ask(&self) -> Option<&LadderLevel> {
+        self.asks.first()
+    }
+
+    /// Walk the ladder to fill `qty` by crossing `side` (Buy takes asks,
+    /// Sell hits bids). Returns (average effective price, filled qty);
+    /// filled may be short of `qty` if the ladder runs out.
+    pub fn sweep(&self, side: TradeSide, qty: f64) -> Option<(f64, f64)> {
+        let levels = match side {
+            TradeSide::Buy => &self.asks,
+            TradeSide::Sell => &self.bids,
+            TradeSide::Unknown => return None,
+        };
+        let mut filled = 0.0;
+        let mut cost = 0.0;
+        for l in levels {
+            if filled >= qty {
+                break;
+            }
+            let take = l.qty.min(qty - filled);
+            filled += take;
+            cost += take * l.effective_price;
+        }
+        (filled > 0.0).then(|| (cost / filled, filled))
+    }
+
+    /// Quantity per venue used by `sweep`, in ladder order of first use.
+    pub fn allocation(&self, side: TradeSide, qty: f64) -> Vec<(Exchange, f64)> {
+        let levels = match side {
+            TradeSide::Buy => &self.asks,
+            TradeSide::Sell => &self.bids,
+            TradeSide::Unknown => return Vec::new(),
+        };
+        let mut out: Vec<(Exchange, f64)> = Vec::new();
+        let mut remaining = qty;
+        for l in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            let take = l.qty.min(remaining);
+            remaining -= take;
+            match out.iter_mut().find(|(e, _)| *e == l.exchange) {
+                Some((_, q)) => *q += take,
+                None => out.push((l.exchange, take)),
+            }
+        }
+        out
+    }
+}
+
+/// `signed_fee` is negative for bids (selling into them nets less).
+fn venue_levels(exchange: Exchange, levels: &[BookLevel], signed_fee: f64) -> impl Iterator<Item = LadderLevel> + '_ {
+    levels.iter().filter(|l| l.qty > 0.0).map(move |l| LadderLevel {
+        exchange,
+        price: l.price,
+        qty: l.qty,
+        effective_price: l.price * (1.0 + signed_fee),
+    })
+}
+
+fn taker_fee(exchange: &Exchange, itype: &InstrumentType) -> f64 {
+    let Some(fees) = get_fees(exchange) else { return 0.0 };
+    let bps = match itype {
+        InstrumentType::Spot => fees.get_spot_fees("").taker_fees_bps,
+        _ => fees.get_perp_fees("").taker_fees_bps,
+    };
+    bps / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> BookSnapshot {
+        let mut b = BookSnapshot::default();
+        for (i, &(price, qty)) in bids.iter().enumerate() {
+            b.bids[i] = BookLevel { price, qty };
+        }
+        for (i, &(price, qty)) in asks.iter().enumerate() {
+            b.asks[i] = BookLevel { price, qty };
+        }
+        b.bid_count = bids.len() as u8;
+        b.ask_count = asks.len() as u8;
+        b
+    }
+
+    #[test]
+    fn test_merge_and_sweep() {
+        let a = book(&[(99.0, 1.0), (98.0, 5.0)], &[(101.0, 1.0), (102.0, 5.0)]);
+        let b = book(&[(99.5, 2.0)], &[(100.5, 2.0), (101.0, 3.0)]);
+        let ladder =
+            ConsolidatedLadder::merge([(Exchange::Okx, &a), (Exchange::Kraken, &b)], FeeAdjust::None);
+
+        assert_eq!(ladder.best_bid().unwrap().exchange, Exchange::Kraken);
+        let asks: Vec<(Exchange, f64)> = ladder.asks.iter().map(|l| (l.exchange, l.price)).collect();
+        // Equal price: the bigger level first.
+        assert_eq!(
+            asks,
+            vec![
+                (Exchange::Kraken, 100.5),
+                (Exchange::Kraken, 101.0),
+                (Exchange::Okx, 101.0),
+                (Exchange::Okx, 102.0)
+            ]
+        );
+
+        let (avg, filled) = ladder.sweep(TradeSide::Buy, 4.0).unwrap();
+        assert_eq!(filled, 4.0);
+        assert!((avg - (2.0 * 100.5 + 2.0 * 101.0) / 4.0).abs() < 1e-12);
+        assert_eq!(ladder.allocation(TradeSide::Buy, 6.0), vec![(Exchange::Kraken, 5.0), (Exchange::Okx, 1.0)]);
+        // Ladder exhausted: partial fill.
+        assert_eq!(ladder.sweep(TradeSide::Sell, 100.0).unwrap().1, 8.0);
+    }
+}
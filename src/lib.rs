@@ -6,30 +6,86 @@ pub mod exchanges;
 pub mod ring_buffer;
 pub mod market_data;
 pub mod trade_data;
+pub mod trade_flow;
+pub mod event_bus;
+pub mod contract_index;
+pub mod options_data;
+pub mod futures_data;
+pub mod index_data;
+pub mod deriv_stats;
+pub mod liquidation_data;
+pub mod candle_data;
+pub mod execution;
+pub mod ladder;
+pub mod carry;
+pub mod stablecoin;
+pub mod maintenance;
+pub mod watchdog;
+pub mod sanitizer;
+pub mod interest;
+pub mod feed_manager;
+pub mod fx;
 pub mod orderbook;
+pub mod book_sync;
+
+#[cfg(feature = "analytics")]
 pub mod display;
+#[cfg(feature = "analytics")]
 pub mod snapshot;
+#[cfg(feature = "analytics")]
 pub mod fair_price;
+#[cfg(feature = "analytics")]
 pub mod analytics;
-pub mod onchain;
+#[cfg(feature = "analytics")]
 pub mod bar_manager;
+#[cfg(feature = "analytics")]
 pub mod historical_bars;
+#[cfg(feature = "analytics")]
+pub mod history;
+#[cfg(feature = "analytics")]
+pub mod instruments;
+#[cfg(feature = "analytics")]
+pub mod candle_builder;
+#[cfg(feature = "analytics")]
+pub mod synthetic;
+#[cfg(feature = "analytics")]
 pub mod vol_engine;
+#[cfg(feature = "analytics")]
 pub mod vol_params;
+#[cfg(feature = "analytics")]
 pub mod vol_provider;
+#[cfg(feature = "analytics")]
 pub mod feed_display;
+#[cfg(feature = "analytics")]
 pub mod fp_display;
+#[cfg(feature = "analytics")]
 pub mod volume_fetcher;
 
+#[cfg(feature = "onchain")]
+pub mod onchain;
+
+#[cfg(feature = "sinks")]
+pub mod collector;
+#[cfg(feature = "sinks")]
+pub mod fix;
+#[cfg(feature = "sinks")]
+pub mod sbe;
+#[cfg(feature = "sinks")]
+pub mod encoding;
+#[cfg(feature = "sinks")]
+pub mod udp_sink;
+
 #[cfg(feature = "python")]
 pub mod python;
 
 #[cfg(feature = "hft")]
 pub mod hft;
 
-pub use exchange_fees::{ExchangeFees, FeeSchedule};
+pub use exchange_fees::{ExchangeFees, FeeSchedule, VenueFeesConfig};
 pub use market_data::{AllMarketData, MarketData, MarketDataCollection, BookCollection, BookSnapshot, BookLevel};
 pub use trade_data::{AllTradeData, TradeData, TradeDataCollection, TradeSide};
 pub use orderbook::OrderBook;
+#[cfg(feature = "analytics")]
 pub use analytics::{Analytics, SnapshotField};
+#[cfg(feature = "analytics")]
 pub use snapshot::{AllSnapshotData, SnapshotConfig, SnapshotData};
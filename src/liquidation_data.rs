@@ -0,0 +1,156 @@
+//! Forced liquidation orders per perp.
+//!
+//! Each event is one liquidation order as the venue reports it. `side` is
+//! the side of that order: a `Sell` closes a liquidated long, a `Buy` a
+//! liquidated short. Venues throttle these streams (Binance sends at most
+//! the latest order per symbol each second), so they suit cascade signals
+//! rather than exact liquidated volume.
+
+use crate::market_data::{DataSink, Exchange, FeedItem};
+use crate::ring_buffer::RingBuffer;
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use crate::trade_data::TradeSide;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LiquidationEvent {
+    pub price: f64,
+    /// Quantity in base units.
+    pub qty: f64,
+    pub side: TradeSide,
+    pub exchange_ts_raw: Option<DateTime<Utc>>,
+    pub exchange_ts: Option<DateTime<Utc>>,
+    pub received_ts: Option<DateTime<Utc>>,
+    pub received_instant: Option<Instant>,
+    pub feed_latency_ns: u64,
+}
+
+impl LiquidationEvent {
+    pub fn notional(&self) -> f64 {
+        self.price * self.qty
+    }
+}
+
+impl FeedItem for LiquidationEvent {
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        self.exchange_ts_raw
+    }
+    fn set_feed_latency_ns(&mut self, ns: u64) {
+        self.feed_latency_ns = ns;
+    }
+}
+
+pub struct LiquidationCollection {
+    slots: Box<[OnceLock<Box<RingBuffer<LiquidationEvent>>>]>,
+}
+
+impl LiquidationCollection {
+    pub fn new() -> Self {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    pub fn push(&self, id: &SymbolId, mut item: LiquidationEvent) {
+        if item.exchange_ts.is_none() {
+            item.exchange_ts = item.exchange_ts_raw;
+        }
+        let ring = self.slots[*id].get_or_init(|| Box::new(RingBuffer::with_capacity(256)));
+        ring.push(item);
+    }
+
+    pub fn latest(&self, id: &SymbolId) -> Option<LiquidationEvent> {
+        self.slots[*id].get()?.latest()
+    }
+
+    pub fn write_count(&self, id: &SymbolId) -> u64 {
+        self.slots[*id].get().map(|r| r.write_count()).unwrap_or(0)
+    }
+
+    pub fn get_buffer(&self, id: &SymbolId) -> Option<&RingBuffer<LiquidationEvent>> {
+        self.slots[*id].get().map(|b| b.as_ref())
+    }
+
+    /// Up to `n` most recent events, oldest first.
+    pub fn recent(&self, id: &SymbolId, n: usize) -> Vec<LiquidationEvent> {
+        let mut out = Vec::new();
+        if let Some(ring) = self.get_buffer(id) {
+            ring.scan_last_n(n, |ev| out.push(*ev));
+            out.reverse();
+        }
+        out
+    }
+}
+
+impl Default for LiquidationCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSink<LiquidationEvent> for LiquidationCollection {
+    fn push(&self, id: &SymbolId, item: LiquidationEvent) {
+        LiquidationCollection::push(self, id, item);
+    }
+}
+
+impl DataSink<LiquidationEvent> for Arc<LiquidationCollection> {
+    fn push(&self, id: &SymbolId, item: LiquidationEvent) {
+        LiquidationCollection::push(self, id, item);
+    }
+}
+
+/// Liquidation streams per venue.
+pub struct AllLiquidationData {
+    pub binance: Arc<LiquidationCollection>,
+    pub bybit: Arc<LiquidationCollection>,
+}
+
+impl AllLiquidationData {
+    pub fn new() -> Self {
+        Self {
+            binance: Arc::new(LiquidationCollection::new()),
+            bybit: Arc::new(LiquidationCollection::new()),
+        }
+    }
+
+    /// None for venues without a liquidation feed.
+    pub fn get(&self, exchange: &Exchange) -> Option<&Arc<LiquidationCollection>> {
+        match exchange {
+            Exchange::Binance => Some(&self.binance),
+            Exchange::Bybit => Some(&self.bybit),
+            _ => None,
+        }
+    }
+}
+
+impl Default for AllLiquidationData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_oldest_first() {
+        let coll = LiquidationCollection::new();
+        assert!(coll.recent(&2, 10).is_empty());
+        for px in [100.0, 101.0, 102.0] {
+            coll.push(&2, LiquidationEvent { price: px, qty: 0.5, side: TradeSide::Sell, ..Default::default() });
+        }
+        let prices: Vec<f64> = coll.recent(&2, 2).iter().map(|ev| ev.price).collect();
+        assert_eq!(prices, vec![101.0, 102.0]);
+        assert_eq!(coll.latest(&2).unwrap().notional(), 51.0);
+        assert_eq!(coll.write_count(&2), 3);
+    }
+}
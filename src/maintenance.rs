@@ -0,0 +1,255 @@
+//! Known exchange downtime.
+//!
+//! Windows come from config (one-off or weekly) and, for venues with a
+//! public status endpoint, from polling it. The connection loop consults
+//! this before reconnecting: during downtime it waits the window out
+//! instead of hammering the venue with backoff retries, and the stale
+//! sweeper stops reporting that venue's quotes as stale.
+
+use crate::market_data::Exchange;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveTime, Utc, Weekday};
+use log::warn;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum MaintenanceWindow {
+    Once {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// Recurs every week, e.g. `{ weekday: thu, start_utc: "06:00", duration_min: 120 }`.
+    Weekly {
+        weekday: String,
+        start_utc: String,
+        duration_min: u32,
+    },
+}
+
+impl MaintenanceWindow {
+    /// End of the occurrence containing `now`, if any.
+    pub fn active_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            MaintenanceWindow::Once { start, end } => (*start <= now && now < *end).then_some(*end),
+            MaintenanceWindow::Weekly { weekday, start_utc, duration_min } => {
+                let day: Weekday = weekday.parse().ok()?;
+                let time = NaiveTime::parse_from_str(start_utc, "%H:%M").ok()?;
+                let days_back = (now.weekday().num_days_from_monday() + 7 - day.num_days_from_monday()) % 7;
+                let mut start = (now.date_naive() - ChronoDuration::days(days_back as i64))
+                    .and_time(time)
+                    .and_utc();
+                if start > now {
+                    start -= ChronoDuration::days(7);
+                }
+                let end = start + ChronoDuration::minutes(*duration_min as i64);
+                (now < end).then_some(end)
+            }
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match self {
+            MaintenanceWindow::Once { start, end } => start < end,
+            MaintenanceWindow::Weekly { weekday, start_utc, .. } => {
+                weekday.parse::<Weekday>().is_ok() && NaiveTime::parse_from_str(start_utc, "%H:%M").is_ok()
+            }
+        }
+    }
+}
+
+fn default_status_poll_interval_s() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceConfig {
+    /// Venues whose status endpoint is polled ("binance", "kraken").
+    #[serde(default)]
+    pub status_poll: Vec<String>,
+    #[serde(default = "default_status_poll_interval_s")]
+    pub status_poll_interval_s: u64,
+    /// Per-venue windows, keyed by venue name.
+    #[serde(flatten)]
+    pub windows: HashMap<String, Vec<MaintenanceWindow>>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            status_poll: Vec::new(),
+            status_poll_interval_s: default_status_poll_interval_s(),
+            windows: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Downtime {
+    /// Scheduled window ending at this time.
+    Until(DateTime<Utc>),
+    /// The venue's status endpoint reports maintenance; end unknown.
+    Reported,
+}
+
+#[derive(Default)]
+struct Schedule {
+    windows: HashMap<Exchange, Vec<MaintenanceWindow>>,
+    reported: HashMap<Exchange, bool>,
+}
+
+static SCHEDULE: Lazy<RwLock<Schedule>> = Lazy::new(|| RwLock::new(Schedule::default()));
+
+/// Replace the configured windows. Status-endpoint state is kept.
+pub fn install(cfg: &MaintenanceConfig) {
+    let mut windows = HashMap::new();
+    for (venue, list) in &cfg.windows {
+        let Some(exchange) = venue.parse::<Exchange>().ok() else {
+            warn!("maintenance: unknown venue '{}', ignoring", venue);
+            continue;
+        };
+        let valid: Vec<MaintenanceWindow> = list
+            .iter()
+            .filter(|w| {
+                let ok = w.is_valid();
+                if !ok {
+                    warn!("maintenance: invalid window for {}: {:?}", venue, w);
+                }
+                ok
+            })
+            .cloned()
+            .collect();
+        windows.insert(exchange, valid);
+    }
+    if let Ok(mut s) = SCHEDULE.write() {
+        s.windows = windows;
+    }
+}
+
+/// Record what a venue's status endpoint says.
+pub fn set_reported(exchange: Exchange, in_maintenance: bool) {
+    if let Ok(mut s) = SCHEDULE.write() {
+        s.reported.insert(exchange, in_maintenance);
+    }
+}
+
+pub fn downtime(exchange: &Exchange, now: DateTime<Utc>) -> Option<Downtime> {
+    let s = SCHEDULE.read().ok()?;
+    let scheduled = s
+        .windows
+        .get(exchange)
+        .and_then(|ws| ws.iter().filter_map(|w| w.active_until(now)).max());
+    if let Some(end) = scheduled {
+        return Some(Downtime::Until(end));
+    }
+    s.reported.get(exchange).copied().unwrap_or(false).then_some(Downtime::Reported)
+}
+
+/// Downtime for a connection loop feed name ("kraken_spot", "binance_perp_trades").
+pub fn feed_downtime(feed_name: &str, now: DateTime<Utc>) -> Option<Downtime> {
+    let venue = feed_name.split('_').next()?;
+    downtime(&venue.parse::<Exchange>().ok()?, now)
+}
+
+#[cfg(feature = "analytics")]
+async fn poll_status(client: &reqwest::Client, exchange: Exchange) -> anyhow::Result<bool> {
+    match exchange {
+        // {"status": 0, "msg": "normal"} / {"status": 1, "msg": "system_maintenance"}
+        Exchange::Binance => {
+            let v: serde_json::Value =
+                client.get("https://api.binance.com/sapi/v1/system/status").send().await?.json().await?;
+            Ok(v["status"].as_i64() == Some(1))
+        }
+        // {"error": [], "result": {"status": "online" | "maintenance" | "cancel_only" | ...}}
+        Exchange::Kraken => {
+            let v: serde_json::Value =
+                client.get("https://api.kraken.com/0/public/SystemStatus").send().await?.json().await?;
+            Ok(v["result"]["status"].as_str() == Some("maintenance"))
+        }
+        _ => Err(anyhow::anyhow!("no status endpoint for {}", exchange.as_str())),
+    }
+}
+
+/// Poll status endpoints for `cfg.status_poll` venues until shutdown.
+#[cfg(feature = "analytics")]
+pub async fn run_status_poller(cfg: MaintenanceConfig, shutdown: std::sync::Arc<tokio::sync::Notify>) {
+    let venues: Vec<Exchange> = cfg
+        .status_poll
+        .iter()
+        .filter_map(|v| match v.parse::<Exchange>() {
+            Ok(e @ (Exchange::Binance | Exchange::Kraken)) => Some(e),
+            _ => {
+                warn!("maintenance: no status endpoint for '{}'", v);
+                None
+            }
+        })
+        .collect();
+    if venues.is_empty() {
+        return;
+    }
+    let client = match reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build() {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("maintenance: HTTP client init failed: {}", e);
+            return;
+        }
+    };
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(cfg.status_poll_interval_s.max(5)));
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = interval.tick() => {}
+        }
+        for exchange in &venues {
+            match poll_status(&client, *exchange).await {
+                Ok(down) => {
+                    let was = SCHEDULE.read().ok().and_then(|s| s.reported.get(exchange).copied()).unwrap_or(false);
+                    if down != was {
+                        log::info!("{} status: {}", exchange.as_str(), if down { "maintenance" } else { "online" });
+                    }
+                    set_reported(*exchange, down);
+                }
+                Err(e) => warn!("{} status poll failed: {:#}", exchange.as_str(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_window_occurrences() {
+        // 2026-10-15 is a Thursday.
+        let weekly = MaintenanceWindow::Weekly { weekday: "thu".into(), start_utc: "06:00".into(), duration_min: 90 };
+        assert_eq!(weekly.active_until(at("2026-10-15T06:30:00Z")), Some(at("2026-10-15T07:30:00Z")));
+        assert_eq!(weekly.active_until(at("2026-10-15T07:30:00Z")), None);
+        assert_eq!(weekly.active_until(at("2026-10-15T05:59:00Z")), None);
+        assert_eq!(weekly.active_until(at("2026-10-22T06:00:00Z")), Some(at("2026-10-22T07:30:00Z")));
+
+        // Crosses midnight into Monday.
+        let sunday = MaintenanceWindow::Weekly { weekday: "sun".into(), start_utc: "23:00".into(), duration_min: 120 };
+        assert_eq!(sunday.active_until(at("2026-10-19T00:30:00Z")), Some(at("2026-10-19T01:00:00Z")));
+
+        let once = MaintenanceWindow::Once { start: at("2026-10-20T00:00:00Z"), end: at("2026-10-20T02:00:00Z") };
+        assert!(once.active_until(at("2026-10-20T01:00:00Z")).is_some());
+        assert!(once.active_until(at("2026-10-21T01:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn test_config_shape() {
+        let yaml = "status_poll: [kraken]\nkraken:\n  - { weekday: thu, start_utc: \"06:00\", duration_min: 60 }\n  - { start: \"2026-10-20T00:00:00Z\", end: \"2026-10-20T02:00:00Z\" }\n";
+        let cfg: MaintenanceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.status_poll, vec!["kraken".to_string()]);
+        assert_eq!(cfg.status_poll_interval_s, 60);
+        assert_eq!(cfg.windows["kraken"].len(), 2);
+        assert!(matches!(cfg.windows["kraken"][1], MaintenanceWindow::Once { .. }));
+    }
+}
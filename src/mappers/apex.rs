@@ -1,5 +1,6 @@
 use crate::mappers::symbol_mapper::SymbolMapper;
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -11,15 +12,8 @@ impl SymbolMapper for ApexMapper {
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
-        }
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2])
-        } else {
-            (parts[0], parts[1])
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Perp => Ok(format!("{}{}", base, quote).to_uppercase()),
             _ => anyhow::bail!("Type not implemented {:?}", itype),
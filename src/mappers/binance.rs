@@ -1,5 +1,7 @@
 use crate::mappers::symbol_mapper::SymbolMapper; // Import from sibling module
+use crate::futures_data::FuturesContract;
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -8,19 +10,22 @@ pub struct BinanceMapper;
 impl SymbolMapper for BinanceMapper {
     fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
         let (base, quote) = self.parse(native, itype)?;
+        if matches!(itype, InstrumentType::Futures) {
+            let expiry = native.rsplit_once('_').map_or("", |(_, e)| e);
+            return Ok(format!("{}_{}_{}_{}", itype.as_str(), base, quote, expiry));
+        }
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
+        // Dated futures are configured as "BTC_USDT_260327" and listed as
+        // "BTCUSDT_260327".
+        if matches!(itype, InstrumentType::Futures) {
+            let contract = FuturesContract::parse(normalized)
+                .ok_or_else(|| anyhow::anyhow!("Invalid dated futures symbol: {}", normalized))?;
+            return Ok(format!("{}{}_{}", contract.base, contract.quote, contract.expiry_code()));
         }
-        // Assume already stripped of SPOT_ prefix, or handle it:
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2]) // SPOT_BTC_USDT
-        } else {
-            (parts[0], parts[1]) // BTC_USDT
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Spot => Ok(format!("{}{}", base, quote).to_uppercase()),
             InstrumentType::Perp => Ok(format!("{}{}", base, quote).to_uppercase()),
@@ -44,6 +49,13 @@ impl SymbolMapper for BinanceMapper {
                 }
                 anyhow::bail!("Could not parse binance symbol: {}", native)
             }
+            InstrumentType::Futures => {
+                let (pair, expiry) = native
+                    .split_once('_')
+                    .filter(|(_, e)| e.len() == 6 && e.bytes().all(|b| b.is_ascii_digit()))
+                    .ok_or_else(|| anyhow::anyhow!("Not a Binance dated future: {}", native))?;
+                self.parse(pair, InstrumentType::Perp)
+            }
             _ => {
                 anyhow::bail!("Unsupported itype {:?}", itype)
             }
@@ -0,0 +1,63 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// Bitfinex trading pairs: "tBTCUSD", "tAVAX:USD" (colon once a code is
+/// longer than three letters), perps "tBTCF0:USTF0". USDT is "UST".
+#[derive(Clone)]
+pub struct BitfinexMapper;
+
+fn to_code(ccy: &str) -> String {
+    match ccy {
+        "USDT" => "UST".to_string(),
+        c => c.to_string(),
+    }
+}
+
+fn from_code(code: &str) -> String {
+    match code {
+        "UST" => "USDT".to_string(),
+        c => c.to_string(),
+    }
+}
+
+impl SymbolMapper for BitfinexMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        let base = to_code(&base.to_uppercase());
+        let quote = to_code(&quote.to_uppercase());
+        match itype {
+            InstrumentType::Spot if base.len() > 3 || quote.len() > 3 => Ok(format!("t{}:{}", base, quote)),
+            InstrumentType::Spot => Ok(format!("t{}{}", base, quote)),
+            InstrumentType::Perp => Ok(format!("t{}F0:{}F0", base, quote)),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        let pair = native.strip_prefix('t').unwrap_or(native);
+        let (base, quote) = match pair.split_once(':') {
+            Some((base, quote)) => (base, quote),
+            None if pair.len() == 6 => pair.split_at(3),
+            None => anyhow::bail!("Could not parse Bitfinex symbol: {}", native),
+        };
+        match itype {
+            InstrumentType::Spot => Ok((from_code(base), from_code(quote))),
+            InstrumentType::Perp => {
+                let (Some(base), Some(quote)) = (base.strip_suffix("F0"), quote.strip_suffix("F0")) else {
+                    anyhow::bail!("Not a Bitfinex perpetual: {}", native);
+                };
+                Ok((from_code(base), from_code(quote)))
+            }
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "bitfinex"
+    }
+}
@@ -1,5 +1,7 @@
 use crate::mappers::symbol_mapper::SymbolMapper; // Import from sibling module
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use crate::options_data::OptionContract;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -11,16 +13,16 @@ impl SymbolMapper for BybitMapper {
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
+        // Options are configured by contract name: "BTC-27JUN25-70000-C",
+        // optionally with a settle suffix ("...-C-USDT").
+        if matches!(itype, InstrumentType::Option) {
+            if OptionContract::parse(normalized).is_none() {
+                anyhow::bail!("Invalid Bybit option symbol: {}", normalized);
+            }
+            return Ok(normalized.to_uppercase());
         }
-        // Assume already stripped of SPOT_ prefix, or handle it:
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2]) // SPOT_BTC_USDT
-        } else {
-            (parts[0], parts[1]) // BTC_USDT
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Spot => Ok(format!("{}{}", base, quote).to_uppercase()),
             InstrumentType::Perp => Ok(format!("{}{}", base, quote).to_uppercase()),
@@ -44,6 +46,13 @@ impl SymbolMapper for BybitMapper {
                 }
                 anyhow::bail!("Could not parse binance symbol: {}", native)
             }
+            InstrumentType::Option => {
+                // (underlying, settle): USDC unless suffixed, e.g. "-USDT".
+                let contract = OptionContract::parse(native)
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse Bybit option symbol: {}", native))?;
+                let settle = native.split('-').nth(4).unwrap_or("USDC").to_uppercase();
+                Ok((contract.underlying, settle))
+            }
             _ => {
                 anyhow::bail!("Unsupported itype {:?}", itype)
             }
@@ -0,0 +1,70 @@
+use crate::futures_data::FuturesContract;
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use crate::options_data::parse_expiry;
+use anyhow::Result;
+
+/// Deribit perpetuals: inverse "BTC-PERPETUAL" (BTC_USD) and linear
+/// USDC-margined "SOL_USDC-PERPETUAL" (SOL_USDC). Inverse dated futures
+/// "BTC-27MAR26" map to "BTC_USD_260327".
+#[derive(Clone)]
+pub struct DeribitMapper;
+
+const PERP_SUFFIX: &str = "-PERPETUAL";
+
+impl SymbolMapper for DeribitMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        if matches!(itype, InstrumentType::Futures) {
+            let expiry = native
+                .split_once('-')
+                .and_then(|(_, e)| parse_expiry(e))
+                .ok_or_else(|| anyhow::anyhow!("Not a Deribit dated future: {}", native))?;
+            return Ok(format!("{}_{}_{}_{}", itype.as_str(), base, quote, expiry.format("%y%m%d")));
+        }
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        if matches!(itype, InstrumentType::Futures) {
+            let contract = FuturesContract::parse(normalized)
+                .ok_or_else(|| anyhow::anyhow!("Invalid dated futures symbol: {}", normalized))?;
+            if contract.quote != "USD" {
+                anyhow::bail!("Deribit has no {} dated futures", contract.quote);
+            }
+            let expiry = contract.expiry.format("%-d%b%y").to_string().to_uppercase();
+            return Ok(format!("{}-{}", contract.base, expiry));
+        }
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Perp => match quote.to_uppercase().as_str() {
+                "USD" => Ok(format!("{}{}", base.to_uppercase(), PERP_SUFFIX)),
+                "USDC" => Ok(format!("{}_USDC{}", base.to_uppercase(), PERP_SUFFIX)),
+                q => anyhow::bail!("Deribit has no {} perpetuals", q),
+            },
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Perp => {
+                let pair = native
+                    .strip_suffix(PERP_SUFFIX)
+                    .ok_or_else(|| anyhow::anyhow!("Not a Deribit perpetual: {}", native))?;
+                match pair.split_once('_') {
+                    Some((base, quote)) => Ok((base.to_uppercase(), quote.to_uppercase())),
+                    None => Ok((pair.to_uppercase(), "USD".to_string())),
+                }
+            }
+            InstrumentType::Futures => match native.split_once('-') {
+                Some((base, expiry)) if parse_expiry(expiry).is_some() => Ok((base.to_uppercase(), "USD".to_string())),
+                _ => anyhow::bail!("Not a Deribit dated future: {}", native),
+            },
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "deribit"
+    }
+}
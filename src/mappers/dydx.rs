@@ -0,0 +1,37 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// dYdX v4 perpetual tickers: "BTC-USD".
+#[derive(Clone)]
+pub struct DydxMapper;
+
+impl SymbolMapper for DydxMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Perp => Ok(format!("{}-{}", base, quote).to_uppercase()),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Perp => {
+                let (base, quote) = native
+                    .split_once('-')
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse dydx ticker: {}", native))?;
+                Ok((base.to_uppercase(), quote.to_uppercase()))
+            }
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "dydx"
+    }
+}
@@ -0,0 +1,37 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// Gate.io spot pairs and USDT futures contracts share one format: "BTC_USDT".
+#[derive(Clone)]
+pub struct GateioMapper;
+
+impl SymbolMapper for GateioMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Spot | InstrumentType::Perp => Ok(format!("{}_{}", base, quote).to_uppercase()),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Spot | InstrumentType::Perp => {
+                let (base, quote) = native
+                    .split_once('_')
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse Gate.io symbol: {}", native))?;
+                Ok((base.to_uppercase(), quote.to_uppercase()))
+            }
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "gateio"
+    }
+}
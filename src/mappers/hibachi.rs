@@ -1,5 +1,6 @@
 use crate::mappers::symbol_mapper::SymbolMapper;
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -12,14 +13,8 @@ impl SymbolMapper for HibachiMapper {
     }
 
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2])
-        } else if parts.len() == 2 {
-            (parts[0], parts[1])
-        } else {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Perp => Ok(format!("{}/{}-P", base, quote).to_uppercase()),
             InstrumentType::Spot => Ok(format!("{}/{}", base, quote).to_uppercase()),
@@ -0,0 +1,50 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// HTX (Huobi): spot "btcusdt", USDT-margined swaps "BTC-USDT".
+#[derive(Clone)]
+pub struct HtxMapper;
+
+impl SymbolMapper for HtxMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Spot => Ok(format!("{}{}", base, quote).to_lowercase()),
+            InstrumentType::Perp => Ok(format!("{}-{}", base, quote).to_uppercase()),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Spot => {
+                const QUOTES: &[&str] = &["USDT", "USDC", "USDD", "BTC", "ETH", "HT"];
+                let upper = native.to_uppercase();
+                for quote in QUOTES {
+                    if let Some(base) = upper.strip_suffix(quote) {
+                        if !base.is_empty() {
+                            return Ok((base.to_string(), quote.to_string()));
+                        }
+                    }
+                }
+                anyhow::bail!("Could not parse HTX symbol: {}", native)
+            }
+            InstrumentType::Perp => {
+                let (base, quote) = native
+                    .split_once('-')
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse HTX contract: {}", native))?;
+                Ok((base.to_uppercase(), quote.to_uppercase()))
+            }
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "htx"
+    }
+}
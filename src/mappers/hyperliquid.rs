@@ -1,5 +1,6 @@
 use crate::mappers::symbol_mapper::SymbolMapper;
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -12,16 +13,10 @@ impl SymbolMapper for HyperliquidMapper {
         Ok(format!("{}_{}_USD", itype.as_str(), native.to_uppercase()))
     }
 
-    fn denormalize(&self, normalized: &str, _itype: InstrumentType) -> Result<String> {
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
         // "BTC_USDT" → "BTC" (just the base coin)
-        let parts: Vec<&str> = normalized.split('_').collect();
-        let base = if parts.len() == 3 {
-            parts[1] // PERP_BTC_USDT
-        } else if parts.len() == 2 {
-            parts[0] // BTC_USDT
-        } else {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let base = sym.base.as_str();
         Ok(base.to_uppercase())
     }
 
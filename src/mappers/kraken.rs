@@ -1,31 +1,37 @@
 use crate::mappers::symbol_mapper::SymbolMapper; // Import from sibling module
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
 pub struct KrakenMapper;
 
+/// Kraken Futures perpetuals: PI_ inverse, PF_ linear multi-collateral.
+const FUTURES_PREFIXES: &[&str] = &["PI_", "PF_"];
+/// Bases with an inverse perpetual.
+const INVERSE_BASES: &[&str] = &["XBT", "ETH", "LTC", "XRP", "BCH"];
+
 impl SymbolMapper for KrakenMapper {
     fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
         let (base, quote) = self.parse(native, itype)?;
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
+        // A futures contract can be named directly, e.g. "PF_XBTUSD".
+        if matches!(itype, InstrumentType::Perp) && FUTURES_PREFIXES.iter().any(|p| normalized.starts_with(p)) {
+            return Ok(normalized.to_string());
         }
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2]) // SPOT_BTC_USDT
-        } else {
-            (parts[0], parts[1]) // BTC_USDT
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Spot => Ok(format!("{}/{}", base, quote).to_uppercase()),
             InstrumentType::Perp => {
                 // Kraken Futures uses XBT instead of BTC
                 let base = if base.eq_ignore_ascii_case("BTC") { "XBT" } else { base };
-                Ok(format!("PI_{}{}", base, quote).to_uppercase())
+                let base = base.to_uppercase();
+                // Inverse (PI_) where one is listed, else the linear multi-collateral (PF_) contract.
+                let prefix = if INVERSE_BASES.contains(&base.as_str()) { "PI_" } else { "PF_" };
+                Ok(format!("{}{}{}", prefix, base, quote.to_uppercase()))
             }
             _ => anyhow::bail!("Type not implemented {:?}", itype),
         }
@@ -39,9 +45,10 @@ impl SymbolMapper for KrakenMapper {
                 Ok((base.to_string(), quote.to_string()))
             }
             InstrumentType::Perp => {
-                // PI_XBTUSD -> ("BTC", "USD")
-                let stripped = native
-                    .strip_prefix("PI_")
+                // PI_XBTUSD / PF_XBTUSD -> ("BTC", "USD")
+                let stripped = FUTURES_PREFIXES
+                    .iter()
+                    .find_map(|p| native.strip_prefix(p))
                     .unwrap_or(native);
                 for quote in &["USDT", "USDC", "USD"] {
                     if let Some(base) = stripped.strip_suffix(quote) {
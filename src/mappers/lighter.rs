@@ -1,5 +1,6 @@
 use crate::mappers::symbol_mapper::SymbolMapper; // Import from sibling module
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -11,16 +12,8 @@ impl SymbolMapper for LighterMapper {
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
     fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
-        let parts: Vec<&str> = normalized.split('_').collect();
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
-        }
-        // Assume already stripped of SPOT_ prefix, or handle it:
-        let (base, quote) = if parts.len() == 3 {
-            (parts[1], parts[2]) // SPOT_BTC_USDT
-        } else {
-            (parts[0], parts[1]) // BTC_USDT
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
         match itype {
             InstrumentType::Spot => Ok(format!("{}/{}", base, quote).to_uppercase()),
             InstrumentType::Perp => Ok(format!("{}", base).to_uppercase()),
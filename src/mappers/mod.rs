@@ -15,6 +15,14 @@ mod hibachi;
 mod hotstuff;
 mod hyperliquid;
 mod zeroone;
+mod dydx;
+mod deribit;
+mod gateio;
+mod htx;
+mod bitfinex;
+mod upbit;
+mod phemex;
+mod vertex;
 
 // Re-export the trait
 pub use symbol_mapper::{SymbolMapper, parse_normalized};
@@ -36,40 +44,91 @@ pub use hibachi::HibachiMapper;
 pub use hotstuff::HotstuffMapper;
 pub use hyperliquid::HyperliquidMapper;
 pub use zeroone::ZeroOneMapper;
+pub use dydx::DydxMapper;
+pub use deribit::DeribitMapper;
+pub use gateio::GateioMapper;
+pub use htx::HtxMapper;
+pub use bitfinex::BitfinexMapper;
+pub use upbit::UpbitMapper;
+pub use phemex::PhemexMapper;
+pub use vertex::VertexMapper;
 
+use crate::market_data::Exchange;
 use anyhow::Result;
 
-/// Factory function to create a mapper for a given exchange
-pub fn get_mapper(exchange: &str) -> Result<Box<dyn SymbolMapper>> {
-    match exchange.to_lowercase().as_str() {
-        "mexc" => Ok(Box::new(MexcMapper)),
-        "binance" => Ok(Box::new(BinanceMapper)),
-        "coinbase" => Ok(Box::new(CoinbaseMapper)),
-        "bybit" => Ok(Box::new(BybitMapper)),
-        "kraken" => Ok(Box::new(KrakenMapper)),
-        "lighter" => Ok(Box::new(LighterMapper)),
-        "extended" => Ok(Box::new(ExtendedMapper)),
-        "nado" => Ok(Box::new(NadoMapper)),
-        "okx" => Ok(Box::new(OkxMapper)),
-        "kucoin" => Ok(Box::new(KucoinMapper)),
-        "bingx" => Ok(Box::new(BingxMapper)),
-        "apex" => Ok(Box::new(ApexMapper)),
-        "hibachi" => Ok(Box::new(HibachiMapper)),
-        "hotstuff" => Ok(Box::new(HotstuffMapper)),
-        "hyperliquid" => Ok(Box::new(HyperliquidMapper)),
-        "zeroone" => Ok(Box::new(ZeroOneMapper)),
-        _ => anyhow::bail!("Unsupported exchange: {}", exchange),
-    }
+/// Factory function to create a mapper for a given exchange. Venues
+/// without symbol mapping (DEX pools, oracles, HFT-only feeds) are errors.
+pub fn get_mapper(exchange: Exchange) -> Result<Box<dyn SymbolMapper>> {
+    Ok(match exchange {
+        Exchange::Mexc => Box::new(MexcMapper),
+        Exchange::Binance => Box::new(BinanceMapper),
+        Exchange::Coinbase => Box::new(CoinbaseMapper),
+        Exchange::Bybit => Box::new(BybitMapper),
+        Exchange::Kraken => Box::new(KrakenMapper),
+        Exchange::Lighter => Box::new(LighterMapper),
+        Exchange::Extended => Box::new(ExtendedMapper),
+        Exchange::Nado => Box::new(NadoMapper),
+        Exchange::Okx => Box::new(OkxMapper),
+        Exchange::Kucoin => Box::new(KucoinMapper),
+        Exchange::Bingx => Box::new(BingxMapper),
+        Exchange::Apex => Box::new(ApexMapper),
+        Exchange::Hibachi => Box::new(HibachiMapper),
+        Exchange::Hotstuff => Box::new(HotstuffMapper),
+        Exchange::Hyperliquid => Box::new(HyperliquidMapper),
+        Exchange::ZeroOne => Box::new(ZeroOneMapper),
+        Exchange::Dydx => Box::new(DydxMapper),
+        Exchange::Deribit => Box::new(DeribitMapper),
+        Exchange::Gateio => Box::new(GateioMapper),
+        Exchange::Htx => Box::new(HtxMapper),
+        Exchange::Bitfinex => Box::new(BitfinexMapper),
+        Exchange::Upbit => Box::new(UpbitMapper),
+        Exchange::Phemex => Box::new(PhemexMapper),
+        Exchange::Vertex => Box::new(VertexMapper),
+        Exchange::Aerodrome
+        | Exchange::Uniswap
+        | Exchange::RiseX
+        | Exchange::Bulk
+        | Exchange::Pyth
+        | Exchange::Chainlink => anyhow::bail!("Unsupported exchange: {}", exchange),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::market_data::InstrumentType;
 
     #[test]
     fn test_get_mapper() {
-        assert!(get_mapper("mexc").is_ok());
-        assert!(get_mapper("binance").is_ok());
-        assert!(get_mapper("invalid").is_err());
+        assert!(get_mapper(Exchange::Mexc).is_ok());
+        assert!(get_mapper("Binance".parse().unwrap()).is_ok());
+        assert!(get_mapper(Exchange::Pyth).is_err());
+        assert!("invalid".parse::<Exchange>().is_err());
+    }
+
+    #[test]
+    fn test_bybit_option_symbols() {
+        let m = BybitMapper;
+        let itype = InstrumentType::Option;
+        assert_eq!(m.denormalize("BTC-27JUN25-70000-C", itype).unwrap(), "BTC-27JUN25-70000-C");
+        assert!(m.denormalize("BTC_USDT", itype).is_err());
+        assert_eq!(m.parse("BTC-27JUN25-70000-C", itype).unwrap(), ("BTC".into(), "USDC".into()));
+        assert_eq!(m.parse("ETH-27JUN25-3000-P-USDT", itype).unwrap(), ("ETH".into(), "USDT".into()));
+    }
+
+    #[test]
+    fn test_dated_futures_symbols() {
+        let itype = InstrumentType::Futures;
+        let b = BinanceMapper;
+        assert_eq!(b.denormalize("BTC_USDT_260327", itype).unwrap(), "BTCUSDT_260327");
+        assert_eq!(b.normalize("ETHUSDT_260626", itype).unwrap(), "FUT_ETH_USDT_260626");
+        assert!(b.parse("BTCUSDT", itype).is_err());
+
+        let d = DeribitMapper;
+        assert_eq!(d.denormalize("BTC_USD_260327", itype).unwrap(), "BTC-27MAR26");
+        assert_eq!(d.denormalize("FUT_ETH_USD_260403", itype).unwrap(), "ETH-3APR26");
+        assert_eq!(d.normalize("ETH-3APR26", itype).unwrap(), "FUT_ETH_USD_260403");
+        assert!(d.denormalize("BTC_USDT_260327", itype).is_err());
+        assert!(d.parse("BTC-PERPETUAL", itype).is_err());
     }
 }
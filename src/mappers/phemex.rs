@@ -0,0 +1,45 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// Phemex contracts: "BTCUSD" (inverse), "BTCUSDT"; spot pairs carry an
+/// "s" prefix: "sBTCUSDT".
+#[derive(Clone)]
+pub struct PhemexMapper;
+
+impl SymbolMapper for PhemexMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Spot => Ok(format!("s{}{}", base.to_uppercase(), quote.to_uppercase())),
+            InstrumentType::Perp => Ok(format!("{}{}", base, quote).to_uppercase()),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        let pair = match itype {
+            InstrumentType::Spot => native.strip_prefix('s').unwrap_or(native),
+            InstrumentType::Perp => native,
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        };
+        const QUOTES: &[&str] = &["USDT", "USDC", "USD", "BTC"];
+        let upper = pair.to_uppercase();
+        for quote in QUOTES {
+            if let Some(base) = upper.strip_suffix(quote) {
+                if !base.is_empty() {
+                    return Ok((base.to_string(), quote.to_string()));
+                }
+            }
+        }
+        anyhow::bail!("Could not parse Phemex symbol: {}", native)
+    }
+    fn exchange(&self) -> &str {
+        "phemex"
+    }
+}
@@ -1,4 +1,5 @@
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 /// Converts between exchange-native and normalized symbol formats
@@ -11,11 +12,6 @@ pub trait SymbolMapper: Send + Sync {
 
 /// Helper to parse normalized symbols
 pub fn parse_normalized(normalized: &str) -> Result<(String, String)> {
-    let parts: Vec<&str> = normalized.split('_').collect();
-
-    match parts.as_slice() {
-        [_itype, base, quote] => Ok((base.to_string(), quote.to_string())),
-        [base, quote] => Ok((base.to_string(), quote.to_string())),
-        _ => anyhow::bail!("Invalid normalized symbol format: {}", normalized),
-    }
+    let sym = CanonicalSymbol::parse_pair(normalized, InstrumentType::Spot)?;
+    Ok((sym.base, sym.quote))
 }
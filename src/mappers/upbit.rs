@@ -0,0 +1,37 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// Upbit market codes put the quote first: "KRW-BTC".
+#[derive(Clone)]
+pub struct UpbitMapper;
+
+impl SymbolMapper for UpbitMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let (base, quote) = (sym.base.as_str(), sym.quote.as_str());
+        match itype {
+            InstrumentType::Spot => Ok(format!("{}-{}", quote, base).to_uppercase()),
+            _ => anyhow::bail!("Type not implemented {:?}", itype),
+        }
+    }
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Spot => {
+                let (quote, base) = native
+                    .split_once('-')
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse Upbit market: {}", native))?;
+                Ok((base.to_uppercase(), quote.to_uppercase()))
+            }
+            _ => anyhow::bail!("Unsupported itype {:?}", itype),
+        }
+    }
+    fn exchange(&self) -> &str {
+        "upbit"
+    }
+}
@@ -0,0 +1,40 @@
+use crate::mappers::symbol_mapper::SymbolMapper;
+use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
+use anyhow::Result;
+
+/// Vertex perps: "BTC-PERP", all margined in USDC.
+#[derive(Clone)]
+pub struct VertexMapper;
+
+impl SymbolMapper for VertexMapper {
+    fn normalize(&self, native: &str, itype: InstrumentType) -> Result<String> {
+        let (base, quote) = self.parse(native, itype)?;
+        Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
+    }
+
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let base = sym.base.as_str();
+        match itype {
+            InstrumentType::Perp => Ok(format!("{}-PERP", base).to_uppercase()),
+            _ => anyhow::bail!("Vertex only supports perp, got {:?}", itype),
+        }
+    }
+
+    fn parse(&self, native: &str, itype: InstrumentType) -> Result<(String, String)> {
+        match itype {
+            InstrumentType::Perp => {
+                let base = native
+                    .strip_suffix("-PERP")
+                    .ok_or_else(|| anyhow::anyhow!("Could not parse Vertex perp symbol: {}", native))?;
+                Ok((base.to_uppercase(), "USDC".to_string()))
+            }
+            _ => anyhow::bail!("Vertex only supports perp, got {:?}", itype),
+        }
+    }
+
+    fn exchange(&self) -> &str {
+        "vertex"
+    }
+}
@@ -1,5 +1,6 @@
 use crate::mappers::symbol_mapper::SymbolMapper;
 use crate::market_data::InstrumentType;
+use crate::symbol_registry::CanonicalSymbol;
 use anyhow::Result;
 
 #[derive(Clone)]
@@ -11,16 +12,10 @@ impl SymbolMapper for ZeroOneMapper {
         Ok(format!("{}_{}_{}", itype.as_str(), base, quote))
     }
 
-    fn denormalize(&self, normalized: &str, _itype: InstrumentType) -> Result<String> {
+    fn denormalize(&self, normalized: &str, itype: InstrumentType) -> Result<String> {
         // BTC_USDT or PERP_BTC_USDT -> BTCUSD
-        let parts: Vec<&str> = normalized.split('_').collect();
-        let base = if parts.len() == 3 {
-            parts[1]
-        } else if parts.len() == 2 {
-            parts[0]
-        } else {
-            anyhow::bail!("Invalid normalized symbol: {}", normalized);
-        };
+        let sym = CanonicalSymbol::parse_pair(normalized, itype)?;
+        let base = sym.base.as_str();
         Ok(format!("{}USD", base.to_uppercase()))
     }
 
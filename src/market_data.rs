@@ -1,11 +1,19 @@
+use crate::deriv_stats::AllDerivStats;
+use crate::event_bus::MarketEvent;
+use crate::liquidation_data::AllLiquidationData;
+use crate::options_data::AllOptionData;
+use crate::orderbook::{BookHandle, LiveBooks};
+use crate::futures_data::AllFuturesData;
 use crate::ring_buffer::RingBuffer;
-use crate::symbol_registry::{MAX_SYMBOLS, SymbolId};
+use crate::sanitizer::{QuoteSanitizer, SanitizerConfig, Verdict};
+use crate::symbol_registry::{REGISTRY, SymbolId, symbol_capacity};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::OnceLock;
-use std::sync::atomic::{AtomicI64, Ordering};
-use std::time::Instant;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 // ── Full order book snapshots ────────────────────────────────────────
 
@@ -17,6 +25,21 @@ pub struct BookLevel {
     pub qty: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// One level change a feed applied to its book; `new_size` 0 removes the
+/// level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookDelta {
+    pub side: BookSide,
+    pub price: f64,
+    pub new_size: f64,
+}
+
 /// Fixed-size order book snapshot (top N levels per side).
 /// Bids sorted descending (best bid first), asks sorted ascending (best ask first).
 #[derive(Clone, Copy)]
@@ -38,6 +61,79 @@ impl Default for BookSnapshot {
     }
 }
 
+impl BookSnapshot {
+    /// Populated bid levels, best first.
+    pub fn bids(&self) -> &[BookLevel] {
+        &self.bids[..self.bid_count as usize]
+    }
+
+    /// Populated ask levels, best first.
+    pub fn asks(&self) -> &[BookLevel] {
+        &self.asks[..self.ask_count as usize]
+    }
+
+    /// Microprice over the top `depth` levels: each side's VWAP weighted by
+    /// the other side's size. `depth == 1` is `MarketData::microprice`.
+    /// None if either side is empty.
+    pub fn weighted_mid(&self, depth: usize) -> Option<f64> {
+        let side = |levels: &[BookLevel]| {
+            let top = &levels[..depth.min(levels.len())];
+            let qty: f64 = top.iter().map(|l| l.qty).sum();
+            (qty > 0.0).then(|| (top.iter().map(|l| l.price * l.qty).sum::<f64>() / qty, qty))
+        };
+        let (bid, bid_qty) = side(self.bids())?;
+        let (ask, ask_qty) = side(self.asks())?;
+        Some((bid * ask_qty + ask * bid_qty) / (bid_qty + ask_qty))
+    }
+
+    /// Size imbalance over the top `depth` levels per side, as
+    /// `OrderBook::imbalance`.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let qty = |levels: &[BookLevel]| levels.iter().take(depth).map(|l| l.qty).sum::<f64>();
+        size_imbalance(qty(self.bids()), qty(self.asks()))
+    }
+
+    /// Average price paid buying `notional` quote units up the asks. None
+    /// if the levels held can't fill it.
+    pub fn vwap_buy(&self, notional: f64) -> Option<f64> {
+        vwap_for_notional(self.asks().iter().map(|l| (l.price, l.qty)), notional)
+    }
+
+    /// Average price received selling `notional` quote units into the bids.
+    pub fn vwap_sell(&self, notional: f64) -> Option<f64> {
+        vwap_for_notional(self.bids().iter().map(|l| (l.price, l.qty)), notional)
+    }
+}
+
+/// `(bid - ask) / (bid + ask)` of two side sizes; None if both are empty.
+pub fn size_imbalance(bid_qty: f64, ask_qty: f64) -> Option<f64> {
+    let total = bid_qty + ask_qty;
+    (total > 0.0).then(|| (bid_qty - ask_qty) / total)
+}
+
+/// Average fill price spending `notional` across `levels` (price, size),
+/// best first. None if `notional` isn't positive or the levels run out.
+pub fn vwap_for_notional(levels: impl IntoIterator<Item = (f64, f64)>, notional: f64) -> Option<f64> {
+    if !(notional > 0.0) {
+        return None;
+    }
+    let mut spent = 0.0;
+    let mut qty = 0.0;
+    for (price, size) in levels {
+        if !(price > 0.0 && size > 0.0) {
+            continue;
+        }
+        let remaining = notional - spent;
+        if price * size >= remaining {
+            qty += remaining / price;
+            return Some(notional / qty);
+        }
+        spent += price * size;
+        qty += size;
+    }
+    None
+}
+
 /// Per-symbol book snapshot storage using seqlock ring buffer (capacity 4).
 pub struct BookCollection {
     slots: Box<[OnceLock<Box<RingBuffer<BookSnapshot>>>]>,
@@ -45,8 +141,9 @@ pub struct BookCollection {
 
 impl BookCollection {
     pub fn new() -> Self {
-        let mut slots = Vec::with_capacity(MAX_SYMBOLS);
-        for _ in 0..MAX_SYMBOLS {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
             slots.push(OnceLock::new());
         }
         Self { slots: slots.into_boxed_slice() }
@@ -60,9 +157,53 @@ impl BookCollection {
     pub fn latest(&self, id: &SymbolId) -> Option<BookSnapshot> {
         self.slots[*id].get()?.latest()
     }
+
+    pub fn get_weighted_mid(&self, id: &SymbolId, depth: usize) -> Option<f64> {
+        self.latest(id)?.weighted_mid(depth)
+    }
+
+    pub fn get_vwap_buy(&self, id: &SymbolId, notional: f64) -> Option<f64> {
+        self.latest(id)?.vwap_buy(notional)
+    }
+
+    pub fn get_vwap_sell(&self, id: &SymbolId, notional: f64) -> Option<f64> {
+        self.latest(id)?.vwap_sell(notional)
+    }
+}
+
+/// Top-N depth published by venues that build full books internally.
+pub struct AllDepthData {
+    pub mexc: Arc<BookCollection>,
+    pub lighter: Arc<BookCollection>,
+}
+
+impl AllDepthData {
+    pub fn new() -> Self {
+        Self {
+            mexc: Arc::new(BookCollection::new()),
+            lighter: Arc::new(BookCollection::new()),
+        }
+    }
+
+    /// None for venues that publish BBO only.
+    pub fn get(&self, exchange: &Exchange) -> Option<&Arc<BookCollection>> {
+        match exchange {
+            Exchange::Mexc => Some(&self.mexc),
+            Exchange::Lighter => Some(&self.lighter),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Copy, Clone)]
+impl Default for AllDepthData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// JSON shape documented in docs/schema.md. `received_instant` is
+/// process-local and never serialized.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub bid: Option<f64>,
     pub ask: Option<f64>,
@@ -71,9 +212,15 @@ pub struct MarketData {
     pub exchange_ts_raw: Option<DateTime<Utc>>,
     pub exchange_ts: Option<DateTime<Utc>>,
     pub received_ts: Option<DateTime<Utc>>,
+    #[serde(skip)]
     pub received_instant: Option<Instant>,
-    /// Exchange-specific monotonic update sequence (e.g. Binance `u` field).
+    /// Exchange-specific monotonic update sequence (e.g. Binance `u`,
+    /// Coinbase `sequence`, OKX `seqId`). Serialized under this name; see
+    /// `sequence()`.
     pub update_id: Option<u64>,
+    /// Book size imbalance over the top `IMBALANCE_LEVELS` per side, from
+    /// feeds that keep a local book; see `OrderBook::imbalance`.
+    pub imbalance: Option<f64>,
     /// Feed processing latency: WS recv → ring buffer write (nanoseconds)
     pub feed_latency_ns: u64,
 }
@@ -90,6 +237,7 @@ impl Default for MarketData {
             received_ts: None,
             received_instant: None,
             update_id: None,
+            imbalance: None,
             feed_latency_ns: 0,
         }
     }
@@ -131,12 +279,65 @@ impl ClockCorrectionConfig {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Quote TTLs for the stale sweeper. Venues are listed by name alongside
+/// the global fields:
+///
+/// ```yaml
+/// stale_ttl:
+///   default_ms: 30000
+///   lighter: 5000
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaleTtlConfig {
+    /// TTL for venues without their own entry. None = never evict them.
+    #[serde(default)]
+    pub default_ms: Option<u64>,
+    #[serde(default = "default_sweep_interval_ms")]
+    pub sweep_interval_ms: u64,
+    #[serde(flatten)]
+    pub exchanges: HashMap<String, u64>,
+}
+
+fn default_sweep_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for StaleTtlConfig {
+    fn default() -> Self {
+        Self {
+            default_ms: None,
+            sweep_interval_ms: default_sweep_interval_ms(),
+            exchanges: HashMap::new(),
+        }
+    }
+}
+
+impl StaleTtlConfig {
+    pub fn ttl_for(&self, exchange: &str) -> Option<Duration> {
+        self.exchanges
+            .get(exchange)
+            .copied()
+            .or(self.default_ms)
+            .map(Duration::from_millis)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.default_ms.is_some() || !self.exchanges.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum InstrumentType {
+    #[serde(rename = "SPOT")]
     Spot,
+    #[serde(rename = "PERP")]
     Perp,
+    #[serde(rename = "OPTION")]
     Option,
+    #[serde(rename = "FUT")]
     Futures,
+    #[serde(rename = "INDEX")]
+    Index,
 }
 
 impl InstrumentType {
@@ -146,14 +347,33 @@ impl InstrumentType {
             InstrumentType::Perp => "PERP",
             InstrumentType::Option => "OPTION",
             InstrumentType::Futures => "FUT",
+            InstrumentType::Index => "INDEX",
         }
     }
+
+    /// Inverse of `as_str`: "PERP" -> Perp. Case-insensitive.
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        Some(match prefix.to_ascii_uppercase().as_str() {
+            "SPOT" => InstrumentType::Spot,
+            "PERP" => InstrumentType::Perp,
+            "OPTION" => InstrumentType::Option,
+            "FUT" => InstrumentType::Futures,
+            "INDEX" => InstrumentType::Index,
+            _ => return None,
+        })
+    }
 }
 
 impl MarketData {
     pub fn midquote(&self) -> Option<f64> {
         return Some((self.bid? + self.ask?) / 2.0);
     }
+    /// Bid-ask spread in basis points of the mid.
+    pub fn spread_bps(&self) -> Option<f64> {
+        let mid = self.midquote()?;
+        (mid > 0.0).then(|| (self.ask? - self.bid?) / mid * 10_000.0)
+    }
+
     pub fn microprice(&self) -> Option<f64> {
         let bid = self.bid?;
         let ask = self.ask?;
@@ -166,12 +386,59 @@ impl MarketData {
             None
         }
     }
+
+    /// Venue sequence number, if the feed carries one.
+    pub fn sequence(&self) -> Option<u64> {
+        self.update_id
+    }
+
+    /// WS receive to ring buffer write.
+    pub fn feed_latency(&self) -> Duration {
+        Duration::from_nanos(self.feed_latency_ns)
+    }
+
+    /// Clock-corrected venue timestamp to local receive. None without an
+    /// exchange timestamp, or if correction left it after receive.
+    pub fn exchange_latency(&self) -> Option<Duration> {
+        (self.received_ts? - self.exchange_ts?).to_std().ok()
+    }
+
+    /// Time since receive, zero if `received_ts` is after `now`. None
+    /// without a receive time.
+    pub fn age(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.received_ts.map(|ts| (now - ts).to_std().unwrap_or_default())
+    }
+
+    /// Time since the clock-corrected exchange timestamp, as `age`.
+    pub fn exchange_age(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.exchange_ts.map(|ts| (now - ts).to_std().unwrap_or_default())
+    }
+
+    /// Received `max_age` or longer ago; quotes without a receive time are
+    /// always stale.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.is_stale_at(max_age, Utc::now())
+    }
+
+    pub fn is_stale_at(&self, max_age: Duration, now: DateTime<Utc>) -> bool {
+        self.age(now).is_none_or(|age| age >= max_age)
+    }
 }
 
 /// Trait for data types that can flow through the generic feed infrastructure.
 pub trait FeedItem: Copy + Default + Send + Sync + 'static {
     fn exchange_ts_raw(&self) -> Option<DateTime<Utc>>;
     fn set_feed_latency_ns(&mut self, ns: u64);
+
+    /// This item as published on the event bus; None for kinds the bus
+    /// doesn't carry.
+    fn to_event(&self, _exchange: Exchange, _id: SymbolId) -> Option<MarketEvent> {
+        None
+    }
+
+    /// Restate a scaled contract's item per unit of the underlying: one
+    /// unit of the symbol is `multiplier` units of the registry base.
+    fn rescale(&mut self, _multiplier: f64) {}
 }
 
 impl FeedItem for MarketData {
@@ -181,34 +448,133 @@ impl FeedItem for MarketData {
     fn set_feed_latency_ns(&mut self, ns: u64) {
         self.feed_latency_ns = ns;
     }
+    fn to_event(&self, exchange: Exchange, id: SymbolId) -> Option<MarketEvent> {
+        Some(MarketEvent::Bbo { exchange, id, data: *self })
+    }
+    fn rescale(&mut self, multiplier: f64) {
+        self.bid = self.bid.map(|p| p / multiplier);
+        self.ask = self.ask.map(|p| p / multiplier);
+        self.bid_qty = self.bid_qty.map(|q| q * multiplier);
+        self.ask_qty = self.ask_qty.map(|q| q * multiplier);
+    }
 }
 
 /// Trait for collections that can receive feed items from the connection loop.
 pub trait DataSink<T>: Send + Sync {
     fn push(&self, id: &SymbolId, item: T);
+
+    /// Hide the symbol's current value until the next push. Called when a
+    /// feed's circuit breaker trips. No-op for event sinks such as trades,
+    /// where there is no "current" value to withdraw.
+    fn invalidate(&self, _id: &SymbolId) {}
 }
 
 impl DataSink<MarketData> for MarketDataCollection {
     fn push(&self, id: &SymbolId, item: MarketData) {
         MarketDataCollection::push(self, id, item);
     }
+    fn invalidate(&self, id: &SymbolId) {
+        MarketDataCollection::invalidate(self, id);
+    }
 }
 
 impl DataSink<MarketData> for Arc<MarketDataCollection> {
     fn push(&self, id: &SymbolId, item: MarketData) {
         MarketDataCollection::push(self, id, item);
     }
+    fn invalidate(&self, id: &SymbolId) {
+        MarketDataCollection::invalidate(self, id);
+    }
 }
 
 struct SymbolSlot {
     ring: OnceLock<Box<RingBuffer<MarketData>>>,
     clock_offset_ewma_ns: AtomicI64,
     clock_offset_max_ns: AtomicI64,
+    /// Write count at which the sweeper evicted this slot (0 = not evicted).
+    /// The next push moves write_count past it, which revalidates the slot.
+    evicted_at: AtomicU64,
+    /// Receive time of the last write, ms since epoch (0 = never).
+    last_update_ms: AtomicI64,
+    /// Allocated on first write, so idle slots cost nothing.
+    rate: OnceLock<Box<RateWindow>>,
+}
+
+const RATE_WINDOW_S: usize = 60;
+
+/// Writes per second over the last `RATE_WINDOW_S` seconds. Each bucket
+/// packs `(unix second << 32) | count`; a bucket from an older second is
+/// restarted on write and ignored on read. Single writer per slot, like
+/// the clock offsets.
+struct RateWindow {
+    buckets: [AtomicU64; RATE_WINDOW_S],
+}
+
+impl RateWindow {
+    fn new() -> Self {
+        Self { buckets: std::array::from_fn(|_| AtomicU64::new(0)) }
+    }
+
+    fn record(&self, sec: u32) {
+        let bucket = &self.buckets[sec as usize % RATE_WINDOW_S];
+        let cur = bucket.load(Ordering::Relaxed);
+        let next = if (cur >> 32) as u32 == sec { cur + 1 } else { ((sec as u64) << 32) | 1 };
+        bucket.store(next, Ordering::Relaxed);
+    }
+
+    fn count(&self, now_sec: u32) -> u64 {
+        let oldest = now_sec.saturating_sub(RATE_WINDOW_S as u32 - 1);
+        self.buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .filter(|v| (oldest..=now_sec).contains(&((v >> 32) as u32)))
+            .map(|v| v & 0xffff_ffff)
+            .sum()
+    }
+}
+
+/// Per-symbol update counters from `MarketDataCollection::stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SymbolStats {
+    /// Ticks written since start.
+    pub updates: u64,
+    /// Receive time of the latest tick.
+    pub last_update: Option<DateTime<Utc>>,
+    /// Mean ticks per second over the last minute.
+    pub updates_per_sec: f64,
+}
+
+impl SymbolStats {
+    /// Time since the latest tick; None if the symbol never ticked.
+    pub fn age(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        Some(now - self.last_update?)
+    }
+}
+
+/// Per-symbol write counts a poller has already consumed; see
+/// `MarketDataCollection::drain_changes`. One cursor per collection.
+#[derive(Debug, Clone)]
+pub struct ChangeCursor {
+    seen: Box<[u64]>,
+}
+
+impl ChangeCursor {
+    pub fn new() -> Self {
+        Self { seen: vec![0; symbol_capacity()].into_boxed_slice() }
+    }
+}
+
+impl Default for ChangeCursor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct MarketDataCollection {
     slots: Box<[SymbolSlot]>,
     clock_config: ClockCorrectionConfig,
+    /// Set once by `AllMarketData::install_sanitizer`.
+    sanitizer: OnceLock<(Arc<QuoteSanitizer>, Exchange)>,
 }
 
 // Debug impl since OnceLock<Box<RingBuffer>> doesn't derive Debug
@@ -241,8 +607,31 @@ pub struct AllMarketData {
     pub zeroone: Arc<MarketDataCollection>,
     pub risex: Arc<MarketDataCollection>,
     pub bulk: Arc<MarketDataCollection>,
+    pub pyth: Arc<MarketDataCollection>,
+    pub chainlink: Arc<MarketDataCollection>,
+    pub dydx: Arc<MarketDataCollection>,
+    pub deribit: Arc<MarketDataCollection>,
+    pub gateio: Arc<MarketDataCollection>,
+    pub htx: Arc<MarketDataCollection>,
+    pub bitfinex: Arc<MarketDataCollection>,
+    pub upbit: Arc<MarketDataCollection>,
+    pub phemex: Arc<MarketDataCollection>,
+    pub vertex: Arc<MarketDataCollection>,
     /// Shared order book snapshots (any exchange that provides full book data).
     pub book: Arc<BookCollection>,
+    /// Per-venue top-N depth, for the venues and levels in `depth_levels`.
+    pub depth: Arc<AllDepthData>,
+    /// Full books of the depth-based feeds (MEXC perp depth mode, Lighter).
+    pub live_books: Arc<LiveBooks>,
+    /// Option quotes and greeks for venues with options feeds.
+    pub options: Arc<AllOptionData>,
+    /// Dated futures quotes for venues with futures feeds.
+    pub futures: Arc<AllFuturesData>,
+    /// Funding, open interest and mark/oracle prices per perp.
+    pub deriv_stats: Arc<AllDerivStats>,
+    /// Forced liquidation orders per perp.
+    pub liquidations: Arc<AllLiquidationData>,
+    sanitizer: OnceLock<Arc<QuoteSanitizer>>,
 }
 
 // Debug impl
@@ -252,7 +641,7 @@ impl std::fmt::Debug for AllMarketData {
     }
 }
 
-#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub enum Exchange {
     Binance,
     Coinbase,
@@ -274,9 +663,58 @@ pub enum Exchange {
     ZeroOne,
     RiseX,
     Bulk,
+    Pyth,
+    Chainlink,
+    Dydx,
+    Deribit,
+    Gateio,
+    Htx,
+    Bitfinex,
+    Upbit,
+    Phemex,
+    Vertex,
 }
 
 impl Exchange {
+    /// Every venue, in declaration order (`ALL[e as usize] == e`).
+    pub const ALL: [Exchange; 30] = [
+        Exchange::Binance,
+        Exchange::Coinbase,
+        Exchange::Bybit,
+        Exchange::Kraken,
+        Exchange::Lighter,
+        Exchange::Mexc,
+        Exchange::Extended,
+        Exchange::Nado,
+        Exchange::Okx,
+        Exchange::Kucoin,
+        Exchange::Bingx,
+        Exchange::Apex,
+        Exchange::Hyperliquid,
+        Exchange::Aerodrome,
+        Exchange::Uniswap,
+        Exchange::Hibachi,
+        Exchange::Hotstuff,
+        Exchange::ZeroOne,
+        Exchange::RiseX,
+        Exchange::Bulk,
+        Exchange::Pyth,
+        Exchange::Chainlink,
+        Exchange::Dydx,
+        Exchange::Deribit,
+        Exchange::Gateio,
+        Exchange::Htx,
+        Exchange::Bitfinex,
+        Exchange::Upbit,
+        Exchange::Phemex,
+        Exchange::Vertex,
+    ];
+
+    /// Reference price sources that cannot be traded on.
+    pub fn is_oracle(&self) -> bool {
+        matches!(self, Exchange::Pyth | Exchange::Chainlink)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Exchange::Binance => "binance",
@@ -299,33 +737,77 @@ impl Exchange {
             Exchange::ZeroOne => "zeroone",
             Exchange::RiseX => "risex",
             Exchange::Bulk => "bulk",
+            Exchange::Pyth => "pyth",
+            Exchange::Chainlink => "chainlink",
+            Exchange::Dydx => "dydx",
+            Exchange::Deribit => "deribit",
+            Exchange::Gateio => "gateio",
+            Exchange::Htx => "htx",
+            Exchange::Bitfinex => "bitfinex",
+            Exchange::Upbit => "upbit",
+            Exchange::Phemex => "phemex",
+            Exchange::Vertex => "vertex",
         }
     }
+}
 
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "binance" => Some(Exchange::Binance),
-            "coinbase" => Some(Exchange::Coinbase),
-            "bybit" => Some(Exchange::Bybit),
-            "kraken" => Some(Exchange::Kraken),
-            "lighter" => Some(Exchange::Lighter),
-            "mexc" => Some(Exchange::Mexc),
-            "extended" => Some(Exchange::Extended),
-            "nado" => Some(Exchange::Nado),
-            "okx" => Some(Exchange::Okx),
-            "kucoin" => Some(Exchange::Kucoin),
-            "bingx" => Some(Exchange::Bingx),
-            "apex" => Some(Exchange::Apex),
-            "hyperliquid" => Some(Exchange::Hyperliquid),
-            "aerodrome" => Some(Exchange::Aerodrome),
-            "uniswap" => Some(Exchange::Uniswap),
-            "hibachi" => Some(Exchange::Hibachi),
-            "hotstuff" => Some(Exchange::Hotstuff),
-            "zeroone" => Some(Exchange::ZeroOne),
-            "risex" | "rise" => Some(Exchange::RiseX),
-            "bulk" => Some(Exchange::Bulk),
-            _ => None,
-        }
+/// Venue name not matching any `Exchange`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownExchange(pub String);
+
+impl std::fmt::Display for UnknownExchange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown exchange '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownExchange {}
+
+/// Case-insensitive; also accepts a few venue aliases ("huobi", "gate").
+impl std::str::FromStr for Exchange {
+    type Err = UnknownExchange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "binance" => Exchange::Binance,
+            "coinbase" => Exchange::Coinbase,
+            "bybit" => Exchange::Bybit,
+            "kraken" => Exchange::Kraken,
+            "lighter" => Exchange::Lighter,
+            "mexc" => Exchange::Mexc,
+            "extended" => Exchange::Extended,
+            "nado" => Exchange::Nado,
+            "okx" => Exchange::Okx,
+            "kucoin" => Exchange::Kucoin,
+            "bingx" => Exchange::Bingx,
+            "apex" => Exchange::Apex,
+            "hyperliquid" => Exchange::Hyperliquid,
+            "aerodrome" => Exchange::Aerodrome,
+            "uniswap" => Exchange::Uniswap,
+            "hibachi" => Exchange::Hibachi,
+            "hotstuff" => Exchange::Hotstuff,
+            "zeroone" => Exchange::ZeroOne,
+            "risex" | "rise" => Exchange::RiseX,
+            "bulk" => Exchange::Bulk,
+            "pyth" => Exchange::Pyth,
+            "chainlink" => Exchange::Chainlink,
+            "dydx" => Exchange::Dydx,
+            "deribit" => Exchange::Deribit,
+            "gateio" | "gate" => Exchange::Gateio,
+            "htx" | "huobi" => Exchange::Htx,
+            "bitfinex" => Exchange::Bitfinex,
+            "upbit" => Exchange::Upbit,
+            "phemex" => Exchange::Phemex,
+            "vertex" => Exchange::Vertex,
+            _ => return Err(UnknownExchange(s.to_string())),
+        })
+    }
+}
+
+/// The lowercase config name, as `as_str`.
+impl std::fmt::Display for Exchange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -353,6 +835,16 @@ impl AllMarketData {
             (ZeroOne, &self.zeroone),
             (RiseX, &self.risex),
             (Bulk, &self.bulk),
+            (Pyth, &self.pyth),
+            (Chainlink, &self.chainlink),
+            (Dydx, &self.dydx),
+            (Deribit, &self.deribit),
+            (Gateio, &self.gateio),
+            (Htx, &self.htx),
+            (Bitfinex, &self.bitfinex),
+            (Upbit, &self.upbit),
+            (Phemex, &self.phemex),
+            (Vertex, &self.vertex),
         ]
         .into_iter()
     }
@@ -379,7 +871,91 @@ impl AllMarketData {
             Exchange::ZeroOne => &self.zeroone,
             Exchange::RiseX => &self.risex,
             Exchange::Bulk => &self.bulk,
+            Exchange::Pyth => &self.pyth,
+            Exchange::Chainlink => &self.chainlink,
+            Exchange::Dydx => &self.dydx,
+            Exchange::Deribit => &self.deribit,
+            Exchange::Gateio => &self.gateio,
+            Exchange::Htx => &self.htx,
+            Exchange::Bitfinex => &self.bitfinex,
+            Exchange::Upbit => &self.upbit,
+            Exchange::Phemex => &self.phemex,
+            Exchange::Vertex => &self.vertex,
+        }
+    }
+
+    /// Run one eviction pass over every venue with a TTL. Returns the number
+    /// of quotes evicted.
+    pub fn evict_stale(&self, cfg: &StaleTtlConfig, now: Instant) -> usize {
+        self.iter()
+            .filter_map(|(exchange, coll)| {
+                cfg.ttl_for(exchange.as_str()).map(|ttl| coll.evict_stale(ttl, now))
+            })
+            .sum()
+    }
+
+    /// Every venue's current quote per symbol as of `now`, with ages and a
+    /// coherence flag: true when all quotes for the symbol were received
+    /// within `max_skew` of each other. Symbols no venue quotes are omitted.
+    /// Evicted quotes are excluded, since `latest()` hides them.
+    pub fn snapshot_at(&self, now: Instant, max_skew: Duration) -> Vec<SymbolQuotes> {
+        let mut out = Vec::new();
+        for id in 0..symbol_capacity() {
+            let quotes: Vec<VenueQuote> = self
+                .iter()
+                .filter_map(|(exchange, coll)| {
+                    let quote = coll.latest(&id)?;
+                    let age = quote.received_instant.map(|t| now.saturating_duration_since(t));
+                    Some(VenueQuote { exchange, quote, age })
+                })
+                .collect();
+            if quotes.is_empty() {
+                continue;
+            }
+            let skew = quotes
+                .iter()
+                .map(|q| q.age)
+                .collect::<Option<Vec<_>>>()
+                .map(|ages| {
+                    let oldest = ages.iter().max().copied().unwrap_or_default();
+                    let newest = ages.iter().min().copied().unwrap_or_default();
+                    oldest - newest
+                });
+            out.push(SymbolQuotes {
+                id,
+                coherent: skew.is_some_and(|s| s <= max_skew),
+                skew,
+                quotes,
+            });
         }
+        out
+    }
+}
+
+/// One venue's quote inside a `snapshot_at` result.
+#[derive(Debug, Clone, Copy)]
+pub struct VenueQuote {
+    pub exchange: Exchange,
+    pub quote: MarketData,
+    /// Time since receipt. None if the feed does not stamp `received_instant`.
+    pub age: Option<Duration>,
+}
+
+/// All venues' quotes for one symbol at a single point in time.
+#[derive(Debug, Clone)]
+pub struct SymbolQuotes {
+    pub id: SymbolId,
+    pub quotes: Vec<VenueQuote>,
+    /// Oldest age minus newest age. None if any quote has no age.
+    pub skew: Option<Duration>,
+    /// Skew is known and within the requested bound. Cross-venue
+    /// computations should reject the symbol when this is false.
+    pub coherent: bool,
+}
+
+impl SymbolQuotes {
+    pub fn get(&self, exchange: &Exchange) -> Option<&VenueQuote> {
+        self.quotes.iter().find(|q| q.exchange == *exchange)
     }
 }
 
@@ -411,29 +987,77 @@ impl AllMarketData {
             zeroone: new_coll(),
             risex: new_coll(),
             bulk: new_coll(),
+            pyth: new_coll(),
+            chainlink: new_coll(),
+            dydx: new_coll(),
+            deribit: new_coll(),
+            gateio: new_coll(),
+            htx: new_coll(),
+            bitfinex: new_coll(),
+            upbit: new_coll(),
+            phemex: new_coll(),
+            vertex: new_coll(),
             book: Arc::new(BookCollection::new()),
+            depth: Arc::new(AllDepthData::new()),
+            live_books: Arc::new(LiveBooks::new()),
+            options: Arc::new(AllOptionData::new()),
+            futures: Arc::new(AllFuturesData::new()),
+            deriv_stats: Arc::new(AllDerivStats::new()),
+            liquidations: Arc::new(AllLiquidationData::new()),
+            sanitizer: OnceLock::new(),
+        }
+    }
+
+    /// Filter spikes on every venue's pushes from now on. Only the first
+    /// call takes effect; later calls return the installed sanitizer.
+    pub fn install_sanitizer(&self, cfg: SanitizerConfig) -> Arc<QuoteSanitizer> {
+        let sanitizer = self.sanitizer.get_or_init(|| Arc::new(QuoteSanitizer::new(cfg))).clone();
+        for (exchange, coll) in self.iter() {
+            let _ = coll.sanitizer.set((sanitizer.clone(), exchange));
         }
+        sanitizer
+    }
+
+    pub fn sanitizer(&self) -> Option<&Arc<QuoteSanitizer>> {
+        self.sanitizer.get()
+    }
+
+    /// The full book `exchange`'s feed keeps for `id`, for depth beyond the
+    /// BBO. None for venues that don't build books or symbols not running.
+    pub fn get_book(&self, exchange: &Exchange, id: &SymbolId) -> Option<BookHandle> {
+        self.live_books.get(exchange, id)
     }
 }
 
 impl MarketDataCollection {
     pub fn new(clock_config: ClockCorrectionConfig) -> Self {
-        let mut slots = Vec::with_capacity(MAX_SYMBOLS);
-        for _ in 0..MAX_SYMBOLS {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
             slots.push(SymbolSlot {
                 ring: OnceLock::new(),
                 clock_offset_ewma_ns: AtomicI64::new(0),
                 clock_offset_max_ns: AtomicI64::new(0),
+                evicted_at: AtomicU64::new(0),
+                last_update_ms: AtomicI64::new(0),
+                rate: OnceLock::new(),
             });
         }
         Self {
             slots: slots.into_boxed_slice(),
             clock_config,
+            sanitizer: OnceLock::new(),
         }
     }
 
     /// Push a new tick for the given symbol (interior-mutable, no &mut self needed).
     pub fn push(&self, id: &SymbolId, mut market_data: MarketData) {
+        if let (Some((sanitizer, exchange)), Some(mid)) = (self.sanitizer.get(), market_data.midquote()) {
+            let now_ms = market_data.received_ts.unwrap_or_else(Utc::now).timestamp_millis();
+            if sanitizer.check(*exchange, *id, mid, now_ms) == Verdict::Reject {
+                return;
+            }
+        }
         let slot = &self.slots[*id];
 
         // Clock correction: track per-symbol offset
@@ -462,13 +1086,93 @@ impl MarketDataCollection {
             .exchange_ts_raw
             .map(|t| t + chrono::Duration::nanoseconds(offset));
 
+        let received_ms = market_data.received_ts.unwrap_or_else(Utc::now).timestamp_millis();
+        slot.last_update_ms.store(received_ms, Ordering::Relaxed);
+        let rate = slot.rate.get_or_init(|| Box::new(RateWindow::new()));
+        rate.record(received_ms.div_euclid(1000) as u32);
+
         let ring = slot.ring.get_or_init(|| Box::new(RingBuffer::new()));
         ring.push(market_data);
     }
 
+    /// Update count, last receive time and recent rate for a symbol. A
+    /// subscribed symbol with `updates == 0`, or an old `last_update`, is
+    /// not ticking.
+    pub fn stats(&self, id: &SymbolId) -> SymbolStats {
+        self.stats_at(id, Utc::now())
+    }
+
+    pub fn stats_at(&self, id: &SymbolId, now: DateTime<Utc>) -> SymbolStats {
+        let slot = &self.slots[*id];
+        let last_ms = slot.last_update_ms.load(Ordering::Relaxed);
+        let recent = slot.rate.get().map_or(0, |r| r.count(now.timestamp().max(0) as u32));
+        SymbolStats {
+            updates: self.write_count(id),
+            last_update: (last_ms != 0).then(|| DateTime::from_timestamp_millis(last_ms)).flatten(),
+            updates_per_sec: recent as f64 / RATE_WINDOW_S as f64,
+        }
+    }
+
+    /// Number of symbol slots; ids must be below it.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
     /// Get the latest tick for a symbol (owned copy via seqlock read).
+    /// None if the stale sweeper evicted it and nothing newer arrived.
     pub fn latest(&self, id: &SymbolId) -> Option<MarketData> {
-        self.slots[*id].ring.get()?.latest()
+        let slot = &self.slots[*id];
+        let ring = slot.ring.get()?;
+        if Self::is_evicted(slot, ring) {
+            return None;
+        }
+        ring.latest()
+    }
+
+    /// `latest` keyed by registry symbol ("BTC_USDT" or any alias) rather
+    /// than id. None for symbols the registry doesn't know.
+    pub fn latest_by_symbol(&self, symbol: &str, itype: &InstrumentType) -> Option<MarketData> {
+        self.latest(REGISTRY.lookup(symbol, itype)?)
+    }
+
+    fn is_evicted(slot: &SymbolSlot, ring: &RingBuffer<MarketData>) -> bool {
+        let evicted_at = slot.evicted_at.load(Ordering::Acquire);
+        evicted_at != 0 && evicted_at == ring.write_count()
+    }
+
+    /// Evict every quote last received more than `ttl` before `now`, so
+    /// `latest()` and the getters built on it return None until the feed
+    /// writes again. Ring buffer history is left intact. Returns the number
+    /// of slots newly evicted.
+    pub fn evict_stale(&self, ttl: Duration, now: Instant) -> usize {
+        let mut evicted = 0;
+        for slot in self.slots.iter() {
+            let Some(ring) = slot.ring.get() else { continue };
+            // Read the count first: if a push lands in between, the marker
+            // won't match the new count and the fresh quote stays visible.
+            let count = ring.write_count();
+            if count == 0 || slot.evicted_at.load(Ordering::Relaxed) == count {
+                continue;
+            }
+            let Some(md) = ring.latest() else { continue };
+            let Some(received) = md.received_instant else { continue };
+            if now.saturating_duration_since(received) > ttl {
+                slot.evicted_at.store(count, Ordering::Release);
+                evicted += 1;
+            }
+        }
+        evicted
+    }
+
+    /// Hide the current quote from `latest()` until the next push, using the
+    /// same marker as `evict_stale`.
+    pub fn invalidate(&self, id: &SymbolId) {
+        let slot = &self.slots[*id];
+        let Some(ring) = slot.ring.get() else { return };
+        let count = ring.write_count();
+        if count > 0 {
+            slot.evicted_at.store(count, Ordering::Release);
+        }
     }
 
     /// Number of ticks written for this symbol. Use to detect new data.
@@ -480,6 +1184,38 @@ impl MarketDataCollection {
             .unwrap_or(0)
     }
 
+    /// Latest quote of every symbol received within `max_age`, by id.
+    /// Evicted symbols are skipped.
+    pub fn fresh_snapshot(&self, max_age: Duration) -> Vec<(SymbolId, MarketData)> {
+        self.fresh_snapshot_at(max_age, Utc::now())
+    }
+
+    pub fn fresh_snapshot_at(&self, max_age: Duration, now: DateTime<Utc>) -> Vec<(SymbolId, MarketData)> {
+        (0..self.slots.len())
+            .filter_map(|id| Some((id, self.latest(&id)?)))
+            .filter(|(_, md)| !md.is_stale_at(max_age, now))
+            .collect()
+    }
+
+    /// Latest quote of every symbol written since the cursor last saw it,
+    /// advancing the cursor. Only per-symbol write counts are compared, so
+    /// an idle universe costs no copies. Evicted symbols are skipped.
+    pub fn drain_changes(&self, cursor: &mut ChangeCursor) -> Vec<(SymbolId, MarketData)> {
+        let mut out = Vec::new();
+        for (id, slot) in self.slots.iter().enumerate() {
+            let Some(ring) = slot.ring.get() else { continue };
+            let count = ring.write_count();
+            if count == cursor.seen[id] {
+                continue;
+            }
+            cursor.seen[id] = count;
+            if let Some(md) = self.latest(&id) {
+                out.push((id, md));
+            }
+        }
+        out
+    }
+
     /// Direct access to the underlying ring buffer for a symbol.
     pub fn get_buffer(&self, id: &SymbolId) -> Option<&RingBuffer<MarketData>> {
         self.slots[*id].ring.get().map(|b| b.as_ref())
@@ -506,9 +1242,18 @@ impl MarketDataCollection {
         }
     }
 
+    pub fn get_spread_bps(&self, id: &SymbolId) -> Option<f64> {
+        self.latest(id)?.spread_bps()
+    }
+
     /// Blocking latest with retries — for diagnostic/non-hot-path reads.
     pub fn latest_blocking(&self, id: &SymbolId) -> Option<MarketData> {
-        self.slots[*id].ring.get()?.latest_blocking()
+        let slot = &self.slots[*id];
+        let ring = slot.ring.get()?;
+        if Self::is_evicted(slot, ring) {
+            return None;
+        }
+        ring.latest_blocking()
     }
 
     pub fn get_midquote_blocking(&self, id: &SymbolId) -> Option<f64> {
@@ -527,3 +1272,229 @@ impl MarketDataCollection {
         Some((mid, md.received_ts, md.exchange_ts))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_data_json_shape() {
+        let md = MarketData {
+            bid: Some(100.0),
+            ask: Some(101.0),
+            exchange_ts: DateTime::from_timestamp(1_700_000_000, 123_000_000),
+            received_instant: Some(Instant::now()),
+            update_id: Some(5),
+            ..Default::default()
+        };
+        let v = serde_json::to_value(md).unwrap();
+        assert_eq!(v["bid"], 100.0);
+        assert!(v["bid_qty"].is_null());
+        assert_eq!(v["exchange_ts"], "2023-11-14T22:13:20.123Z");
+        assert_eq!(v["update_id"], 5);
+        assert!(v.get("received_instant").is_none());
+
+        let back: MarketData = serde_json::from_value(v).unwrap();
+        assert_eq!(back.ask, Some(101.0));
+        assert_eq!(back.exchange_ts, md.exchange_ts);
+        assert!(back.received_instant.is_none());
+    }
+
+    #[test]
+    fn test_latency_accessors() {
+        let md = MarketData {
+            exchange_ts: DateTime::from_timestamp_millis(1_700_000_000_000),
+            received_ts: DateTime::from_timestamp_millis(1_700_000_000_004),
+            update_id: Some(9),
+            feed_latency_ns: 1_500,
+            ..Default::default()
+        };
+        assert_eq!(md.sequence(), Some(9));
+        assert_eq!(md.feed_latency(), Duration::from_nanos(1_500));
+        assert_eq!(md.exchange_latency(), Some(Duration::from_millis(4)));
+        let ahead = MarketData { exchange_ts: DateTime::from_timestamp_millis(1_700_000_000_010), ..md };
+        assert_eq!(ahead.exchange_latency(), None);
+    }
+
+    #[test]
+    fn test_fresh_snapshot_drops_stale_quotes() {
+        let coll = MarketDataCollection::new(ClockCorrectionConfig::default());
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let quote = |age_ms: i64| MarketData {
+            bid: Some(1.0),
+            ask: Some(2.0),
+            received_ts: Some(now - chrono::Duration::milliseconds(age_ms)),
+            ..Default::default()
+        };
+        coll.push(&1, quote(100));
+        coll.push(&2, quote(1_500));
+        coll.push(&3, MarketData { received_ts: None, ..quote(0) });
+
+        let fresh: Vec<SymbolId> = coll.fresh_snapshot_at(Duration::from_secs(1), now).iter().map(|(id, _)| *id).collect();
+        assert_eq!(fresh, vec![1]);
+        assert!(quote(1_000).is_stale_at(Duration::from_secs(1), now));
+        assert_eq!(quote(-50).age(now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_exchange_names_round_trip() {
+        for e in Exchange::ALL {
+            assert_eq!(e.to_string().parse::<Exchange>(), Ok(e));
+            assert_eq!(e.as_str().to_uppercase().parse::<Exchange>(), Ok(e));
+        }
+        assert_eq!("huobi".parse::<Exchange>(), Ok(Exchange::Htx));
+        assert_eq!("nope".parse::<Exchange>().unwrap_err().to_string(), "unknown exchange 'nope'");
+    }
+
+    #[test]
+    fn test_spread_bps_and_weighted_mid() {
+        let md = MarketData { bid: Some(99.0), ask: Some(101.0), bid_qty: Some(3.0), ask_qty: Some(1.0), ..Default::default() };
+        assert!((md.spread_bps().unwrap() - 200.0).abs() < 1e-9);
+        assert_eq!(MarketData { ask: None, ..md }.spread_bps(), None);
+
+        let mut snap = BookSnapshot::default();
+        for (i, (px, qty)) in [(99.0, 3.0), (98.0, 1.0)].into_iter().enumerate() {
+            snap.bids[i] = BookLevel { price: px, qty };
+        }
+        for (i, (px, qty)) in [(101.0, 1.0), (103.0, 3.0)].into_iter().enumerate() {
+            snap.asks[i] = BookLevel { price: px, qty };
+        }
+        snap.bid_count = 2;
+        snap.ask_count = 2;
+        assert_eq!(snap.weighted_mid(1), md.microprice());
+        // Bid VWAP 98.75 x 4, ask VWAP 102.5 x 4.
+        assert!((snap.weighted_mid(5).unwrap() - 100.625).abs() < 1e-9);
+        assert_eq!(BookSnapshot::default().weighted_mid(5), None);
+
+        // Half the first ask, then both asks: 4.0 for 410.
+        assert_eq!(snap.vwap_buy(50.5), Some(101.0));
+        assert!((snap.vwap_buy(410.0).unwrap() - 410.0 / (1.0 + 309.0 / 103.0)).abs() < 1e-9);
+        assert_eq!(snap.vwap_buy(1000.0), None);
+        assert!((snap.vwap_sell(395.0).unwrap() - 395.0 / 4.0).abs() < 1e-9);
+        assert_eq!(snap.vwap_sell(0.0), None);
+    }
+
+    #[test]
+    fn test_symbol_stats_rate_window() {
+        let coll = MarketDataCollection::new(ClockCorrectionConfig::default());
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(coll.stats_at(&4, t0), SymbolStats::default());
+
+        for s in [0, 0, 10, 59, 90] {
+            let received_ts = Some(t0 + chrono::Duration::seconds(s));
+            coll.push(&4, MarketData { bid: Some(1.0), ask: Some(2.0), received_ts, ..Default::default() });
+        }
+        let now = t0 + chrono::Duration::seconds(95);
+        let stats = coll.stats_at(&4, now);
+        assert_eq!(stats.updates, 5);
+        assert_eq!(stats.last_update, Some(t0 + chrono::Duration::seconds(90)));
+        assert_eq!(stats.age(now), Some(chrono::Duration::seconds(5)));
+        // 59s and 90s fall inside (35s, 95s].
+        assert!((stats.updates_per_sec - 2.0 / 60.0).abs() < 1e-12);
+        assert_eq!(coll.stats_at(&4, t0 + chrono::Duration::seconds(500)).updates_per_sec, 0.0);
+    }
+
+    #[test]
+    fn test_latest_by_symbol() {
+        let coll = MarketDataCollection::new(ClockCorrectionConfig::default());
+        let id = *REGISTRY.lookup("BTC_USDT", &InstrumentType::Spot).unwrap();
+        coll.push(&id, MarketData { bid: Some(1.0), ask: Some(2.0), ..Default::default() });
+        assert_eq!(coll.latest_by_symbol("BTC_USDT", &InstrumentType::Spot).unwrap().bid, Some(1.0));
+        assert!(coll.latest_by_symbol("BTC_USDT", &InstrumentType::Perp).is_none());
+        assert!(coll.latest_by_symbol("NOT_A_SYMBOL", &InstrumentType::Spot).is_none());
+    }
+
+    #[test]
+    fn test_evict_stale_until_next_push() {
+        let coll = MarketDataCollection::new(ClockCorrectionConfig::default());
+        let t0 = Instant::now();
+        let tick = |at: Instant| MarketData {
+            bid: Some(1.0),
+            ask: Some(2.0),
+            received_instant: Some(at),
+            ..Default::default()
+        };
+        coll.push(&0, tick(t0));
+        coll.push(&1, tick(t0 + Duration::from_secs(9)));
+
+        let now = t0 + Duration::from_secs(10);
+        assert_eq!(coll.evict_stale(Duration::from_secs(5), now), 1);
+        assert!(coll.latest(&0).is_none());
+        assert!(coll.get_midquote(&0).is_none());
+        assert_eq!(coll.latest(&1).unwrap().bid, Some(1.0));
+        // Already evicted; not counted twice.
+        assert_eq!(coll.evict_stale(Duration::from_secs(5), now), 0);
+
+        coll.push(&0, tick(now));
+        assert_eq!(coll.get_midquote(&0), Some(1.5));
+    }
+
+    #[test]
+    fn test_drain_changes() {
+        let coll = MarketDataCollection::new(ClockCorrectionConfig::default());
+        let tick = |bid: f64| MarketData { bid: Some(bid), ask: Some(bid + 1.0), ..Default::default() };
+        let mut cursor = ChangeCursor::new();
+        assert!(coll.drain_changes(&mut cursor).is_empty());
+
+        coll.push(&3, tick(10.0));
+        coll.push(&7, tick(20.0));
+        coll.push(&7, tick(21.0));
+        let changes = coll.drain_changes(&mut cursor);
+        assert_eq!(changes.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![3, 7]);
+        assert_eq!(changes[1].1.bid, Some(21.0));
+        assert!(coll.drain_changes(&mut cursor).is_empty());
+
+        coll.push(&7, tick(22.0));
+        let changes = coll.drain_changes(&mut cursor);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, 7);
+
+        // Independent cursors each see everything.
+        assert_eq!(coll.drain_changes(&mut ChangeCursor::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_at_coherence() {
+        let all = AllMarketData::new();
+        let t0 = Instant::now();
+        let tick = |at: Instant| MarketData {
+            bid: Some(1.0),
+            ask: Some(2.0),
+            received_instant: Some(at),
+            ..Default::default()
+        };
+        all.binance.push(&4, tick(t0));
+        all.okx.push(&4, tick(t0 + Duration::from_millis(40)));
+        all.bybit.push(&5, tick(t0));
+        all.okx.push(&5, tick(t0 + Duration::from_millis(400)));
+        all.kraken.push(&6, MarketData { bid: Some(1.0), ..Default::default() });
+
+        let now = t0 + Duration::from_millis(500);
+        let snap = all.snapshot_at(now, Duration::from_millis(100));
+        assert_eq!(snap.iter().map(|s| s.id).collect::<Vec<_>>(), vec![4, 5, 6]);
+
+        assert!(snap[0].coherent);
+        assert_eq!(snap[0].skew, Some(Duration::from_millis(40)));
+        assert_eq!(snap[0].get(&Exchange::Binance).unwrap().age, Some(Duration::from_millis(500)));
+        assert!(!snap[1].coherent);
+        // No receipt instant: age unknown, never coherent.
+        assert!(snap[2].skew.is_none() && !snap[2].coherent);
+    }
+
+    #[test]
+    fn test_stale_ttl_config() {
+        let cfg: StaleTtlConfig =
+            serde_yaml::from_str("default_ms: 30000\nlighter: 5000\n").unwrap();
+        assert_eq!(cfg.ttl_for("lighter"), Some(Duration::from_millis(5000)));
+        assert_eq!(cfg.ttl_for("binance"), Some(Duration::from_millis(30000)));
+        assert_eq!(cfg.sweep_interval_ms, 1000);
+        assert!(!StaleTtlConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_instrument_type_json() {
+        assert_eq!(serde_json::to_string(&InstrumentType::Futures).unwrap(), "\"FUT\"");
+        let t: InstrumentType = serde_json::from_str("\"PERP\"").unwrap();
+        assert_eq!(t.as_str(), "PERP");
+    }
+}
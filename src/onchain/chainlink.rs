@@ -0,0 +1,243 @@
+//! Chainlink aggregator reads.
+//!
+//! Aggregators update on deviation/heartbeat rather than every block, so
+//! they are polled on a timer (`latestRoundData` for all feeds in one
+//! Multicall3 batch) instead of riding the block subscription. A value is
+//! only pushed when the round's `updatedAt` moves.
+
+use alloy::primitives::{Address, Bytes};
+use alloy::providers::{Provider, ProviderBuilder, WsConnect};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::exchanges::connection::calculate_backoff;
+use crate::market_data::{InstrumentType, MarketData, MarketDataCollection};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+
+use super::listener::rpc_label;
+use super::{Call3, decimalsCall, do_try_multicall, normalize_token};
+
+sol! {
+    function latestRoundData() external view returns (
+        uint80 roundId,
+        int256 answer,
+        uint256 startedAt,
+        uint256 updatedAt,
+        uint80 answeredInRound
+    );
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainlinkConfig {
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    pub feeds: Vec<ChainlinkFeedEntry>,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    5_000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainlinkFeedEntry {
+    /// Aggregator proxy address.
+    pub address: String,
+    /// Spot symbol the answer is published under, e.g. "ETH_USD".
+    pub symbol: String,
+}
+
+impl ChainlinkConfig {
+    /// Feeds with a parseable address and a registry symbol, with wrapped
+    /// token names normalised (WETH_USD → ETH_USD).
+    pub fn validated_feeds(&self) -> Vec<(Address, String, SymbolId)> {
+        self.feeds
+            .iter()
+            .filter_map(|e| {
+                let Ok(address) = e.address.parse::<Address>() else {
+                    warn!("Chainlink feed '{}': invalid address '{}', skipping", e.symbol, e.address);
+                    return None;
+                };
+                let symbol = match e.symbol.split_once('_') {
+                    Some((base, quote)) => {
+                        format!("{}_{}", normalize_token(base), normalize_token(quote))
+                    }
+                    None => e.symbol.clone(),
+                };
+                let Some(&id) = REGISTRY.lookup(&symbol, &InstrumentType::Spot) else {
+                    warn!("Chainlink feed {} symbol '{}' not found in registry", e.address, symbol);
+                    return None;
+                };
+                Some((address, symbol, id))
+            })
+            .collect()
+    }
+}
+
+struct FeedState {
+    address: Address,
+    label: String,
+    symbol_id: SymbolId,
+    scale: f64,
+    last_updated_at: u64,
+}
+
+async fn fetch_decimals(
+    provider: &impl Provider,
+    feeds: &[(Address, String, SymbolId)],
+) -> Result<Vec<FeedState>> {
+    let dec_cd: Bytes = decimalsCall {}.abi_encode().into();
+    let calls = feeds
+        .iter()
+        .map(|(addr, _, _)| Call3 { target: *addr, allowFailure: true, callData: dec_cd.clone() })
+        .collect();
+    let results = do_try_multicall(provider, calls).await.context("chainlink decimals multicall")?;
+
+    let mut states = Vec::with_capacity(feeds.len());
+    for ((address, label, symbol_id), r) in feeds.iter().zip(results.iter()) {
+        let decimals = match r.success.then(|| decimalsCall::abi_decode_returns(&r.returnData).ok()).flatten() {
+            Some(d) => d,
+            None => {
+                warn!("Chainlink feed {} ({}): decimals() failed, skipping", label, address);
+                continue;
+            }
+        };
+        info!("Chainlink feed {} ({}) decimals={}", label, address, decimals);
+        states.push(FeedState {
+            address: *address,
+            label: label.clone(),
+            symbol_id: *symbol_id,
+            scale: 10_f64.powi(-(decimals as i32)),
+            last_updated_at: 0,
+        });
+    }
+    Ok(states)
+}
+
+async fn run_poll(
+    data: &MarketDataCollection,
+    states: &mut [FeedState],
+    provider: &impl Provider,
+    poll_interval: Duration,
+) -> Result<()> {
+    let round_cd: Bytes = latestRoundDataCall {}.abi_encode().into();
+    let calls: Vec<Call3> = states
+        .iter()
+        .map(|s| Call3 { target: s.address, allowFailure: true, callData: round_cd.clone() })
+        .collect();
+
+    let mut interval = tokio::time::interval(poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let results = do_try_multicall(provider, calls.clone())
+            .await
+            .context("chainlink latestRoundData multicall")?;
+        let received_ts = Utc::now();
+
+        for (state, r) in states.iter_mut().zip(results.iter()) {
+            if !r.success {
+                warn!("Chainlink feed {}: latestRoundData() reverted", state.label);
+                continue;
+            }
+            let round = match latestRoundDataCall::abi_decode_returns(&r.returnData) {
+                Ok(v) => v,
+                Err(e) => {
+                    error!("Chainlink feed {} decode failed: {}", state.label, e);
+                    continue;
+                }
+            };
+            let updated_at = round.updatedAt.to::<u64>();
+            if updated_at == state.last_updated_at {
+                continue;
+            }
+            let answer = match i128::try_from(round.answer) {
+                Ok(a) if a > 0 => a,
+                _ => {
+                    warn!("Chainlink feed {}: non-positive answer {}", state.label, round.answer);
+                    continue;
+                }
+            };
+            state.last_updated_at = updated_at;
+
+            let price = answer as f64 * state.scale;
+            data.push(
+                &state.symbol_id,
+                MarketData {
+                    bid: Some(price),
+                    ask: Some(price),
+                    exchange_ts_raw: DateTime::from_timestamp(updated_at as i64, 0),
+                    received_ts: Some(received_ts),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+pub async fn listen_chainlink(
+    data: Arc<MarketDataCollection>,
+    cfg: ChainlinkConfig,
+    rpc_url: String,
+    shutdown: Arc<Notify>,
+) -> Result<()> {
+    let feeds = cfg.validated_feeds();
+    if feeds.is_empty() {
+        warn!("Chainlink: no valid feeds configured");
+        return Ok(());
+    }
+    let poll_interval = Duration::from_millis(cfg.poll_interval_ms.max(1));
+    let label = rpc_label(&rpc_url);
+    let mut retry_count = 0u32;
+
+    loop {
+        let attempt_start = std::time::Instant::now();
+
+        let result = tokio::select! {
+            _ = shutdown.notified() => {
+                info!("Chainlink feed shutdown (rpc={})", label);
+                return Ok(());
+            }
+            result = async {
+                let ws = WsConnect::new(&rpc_url);
+                let provider = ProviderBuilder::new()
+                    .connect_ws(ws)
+                    .await
+                    .context("WS connect failed")?;
+                let mut states = fetch_decimals(&provider, &feeds).await?;
+                if states.is_empty() {
+                    return Err(anyhow::anyhow!("no Chainlink feeds resolved"));
+                }
+                run_poll(&data, &mut states, &provider, poll_interval).await
+            } => result,
+        };
+
+        let was_long_lived = attempt_start.elapsed() > Duration::from_secs(60);
+        if was_long_lived { retry_count = 0; } else { retry_count += 1; }
+
+        let backoff = calculate_backoff(
+            retry_count,
+            Duration::from_secs(1),
+            Duration::from_secs(60),
+        );
+
+        if let Err(e) = result {
+            error!("Chainlink feed error (rpc={}): {:#}. Reconnecting in {:?}", label, e, backoff);
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown.notified() => {
+                    info!("Chainlink shutdown during backoff (rpc={})", label);
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -88,6 +88,17 @@ async fn run_feed(
     Err(anyhow::anyhow!("block subscription ended"))
 }
 
+/// Scheme + host only — strips the path to avoid leaking API keys (e.g. /v3/<key>).
+pub(crate) fn rpc_label(rpc_url: &str) -> &str {
+    if let Some(idx) = rpc_url.find("://") {
+        let after_scheme = &rpc_url[idx + 3..];
+        let host_end = after_scheme.find('/').unwrap_or(after_scheme.len());
+        &rpc_url[..idx + 3 + host_end]
+    } else {
+        rpc_url
+    }
+}
+
 pub async fn listen_onchain(
     aero_data: Option<Arc<MarketDataCollection>>,
     aero_pools: Vec<PoolEntry>,
@@ -96,14 +107,7 @@ pub async fn listen_onchain(
     rpc_url: String,
     shutdown: Arc<Notify>,
 ) -> Result<()> {
-    // Show scheme + host only — strip path to avoid leaking API keys (e.g. /v3/<key>)
-    let rpc_label = if let Some(idx) = rpc_url.find("://") {
-        let after_scheme = &rpc_url[idx + 3..];
-        let host_end = after_scheme.find('/').unwrap_or(after_scheme.len());
-        &rpc_url[..idx + 3 + host_end]
-    } else {
-        &rpc_url
-    };
+    let rpc_label = rpc_label(&rpc_url);
     let mut retry_count = 0u32;
 
     loop {
@@ -1,4 +1,5 @@
 pub mod aerodrome;
+pub mod chainlink;
 pub mod listener;
 pub mod uniswap;
 
@@ -62,6 +63,8 @@ pub struct OnchainConfig {
     pub aerodrome: Option<DexPoolsConfig>,
     #[serde(default)]
     pub uniswap: Option<DexPoolsConfig>,
+    #[serde(default)]
+    pub chainlink: Option<chainlink::ChainlinkConfig>,
 }
 
 impl OnchainConfig {
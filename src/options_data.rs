@@ -0,0 +1,373 @@
+//! Options market data: per-contract quotes, IV and greeks.
+//!
+//! Option chains are too large and too dynamic for the fixed symbol
+//! registry, so each `OptionDataCollection` keeps its own contract index.
+//! Contracts are assigned an `OptionId` the first time a feed sees them;
+//! the ring buffers behind those ids behave like `MarketDataCollection`'s.
+//! `OptionDataCollection::chain` groups the latest quotes into an
+//! `OptionsChain` by expiry and strike.
+
+use crate::contract_index::ContractIndex;
+use crate::market_data::{DataSink, Exchange, FeedItem};
+use crate::ring_buffer::RingBuffer;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+pub type OptionId = usize;
+
+pub const MAX_OPTION_CONTRACTS: usize = 8192;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// Parsed option contract name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionContract {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub strike: f64,
+    pub kind: OptionKind,
+}
+
+impl OptionContract {
+    /// Parse `UNDERLYING-EXPIRY-STRIKE-C|P`. Expiry may be `YYMMDD`
+    /// (Binance, Bybit: "BTC-250628-60000-C") or `DMMMYY`
+    /// (Deribit: "BTC-28JUN25-60000-C"). Bybit's trailing settle
+    /// currency ("...-C-USDT") is ignored.
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut parts = name.split('-');
+        let underlying = parts.next()?.to_uppercase();
+        let expiry_str = parts.next()?;
+        let strike = parts.next()?.replace('d', ".").parse::<f64>().ok()?;
+        let kind = match parts.next()? {
+            "C" | "c" => OptionKind::Call,
+            "P" | "p" => OptionKind::Put,
+            _ => return None,
+        };
+        let expiry = parse_expiry(expiry_str)?;
+        Some(Self { underlying, expiry, strike, kind })
+    }
+}
+
+/// `YYMMDD` ("250628") or `DMMMYY` ("28JUN25").
+pub fn parse_expiry(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(&s.to_uppercase(), "%d%b%y"))
+        .ok()
+}
+
+/// A slice of a chain to track: `UNDERLYING@EXPIRY[@MIN-MAX]`, e.g.
+/// "BTC@28JUN25" for a whole expiry or "BTC@250628@50000-70000" for a
+/// strike band. Either bound may be left empty ("BTC@28JUN25@-70000").
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainSpec {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub min_strike: Option<f64>,
+    pub max_strike: Option<f64>,
+}
+
+impl ChainSpec {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.split('@');
+        let underlying = parts.next().filter(|u| !u.is_empty())?.to_uppercase();
+        let expiry = parse_expiry(parts.next()?)?;
+        let (min_strike, max_strike) = match parts.next() {
+            None => (None, None),
+            Some(band) => {
+                let (lo, hi) = band.split_once('-')?;
+                let bound = |b: &str| -> Option<Option<f64>> {
+                    if b.is_empty() { Some(None) } else { b.parse().ok().map(Some) }
+                };
+                (bound(lo)?, bound(hi)?)
+            }
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { underlying, expiry, min_strike, max_strike })
+    }
+
+    pub fn matches(&self, contract: &OptionContract) -> bool {
+        contract.underlying == self.underlying
+            && contract.expiry == self.expiry
+            && self.min_strike.is_none_or(|lo| contract.strike >= lo)
+            && self.max_strike.is_none_or(|hi| contract.strike <= hi)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct OptionData {
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub bid_qty: Option<f64>,
+    pub ask_qty: Option<f64>,
+    pub mark_price: Option<f64>,
+    /// Implied vols as decimals (0.55 = 55%).
+    pub mark_iv: Option<f64>,
+    pub bid_iv: Option<f64>,
+    pub ask_iv: Option<f64>,
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub theta: Option<f64>,
+    pub vega: Option<f64>,
+    pub underlying_price: Option<f64>,
+    pub exchange_ts_raw: Option<DateTime<Utc>>,
+    pub exchange_ts: Option<DateTime<Utc>>,
+    pub received_ts: Option<DateTime<Utc>>,
+    pub received_instant: Option<Instant>,
+    pub feed_latency_ns: u64,
+}
+
+impl OptionData {
+    pub fn midquote(&self) -> Option<f64> {
+        Some((self.bid? + self.ask?) / 2.0)
+    }
+}
+
+impl FeedItem for OptionData {
+    fn exchange_ts_raw(&self) -> Option<DateTime<Utc>> {
+        self.exchange_ts_raw
+    }
+    fn set_feed_latency_ns(&mut self, ns: u64) {
+        self.feed_latency_ns = ns;
+    }
+}
+
+/// Contract name ↔ OptionId index. Ids are never reused.
+pub type OptionContracts = ContractIndex<MAX_OPTION_CONTRACTS>;
+
+pub struct OptionDataCollection {
+    contracts: Arc<OptionContracts>,
+    slots: Box<[OnceLock<Box<RingBuffer<OptionData>>>]>,
+}
+
+impl OptionDataCollection {
+    pub fn new() -> Self {
+        let mut slots = Vec::with_capacity(MAX_OPTION_CONTRACTS);
+        for _ in 0..MAX_OPTION_CONTRACTS {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            contracts: Arc::new(OptionContracts::new()),
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    /// Shared contract index; feeds use it to resolve contract names.
+    pub fn contracts(&self) -> &Arc<OptionContracts> {
+        &self.contracts
+    }
+
+    pub fn push(&self, id: &OptionId, mut item: OptionData) {
+        if item.exchange_ts.is_none() {
+            item.exchange_ts = item.exchange_ts_raw;
+        }
+        let ring = self.slots[*id].get_or_init(|| Box::new(RingBuffer::with_capacity(64)));
+        ring.push(item);
+    }
+
+    pub fn latest(&self, id: &OptionId) -> Option<OptionData> {
+        self.slots[*id].get()?.latest()
+    }
+
+    pub fn latest_by_name(&self, name: &str) -> Option<OptionData> {
+        self.latest(&self.contracts.lookup(name)?)
+    }
+
+    pub fn write_count(&self, id: &OptionId) -> u64 {
+        self.slots[*id].get().map(|r| r.write_count()).unwrap_or(0)
+    }
+
+    /// Latest quotes for every quoted contract on `underlying`.
+    pub fn chain(&self, underlying: &str) -> OptionsChain {
+        let underlying = underlying.to_uppercase();
+        let mut quotes: Vec<(OptionContract, OptionData)> = self
+            .contracts
+            .all()
+            .into_iter()
+            .filter_map(|(id, name)| {
+                let contract = OptionContract::parse(&name).filter(|c| c.underlying == underlying)?;
+                Some((contract, self.latest(&id)?))
+            })
+            .collect();
+        quotes.sort_by(|(a, _), (b, _)| a.expiry.cmp(&b.expiry).then(a.strike.total_cmp(&b.strike)));
+
+        let mut expiries: BTreeMap<NaiveDate, Vec<ChainRow>> = BTreeMap::new();
+        for (contract, data) in quotes {
+            let rows = expiries.entry(contract.expiry).or_default();
+            if rows.last().is_none_or(|r| r.strike != contract.strike) {
+                rows.push(ChainRow { strike: contract.strike, call: None, put: None });
+            }
+            let row = rows.last_mut().unwrap();
+            match contract.kind {
+                OptionKind::Call => row.call = Some(data),
+                OptionKind::Put => row.put = Some(data),
+            }
+        }
+        OptionsChain { underlying, expiries }
+    }
+}
+
+impl Default for OptionDataCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataSink<OptionData> for OptionDataCollection {
+    fn push(&self, id: &OptionId, item: OptionData) {
+        OptionDataCollection::push(self, id, item);
+    }
+}
+
+impl DataSink<OptionData> for Arc<OptionDataCollection> {
+    fn push(&self, id: &OptionId, item: OptionData) {
+        OptionDataCollection::push(self, id, item);
+    }
+}
+
+/// One strike of a chain, call and put side by side.
+#[derive(Debug, Clone, Default)]
+pub struct ChainRow {
+    pub strike: f64,
+    pub call: Option<OptionData>,
+    pub put: Option<OptionData>,
+}
+
+/// Snapshot of one underlying's option quotes, by expiry then ascending
+/// strike. Build it again to refresh.
+#[derive(Debug, Clone, Default)]
+pub struct OptionsChain {
+    pub underlying: String,
+    pub expiries: BTreeMap<NaiveDate, Vec<ChainRow>>,
+}
+
+impl OptionsChain {
+    pub fn rows(&self, expiry: NaiveDate) -> &[ChainRow] {
+        self.expiries.get(&expiry).map_or(&[], |r| r.as_slice())
+    }
+
+    /// Row whose strike is closest to `price` (e.g. the underlying, for ATM).
+    pub fn nearest_strike(&self, expiry: NaiveDate, price: f64) -> Option<&ChainRow> {
+        self.rows(expiry)
+            .iter()
+            .min_by(|a, b| (a.strike - price).abs().total_cmp(&(b.strike - price).abs()))
+    }
+}
+
+/// Options collections per venue.
+pub struct AllOptionData {
+    pub binance: Arc<OptionDataCollection>,
+    pub deribit: Arc<OptionDataCollection>,
+    pub bybit: Arc<OptionDataCollection>,
+}
+
+impl AllOptionData {
+    pub fn new() -> Self {
+        Self {
+            binance: Arc::new(OptionDataCollection::new()),
+            deribit: Arc::new(OptionDataCollection::new()),
+            bybit: Arc::new(OptionDataCollection::new()),
+        }
+    }
+
+    /// None for venues without an options feed.
+    pub fn get(&self, exchange: &Exchange) -> Option<&Arc<OptionDataCollection>> {
+        match exchange {
+            Exchange::Binance => Some(&self.binance),
+            Exchange::Deribit => Some(&self.deribit),
+            Exchange::Bybit => Some(&self.bybit),
+            _ => None,
+        }
+    }
+}
+
+impl Default for AllOptionData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_contract_names() {
+        let c = OptionContract::parse("BTC-250628-60000-C").unwrap();
+        assert_eq!(c.underlying, "BTC");
+        assert_eq!(c.expiry, NaiveDate::from_ymd_opt(2025, 6, 28).unwrap());
+        assert_eq!(c.strike, 60000.0);
+        assert_eq!(c.kind, OptionKind::Call);
+
+        let d = OptionContract::parse("ETH-28JUN25-3500-P").unwrap();
+        assert_eq!(d.expiry, NaiveDate::from_ymd_opt(2025, 6, 28).unwrap());
+        assert_eq!(d.kind, OptionKind::Put);
+
+        let b = OptionContract::parse("BTC-250628-60000-C-USDT").unwrap();
+        assert_eq!(b.strike, 60000.0);
+
+        assert!(OptionContract::parse("BTC-PERPETUAL").is_none());
+    }
+
+    #[test]
+    fn test_collection_push_latest() {
+        let coll = OptionDataCollection::new();
+        let id = coll.contracts().get_or_insert("ETH-250628-3000-C").unwrap();
+        coll.push(&id, OptionData { bid: Some(10.0), ask: Some(12.0), ..Default::default() });
+        assert_eq!(coll.latest_by_name("ETH-250628-3000-C").unwrap().midquote(), Some(11.0));
+        assert_eq!(coll.write_count(&id), 1);
+    }
+
+    #[test]
+    fn test_chain_spec() {
+        let spec = ChainSpec::parse("btc@28JUN25").unwrap();
+        assert_eq!(spec.underlying, "BTC");
+        assert_eq!(spec.expiry, NaiveDate::from_ymd_opt(2025, 6, 28).unwrap());
+        assert_eq!((spec.min_strike, spec.max_strike), (None, None));
+
+        let band = ChainSpec::parse("BTC@250628@50000-70000").unwrap();
+        assert!(band.matches(&OptionContract::parse("BTC-28JUN25-60000-C").unwrap()));
+        assert!(!band.matches(&OptionContract::parse("BTC-28JUN25-80000-C").unwrap()));
+        assert!(!band.matches(&OptionContract::parse("BTC-27JUN25-60000-C").unwrap()));
+        assert!(!band.matches(&OptionContract::parse("ETH-28JUN25-60000-C").unwrap()));
+
+        let upper = ChainSpec::parse("BTC@28JUN25@-70000").unwrap();
+        assert_eq!((upper.min_strike, upper.max_strike), (None, Some(70000.0)));
+
+        assert!(ChainSpec::parse("BTC-28JUN25-60000-C").is_none());
+        assert!(ChainSpec::parse("BTC@28JUN25@abc-1").is_none());
+    }
+
+    #[test]
+    fn test_chain_groups_by_expiry_and_strike() {
+        let coll = OptionDataCollection::new();
+        let quote = |name: &str, bid: f64| {
+            let id = coll.contracts().get_or_insert(name).unwrap();
+            coll.push(&id, OptionData { bid: Some(bid), ask: Some(bid + 1.0), ..Default::default() });
+        };
+        quote("BTC-28JUN25-70000-C", 1.0);
+        quote("BTC-28JUN25-60000-P", 2.0);
+        quote("BTC-28JUN25-60000-C", 3.0);
+        quote("BTC-26SEP25-60000-C", 4.0);
+        quote("ETH-28JUN25-3000-C", 5.0);
+        coll.contracts().get_or_insert("BTC-28JUN25-65000-C");
+
+        let chain = coll.chain("btc");
+        assert_eq!(chain.expiries.len(), 2);
+        let june = NaiveDate::from_ymd_opt(2025, 6, 28).unwrap();
+        let rows = chain.rows(june);
+        assert_eq!(rows.iter().map(|r| r.strike).collect::<Vec<_>>(), vec![60000.0, 70000.0]);
+        assert_eq!(rows[0].call.unwrap().bid, Some(3.0));
+        assert_eq!(rows[0].put.unwrap().bid, Some(2.0));
+        assert!(rows[1].put.is_none());
+        assert_eq!(chain.nearest_strike(june, 66_000.0).unwrap().strike, 70000.0);
+        assert!(chain.rows(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).is_empty());
+    }
+}
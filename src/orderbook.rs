@@ -1,5 +1,14 @@
+use chrono::{DateTime, Utc};
+use ordered_float::OrderedFloat;
 use std::cell::UnsafeCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::event_bus::{self, MarketEvent};
+use crate::market_data::{
+    BookCollection, BookDelta, BookLevel, BookSide, BookSnapshot, Exchange, InstrumentType, MAX_BOOK_LEVELS, size_imbalance, vwap_for_notional,
+};
+use crate::symbol_registry::{REGISTRY, SymbolId};
 
 /// `UnsafeCell<OrderBook>` wrapper that is `Send + Sync`.
 ///
@@ -17,6 +26,19 @@ impl SyncBook {
         Self(UnsafeCell::new(OrderBook::new()))
     }
 
+    pub fn from_book(book: OrderBook) -> Self {
+        Self(UnsafeCell::new(book))
+    }
+
+    pub fn with_tick_size(tick_size: f64) -> Self {
+        Self(UnsafeCell::new(OrderBook::with_tick_size(tick_size)))
+    }
+
+    /// Book holding at most `levels` per side; see `OrderBook::set_max_levels`.
+    pub fn with_max_levels(levels: usize) -> Self {
+        Self(UnsafeCell::new(OrderBook::with_max_levels(levels)))
+    }
+
     /// Get a mutable reference to the inner order book.
     ///
     /// # Safety
@@ -27,86 +49,711 @@ impl SyncBook {
     }
 }
 
-pub struct OrderBook {
-    pub bids: BTreeMap<ordered_float::OrderedFloat<f64>, f64>, // price -> size
-    pub asks: BTreeMap<ordered_float::OrderedFloat<f64>, f64>,
+/// CRC32 (IEEE, as zlib) of `data`: the book checksum Kraken v2, OKX and
+/// Bitget publish. OKX sends it as a signed i32; compare `as u32`.
+pub fn crc32(data: &[u8]) -> u32 {
+    crc32fast::hash(data)
 }
 
-impl OrderBook {
+/// Book levels exactly as the venue wrote them, for checksums computed over
+/// the original price and size strings (OKX, Kraken v2). `OrderBook` keeps
+/// tick keys and f64 sizes, which lose trailing zeros, so a feed that
+/// validates keeps one of these beside its book.
+#[derive(Default)]
+pub struct RawLevels {
+    bids: BTreeMap<OrderedFloat<f64>, (String, String)>,
+    asks: BTreeMap<OrderedFloat<f64>, (String, String)>,
+}
+
+impl RawLevels {
     pub fn new() -> Self {
-        Self {
+        Self::default()
+    }
+
+    /// Apply (price, size) updates; a zero or unparsable size removes the
+    /// level, an unparsable price is skipped.
+    pub fn update_bids<'a>(&mut self, updates: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        apply_raw_levels(&mut self.bids, updates);
+    }
+
+    pub fn update_asks<'a>(&mut self, updates: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        apply_raw_levels(&mut self.asks, updates);
+    }
+
+    /// Keep only the best `levels` bids and asks.
+    pub fn truncate(&mut self, levels: usize) {
+        while self.bids.len() > levels {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > levels {
+            self.asks.pop_last();
+        }
+    }
+
+    /// Every bid as (price, size), best first.
+    pub fn bids(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.bids.values().rev().map(|(p, q)| (p.as_str(), q.as_str()))
+    }
+
+    /// Every ask as (price, size), best first.
+    pub fn asks(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.asks.values().map(|(p, q)| (p.as_str(), q.as_str()))
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Whether the CRC32 of `format(self)` matches the venue's `expected`.
+    /// `format` renders the top levels the way the venue specifies (level
+    /// count, interleaving, separators); a mismatch means missed deltas and
+    /// the book should be rebuilt from a snapshot.
+    pub fn validate_checksum(&self, expected: u32, format: impl FnOnce(&RawLevels) -> String) -> bool {
+        crc32(format(self).as_bytes()) == expected
+    }
+}
+
+fn apply_raw_levels<'a>(
+    side: &mut BTreeMap<OrderedFloat<f64>, (String, String)>,
+    updates: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    for (price, size) in updates {
+        let Ok(key) = price.parse::<f64>() else { continue };
+        if size.parse::<f64>().is_ok_and(|s| s != 0.0) {
+            side.insert(OrderedFloat(key), (price.to_string(), size.to_string()));
+        } else {
+            side.remove(&OrderedFloat(key));
+        }
+    }
+}
+
+/// Levels per side feeds sum for `MarketData::imbalance`.
+pub const IMBALANCE_LEVELS: usize = 5;
+
+/// Per-side cap for books of venues that stream unbounded depth.
+pub const DEFAULT_MAX_LEVELS: usize = 200;
+
+/// Tick size for books built without one: fine enough to keep any quoted
+/// price, coarse enough for prices up to ~9e9.
+pub const DEFAULT_TICK_SIZE: f64 = 1e-9;
+
+/// Price <-> integer tick conversion.
+#[derive(Clone, Copy)]
+struct TickScale {
+    tick: f64,
+    /// `1 / tick` when that is a whole number, as for decimal ticks; dividing
+    /// by it turns ticks back into the exact quoted price.
+    per_unit: Option<f64>,
+}
+
+impl TickScale {
+    fn new(tick: f64) -> Self {
+        let inv = 1.0 / tick;
+        let per_unit = (tick < 1.0 && (inv - inv.round()).abs() < 1e-6).then(|| inv.round());
+        Self { tick, per_unit }
+    }
+
+    #[inline]
+    fn ticks(&self, price: f64) -> i64 {
+        match self.per_unit {
+            Some(n) => (price * n).round() as i64,
+            None => (price / self.tick).round() as i64,
+        }
+    }
+
+    #[inline]
+    fn price(&self, ticks: i64) -> f64 {
+        match self.per_unit {
+            Some(n) => ticks as f64 / n,
+            None => ticks as f64 * self.tick,
+        }
+    }
+}
+
+/// Levels are keyed by integer price ticks, so an update or delete always
+/// hits the level it names however the venue's price string rounds; prices
+/// are converted at the edges.
+pub struct OrderBook {
+    pub bids: BTreeMap<i64, f64>, // price ticks -> size
+    pub asks: BTreeMap<i64, f64>,
+    /// Venue sequence of the last update applied (Lighter `offset`, MEXC
+    /// `version`), set by the feed; None until then and after `clear`.
+    pub last_update_seq: Option<u64>,
+    /// Venue timestamp of the last update applied, or its receive time
+    /// where the venue sends none.
+    pub last_update_ts: Option<DateTime<Utc>>,
+    scale: TickScale,
+    /// Levels kept per side; 0 keeps all.
+    max_levels: usize,
+    /// Depth whose size the update methods keep summed for `imbalance`.
+    imbalance_levels: usize,
+    top_bid_qty: f64,
+    top_ask_qty: f64,
+}
+
+/// Tick size, level cap and imbalance depth of a new book, set together:
+/// `OrderBook::builder().tick_size(t).max_levels(n).build()`.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderBookBuilder {
+    tick_size: f64,
+    max_levels: usize,
+    imbalance_levels: usize,
+}
+
+impl Default for OrderBookBuilder {
+    fn default() -> Self {
+        Self { tick_size: DEFAULT_TICK_SIZE, max_levels: 0, imbalance_levels: IMBALANCE_LEVELS }
+    }
+}
+
+impl OrderBookBuilder {
+    /// The instrument's price increment; a non-positive one (tick unknown)
+    /// keeps `DEFAULT_TICK_SIZE`.
+    pub fn tick_size(mut self, tick_size: f64) -> Self {
+        if tick_size > 0.0 {
+            self.tick_size = tick_size;
+        }
+        self
+    }
+
+    /// Levels kept per side; see `OrderBook::set_max_levels`.
+    pub fn max_levels(mut self, levels: usize) -> Self {
+        self.max_levels = levels;
+        self
+    }
+
+    /// Depth whose `imbalance` is kept current; 0 leaves every call to sum
+    /// the levels.
+    pub fn imbalance_levels(mut self, levels: usize) -> Self {
+        self.imbalance_levels = levels;
+        self
+    }
+
+    pub fn build(self) -> OrderBook {
+        OrderBook {
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
+            last_update_seq: None,
+            last_update_ts: None,
+            scale: TickScale::new(self.tick_size),
+            max_levels: self.max_levels,
+            imbalance_levels: self.imbalance_levels,
+            top_bid_qty: 0.0,
+            top_ask_qty: 0.0,
         }
     }
 
-    pub fn update_bids(&mut self, updates: Vec<(String, f64)>) {
-        for (price, size) in updates {
-            if let Ok(price_f64) = price.parse::<f64>() {
-                let key = ordered_float::OrderedFloat(price_f64);
-                if size == 0.0 {
-                    // Size 0 means remove this level
-                    self.bids.remove(&key);
-                } else {
-                    self.bids.insert(key, size);
-                }
+    pub fn build_sync(self) -> SyncBook {
+        SyncBook::from_book(self.build())
+    }
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> OrderBookBuilder {
+        OrderBookBuilder::default()
+    }
+
+    /// Book for an instrument quoted in multiples of `tick_size`.
+    pub fn with_tick_size(tick_size: f64) -> Self {
+        Self::builder().tick_size(tick_size).build()
+    }
+
+    /// Book keeping `imbalance(levels)` current as it updates; 0 leaves
+    /// every `imbalance` call to sum the levels.
+    pub fn with_imbalance_levels(levels: usize) -> Self {
+        Self::builder().imbalance_levels(levels).build()
+    }
+
+    /// Book holding at most `levels` per side; see `set_max_levels`.
+    pub fn with_max_levels(levels: usize) -> Self {
+        Self::builder().max_levels(levels).build()
+    }
+
+    pub fn tick_size(&self) -> f64 {
+        self.scale.tick
+    }
+
+    /// Keep only the best `levels` bids and asks, dropping the farthest as
+    /// updates insert past the cap; 0 keeps every level. Far levels the
+    /// venue later exposes by deleting nearer ones stay missing until the
+    /// next snapshot.
+    pub fn set_max_levels(&mut self, levels: usize) {
+        self.max_levels = levels;
+        self.prune_bids();
+        self.prune_asks();
+        // A cap below the tracked depth drops levels from the sums.
+        self.refresh_top_bids(i64::MAX);
+        self.refresh_top_asks(i64::MIN);
+    }
+
+    fn prune_bids(&mut self) {
+        if self.max_levels > 0 {
+            while self.bids.len() > self.max_levels {
+                self.bids.pop_first();
             }
         }
     }
-    pub fn update_asks(&mut self, updates: Vec<(String, f64)>) {
-        for (price, size) in updates {
-            if let Ok(price_f64) = price.parse::<f64>() {
-                let key = ordered_float::OrderedFloat(price_f64);
-                if size == 0.0 {
-                    // Size 0 means remove this level
-                    self.asks.remove(&key);
-                } else {
-                    self.asks.insert(key, size);
-                }
+
+    fn prune_asks(&mut self) {
+        if self.max_levels > 0 {
+            while self.asks.len() > self.max_levels {
+                self.asks.pop_last();
             }
         }
     }
 
+    #[inline]
+    fn level(&self, (&ticks, &size): (&i64, &f64)) -> (f64, f64) {
+        (self.scale.price(ticks), size)
+    }
+
+    /// Apply (price string, size) updates; unparsable prices are skipped.
+    /// Prefer `update_bids_iter` when the venue sends numbers.
+    pub fn update_bids(&mut self, updates: Vec<(String, f64)>) {
+        self.update_bids_iter(updates.iter().filter_map(|(p, s)| Some((p.parse().ok()?, *s))));
+    }
+    pub fn update_asks(&mut self, updates: Vec<(String, f64)>) {
+        self.update_asks_iter(updates.iter().filter_map(|(p, s)| Some((p.parse().ok()?, *s))));
+    }
+
+    /// Re-sum the tracked bids if the best level an update touched is
+    /// within them. A change below the Nth bid leaves the top N alone;
+    /// `i64::MAX` always re-sums.
+    fn refresh_top_bids(&mut self, touched: i64) {
+        let n = self.imbalance_levels;
+        if n == 0 {
+            return;
+        }
+        let floor = self.bids.keys().rev().nth(n - 1).copied().unwrap_or(i64::MIN);
+        if touched >= floor {
+            self.top_bid_qty = self.bids.values().rev().take(n).sum();
+        }
+    }
+
+    /// As `refresh_top_bids`; `i64::MIN` always re-sums.
+    fn refresh_top_asks(&mut self, touched: i64) {
+        let n = self.imbalance_levels;
+        if n == 0 {
+            return;
+        }
+        let ceiling = self.asks.keys().nth(n - 1).copied().unwrap_or(i64::MAX);
+        if touched <= ceiling {
+            self.top_ask_qty = self.asks.values().take(n).sum();
+        }
+    }
+
+    /// `(bid size - ask size) / (bid size + ask size)` over the top `levels`
+    /// per side, in [-1, 1]; positive when bids are deeper. O(1) at the
+    /// tracked depth. None for an empty book.
+    pub fn imbalance(&self, levels: usize) -> Option<f64> {
+        let (bid_qty, ask_qty) = if levels == self.imbalance_levels {
+            (self.top_bid_qty, self.top_ask_qty)
+        } else {
+            (self.bids.values().rev().take(levels).sum(), self.asks.values().take(levels).sum())
+        };
+        size_imbalance(bid_qty, ask_qty)
+    }
+
     pub fn best_bid(&self) -> Option<(f64, f64)> {
         // BTreeMap is sorted, get highest bid
-        self.bids
-            .iter()
-            .next_back()
-            .map(|(price, &size)| (price.0, size))
+        self.bids.iter().next_back().map(|l| self.level(l))
     }
 
     pub fn best_ask(&self) -> Option<(f64, f64)> {
         // Get lowest ask
-        self.asks
-            .iter()
-            .next()
-            .map(|(price, &size)| (price.0, size))
+        self.asks.iter().next().map(|l| self.level(l))
+    }
+
+    /// `i`th best bid as (price, size); `bid_at(0)` is `best_bid`.
+    pub fn bid_at(&self, i: usize) -> Option<(f64, f64)> {
+        self.bids.iter().rev().nth(i).map(|l| self.level(l))
+    }
+
+    /// `i`th best ask as (price, size); `ask_at(0)` is `best_ask`.
+    pub fn ask_at(&self, i: usize) -> Option<(f64, f64)> {
+        self.asks.iter().nth(i).map(|l| self.level(l))
+    }
+
+    /// Every bid as (price, size), best first, without allocating.
+    pub fn bid_levels(&self) -> impl DoubleEndedIterator<Item = (f64, f64)> + '_ {
+        self.bids.iter().rev().map(|l| self.level(l))
+    }
+
+    /// Every ask as (price, size), best first.
+    pub fn ask_levels(&self) -> impl DoubleEndedIterator<Item = (f64, f64)> + '_ {
+        self.asks.iter().map(|l| self.level(l))
+    }
+
+    /// Best `n` (price, size) levels per side, best first: (bids, asks).
+    pub fn depth(&self, n: usize) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
+        (self.bid_levels().take(n).collect(), self.ask_levels().take(n).collect())
+    }
+
+    /// Average price paid buying `notional` quote units up the asks. None
+    /// if the book can't fill it.
+    pub fn vwap_buy(&self, notional: f64) -> Option<f64> {
+        vwap_for_notional(self.asks.iter().map(|l| self.level(l)), notional)
+    }
+
+    /// Average price received selling `notional` quote units into the bids.
+    pub fn vwap_sell(&self, notional: f64) -> Option<f64> {
+        vwap_for_notional(self.bids.iter().rev().map(|l| self.level(l)), notional)
     }
 
     pub fn update_bids_f64(&mut self, updates: &[(f64, f64)]) {
-        for &(price, size) in updates {
-            let key = ordered_float::OrderedFloat(price);
+        self.update_bids_iter(updates.iter().copied());
+    }
+
+    pub fn update_asks_f64(&mut self, updates: &[(f64, f64)]) {
+        self.update_asks_iter(updates.iter().copied());
+    }
+
+    /// Apply (price, size) updates straight off a decoded message, without
+    /// collecting them first. Size 0 removes the level.
+    pub fn update_bids_iter(&mut self, updates: impl IntoIterator<Item = (f64, f64)>) {
+        let mut touched = i64::MIN;
+        for (price, size) in updates {
+            let key = self.scale.ticks(price);
             if size == 0.0 {
                 self.bids.remove(&key);
             } else {
                 self.bids.insert(key, size);
             }
+            touched = touched.max(key);
         }
+        self.prune_bids();
+        self.refresh_top_bids(touched);
     }
 
-    pub fn update_asks_f64(&mut self, updates: &[(f64, f64)]) {
-        for &(price, size) in updates {
-            let key = ordered_float::OrderedFloat(price);
+    pub fn update_asks_iter(&mut self, updates: impl IntoIterator<Item = (f64, f64)>) {
+        let mut touched = i64::MAX;
+        for (price, size) in updates {
+            let key = self.scale.ticks(price);
             if size == 0.0 {
                 self.asks.remove(&key);
             } else {
                 self.asks.insert(key, size);
             }
+            touched = touched.min(key);
         }
+        self.prune_asks();
+        self.refresh_top_asks(touched);
+    }
+
+    /// Record the venue sequence and time of the update just applied.
+    pub fn mark_update(&mut self, seq: Option<u64>, ts: Option<DateTime<Utc>>) {
+        self.last_update_seq = seq;
+        self.last_update_ts = ts;
+    }
+
+    /// Time since the last update, measured against `now`. None before one.
+    pub fn age(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        self.last_update_ts.map(|ts| now - ts)
     }
 
     pub fn clear(&mut self) {
         self.bids.clear();
         self.asks.clear();
+        self.last_update_seq = None;
+        self.last_update_ts = None;
+        self.top_bid_qty = 0.0;
+        self.top_ask_qty = 0.0;
+    }
+
+    /// Best `levels` per side (at most `MAX_BOOK_LEVELS`), best first.
+    pub fn snapshot(&self, levels: usize) -> BookSnapshot {
+        let levels = levels.min(MAX_BOOK_LEVELS);
+        let mut snap = BookSnapshot::default();
+        for (slot, l) in snap.bids.iter_mut().zip(self.bids.iter().rev().take(levels)) {
+            let (price, qty) = self.level(l);
+            *slot = BookLevel { price, qty };
+            snap.bid_count += 1;
+        }
+        for (slot, l) in snap.asks.iter_mut().zip(self.asks.iter().take(levels)) {
+            let (price, qty) = self.level(l);
+            *slot = BookLevel { price, qty };
+            snap.ask_count += 1;
+        }
+        snap
+    }
+}
+
+/// A full book shared by the feed that writes it and any readers.
+pub type BookHandle = Arc<Mutex<OrderBook>>;
+
+/// Full books of the depth-based feeds, by venue and registry id. Feeds
+/// register each book once when built and keep writing through the handle;
+/// hold the lock only briefly, as the feed waits on it for every update.
+#[derive(Default)]
+pub struct LiveBooks {
+    books: RwLock<HashMap<(Exchange, SymbolId), BookHandle>>,
+}
+
+impl LiveBooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share `book` as `symbol`'s on `exchange`; symbols the registry
+    /// doesn't know are skipped.
+    pub fn register(&self, exchange: Exchange, symbol: &str, itype: &InstrumentType, book: &BookHandle) {
+        if let Some(id) = REGISTRY.lookup(symbol, itype) {
+            self.books.write().unwrap().insert((exchange, *id), Arc::clone(book));
+        }
+    }
+
+    pub fn get(&self, exchange: &Exchange, id: &SymbolId) -> Option<BookHandle> {
+        self.books.read().unwrap().get(&(*exchange, *id)).cloned()
+    }
+
+    /// Registry ids with a book on `exchange`.
+    pub fn symbols(&self, exchange: &Exchange) -> Vec<SymbolId> {
+        let mut ids: Vec<SymbolId> =
+            self.books.read().unwrap().keys().filter(|(ex, _)| ex == exchange).map(|(_, id)| *id).collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// Publish the levels just applied to one side of `symbol`'s book as
+/// `BookDelta` events, if the bus carries them. Levels the book then pruned
+/// past its `max_levels` are not reported; a mirror should apply the same
+/// cap.
+pub fn publish_deltas(
+    exchange: Exchange,
+    symbol: &str,
+    itype: &InstrumentType,
+    side: BookSide,
+    levels: impl IntoIterator<Item = (f64, f64)>,
+) {
+    if !event_bus::book_deltas_active() {
+        return;
+    }
+    let Some(id) = REGISTRY.lookup(symbol, itype) else {
+        return;
+    };
+    for (price, new_size) in levels {
+        event_bus::publish(MarketEvent::BookDelta { exchange, id: *id, data: BookDelta { side, price, new_size } });
+    }
+}
+
+/// Announce that `symbol`'s book was cleared, if the bus carries deltas.
+pub fn publish_reset(exchange: Exchange, symbol: &str, itype: &InstrumentType) {
+    if event_bus::book_deltas_active() {
+        if let Some(id) = REGISTRY.lookup(symbol, itype) {
+            event_bus::publish(MarketEvent::BookReset { exchange, id: *id });
+        }
+    }
+}
+
+/// Publishes a feed's internal books as top-N snapshots.
+pub struct DepthPublisher {
+    exchange: Exchange,
+    books: Arc<BookCollection>,
+    levels: usize,
+}
+
+impl DepthPublisher {
+    /// `levels` is capped at `MAX_BOOK_LEVELS`.
+    pub fn new(exchange: Exchange, books: Arc<BookCollection>, levels: usize) -> Self {
+        Self { exchange, books, levels: levels.min(MAX_BOOK_LEVELS) }
+    }
+
+    /// Push `book`'s top levels under `symbol`'s registry id, and onto the
+    /// event bus if installed; unknown symbols are skipped.
+    pub fn publish(&self, symbol: &str, itype: &InstrumentType, book: &OrderBook) {
+        if let Some(id) = REGISTRY.lookup(symbol, itype) {
+            let snap = book.snapshot(self.levels);
+            self.books.push(id, snap);
+            if event_bus::is_active() {
+                event_bus::publish(MarketEvent::Depth { exchange: self.exchange, id: *id, data: Box::new(snap) });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_top_levels() {
+        let mut book = OrderBook::new();
+        book.update_bids_f64(&[(99.0, 1.0), (100.0, 2.0), (98.0, 3.0)]);
+        book.update_asks_f64(&[(101.0, 4.0), (102.0, 5.0)]);
+
+        let snap = book.snapshot(2);
+        let bids: Vec<(f64, f64)> = snap.bids().iter().map(|l| (l.price, l.qty)).collect();
+        let asks: Vec<(f64, f64)> = snap.asks().iter().map(|l| (l.price, l.qty)).collect();
+        assert_eq!(bids, vec![(100.0, 2.0), (99.0, 1.0)]);
+        assert_eq!(asks, vec![(101.0, 4.0), (102.0, 5.0)]);
+        assert_eq!(book.snapshot(100).bid_count, 3);
+    }
+
+    #[test]
+    fn test_depth_and_level_accessors() {
+        let mut book = OrderBook::new();
+        book.update_bids_f64(&[(99.0, 1.0), (100.0, 2.0), (98.0, 3.0)]);
+        book.update_asks_f64(&[(101.0, 4.0), (102.0, 5.0)]);
+
+        let (bids, asks) = book.depth(2);
+        assert_eq!(bids, vec![(100.0, 2.0), (99.0, 1.0)]);
+        assert_eq!(asks, vec![(101.0, 4.0), (102.0, 5.0)]);
+        assert_eq!(book.depth(10).0.len(), 3);
+        assert_eq!(book.bid_at(0), book.best_bid());
+        assert_eq!(book.bid_at(2), Some((98.0, 3.0)));
+        assert_eq!(book.ask_at(1), Some((102.0, 5.0)));
+        assert_eq!(book.ask_at(2), None);
+        assert_eq!(book.vwap_buy(914.0), Some(914.0 / 9.0));
+        assert_eq!(book.vwap_sell(100.0), Some(100.0));
+        assert_eq!(book.vwap_buy(2000.0), None);
+    }
+
+    #[test]
+    fn test_tick_keys() {
+        let mut book = OrderBook::with_tick_size(0.01);
+        book.update_bids_f64(&[(0.1 + 0.2, 1.0), (0.29, 2.0)]);
+        assert_eq!(book.bids.len(), 2);
+        // 0.1 + 0.2 != 0.3 as f64, but both are tick 30.
+        book.update_bids(vec![("0.30".to_string(), 0.0)]);
+        assert_eq!(book.best_bid(), Some((0.29, 2.0)));
+
+        let mut book = OrderBook::with_tick_size(5.0);
+        book.update_asks_f64(&[(64_005.0, 1.0), (64_010.0, 2.0)]);
+        assert_eq!(book.ask_at(1), Some((64_010.0, 2.0)));
+        assert_eq!(OrderBook::new().tick_size(), DEFAULT_TICK_SIZE);
+    }
+
+    #[test]
+    fn test_builder_combines_settings() {
+        let mut book = OrderBook::builder().tick_size(0.5).max_levels(2).imbalance_levels(1).build();
+        assert_eq!(book.tick_size(), 0.5);
+        book.update_bids_f64(&[(99.0, 1.0), (100.0, 2.0), (98.5, 3.0)]);
+        book.update_asks_f64(&[(100.5, 2.0)]);
+        assert_eq!(book.bids.len(), 2);
+        assert_eq!(book.imbalance(1), Some(0.0));
+        assert_eq!(OrderBook::builder().tick_size(0.0).build().tick_size(), DEFAULT_TICK_SIZE);
+    }
+
+    #[test]
+    fn test_max_levels_prunes_far_side() {
+        let mut book = OrderBook::new();
+        book.set_max_levels(2);
+        book.update_bids_f64(&[(99.0, 1.0), (100.0, 2.0), (98.0, 3.0)]);
+        book.update_asks_f64(&[(103.0, 1.0), (101.0, 4.0), (102.0, 5.0)]);
+        assert_eq!(book.depth(10), (vec![(100.0, 2.0), (99.0, 1.0)], vec![(101.0, 4.0), (102.0, 5.0)]));
+
+        // A better bid pushes 99 out.
+        book.update_bids_f64(&[(100.5, 1.0)]);
+        assert_eq!(book.bid_at(1), Some((100.0, 2.0)));
+        assert_eq!(book.bids.len(), 2);
+        assert_eq!(book.imbalance(5), Some(-6.0 / 12.0));
+    }
+
+    #[test]
+    fn test_last_update_tracking() {
+        let mut book = OrderBook::new();
+        assert!(book.age(Utc::now()).is_none());
+        let ts = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        book.update_bids_f64(&[(100.0, 1.0)]);
+        book.mark_update(Some(42), Some(ts));
+        assert_eq!(book.last_update_seq, Some(42));
+        assert_eq!(book.age(ts + chrono::Duration::seconds(3)), Some(chrono::Duration::seconds(3)));
+        book.clear();
+        assert_eq!((book.last_update_seq, book.last_update_ts), (None, None));
+    }
+
+    #[test]
+    fn test_validate_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+
+        let mut raw = RawLevels::new();
+        raw.update_bids([("100.0", "2.50"), ("99.5", "1")]);
+        raw.update_asks([("101.0", "3")]);
+        // OKX style: bid:size:ask:size interleaved, best first, with the
+        // venue's own strings.
+        let okx = |r: &RawLevels| {
+            let (mut bids, mut asks) = (r.bids(), r.asks());
+            let mut parts = Vec::new();
+            loop {
+                let (bid, ask) = (bids.next(), asks.next());
+                if bid.is_none() && ask.is_none() {
+                    break;
+                }
+                for (p, q) in [bid, ask].into_iter().flatten() {
+                    parts.push(format!("{}:{}", p, q));
+                }
+            }
+            parts.join(":")
+        };
+        assert_eq!(okx(&raw), "100.0:2.50:101.0:3:99.5:1");
+        let expected = crc32(b"100.0:2.50:101.0:3:99.5:1");
+        assert!(raw.validate_checksum(expected, okx));
+        raw.update_asks([("101.0", "0")]);
+        assert!(!raw.validate_checksum(expected, okx));
+        raw.update_asks([("101.0", "3"), ("102.0", "1")]);
+        raw.truncate(1);
+        assert_eq!(okx(&raw), "100.0:2.50:101.0:3");
+    }
+
+    #[test]
+    fn test_imbalance_tracks_updates() {
+        let mut book = OrderBook::with_imbalance_levels(2);
+        assert_eq!(book.imbalance(2), None);
+        book.update_bids_f64(&[(100.0, 3.0), (99.0, 3.0), (98.0, 10.0)]);
+        book.update_asks_f64(&[(101.0, 1.0), (102.0, 1.0)]);
+        assert_eq!(book.imbalance(2), Some(0.5));
+        assert_eq!(book.imbalance(3), Some(14.0 / 18.0));
+
+        // Below the top two bids: no change.
+        book.update_bids_f64(&[(97.0, 50.0)]);
+        assert_eq!(book.imbalance(2), Some(0.5));
+        // Removing a top bid pulls 98 into the window.
+        book.update_bids(vec![("99".to_string(), 0.0)]);
+        assert_eq!(book.imbalance(2), Some(11.0 / 15.0));
+        book.update_asks_f64(&[(100.5, 12.0)]);
+        assert_eq!(book.imbalance(2), Some(0.0));
+
+        // Capping below the tracked depth re-sums what is left.
+        book.set_max_levels(1);
+        assert_eq!(book.imbalance(2), Some(-9.0 / 15.0));
+
+        book.clear();
+        assert_eq!(book.imbalance(2), None);
+    }
+
+    #[test]
+    fn test_level_iterators() {
+        let mut book = OrderBook::new();
+        book.update_bids_iter([(99.0, 1.0), (100.0, 2.0)]);
+        book.update_asks(vec![("101.5".to_string(), 3.0), ("bad".to_string(), 1.0)]);
+        book.update_asks_iter([(102.0, 4.0), (101.5, 0.0)]);
+
+        assert_eq!(book.bid_levels().collect::<Vec<_>>(), vec![(100.0, 2.0), (99.0, 1.0)]);
+        assert_eq!(book.bid_levels().next_back(), Some((99.0, 1.0)));
+        assert_eq!(book.ask_levels().collect::<Vec<_>>(), vec![(102.0, 4.0)]);
+        assert_eq!(book.imbalance(5), Some((3.0 - 4.0) / 7.0));
+    }
+
+    #[test]
+    fn test_live_books_share_handles() {
+        let live = LiveBooks::new();
+        let book: BookHandle = Arc::new(Mutex::new(OrderBook::with_max_levels(2)));
+        live.register(Exchange::Lighter, "ETH_USDT", &InstrumentType::Perp, &book);
+        live.register(Exchange::Lighter, "NOT_A_SYMBOL", &InstrumentType::Perp, &book);
+        let id = *REGISTRY.lookup("ETH_USDT", &InstrumentType::Perp).unwrap();
+
+        book.lock().unwrap().update_bids_f64(&[(100.0, 1.0), (99.0, 2.0), (98.0, 3.0)]);
+        let shared = live.get(&Exchange::Lighter, &id).unwrap();
+        assert_eq!(shared.lock().unwrap().bid_at(1), Some((99.0, 2.0)));
+        assert_eq!(shared.lock().unwrap().bids.len(), 2);
+        assert_eq!(live.symbols(&Exchange::Lighter), vec![id]);
+        assert!(live.get(&Exchange::Mexc, &id).is_none());
     }
 }
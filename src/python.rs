@@ -1,47 +1,56 @@
 use crate::analytics::{Analytics, QuoteSide, RangeStat, SnapshotField};
-use crate::app_config::{AppConfig, load_config, load_perp, load_spot};
+use crate::app_config::{AppConfig, load_config, load_deriv_stats, load_futures, load_klines, load_liquidations, load_options, load_perp, load_spot, load_trades, spawn_instrument_fetch};
 use crate::bar_manager::{BarManager, BarSymbol};
+use crate::candle_builder::{CandleConfig, run_candle_builder};
+use crate::candle_data::{Candle, CandleInterval, CandleStore};
+use crate::instruments::InstrumentStore;
+use crate::deriv_stats::{DerivStats, DerivStatsCollection};
+use crate::event_bus::{self, EventBusConfig};
+use crate::liquidation_data::{LiquidationCollection, LiquidationEvent};
+use crate::feed_manager::FeedRuntime;
+use crate::exchanges::get_fees;
+use crate::execution::{VenueSelectConfig, best_venue};
 use crate::fair_price::{
     FairPriceConfig, FairPriceEngine, FairPriceGroupConfig, FairPriceModel, FairPriceOutput,
     FairPriceOutputs, GroupMember, SigmaMode, run_fair_price_task,
 };
 use crate::vol_provider::VolProvider;
+use crate::interest::register_interest;
 use crate::historical_bars::{aggregate_bars, load_1m_bars_with_backfill};
-use crate::market_data::{AllMarketData, Exchange, InstrumentType, MarketDataCollection};
+use crate::market_data::{AllMarketData, BookCollection, BookLevel, ChangeCursor, Exchange, InstrumentType, MarketData, MarketDataCollection, UnknownExchange};
+use crate::options_data::{OptionData, OptionDataCollection};
+use crate::futures_data::{FuturesData, FuturesDataCollection};
 use crate::snapshot::{AllSnapshotData, SnapshotConfig, run_snapshot_task};
-use crate::symbol_registry::{SymbolId, seed_extra_bases, REGISTRY};
+use crate::stablecoin::{StablecoinConfig, StablecoinRates};
+use crate::symbol_registry::{self, SymbolId, seed_extra_bases, REGISTRY};
+use crate::trade_data::{AllTradeData, TradeSide};
+use crate::trade_flow::FlowStats;
 use crate::vol_engine::VolEngine;
 use crate::vol_params;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use numpy::PyArray1;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use std::sync::Arc;
 use std::sync::Once;
-use tokio::runtime::Runtime;
+use tokio::runtime::Handle;
 use tokio::sync::Notify;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::task::JoinHandle;
 
 static INIT_LOGGER: Once = Once::new();
 
 fn parse_exchange(exchange: &str) -> PyResult<Exchange> {
-    match exchange.to_lowercase().as_str() {
-        "binance" => Ok(Exchange::Binance),
-        "coinbase" => Ok(Exchange::Coinbase),
-        "bybit" => Ok(Exchange::Bybit),
-        "kraken" => Ok(Exchange::Kraken),
-        "lighter" => Ok(Exchange::Lighter),
-        "mexc" => Ok(Exchange::Mexc),
-        "extended" => Ok(Exchange::Extended),
-        "nado" => Ok(Exchange::Nado),
-        "okx" => Ok(Exchange::Okx),
-        "kucoin" => Ok(Exchange::Kucoin),
-        "bingx" => Ok(Exchange::Bingx),
-        "apex" => Ok(Exchange::Apex),
-        "hyperliquid" => Ok(Exchange::Hyperliquid),
-        "uniswap" => Ok(Exchange::Uniswap),
+    exchange.parse().map_err(|e: UnknownExchange| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+fn parse_instrument_type(instrument_type: &str) -> PyResult<InstrumentType> {
+    match instrument_type.to_lowercase().as_str() {
+        "spot" => Ok(InstrumentType::Spot),
+        "perp" => Ok(InstrumentType::Perp),
         _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
-            "Unknown exchange: {}",
-            exchange
+            "Invalid instrument type: {}. Must be 'spot' or 'perp'",
+            instrument_type
         ))),
     }
 }
@@ -93,32 +102,125 @@ impl PySymbolRegistry {
 
     /// Lookup a symbol and return its integer ID.
     fn lookup(&self, symbol: &str, instrument_type: &str) -> PyResult<Option<SymbolId>> {
-        let itype = match instrument_type.to_lowercase().as_str() {
-            "spot" => InstrumentType::Spot,
-            "perp" => InstrumentType::Perp,
-            _ => {
-                return Err(pyo3::exceptions::PyValueError::new_err(format!(
-                    "Invalid instrument type: {}. Must be 'spot' or 'perp'",
-                    instrument_type
-                )))
-            }
-        };
-
+        let itype = parse_instrument_type(instrument_type)?;
         Ok(REGISTRY.lookup(symbol, &itype).copied())
     }
 
     /// Get the canonical symbol name from an integer ID.
     fn get_symbol(&self, symbol_id: SymbolId) -> PyResult<Option<String>> {
-        if symbol_id >= crate::symbol_registry::MAX_SYMBOLS {
-            return Ok(None);
-        }
         Ok(REGISTRY.get_symbol(symbol_id).map(|s| s.to_string()))
     }
 }
 
+fn market_data_to_dict(py: Python, md: &MarketData) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("bid", md.bid)?;
+    dict.set_item("ask", md.ask)?;
+    dict.set_item("bid_qty", md.bid_qty)?;
+    dict.set_item("ask_qty", md.ask_qty)?;
+    dict.set_item("exchange_ts", md.exchange_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("received_ts", md.received_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("sequence", md.sequence())?;
+    dict.set_item("imbalance", md.imbalance)?;
+    dict.set_item("feed_latency_ns", md.feed_latency_ns)?;
+    Ok(dict.into())
+}
+
+fn option_data_to_dict(py: Python, od: &OptionData) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("bid", od.bid)?;
+    dict.set_item("ask", od.ask)?;
+    dict.set_item("bid_qty", od.bid_qty)?;
+    dict.set_item("ask_qty", od.ask_qty)?;
+    dict.set_item("mark_price", od.mark_price)?;
+    dict.set_item("mark_iv", od.mark_iv)?;
+    dict.set_item("bid_iv", od.bid_iv)?;
+    dict.set_item("ask_iv", od.ask_iv)?;
+    dict.set_item("delta", od.delta)?;
+    dict.set_item("gamma", od.gamma)?;
+    dict.set_item("theta", od.theta)?;
+    dict.set_item("vega", od.vega)?;
+    dict.set_item("underlying_price", od.underlying_price)?;
+    dict.set_item("exchange_ts", od.exchange_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("received_ts", od.received_ts.map(|ts| ts.timestamp_millis()))?;
+    Ok(dict.into())
+}
+
+fn futures_data_to_dict(py: Python, fd: &FuturesData) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("bid", fd.bid)?;
+    dict.set_item("ask", fd.ask)?;
+    dict.set_item("bid_qty", fd.bid_qty)?;
+    dict.set_item("ask_qty", fd.ask_qty)?;
+    dict.set_item("mark_price", fd.mark_price)?;
+    dict.set_item("index_price", fd.index_price)?;
+    dict.set_item("exchange_ts", fd.exchange_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("received_ts", fd.received_ts.map(|ts| ts.timestamp_millis()))?;
+    Ok(dict.into())
+}
+
+fn deriv_stats_to_dict(py: Python, ds: &DerivStats) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("funding_rate", ds.funding_rate)?;
+    dict.set_item("funding_interval_h", ds.funding_interval_h)?;
+    dict.set_item("next_funding_ts", ds.next_funding_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("open_interest", ds.open_interest)?;
+    dict.set_item("mark_price", ds.mark_price)?;
+    dict.set_item("oracle_price", ds.oracle_price)?;
+    dict.set_item("index_price", ds.index_price)?;
+    dict.set_item("premium", ds.premium)?;
+    dict.set_item("premium_index", ds.premium_index)?;
+    dict.set_item("exchange_ts", ds.exchange_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("received_ts", ds.received_ts.map(|ts| ts.timestamp_millis()))?;
+    Ok(dict.into())
+}
+
+fn liquidation_to_dict(py: Python, ev: &LiquidationEvent) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("price", ev.price)?;
+    dict.set_item("qty", ev.qty)?;
+    let side = match ev.side {
+        TradeSide::Buy => "buy",
+        TradeSide::Sell => "sell",
+        TradeSide::Unknown => "unknown",
+    };
+    dict.set_item("side", side)?;
+    dict.set_item("exchange_ts", ev.exchange_ts.map(|ts| ts.timestamp_millis()))?;
+    dict.set_item("received_ts", ev.received_ts.map(|ts| ts.timestamp_millis()))?;
+    Ok(dict.into())
+}
+
+fn flow_to_dict(py: Python, fs: &FlowStats) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("volume", fs.volume)?;
+    dict.set_item("notional", fs.notional)?;
+    dict.set_item("vwap", fs.vwap())?;
+    dict.set_item("buy_volume", fs.buy_volume)?;
+    dict.set_item("sell_volume", fs.sell_volume)?;
+    dict.set_item("ofi", fs.ofi())?;
+    dict.set_item("ofi_ratio", fs.ofi_ratio())?;
+    dict.set_item("trades", fs.trades)?;
+    Ok(dict.into())
+}
+
+/// Poll position for `PyMarketData.drain_changes`.
+#[pyclass]
+pub struct PyChangeCursor {
+    inner: ChangeCursor,
+}
+
+#[pymethods]
+impl PyChangeCursor {
+    #[new]
+    fn new() -> Self {
+        Self { inner: ChangeCursor::new() }
+    }
+}
+
 #[pyclass]
 pub struct PyMarketData {
     all_data: Arc<AllMarketData>,
+    trade_data: Arc<AllTradeData>,
 }
 
 #[pymethods]
@@ -127,37 +229,59 @@ impl PyMarketData {
     fn new() -> Self {
         Self {
             all_data: Arc::new(AllMarketData::new()),
+            trade_data: Arc::new(AllTradeData::new()),
         }
     }
 
     fn get_bid(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        Ok(collection.latest(&symbol_id).and_then(|md| md.bid))
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.bid))
     }
 
     fn get_ask(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        Ok(collection.latest(&symbol_id).and_then(|md| md.ask))
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.ask))
     }
 
     fn get_bid_qty(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        Ok(collection.latest(&symbol_id).and_then(|md| md.bid_qty))
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.bid_qty))
     }
 
     fn get_ask_qty(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        Ok(collection.latest(&symbol_id).and_then(|md| md.ask_qty))
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.ask_qty))
     }
 
     fn get_midquote(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        Ok(collection.get_midquote(&symbol_id))
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.midquote()))
+    }
+
+    /// Size-weighted mid of the top of book.
+    fn get_microprice(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.microprice()))
+    }
+
+    fn get_spread_bps(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
+        Ok(self.latest(exchange, symbol_id)?.and_then(|md| md.spread_bps()))
+    }
+
+    /// Microprice over the top `depth` levels of the published book. None
+    /// until the venue publishes depth (`depth_levels`) for the symbol.
+    #[pyo3(signature = (exchange, symbol_id, depth=5))]
+    fn get_weighted_mid(&self, exchange: &str, symbol_id: SymbolId, depth: usize) -> PyResult<Option<f64>> {
+        Ok(self.depth_books(exchange)?.get_weighted_mid(&symbol_id, depth))
+    }
+
+    /// Average price buying `notional` quote units up the published asks.
+    /// None until the venue publishes depth, or if the levels can't fill it.
+    fn get_vwap_buy(&self, exchange: &str, symbol_id: SymbolId, notional: f64) -> PyResult<Option<f64>> {
+        Ok(self.depth_books(exchange)?.get_vwap_buy(&symbol_id, notional))
+    }
+
+    /// Average price selling `notional` quote units into the published bids.
+    fn get_vwap_sell(&self, exchange: &str, symbol_id: SymbolId, notional: f64) -> PyResult<Option<f64>> {
+        Ok(self.depth_books(exchange)?.get_vwap_sell(&symbol_id, notional))
     }
 
     fn get_spread(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<f64>> {
-        let collection = self.get_collection(exchange)?;
-        if let Some(md) = collection.latest(&symbol_id) {
+        if let Some(md) = self.latest(exchange, symbol_id)? {
             if let (Some(bid), Some(ask)) = (md.bid, md.ask) {
                 return Ok(Some(ask - bid));
             }
@@ -173,42 +297,16 @@ impl PyMarketData {
         max_exchange_age_ns: Option<i64>,
     ) -> PyResult<Option<f64>> {
         let now = Utc::now();
-        let quotes: Vec<Option<(f64, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>> = self
+        let max_age = |ns: Option<i64>| ns.map(|ns| std::time::Duration::from_nanos(ns.max(0) as u64));
+        let (max_recv, max_exch) = (max_age(max_receive_age_ns), max_age(max_exchange_age_ns));
+        let (sum, count) = self
             .all_data
             .iter()
-            .map(|(_, data)| data.get_midquote_w_timestamps(&symbol_id))
-            .collect();
-
-        let (sum, count) = quotes
-            .iter()
-            .flatten()
-            .filter(|(_, received_ts, exchange_ts)| {
-                if let Some(max_recv) = max_receive_age_ns {
-                    match received_ts {
-                        Some(ts) => {
-                            let age_ns = (now - *ts).num_nanoseconds().unwrap_or(i64::MAX);
-                            if age_ns >= max_recv {
-                                return false;
-                            }
-                        }
-                        None => return false,
-                    }
-                }
-                if let Some(max_exch) = max_exchange_age_ns {
-                    match exchange_ts {
-                        Some(ts) => {
-                            let age_ns = (now - *ts).num_nanoseconds().unwrap_or(i64::MAX);
-                            if age_ns >= max_exch {
-                                return false;
-                            }
-                        }
-                        None => return false,
-                    }
-                }
-                true
-            })
-            .map(|(val, _, _)| val)
-            .fold((0.0, 0), |(sum, count), &val| (sum + val, count + 1));
+            .filter_map(|(_, data)| data.latest(&symbol_id))
+            .filter(|md| max_recv.is_none_or(|max| !md.is_stale_at(max, now)))
+            .filter(|md| max_exch.is_none_or(|max| md.exchange_age(now).is_some_and(|age| age < max)))
+            .filter_map(|md| md.midquote())
+            .fold((0.0, 0), |(sum, count), mid| (sum + mid, count + 1));
 
         if count > 0 {
             Ok(Some(sum / count as f64))
@@ -223,30 +321,378 @@ impl PyMarketData {
         symbol_id: SymbolId,
         py: Python,
     ) -> PyResult<Option<PyObject>> {
+        match self.latest(exchange, symbol_id)? {
+            Some(md) => Ok(Some(market_data_to_dict(py, &md)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Up to `n` most recent ticks (default: the whole ring buffer), oldest
+    /// first, as a dict of NumPy arrays: `received_ts_ns` / `exchange_ts_ns`
+    /// (int64, 0 if missing) and `bid`, `ask`, `bid_qty`, `ask_qty`
+    /// (float64, NaN if missing).
+    #[pyo3(signature = (exchange, symbol_id, n=None))]
+    fn get_history(&self, py: Python, exchange: &str, symbol_id: SymbolId, n: Option<usize>) -> PyResult<PyObject> {
+        let ex = parse_exchange(exchange)?;
+        register_interest(ex, symbol_id);
+        let collection = self.all_data.get_collection(&ex);
+        let mut ticks: Vec<MarketData> = Vec::new();
+        if let Some(ring) = collection.get_buffer(&symbol_id) {
+            let n = n.unwrap_or(ring.capacity());
+            ticks.reserve(n.min(ring.capacity()));
+            ring.scan_last_n(n, |md| ticks.push(*md));
+            ticks.reverse();
+        }
+        let ts_ns = |ts: Option<DateTime<Utc>>| ts.and_then(|t| t.timestamp_nanos_opt()).unwrap_or(0);
+        let col = |f: fn(&MarketData) -> Option<f64>| -> Vec<f64> {
+            ticks.iter().map(|md| f(md).unwrap_or(f64::NAN)).collect()
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("received_ts_ns", PyArray1::from_vec_bound(py, ticks.iter().map(|md| ts_ns(md.received_ts)).collect()))?;
+        dict.set_item("exchange_ts_ns", PyArray1::from_vec_bound(py, ticks.iter().map(|md| ts_ns(md.exchange_ts)).collect()))?;
+        dict.set_item("bid", PyArray1::from_vec_bound(py, col(|md| md.bid)))?;
+        dict.set_item("ask", PyArray1::from_vec_bound(py, col(|md| md.ask)))?;
+        dict.set_item("bid_qty", PyArray1::from_vec_bound(py, col(|md| md.bid_qty)))?;
+        dict.set_item("ask_qty", PyArray1::from_vec_bound(py, col(|md| md.ask_qty)))?;
+        Ok(dict.into())
+    }
+
+    /// {symbol_id: market data dict} for symbols updated on `exchange`
+    /// since `cursor` last drained it. Use one cursor per exchange.
+    fn drain_changes(&self, py: Python, exchange: &str, cursor: &mut PyChangeCursor) -> PyResult<PyObject> {
         let collection = self.get_collection(exchange)?;
+        let dict = PyDict::new_bound(py);
+        for (id, md) in collection.drain_changes(&mut cursor.inner) {
+            dict.set_item(id, market_data_to_dict(py, &md)?)?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Awaitable resolving to the market data dict of the next tick written
+    /// for `symbol_id` on `exchange`, or None if none arrives within
+    /// `timeout_s`. Waits on the event bus, which the feed manager enables
+    /// for spot and perp feeds.
+    #[pyo3(signature = (exchange, symbol_id, timeout_s=None))]
+    fn wait_for_update<'py>(
+        &self,
+        py: Python<'py>,
+        exchange: &str,
+        symbol_id: SymbolId,
+        timeout_s: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ex = parse_exchange(exchange)?;
+        let collection = Arc::clone(self.all_data.get_collection(&ex));
+        if symbol_id >= collection.capacity() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "symbol id {} out of range; feeds were created for {}",
+                symbol_id,
+                collection.capacity()
+            )));
+        }
+        let mut events = event_bus::subscribe().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("wait_for_update needs feeds started by a FeedManager")
+        })?;
+        register_interest(ex, symbol_id);
+        let deadline = timeout_s.map(|s| tokio::time::Instant::now() + std::time::Duration::from_secs_f64(s.max(0.0)));
+        let seen = collection.write_count(&symbol_id);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            loop {
+                let next = match deadline {
+                    Some(d) => match tokio::time::timeout_at(d, events.recv()).await {
+                        Ok(next) => next,
+                        Err(_) => return Ok(None),
+                    },
+                    None => events.recv().await,
+                };
+                let md = match next {
+                    Ok(ev) => match ev.as_bbo() {
+                        Some((e, id, md)) if e == ex && id == symbol_id => Some(md),
+                        _ => None,
+                    },
+                    // The skipped events may have held the tick.
+                    Err(RecvError::Lagged(_)) if collection.write_count(&symbol_id) != seen => collection.latest(&symbol_id),
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => return Ok(None),
+                };
+                if let Some(md) = md {
+                    return Python::with_gil(|py| market_data_to_dict(py, &md).map(Some));
+                }
+            }
+        })
+    }
+
+    /// USD value of one unit of a tracked stablecoin ("USDT", "USDC"), from
+    /// the Kraken/Coinbase `<COIN>_USD` spot mids.
+    fn get_usd_rate(&self, ccy: &str) -> PyResult<Option<f64>> {
+        let rates = StablecoinRates::new(&StablecoinConfig::default());
+        Ok(rates.usd_rate(&self.all_data, ccy, std::time::Instant::now()))
+    }
+
+    /// (rejected, flagged) tick counts for `exchange`, or None when no
+    /// sanitizer is configured.
+    fn get_sanitizer_counts(&self, exchange: &str) -> PyResult<Option<(u64, u64)>> {
+        let ex = parse_exchange(exchange)?;
+        Ok(self.all_data.sanitizer().map(|s| (s.rejected(&ex), s.flagged(&ex))))
+    }
+
+    /// Update count, last receive time (ms) and ticks/sec over the last
+    /// minute for a symbol. Doesn't register interest.
+    fn get_symbol_stats(&self, py: Python, exchange: &str, symbol_id: SymbolId) -> PyResult<PyObject> {
+        let ex = parse_exchange(exchange)?;
+        let stats = self.all_data.get_collection(&ex).stats(&symbol_id);
+        let dict = PyDict::new_bound(py);
+        dict.set_item("updates", stats.updates)?;
+        dict.set_item("last_update", stats.last_update.map(|ts| ts.timestamp_millis()))?;
+        dict.set_item("updates_per_sec", stats.updates_per_sec)?;
+        Ok(dict.into())
+    }
+
+    /// WebSocket ping round trip of a feed ("binance_spot") in ms: last,
+    /// smoothed and min. None until the feed's first Pong.
+    fn get_ping_rtt(&self, py: Python, feed_name: &str) -> PyResult<Option<PyObject>> {
+        let Some(rtt) = crate::exchanges::connection::ping_rtt(feed_name) else {
+            return Ok(None);
+        };
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1e3;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("last_ms", ms(rtt.last))?;
+        dict.set_item("smoothed_ms", ms(rtt.smoothed))?;
+        dict.set_item("min_ms", ms(rtt.min))?;
+        dict.set_item("samples", rtt.samples)?;
+        Ok(Some(dict.into()))
+    }
+
+    /// Last connection state of a feed: "connected", "disconnected",
+    /// "circuit_open", "degraded" or "stopped". None before it first connects.
+    fn get_feed_status(&self, feed_name: &str) -> Option<&'static str> {
+        crate::exchanges::connection::feed_status(feed_name).map(|s| s.as_str())
+    }
+
+    /// Midquote with the symbol's quote currency converted to `reference`.
+    #[pyo3(signature = (exchange, symbol_id, reference="USD"))]
+    fn get_midquote_normalized(&self, exchange: &str, symbol_id: SymbolId, reference: &str) -> PyResult<Option<f64>> {
+        let ex = parse_exchange(exchange)?;
+        let rates = StablecoinRates::new(&StablecoinConfig {
+            reference: reference.to_string(),
+            ..Default::default()
+        });
+        Ok(rates.normalized_latest(&self.all_data, &ex, &symbol_id).and_then(|md| md.midquote()))
+    }
+
+    /// Maker and taker fees in bps for `symbol` ("BTC_USDT") on `exchange`,
+    /// with any `fees` config applied. None for venues without a schedule.
+    fn get_fees(&self, py: Python, exchange: &str, symbol: &str, instrument_type: &str) -> PyResult<Option<PyObject>> {
+        let ex = parse_exchange(exchange)?;
+        let itype = parse_instrument_type(instrument_type)?;
+        let Some(fees) = get_fees(&ex) else {
+            return Ok(None);
+        };
+        let schedule = match itype {
+            InstrumentType::Spot => fees.get_spot_fees(symbol),
+            _ => fees.get_perp_fees(symbol),
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("maker_bps", schedule.maker_fees_bps)?;
+        dict.set_item("taker_bps", schedule.taker_fees_bps)?;
+        Ok(Some(dict.into()))
+    }
+
+    /// Venue with the best all-in price for crossing `side` ("buy"/"sell")
+    /// with `qty` on a config-style symbol, or None if no venue qualifies.
+    #[pyo3(signature = (symbol, side, qty, max_age_ms=2000, stale_penalty_bps_per_s=1.0))]
+    fn best_venue(
+        &self,
+        py: Python,
+        symbol: &str,
+        side: &str,
+        qty: f64,
+        max_age_ms: u64,
+        stale_penalty_bps_per_s: f64,
+    ) -> PyResult<Option<PyObject>> {
+        let side = match side.to_lowercase().as_str() {
+            "buy" => TradeSide::Buy,
+            "sell" => TradeSide::Sell,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "side must be 'buy' or 'sell', got '{}'",
+                    side
+                )));
+            }
+        };
+        let cfg = VenueSelectConfig {
+            max_age: std::time::Duration::from_millis(max_age_ms),
+            stale_penalty_bps_per_s,
+        };
+        let Some(est) = best_venue(&self.all_data, symbol, side, qty, &cfg) else {
+            return Ok(None);
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("exchange", est.exchange.as_str())?;
+        dict.set_item("price", est.price)?;
+        dict.set_item("all_in_price", est.all_in_price)?;
+        dict.set_item("taker_fee_bps", est.taker_fee_bps)?;
+        dict.set_item("age_ms", est.age.as_secs_f64() * 1000.0)?;
+        dict.set_item("covers_qty", est.covers_qty)?;
+        Ok(Some(dict.into()))
+    }
+
+    /// Latest quote, IVs and greeks for an option contract
+    /// ("BTC-28JUN25-60000-C").
+    fn get_option(&self, py: Python, exchange: &str, contract: &str) -> PyResult<Option<PyObject>> {
+        match self.get_options(exchange)?.latest_by_name(contract) {
+            Some(od) => Ok(Some(option_data_to_dict(py, &od)?)),
+            None => Ok(None),
+        }
+    }
 
-        if let Some(md) = collection.latest(&symbol_id) {
+    /// Chain for `underlying` as a list of `{expiry, strike, call, put}`
+    /// rows sorted by expiry then strike; `call`/`put` are option dicts or
+    /// None. `expiry` ("YYYY-MM-DD") keeps a single expiry.
+    #[pyo3(signature = (exchange, underlying, expiry=None))]
+    fn get_option_chain(&self, py: Python, exchange: &str, underlying: &str, expiry: Option<&str>) -> PyResult<PyObject> {
+        let only = expiry
+            .map(|e| {
+                NaiveDate::parse_from_str(e, "%Y-%m-%d").map_err(|_| {
+                    pyo3::exceptions::PyValueError::new_err(format!("expiry must be YYYY-MM-DD, got '{}'", e))
+                })
+            })
+            .transpose()?;
+        let chain = self.get_options(exchange)?.chain(underlying);
+        let list = PyList::empty_bound(py);
+        for (exp, rows) in &chain.expiries {
+            if only.is_some_and(|d| d != *exp) {
+                continue;
+            }
+            for row in rows {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("expiry", exp.format("%Y-%m-%d").to_string())?;
+                dict.set_item("strike", row.strike)?;
+                dict.set_item("call", row.call.as_ref().map(|od| option_data_to_dict(py, od)).transpose()?)?;
+                dict.set_item("put", row.put.as_ref().map(|od| option_data_to_dict(py, od)).transpose()?)?;
+                list.append(dict)?;
+            }
+        }
+        Ok(list.into())
+    }
+
+    /// Latest quote for a dated future ("BTC_USDT_260327").
+    fn get_future(&self, py: Python, exchange: &str, symbol: &str) -> PyResult<Option<PyObject>> {
+        match self.get_futures(exchange)?.latest_by_name(symbol) {
+            Some(fd) => Ok(Some(futures_data_to_dict(py, &fd)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Basis per expiry on `base` ("BTC"), nearest first, as `{symbol,
+    /// expiry, days, price, reference, basis, annualized_basis}` dicts.
+    /// `reference` defaults to the index on each quote (Deribit) or the
+    /// venue's `BASE_USDT` spot mid (Binance).
+    #[pyo3(signature = (exchange, base, reference=None))]
+    fn get_term_structure(&self, py: Python, exchange: &str, base: &str, reference: Option<f64>) -> PyResult<PyObject> {
+        let ex = parse_exchange(exchange)?;
+        let futures = self.get_futures(exchange)?;
+        let reference = match (reference, ex) {
+            (None, Exchange::Binance) => REGISTRY
+                .lookup(&format!("{}_USDT", base.to_uppercase()), &InstrumentType::Spot)
+                .and_then(|id| self.all_data.get_collection(&ex).latest(id))
+                .and_then(|md| md.midquote()),
+            (reference, _) => reference,
+        };
+        let list = PyList::empty_bound(py);
+        for p in futures.term_structure(base, reference, Utc::now()).points {
             let dict = PyDict::new_bound(py);
-            dict.set_item("bid", md.bid)?;
-            dict.set_item("ask", md.ask)?;
-            dict.set_item("bid_qty", md.bid_qty)?;
-            dict.set_item("ask_qty", md.ask_qty)?;
-            dict.set_item(
-                "exchange_ts",
-                md.exchange_ts.map(|ts| ts.timestamp_millis()),
-            )?;
-            dict.set_item(
-                "received_ts",
-                md.received_ts.map(|ts| ts.timestamp_millis()),
-            )?;
-            Ok(Some(dict.into()))
-        } else {
-            Ok(None)
+            dict.set_item("symbol", p.symbol)?;
+            dict.set_item("expiry", p.expiry.format("%Y-%m-%d").to_string())?;
+            dict.set_item("days", p.days)?;
+            dict.set_item("price", p.price)?;
+            dict.set_item("reference", p.reference)?;
+            dict.set_item("basis", p.basis)?;
+            dict.set_item("annualized_basis", p.annualized_basis)?;
+            list.append(dict)?;
         }
+        Ok(list.into())
+    }
+
+    /// Latest funding / open interest / mark stats for a perp, as a dict.
+    fn get_deriv_stats(&self, py: Python, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<PyObject>> {
+        match self.get_deriv_collection(exchange)?.latest(&symbol_id) {
+            Some(ds) => Ok(Some(deriv_stats_to_dict(py, &ds)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Latest open interest in base units, with its exchange timestamp (ms).
+    fn get_open_interest(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<(f64, Option<i64>)>> {
+        let latest = self.get_deriv_collection(exchange)?.latest_field(&symbol_id, |ds| ds.open_interest);
+        Ok(latest.map(|(oi, ts)| (oi, ts.map(|ts| ts.timestamp_millis()))))
+    }
+
+    /// Latest mark price, with its exchange timestamp (ms).
+    fn get_mark_price(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<(f64, Option<i64>)>> {
+        let latest = self.get_deriv_collection(exchange)?.latest_field(&symbol_id, |ds| ds.mark_price);
+        Ok(latest.map(|(px, ts)| (px, ts.map(|ts| ts.timestamp_millis()))))
+    }
+
+    /// Latest premium index (decimal), with its exchange timestamp (ms).
+    fn get_premium_index(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<(f64, Option<i64>)>> {
+        let latest = self.get_deriv_collection(exchange)?.latest_field(&symbol_id, |ds| ds.premium_index);
+        Ok(latest.map(|(p, ts)| (p, ts.map(|ts| ts.timestamp_millis()))))
+    }
+
+    /// Up to `n` most recent liquidations for a perp, oldest first, as
+    /// `{price, qty, side, exchange_ts, received_ts}` dicts. `side` is the
+    /// liquidation order's side ("sell" closes a long).
+    #[pyo3(signature = (exchange, symbol_id, n=100))]
+    fn get_liquidations(&self, py: Python, exchange: &str, symbol_id: SymbolId, n: usize) -> PyResult<PyObject> {
+        let list = PyList::empty_bound(py);
+        for ev in self.get_liquidation_collection(exchange)?.recent(&symbol_id, n) {
+            list.append(liquidation_to_dict(py, &ev)?)?;
+        }
+        Ok(list.into())
+    }
+
+    /// Rolling traded volume over the last `window_s` seconds as
+    /// `{volume, notional, vwap, buy_volume, sell_volume, trades}`. None
+    /// unless `trade_flow` lists the window and the symbol has traded.
+    fn get_trade_flow(&self, py: Python, exchange: &str, symbol_id: SymbolId, window_s: u64) -> PyResult<Option<PyObject>> {
+        let ex = parse_exchange(exchange)?;
+        let Some(flow) = self.trade_data.get_collection(&ex).flow() else {
+            return Ok(None);
+        };
+        flow.stats(&symbol_id, std::time::Duration::from_secs(window_s))
+            .map(|fs| flow_to_dict(py, &fs))
+            .transpose()
+    }
+
+    /// Signed trade volume (buy minus sell) over one of the `trade_flow`
+    /// windows.
+    fn get_ofi(&self, exchange: &str, symbol_id: SymbolId, window_s: u64) -> PyResult<Option<f64>> {
+        let ex = parse_exchange(exchange)?;
+        Ok(self.trade_data.get_ofi(&ex, &symbol_id, std::time::Duration::from_secs(window_s)))
+    }
+
+    /// Latest top-N book as `{bids, asks}`, each a list of (price, qty)
+    /// best first. None until the venue publishes depth for the symbol.
+    fn get_depth(&self, py: Python, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<PyObject>> {
+        let Some(snap) = self.depth_books(exchange)?.latest(&symbol_id) else {
+            return Ok(None);
+        };
+        let levels = |side: &[BookLevel]| side.iter().map(|l| (l.price, l.qty)).collect::<Vec<_>>();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("bids", levels(snap.bids()))?;
+        dict.set_item("asks", levels(snap.asks()))?;
+        Ok(Some(dict.into()))
     }
 }
 
 impl PyMarketData {
+    fn depth_books(&self, exchange: &str) -> PyResult<&Arc<BookCollection>> {
+        let ex = parse_exchange(exchange)?;
+        self.all_data.depth.get(&ex).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("No depth data for exchange: {}", exchange))
+        })
+    }
+
     fn get_collection(
         &self,
         exchange: &str,
@@ -255,6 +701,41 @@ impl PyMarketData {
         Ok(self.all_data.get_collection(&ex))
     }
 
+    /// Latest quote, registering interest for lazy subscriptions.
+    fn latest(&self, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<MarketData>> {
+        let ex = parse_exchange(exchange)?;
+        register_interest(ex, symbol_id);
+        Ok(self.all_data.get_collection(&ex).latest(&symbol_id))
+    }
+
+    fn get_options(&self, exchange: &str) -> PyResult<&Arc<OptionDataCollection>> {
+        let ex = parse_exchange(exchange)?;
+        self.all_data.options.get(&ex).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("No options data for exchange: {}", exchange))
+        })
+    }
+
+    fn get_futures(&self, exchange: &str) -> PyResult<&Arc<FuturesDataCollection>> {
+        let ex = parse_exchange(exchange)?;
+        self.all_data.futures.get(&ex).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("No futures data for exchange: {}", exchange))
+        })
+    }
+
+    fn get_deriv_collection(&self, exchange: &str) -> PyResult<&Arc<DerivStatsCollection>> {
+        let ex = parse_exchange(exchange)?;
+        self.all_data.deriv_stats.get(&ex).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("No derivatives stats for exchange: {}", exchange))
+        })
+    }
+
+    fn get_liquidation_collection(&self, exchange: &str) -> PyResult<&Arc<LiquidationCollection>> {
+        let ex = parse_exchange(exchange)?;
+        self.all_data.liquidations.get(&ex).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("No liquidation data for exchange: {}", exchange))
+        })
+    }
+
     fn get_arc(&self) -> Arc<AllMarketData> {
         Arc::clone(&self.all_data)
     }
@@ -656,6 +1137,57 @@ fn fair_price_to_dict(py: Python, fp: &FairPriceOutput) -> PyResult<PyObject> {
     Ok(dict.into())
 }
 
+#[pyclass]
+pub struct PyCandles {
+    store: Arc<CandleStore>,
+}
+
+fn parse_interval(interval: &str) -> PyResult<CandleInterval> {
+    CandleInterval::from_str(interval).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown interval: {}. Valid: 1s, 1m, 5m, 15m, 1h",
+            interval
+        ))
+    })
+}
+
+fn candle_to_dict(py: Python, c: &Candle) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("open_time_ms", c.open_time_ms)?;
+    dict.set_item("open", c.open)?;
+    dict.set_item("high", c.high)?;
+    dict.set_item("low", c.low)?;
+    dict.set_item("close", c.close)?;
+    dict.set_item("volume", c.volume)?;
+    dict.set_item("ticks", c.ticks)?;
+    Ok(dict.into())
+}
+
+#[pymethods]
+impl PyCandles {
+    /// Last `n` closed candles (oldest first) as a list of dicts.
+    #[pyo3(signature = (exchange, symbol_id, interval, n=100))]
+    fn get(&self, py: Python, exchange: &str, symbol_id: SymbolId, interval: &str, n: usize) -> PyResult<PyObject> {
+        let exchange = parse_exchange(exchange)?;
+        let interval = parse_interval(interval)?;
+        let list = PyList::empty_bound(py);
+        for c in self.store.last_n(&exchange, &symbol_id, interval, n) {
+            list.append(candle_to_dict(py, &c)?)?;
+        }
+        Ok(list.into())
+    }
+
+    /// Most recent closed candle.
+    fn latest(&self, py: Python, exchange: &str, symbol_id: SymbolId, interval: &str) -> PyResult<Option<PyObject>> {
+        let exchange = parse_exchange(exchange)?;
+        let interval = parse_interval(interval)?;
+        self.store
+            .latest(&exchange, &symbol_id, interval)
+            .map(|c| candle_to_dict(py, &c))
+            .transpose()
+    }
+}
+
 #[pyclass]
 pub struct PyVolEngine {
     engine: Arc<VolEngine>,
@@ -920,7 +1452,7 @@ impl PyAppConfig {
             }
         }
 
-        let config = AppConfig { spot, perp, sample_interval_ms: 10, onchain: None, vol_models: None, fair_price: Default::default(), clock_correction: Default::default(), trades: std::collections::HashMap::new() };
+        let config = AppConfig { spot, perp, extra_bases: Vec::new(), quote_currencies: Vec::new(), symbol_id_map: None, sample_interval_ms: 10, onchain: None, vol_models: None, fair_price: Default::default(), clock_correction: Default::default(), stale_ttl: Default::default(), stablecoins: None, maintenance: Default::default(), dead_man: None, sanitizer: None, lazy_subscriptions: None, connection: Default::default(), event_bus: None, quote_conversion: std::collections::HashMap::new(), fees: std::collections::HashMap::new(), coinbase_spot_mode: Default::default(), mexc_perp_mode: Default::default(), kraken_spot_mode: Default::default(), binance_book_mode: Default::default(), okx_book_mode: Default::default(), binance_depth_levels: 5, depth_levels: std::collections::HashMap::new(), trades: std::collections::HashMap::new(), trade_flow: None, options: std::collections::HashMap::new(), futures: std::collections::HashMap::new(), indices: std::collections::HashMap::new(), deriv_stats: std::collections::HashMap::new(), liquidations: std::collections::HashMap::new(), oi_poll_interval_s: 30, klines: None, candles: None, synthetic: None, instruments: false, check_listings: false, collector: None, fix: None, udp_sink: None };
         seed_extra_bases(config.base_assets());
         Ok(Self { config })
     }
@@ -946,27 +1478,38 @@ impl PyAppConfig {
 
 #[pyclass]
 pub struct PyFeedManager {
-    runtime: Runtime,
+    runtime: FeedRuntime,
     market_data: Py<PyMarketData>,
     shutdown: Arc<Notify>,
     perp_handles: Vec<JoinHandle<()>>,
     spot_handles: Vec<JoinHandle<()>>,
+    option_handles: Vec<JoinHandle<()>>,
+    futures_handles: Vec<JoinHandle<()>>,
+    deriv_stats_handles: Vec<JoinHandle<()>>,
+    liquidation_handles: Vec<JoinHandle<()>>,
+    trade_handles: Vec<JoinHandle<()>>,
+    kline_handles: Vec<JoinHandle<()>>,
+    instrument_handles: Vec<JoinHandle<()>>,
+    klines: Option<Py<PyCandles>>,
+    instruments: Option<Arc<InstrumentStore>>,
     analytics: Option<Py<PyAnalytics>>,
     snapshot_handle: Option<JoinHandle<()>>,
     fair_price_handle: Option<JoinHandle<()>>,
     fair_price: Option<Py<PyFairPrice>>,
     vol_engine: Option<Py<PyVolEngine>>,
     bar_maintenance_handle: Option<JoinHandle<()>>,
+    candle_handle: Option<JoinHandle<()>>,
+    candles: Option<Py<PyCandles>>,
 }
 
-#[pymethods]
 impl PyFeedManager {
-    #[new]
-    fn new(py: Python) -> PyResult<Self> {
-        let runtime = Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+    /// Manager whose feeds run on an existing tokio runtime, for Rust
+    /// applications embedding the extension.
+    pub fn with_handle(py: Python, handle: Handle) -> PyResult<Self> {
+        Self::with_runtime(py, FeedRuntime::Shared(handle))
+    }
 
+    fn with_runtime(py: Python, runtime: FeedRuntime) -> PyResult<Self> {
         let market_data = Py::new(py, PyMarketData::new())?;
         let shutdown = Arc::new(Notify::new());
 
@@ -976,16 +1519,66 @@ impl PyFeedManager {
             shutdown,
             perp_handles: Vec::new(),
             spot_handles: Vec::new(),
+            option_handles: Vec::new(),
+            futures_handles: Vec::new(),
+            deriv_stats_handles: Vec::new(),
+            liquidation_handles: Vec::new(),
+            trade_handles: Vec::new(),
+            kline_handles: Vec::new(),
+            instrument_handles: Vec::new(),
+            klines: None,
+            instruments: None,
             analytics: None,
             snapshot_handle: None,
             fair_price_handle: None,
             fair_price: None,
             vol_engine: None,
             bar_maintenance_handle: None,
+            candle_handle: None,
+            candles: None,
         })
     }
+}
+
+/// Build the symbol registry from `cfg` unless one is already in use, so
+/// the extension needs no symbols.yaml or `SYMBOL_CONFIG`. Collections
+/// too small for it need `max_symbols` on the FeedManager.
+fn use_config_registry(cfg: &AppConfig) -> PyResult<()> {
+    symbol_registry::install_from_app_config(cfg).map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Enable the event bus `wait_for_update` awaits, with the config's
+/// settings if it has any. Must run before the feeds start.
+fn use_event_bus(cfg: &AppConfig) {
+    event_bus::install(cfg.event_bus.as_ref().unwrap_or(&EventBusConfig::default()));
+}
+
+#[pymethods]
+impl PyFeedManager {
+    /// `worker_threads` caps the feed runtime's thread count (default: one
+    /// per core). `max_symbols` makes room for configs with more registry
+    /// ids than the default 1000.
+    #[new]
+    #[pyo3(signature = (worker_threads=None, max_symbols=None))]
+    fn new(py: Python, worker_threads: Option<usize>, max_symbols: Option<usize>) -> PyResult<Self> {
+        if let Some(n) = max_symbols {
+            if !symbol_registry::reserve_symbols(n) {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "max_symbols {} exceeds the {} slots already allocated",
+                    n,
+                    symbol_registry::symbol_capacity()
+                )));
+            }
+        }
+        let runtime = FeedRuntime::owned(worker_threads).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
+        })?;
+        Self::with_runtime(py, runtime)
+    }
 
     fn start_spot_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        use_event_bus(&config.config);
         let market_data_ref = self.market_data.borrow(py);
         let all_data = market_data_ref.get_arc();
 
@@ -1009,6 +1602,8 @@ impl PyFeedManager {
     }
 
     fn start_perp_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        use_event_bus(&config.config);
         let market_data_ref = self.market_data.borrow(py);
         let all_data = market_data_ref.get_arc();
 
@@ -1026,6 +1621,157 @@ impl PyFeedManager {
         Ok(())
     }
 
+    fn start_option_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let market_data_ref = self.market_data.borrow(py);
+        let all_data = market_data_ref.get_arc();
+
+        self.runtime
+            .block_on(async {
+                load_options(&mut self.option_handles, &config.config, &all_data.options, &self.shutdown)
+            })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start option feeds: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn start_futures_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let market_data_ref = self.market_data.borrow(py);
+        let all_data = market_data_ref.get_arc();
+
+        self.runtime
+            .block_on(async {
+                load_futures(&mut self.futures_handles, &config.config, &all_data.futures, &self.shutdown)
+            })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start futures feeds: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn start_deriv_stats_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let market_data_ref = self.market_data.borrow(py);
+        let all_data = market_data_ref.get_arc();
+
+        self.runtime
+            .block_on(async {
+                load_deriv_stats(&mut self.deriv_stats_handles, &config.config, &all_data.deriv_stats, &self.shutdown)
+            })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start derivatives stats feeds: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn start_liquidation_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let market_data_ref = self.market_data.borrow(py);
+        let all_data = market_data_ref.get_arc();
+
+        self.runtime
+            .block_on(async {
+                load_liquidations(&mut self.liquidation_handles, &config.config, &all_data.liquidations, &self.shutdown)
+            })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start liquidation feeds: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    /// Start the `trades` feeds, with the `trade_flow` windows if set.
+    fn start_trade_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let market_data_ref = self.market_data.borrow(py);
+        let trade_data = Arc::clone(&market_data_ref.trade_data);
+
+        self.runtime
+            .block_on(async {
+                load_trades(&mut self.trade_handles, &config.config, &trade_data, &self.shutdown)
+            })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start trade feeds: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    /// Start the venue kline feeds from the config's `klines` section. The
+    /// bars are read through `get_klines()`.
+    fn start_kline_feeds(&mut self, py: Python, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        if self.klines.is_some() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "Kline feeds already started",
+            ));
+        }
+        let store = self
+            .runtime
+            .block_on(async { load_klines(&mut self.kline_handles, &config.config, &self.shutdown) })
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to start kline feeds: {}",
+                    e
+                ))
+            })?;
+        if let Some(store) = store {
+            self.klines = Some(Py::new(py, PyCandles { store })?);
+        }
+        Ok(())
+    }
+
+    /// Closed venue klines, or None before `start_kline_feeds`.
+    fn get_klines(&self, py: Python) -> PyResult<Option<Py<PyCandles>>> {
+        Ok(self.klines.as_ref().map(|c| c.clone_ref(py)))
+    }
+
+    /// Fetch instrument metadata for the config's spot and perp symbols in
+    /// the background; read it with `get_instrument`.
+    fn start_instrument_fetch(&mut self, config: &PyAppConfig) -> PyResult<()> {
+        use_config_registry(&config.config)?;
+        let _guard = self.runtime.handle().enter();
+        self.instruments = Some(spawn_instrument_fetch(&mut self.instrument_handles, &config.config, &self.shutdown));
+        Ok(())
+    }
+
+    /// Tick size, lot size, min notional and contract multiplier for a
+    /// symbol, or None until its venue's listing has been fetched.
+    fn get_instrument(&self, py: Python, exchange: &str, symbol_id: SymbolId) -> PyResult<Option<PyObject>> {
+        let ex = parse_exchange(exchange)?;
+        let Some(info) = self.instruments.as_ref().and_then(|s| s.get(&ex, &symbol_id)) else {
+            return Ok(None);
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("symbol", info.symbol)?;
+        dict.set_item("native", info.native)?;
+        dict.set_item("tick_size", info.tick_size)?;
+        dict.set_item("lot_size", info.lot_size)?;
+        dict.set_item("min_notional", info.min_notional)?;
+        dict.set_item("contract_multiplier", info.contract_multiplier)?;
+        Ok(Some(dict.into()))
+    }
+
     #[pyo3(signature = (interval_ms=100, buffer_capacity=65536))]
     fn start_snapshots(
         &mut self,
@@ -1217,9 +1963,83 @@ impl PyFeedManager {
         Ok(self.vol_engine.as_ref().map(|ve| ve.clone_ref(py)))
     }
 
+    /// Start building OHLCV candles from quote mids.
+    ///
+    /// Args:
+    ///     symbols: list of (exchange, symbol_id)
+    ///     intervals: any of "1s", "1m", "5m", "15m", "1h"
+    ///     close_grace_ms: wall-clock delay before an idle bucket closes
+    ///     max_gap_fill: max consecutive flat candles emitted for empty buckets
+    ///     capacity: closed candles kept per series (power of two)
+    #[pyo3(signature = (symbols, intervals=vec!["1s".to_string(), "1m".to_string(), "5m".to_string()], close_grace_ms=500, max_gap_fill=300, capacity=1024))]
+    fn start_candles(
+        &mut self,
+        py: Python,
+        symbols: Vec<(String, SymbolId)>,
+        intervals: Vec<String>,
+        close_grace_ms: i64,
+        max_gap_fill: usize,
+        capacity: usize,
+    ) -> PyResult<()> {
+        if self.candle_handle.is_some() {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "Candles already started",
+            ));
+        }
+        if !capacity.is_power_of_two() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "capacity must be a power of 2",
+            ));
+        }
+        let series = symbols
+            .iter()
+            .map(|(ex, id)| Ok((parse_exchange(ex)?, *id)))
+            .collect::<PyResult<Vec<_>>>()?;
+        let cfg = CandleConfig {
+            intervals,
+            close_grace_ms,
+            max_gap_fill,
+            capacity,
+            ..Default::default()
+        };
+        cfg.parsed_intervals()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+        let tick_data = self.market_data.borrow(py).get_arc();
+        let store = Arc::new(CandleStore::new(capacity));
+        let shutdown = Arc::clone(&self.shutdown);
+        let store_clone = Arc::clone(&store);
+        let handle = self.runtime.spawn(async move {
+            if let Err(e) = run_candle_builder(cfg, series, tick_data, None, store_clone, shutdown).await {
+                log::error!("Candle builder exited with error {:?}", e);
+            }
+        });
+
+        self.candle_handle = Some(handle);
+        self.candles = Some(Py::new(py, PyCandles { store })?);
+        Ok(())
+    }
+
+    fn get_candles(&self, py: Python) -> PyResult<Option<Py<PyCandles>>> {
+        Ok(self.candles.as_ref().map(|c| c.clone_ref(py)))
+    }
+
     fn shutdown(&self) {
         self.shutdown.notify_waiters();
     }
+
+    /// Awaitable that resolves on the next `shutdown()`, so an asyncio
+    /// application can run the started feeds as one of its tasks. The
+    /// feeds themselves keep running on the manager's runtime.
+    fn run<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        // Registered now, so a shutdown before the loop polls still counts.
+        let mut notified = Box::pin(Arc::clone(&self.shutdown).notified_owned());
+        notified.as_mut().enable();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            notified.await;
+            Ok(())
+        })
+    }
 }
 
 #[pymodule]
@@ -1227,10 +2047,12 @@ fn crypto_feeds(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(init_logging, m)?)?;
     m.add_class::<PySymbolRegistry>()?;
     m.add_class::<PyMarketData>()?;
+    m.add_class::<PyChangeCursor>()?;
     m.add_class::<PyAppConfig>()?;
     m.add_class::<PyFeedManager>()?;
     m.add_class::<PyAnalytics>()?;
     m.add_class::<PyFairPrice>()?;
     m.add_class::<PyVolEngine>()?;
+    m.add_class::<PyCandles>()?;
     Ok(())
 }
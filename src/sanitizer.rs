@@ -0,0 +1,254 @@
+//! Spike and fat-finger filtering for quotes.
+//!
+//! Each incoming mid is compared with that (venue, symbol)'s own EWMA and
+//! with the median of the other venues' latest mids. A tick beyond either
+//! threshold is suspect: it is dropped (`reject`) or stored with a flag
+//! (`flag`), and counted either way. Suspect ticks do not move the
+//! references, so after `max_consecutive` suspects in a row the move is
+//! taken as a genuine level change and adopted.
+//!
+//! The consensus check assumes venues quote the same thing; keep its
+//! threshold above any structural basis (e.g. USDT vs USDC quotes).
+
+use crate::market_data::Exchange;
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering};
+
+const N_VENUES: usize = Exchange::ALL.len();
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SanitizeMode {
+    /// Drop suspect ticks.
+    Reject,
+    /// Store suspect ticks and mark them (`QuoteSanitizer::is_flagged`).
+    Flag,
+}
+
+fn default_mode() -> SanitizeMode {
+    SanitizeMode::Reject
+}
+fn default_ewma_alpha() -> f64 {
+    0.05
+}
+fn default_min_consensus_venues() -> usize {
+    2
+}
+fn default_consensus_max_age_ms() -> i64 {
+    5_000
+}
+fn default_max_consecutive() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SanitizerConfig {
+    #[serde(default = "default_mode")]
+    pub mode: SanitizeMode,
+    /// Max deviation from the venue's own EWMA mid, in percent. None disables.
+    #[serde(default)]
+    pub max_ewma_deviation_pct: Option<f64>,
+    #[serde(default = "default_ewma_alpha")]
+    pub ewma_alpha: f64,
+    /// Max deviation from the other venues' median mid, in percent. None disables.
+    #[serde(default)]
+    pub max_consensus_deviation_pct: Option<f64>,
+    /// Other venues needed before the consensus check applies.
+    #[serde(default = "default_min_consensus_venues")]
+    pub min_consensus_venues: usize,
+    /// Other venues' mids older than this are left out of the consensus.
+    #[serde(default = "default_consensus_max_age_ms")]
+    pub consensus_max_age_ms: i64,
+    #[serde(default = "default_max_consecutive")]
+    pub max_consecutive: u32,
+}
+
+impl Default for SanitizerConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_mode(),
+            max_ewma_deviation_pct: Some(5.0),
+            ewma_alpha: default_ewma_alpha(),
+            max_consensus_deviation_pct: None,
+            min_consensus_venues: default_min_consensus_venues(),
+            consensus_max_age_ms: default_consensus_max_age_ms(),
+            max_consecutive: default_max_consecutive(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Accept,
+    Flag,
+    Reject,
+}
+
+/// Shared by every venue's collection. Each (venue, symbol) entry has one
+/// writer (its feed); other venues only read its consensus mid.
+pub struct QuoteSanitizer {
+    cfg: SanitizerConfig,
+    /// Entries per venue.
+    n_symbols: usize,
+    ewma_bits: Box<[AtomicU64]>,
+    /// Last accepted mid and its receive time (unix ms), for consensus.
+    mid_bits: Box<[AtomicU64]>,
+    mid_ts_ms: Box<[AtomicI64]>,
+    consecutive: Box<[AtomicU32]>,
+    flagged: Box<[AtomicBool]>,
+    rejected_count: [AtomicU64; N_VENUES],
+    flagged_count: [AtomicU64; N_VENUES],
+}
+
+impl QuoteSanitizer {
+    pub fn new(cfg: SanitizerConfig) -> Self {
+        let n_symbols = symbol_capacity();
+        let n = N_VENUES * n_symbols;
+        Self {
+            cfg,
+            n_symbols,
+            ewma_bits: (0..n).map(|_| AtomicU64::new(0)).collect(),
+            mid_bits: (0..n).map(|_| AtomicU64::new(0)).collect(),
+            mid_ts_ms: (0..n).map(|_| AtomicI64::new(0)).collect(),
+            consecutive: (0..n).map(|_| AtomicU32::new(0)).collect(),
+            flagged: (0..n).map(|_| AtomicBool::new(false)).collect(),
+            rejected_count: std::array::from_fn(|_| AtomicU64::new(0)),
+            flagged_count: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    fn index(&self, exchange: Exchange, id: SymbolId) -> usize {
+        exchange as usize * self.n_symbols + id
+    }
+
+    /// Median of other venues' recent mids for `id`, if enough are live.
+    fn consensus(&self, exchange: Exchange, id: SymbolId, now_ms: i64) -> Option<f64> {
+        let mut mids = [0.0f64; N_VENUES];
+        let mut n = 0;
+        for other in Exchange::ALL {
+            if other == exchange {
+                continue;
+            }
+            let i = self.index(other, id);
+            let mid = f64::from_bits(self.mid_bits[i].load(Ordering::Relaxed));
+            let age = now_ms - self.mid_ts_ms[i].load(Ordering::Relaxed);
+            if mid > 0.0 && age <= self.cfg.consensus_max_age_ms {
+                mids[n] = mid;
+                n += 1;
+            }
+        }
+        if n == 0 || n < self.cfg.min_consensus_venues {
+            return None;
+        }
+        let mids = &mut mids[..n];
+        mids.sort_by(|a, b| a.total_cmp(b));
+        Some(if n % 2 == 1 { mids[n / 2] } else { (mids[n / 2 - 1] + mids[n / 2]) / 2.0 })
+    }
+
+    /// Judge a new mid for (exchange, id) received at `now_ms` (unix ms).
+    pub fn check(&self, exchange: Exchange, id: SymbolId, mid: f64, now_ms: i64) -> Verdict {
+        let i = self.index(exchange, id);
+        let ewma = f64::from_bits(self.ewma_bits[i].load(Ordering::Relaxed));
+        let deviates = |reference: f64, max_pct: f64| (mid / reference - 1.0).abs() * 100.0 > max_pct;
+
+        let suspect = self.cfg.max_ewma_deviation_pct.is_some_and(|max| ewma > 0.0 && deviates(ewma, max))
+            || self
+                .cfg
+                .max_consensus_deviation_pct
+                .is_some_and(|max| self.consensus(exchange, id, now_ms).is_some_and(|c| deviates(c, max)));
+
+        if suspect {
+            let n = self.consecutive[i].fetch_add(1, Ordering::Relaxed) + 1;
+            if n <= self.cfg.max_consecutive {
+                self.flagged[i].store(true, Ordering::Relaxed);
+                return match self.cfg.mode {
+                    SanitizeMode::Reject => {
+                        self.rejected_count[exchange as usize].fetch_add(1, Ordering::Relaxed);
+                        Verdict::Reject
+                    }
+                    SanitizeMode::Flag => {
+                        self.flagged_count[exchange as usize].fetch_add(1, Ordering::Relaxed);
+                        Verdict::Flag
+                    }
+                };
+            }
+            // Persistent: adopt as the new level.
+            self.ewma_bits[i].store(mid.to_bits(), Ordering::Relaxed);
+        } else {
+            let alpha = self.cfg.ewma_alpha;
+            let next = if ewma > 0.0 { alpha * mid + (1.0 - alpha) * ewma } else { mid };
+            self.ewma_bits[i].store(next.to_bits(), Ordering::Relaxed);
+        }
+        self.consecutive[i].store(0, Ordering::Relaxed);
+        self.flagged[i].store(false, Ordering::Relaxed);
+        self.mid_bits[i].store(mid.to_bits(), Ordering::Relaxed);
+        self.mid_ts_ms[i].store(now_ms, Ordering::Relaxed);
+        Verdict::Accept
+    }
+
+    pub fn config(&self) -> &SanitizerConfig {
+        &self.cfg
+    }
+
+    /// Whether the latest tick judged for (exchange, id) was suspect.
+    pub fn is_flagged(&self, exchange: &Exchange, id: &SymbolId) -> bool {
+        self.flagged[self.index(*exchange, *id)].load(Ordering::Relaxed)
+    }
+
+    pub fn rejected(&self, exchange: &Exchange) -> u64 {
+        self.rejected_count[*exchange as usize].load(Ordering::Relaxed)
+    }
+
+    pub fn flagged(&self, exchange: &Exchange) -> u64 {
+        self.flagged_count[*exchange as usize].load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ewma_spike_rejected_then_level_adopted() {
+        let san = QuoteSanitizer::new(SanitizerConfig { max_consecutive: 2, ..Default::default() });
+        let ex = Exchange::Binance;
+        for _ in 0..10 {
+            assert_eq!(san.check(ex, 1, 100.0, 0), Verdict::Accept);
+        }
+        // 10% fat finger.
+        assert_eq!(san.check(ex, 1, 110.0, 0), Verdict::Reject);
+        assert!(san.is_flagged(&ex, &1));
+        assert_eq!(san.check(ex, 1, 100.1, 0), Verdict::Accept);
+        assert!(!san.is_flagged(&ex, &1));
+
+        // A genuine move persists and is adopted after two rejects.
+        assert_eq!(san.check(ex, 1, 90.0, 0), Verdict::Reject);
+        assert_eq!(san.check(ex, 1, 90.0, 0), Verdict::Reject);
+        assert_eq!(san.check(ex, 1, 90.0, 0), Verdict::Accept);
+        assert_eq!(san.check(ex, 1, 90.1, 0), Verdict::Accept);
+        assert_eq!(san.rejected(&ex), 3);
+    }
+
+    #[test]
+    fn test_consensus_flags_outlier_venue() {
+        let san = QuoteSanitizer::new(SanitizerConfig {
+            mode: SanitizeMode::Flag,
+            max_ewma_deviation_pct: None,
+            max_consensus_deviation_pct: Some(1.0),
+            ..Default::default()
+        });
+        // One other venue is not enough for a consensus.
+        san.check(Exchange::Okx, 2, 100.0, 1_000);
+        assert_eq!(san.check(Exchange::Kraken, 2, 97.0, 1_000), Verdict::Accept);
+        assert_eq!(san.check(Exchange::Kraken, 2, 100.0, 1_000), Verdict::Accept);
+
+        assert_eq!(san.check(Exchange::Bybit, 2, 100.2, 1_000), Verdict::Accept);
+        assert_eq!(san.check(Exchange::Kraken, 2, 97.0, 1_000), Verdict::Flag);
+        assert_eq!(san.check(Exchange::Kraken, 2, 100.1, 1_000), Verdict::Accept);
+        assert_eq!(san.flagged(&Exchange::Kraken), 1);
+
+        // Consensus entries past max age drop out.
+        assert_eq!(san.check(Exchange::Kraken, 2, 97.0, 10_000), Verdict::Accept);
+    }
+}
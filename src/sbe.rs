@@ -0,0 +1,290 @@
+//! Simple Binary Encoding for quote/trade updates.
+//!
+//! Schema: `proto/sbe_market_data.xml` (schema id 7401, version 1).
+//! Encoders write into a caller-owned buffer and never allocate, so they
+//! can run on the hot path of publishers such as `udp_sink`. Every message
+//! is an 8-byte SBE header followed by a fixed-size, little-endian block.
+
+use crate::market_data::{Exchange, MarketData};
+use crate::symbol_registry::SymbolId;
+use crate::trade_data::{TradeData, TradeSide};
+use chrono::{DateTime, Utc};
+
+pub const SCHEMA_ID: u16 = 7401;
+pub const SCHEMA_VERSION: u16 = 1;
+pub const HEADER_LEN: usize = 8;
+
+pub const QUOTE_TEMPLATE_ID: u16 = 1;
+pub const QUOTE_BLOCK_LEN: usize = 61;
+pub const QUOTE_LEN: usize = HEADER_LEN + QUOTE_BLOCK_LEN;
+
+pub const TRADE_TEMPLATE_ID: u16 = 2;
+pub const TRADE_BLOCK_LEN: usize = 38;
+pub const TRADE_LEN: usize = HEADER_LEN + TRADE_BLOCK_LEN;
+
+const NULL_TS: i64 = i64::MIN;
+const NULL_U64: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub block_length: u16,
+    pub template_id: u16,
+    pub schema_id: u16,
+    pub version: u16,
+}
+
+// ── Primitive helpers ────────────────────────────────────────────────
+
+#[inline]
+fn put_u16(buf: &mut [u8], off: usize, v: u16) {
+    buf[off..off + 2].copy_from_slice(&v.to_le_bytes());
+}
+
+#[inline]
+fn put_u32(buf: &mut [u8], off: usize, v: u32) {
+    buf[off..off + 4].copy_from_slice(&v.to_le_bytes());
+}
+
+#[inline]
+fn put_u64(buf: &mut [u8], off: usize, v: u64) {
+    buf[off..off + 8].copy_from_slice(&v.to_le_bytes());
+}
+
+#[inline]
+fn put_i64(buf: &mut [u8], off: usize, v: i64) {
+    buf[off..off + 8].copy_from_slice(&v.to_le_bytes());
+}
+
+#[inline]
+fn put_f64(buf: &mut [u8], off: usize, v: f64) {
+    buf[off..off + 8].copy_from_slice(&v.to_le_bytes());
+}
+
+#[inline]
+fn get_u16(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+#[inline]
+fn get_u32(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes(buf[off..off + 4].try_into().unwrap())
+}
+
+#[inline]
+fn get_u64(buf: &[u8], off: usize) -> u64 {
+    u64::from_le_bytes(buf[off..off + 8].try_into().unwrap())
+}
+
+#[inline]
+fn get_i64(buf: &[u8], off: usize) -> i64 {
+    i64::from_le_bytes(buf[off..off + 8].try_into().unwrap())
+}
+
+#[inline]
+fn get_f64(buf: &[u8], off: usize) -> f64 {
+    f64::from_le_bytes(buf[off..off + 8].try_into().unwrap())
+}
+
+#[inline]
+fn ts_to_ns(ts: Option<DateTime<Utc>>) -> i64 {
+    ts.and_then(|t| t.timestamp_nanos_opt()).unwrap_or(NULL_TS)
+}
+
+#[inline]
+fn ns_to_ts(ns: i64) -> Option<DateTime<Utc>> {
+    if ns == NULL_TS {
+        return None;
+    }
+    DateTime::from_timestamp(ns.div_euclid(1_000_000_000), ns.rem_euclid(1_000_000_000) as u32)
+}
+
+#[inline]
+fn opt_f64(v: f64) -> Option<f64> {
+    if v.is_nan() { None } else { Some(v) }
+}
+
+fn exchange_from_code(code: u8) -> Option<Exchange> {
+    Exchange::ALL.get(code as usize).copied()
+}
+
+fn write_header(buf: &mut [u8], block_length: usize, template_id: u16) {
+    put_u16(buf, 0, block_length as u16);
+    put_u16(buf, 2, template_id);
+    put_u16(buf, 4, SCHEMA_ID);
+    put_u16(buf, 6, SCHEMA_VERSION);
+}
+
+/// Read the SBE header. Returns None if `buf` is too short.
+pub fn read_header(buf: &[u8]) -> Option<MessageHeader> {
+    if buf.len() < HEADER_LEN {
+        return None;
+    }
+    Some(MessageHeader {
+        block_length: get_u16(buf, 0),
+        template_id: get_u16(buf, 2),
+        schema_id: get_u16(buf, 4),
+        version: get_u16(buf, 6),
+    })
+}
+
+// ── Quote ────────────────────────────────────────────────────────────
+
+/// Encode a quote into `buf`. Returns bytes written, or None if `buf` is short.
+pub fn encode_quote(buf: &mut [u8], exchange: Exchange, id: SymbolId, md: &MarketData) -> Option<usize> {
+    if buf.len() < QUOTE_LEN {
+        return None;
+    }
+    write_header(buf, QUOTE_BLOCK_LEN, QUOTE_TEMPLATE_ID);
+    let b = &mut buf[HEADER_LEN..QUOTE_LEN];
+    put_i64(b, 0, ts_to_ns(md.exchange_ts));
+    put_i64(b, 8, ts_to_ns(md.received_ts));
+    put_u64(b, 16, md.update_id.unwrap_or(NULL_U64));
+    put_f64(b, 24, md.bid.unwrap_or(f64::NAN));
+    put_f64(b, 32, md.ask.unwrap_or(f64::NAN));
+    put_f64(b, 40, md.bid_qty.unwrap_or(f64::NAN));
+    put_f64(b, 48, md.ask_qty.unwrap_or(f64::NAN));
+    put_u32(b, 56, id as u32);
+    b[60] = exchange as u8;
+    Some(QUOTE_LEN)
+}
+
+/// Decode a quote message (header included).
+pub fn decode_quote(buf: &[u8]) -> Option<(Exchange, SymbolId, MarketData)> {
+    let header = read_header(buf)?;
+    let block = header.block_length as usize;
+    if header.schema_id != SCHEMA_ID
+        || header.template_id != QUOTE_TEMPLATE_ID
+        || block < QUOTE_BLOCK_LEN
+        || buf.len() < HEADER_LEN + block
+    {
+        return None;
+    }
+    let b = &buf[HEADER_LEN..];
+    let exchange_ts = ns_to_ts(get_i64(b, 0));
+    let update_id = get_u64(b, 16);
+    let md = MarketData {
+        bid: opt_f64(get_f64(b, 24)),
+        ask: opt_f64(get_f64(b, 32)),
+        bid_qty: opt_f64(get_f64(b, 40)),
+        ask_qty: opt_f64(get_f64(b, 48)),
+        exchange_ts_raw: exchange_ts,
+        exchange_ts,
+        received_ts: ns_to_ts(get_i64(b, 8)),
+        update_id: if update_id == NULL_U64 { None } else { Some(update_id) },
+        ..Default::default()
+    };
+    Some((exchange_from_code(b[60])?, get_u32(b, 56) as SymbolId, md))
+}
+
+// ── Trade ────────────────────────────────────────────────────────────
+
+/// Encode a trade into `buf`. Returns bytes written, or None if `buf` is short.
+pub fn encode_trade(buf: &mut [u8], exchange: Exchange, id: SymbolId, trade: &TradeData) -> Option<usize> {
+    if buf.len() < TRADE_LEN {
+        return None;
+    }
+    write_header(buf, TRADE_BLOCK_LEN, TRADE_TEMPLATE_ID);
+    let b = &mut buf[HEADER_LEN..TRADE_LEN];
+    put_i64(b, 0, ts_to_ns(trade.exchange_ts));
+    put_i64(b, 8, ts_to_ns(trade.received_ts));
+    put_f64(b, 16, trade.price);
+    put_f64(b, 24, trade.qty);
+    put_u32(b, 32, id as u32);
+    b[36] = exchange as u8;
+    b[37] = trade.side as u8;
+    Some(TRADE_LEN)
+}
+
+/// Decode a trade message (header included).
+pub fn decode_trade(buf: &[u8]) -> Option<(Exchange, SymbolId, TradeData)> {
+    let header = read_header(buf)?;
+    let block = header.block_length as usize;
+    if header.schema_id != SCHEMA_ID
+        || header.template_id != TRADE_TEMPLATE_ID
+        || block < TRADE_BLOCK_LEN
+        || buf.len() < HEADER_LEN + block
+    {
+        return None;
+    }
+    let b = &buf[HEADER_LEN..];
+    let exchange_ts = ns_to_ts(get_i64(b, 0));
+    let side = match b[37] {
+        1 => TradeSide::Buy,
+        2 => TradeSide::Sell,
+        _ => TradeSide::Unknown,
+    };
+    let trade = TradeData {
+        price: get_f64(b, 16),
+        qty: get_f64(b, 24),
+        side,
+        exchange_ts_raw: exchange_ts,
+        exchange_ts,
+        received_ts: ns_to_ts(get_i64(b, 8)),
+        ..Default::default()
+    };
+    Some((exchange_from_code(b[36])?, get_u32(b, 32) as SymbolId, trade))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exchange_codes_match_discriminants() {
+        for (i, e) in Exchange::ALL.iter().enumerate() {
+            assert_eq!(*e as usize, i);
+        }
+    }
+
+    #[test]
+    fn test_quote_roundtrip() {
+        let md = MarketData {
+            bid: Some(50_000.5),
+            ask: None,
+            bid_qty: Some(1.25),
+            ask_qty: None,
+            exchange_ts: DateTime::from_timestamp(1_700_000_000, 5),
+            received_ts: DateTime::from_timestamp(1_700_000_001, 7),
+            update_id: Some(99),
+            ..Default::default()
+        };
+        let mut buf = [0u8; 128];
+        let n = encode_quote(&mut buf, Exchange::Okx, 17, &md).unwrap();
+        assert_eq!(n, QUOTE_LEN);
+        assert_eq!(read_header(&buf).unwrap().template_id, QUOTE_TEMPLATE_ID);
+
+        let (ex, id, out) = decode_quote(&buf[..n]).unwrap();
+        assert!(ex == Exchange::Okx);
+        assert_eq!(id, 17);
+        assert_eq!(out.bid, Some(50_000.5));
+        assert_eq!(out.ask, None);
+        assert_eq!(out.bid_qty, Some(1.25));
+        assert_eq!(out.exchange_ts, md.exchange_ts);
+        assert_eq!(out.received_ts, md.received_ts);
+        assert_eq!(out.update_id, Some(99));
+
+        assert!(decode_trade(&buf[..n]).is_none());
+        assert!(encode_quote(&mut buf[..QUOTE_LEN - 1], Exchange::Okx, 17, &md).is_none());
+    }
+
+    #[test]
+    fn test_trade_roundtrip() {
+        let trade = TradeData {
+            price: 3000.0,
+            qty: 0.5,
+            side: TradeSide::Sell,
+            exchange_ts: DateTime::from_timestamp(1_700_000_000, 0),
+            ..Default::default()
+        };
+        let mut buf = [0u8; TRADE_LEN];
+        encode_trade(&mut buf, Exchange::Bybit, 3, &trade).unwrap();
+        let (ex, id, out) = decode_trade(&buf).unwrap();
+        assert!(ex == Exchange::Bybit);
+        assert_eq!(id, 3);
+        assert_eq!(out.price, 3000.0);
+        assert_eq!(out.qty, 0.5);
+        assert_eq!(out.side, TradeSide::Sell);
+        assert_eq!(out.exchange_ts, trade.exchange_ts);
+        assert_eq!(out.received_ts, None);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::market_data::{AllMarketData, Exchange};
 use crate::ring_buffer::RingBuffer;
-use crate::symbol_registry::{MAX_SYMBOLS, SymbolId};
+use crate::symbol_registry::{SymbolId, symbol_capacity};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use tokio::sync::Notify;
@@ -56,8 +56,9 @@ impl std::fmt::Debug for SnapshotCollection {
 
 impl SnapshotCollection {
     pub fn new(buffer_capacity: usize) -> Self {
-        let mut buffers = Vec::with_capacity(MAX_SYMBOLS);
-        for _ in 0..MAX_SYMBOLS {
+        let n = symbol_capacity();
+        let mut buffers = Vec::with_capacity(n);
+        for _ in 0..n {
             buffers.push(OnceLock::new());
         }
         Self {
@@ -102,6 +103,16 @@ pub struct AllSnapshotData {
     pub zeroone: Arc<SnapshotCollection>,
     pub risex: Arc<SnapshotCollection>,
     pub bulk: Arc<SnapshotCollection>,
+    pub pyth: Arc<SnapshotCollection>,
+    pub chainlink: Arc<SnapshotCollection>,
+    pub dydx: Arc<SnapshotCollection>,
+    pub deribit: Arc<SnapshotCollection>,
+    pub gateio: Arc<SnapshotCollection>,
+    pub htx: Arc<SnapshotCollection>,
+    pub bitfinex: Arc<SnapshotCollection>,
+    pub upbit: Arc<SnapshotCollection>,
+    pub phemex: Arc<SnapshotCollection>,
+    pub vertex: Arc<SnapshotCollection>,
 }
 
 impl std::fmt::Debug for AllSnapshotData {
@@ -134,6 +145,16 @@ impl AllSnapshotData {
             zeroone: new_coll(),
             risex: new_coll(),
             bulk: new_coll(),
+            pyth: new_coll(),
+            chainlink: new_coll(),
+            dydx: new_coll(),
+            deribit: new_coll(),
+            gateio: new_coll(),
+            htx: new_coll(),
+            bitfinex: new_coll(),
+            upbit: new_coll(),
+            phemex: new_coll(),
+            vertex: new_coll(),
         }
     }
 
@@ -159,6 +180,16 @@ impl AllSnapshotData {
             Exchange::ZeroOne => &self.zeroone,
             Exchange::RiseX => &self.risex,
             Exchange::Bulk => &self.bulk,
+            Exchange::Pyth => &self.pyth,
+            Exchange::Chainlink => &self.chainlink,
+            Exchange::Dydx => &self.dydx,
+            Exchange::Deribit => &self.deribit,
+            Exchange::Gateio => &self.gateio,
+            Exchange::Htx => &self.htx,
+            Exchange::Bitfinex => &self.bitfinex,
+            Exchange::Upbit => &self.upbit,
+            Exchange::Phemex => &self.phemex,
+            Exchange::Vertex => &self.vertex,
         }
     }
 
@@ -185,12 +216,22 @@ impl AllSnapshotData {
             (ZeroOne, &self.zeroone),
             (RiseX, &self.risex),
             (Bulk, &self.bulk),
+            (Pyth, &self.pyth),
+            (Chainlink, &self.chainlink),
+            (Dydx, &self.dydx),
+            (Deribit, &self.deribit),
+            (Gateio, &self.gateio),
+            (Htx, &self.htx),
+            (Bitfinex, &self.bitfinex),
+            (Upbit, &self.upbit),
+            (Phemex, &self.phemex),
+            (Vertex, &self.vertex),
         ]
         .into_iter()
     }
 }
 
-const NUM_EXCHANGES: usize = 20;
+const NUM_EXCHANGES: usize = 30;
 
 fn exchange_index(exchange: &Exchange) -> usize {
     match exchange {
@@ -214,6 +255,16 @@ fn exchange_index(exchange: &Exchange) -> usize {
         Exchange::ZeroOne => 17,
         Exchange::RiseX => 18,
         Exchange::Bulk => 19,
+        Exchange::Pyth => 20,
+        Exchange::Chainlink => 21,
+        Exchange::Dydx => 22,
+        Exchange::Deribit => 23,
+        Exchange::Gateio => 24,
+        Exchange::Htx => 25,
+        Exchange::Bitfinex => 26,
+        Exchange::Upbit => 27,
+        Exchange::Phemex => 28,
+        Exchange::Vertex => 29,
     }
 }
 
@@ -226,8 +277,9 @@ pub async fn run_snapshot_task(
     let mut interval = time::interval(std::time::Duration::from_millis(config.interval_ms));
     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
-    let mut prev_mid = vec![f64::NAN; NUM_EXCHANGES * MAX_SYMBOLS];
-    let mut prev_tick_pos = vec![0u64; NUM_EXCHANGES * MAX_SYMBOLS];
+    let n_symbols = symbol_capacity();
+    let mut prev_mid = vec![f64::NAN; NUM_EXCHANGES * n_symbols];
+    let mut prev_tick_pos = vec![0u64; NUM_EXCHANGES * n_symbols];
 
     // Create the shutdown future once so we don't miss notifications
     // between loop iterations
@@ -249,7 +301,7 @@ pub async fn run_snapshot_task(
             let ex_idx = exchange_index(&exchange);
             let snap_coll = snap_data.get_collection(&exchange);
 
-            for sym_id in 0..MAX_SYMBOLS {
+            for sym_id in 0..n_symbols {
                 let md = match tick_coll.latest(&sym_id) {
                     Some(md) => md,
                     None => continue,
@@ -264,7 +316,7 @@ pub async fn run_snapshot_task(
                 let spread = ask - bid;
 
                 // Scan raw ticks since last snapshot for min/max midquote
-                let prev_idx = ex_idx * MAX_SYMBOLS + sym_id;
+                let prev_idx = ex_idx * n_symbols + sym_id;
                 let (mid_high, mid_low, bid_high, ask_low) = if let Some(tick_buf) = tick_coll.get_buffer(&sym_id) {
                     let cur_pos = tick_buf.write_pos();
                     let start_pos = prev_tick_pos[prev_idx];
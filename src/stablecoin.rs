@@ -0,0 +1,192 @@
+//! Stablecoin rates and quote normalisation.
+//!
+//! USDT/USD and USDC/USD are read from the spot feeds of fiat venues
+//! (Kraken, Coinbase by default), so the relevant `*_USD` pairs must be in
+//! those venues' `spot` lists. A quote in any tracked stablecoin can then
+//! be re-expressed in one reference currency, which keeps cross-venue
+//! comparisons honest while a stablecoin trades off its peg.
+
+use crate::market_data::{AllMarketData, Exchange, InstrumentType, MarketData};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+use log::warn;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+fn default_reference() -> String {
+    "USD".into()
+}
+fn default_stablecoins() -> Vec<String> {
+    vec!["USDT".into(), "USDC".into()]
+}
+fn default_sources() -> Vec<String> {
+    vec!["kraken".into(), "coinbase".into()]
+}
+fn default_max_age_ms() -> u64 {
+    60_000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StablecoinConfig {
+    /// Currency quotes are normalised into: "USD" or a tracked stablecoin.
+    #[serde(default = "default_reference")]
+    pub reference: String,
+    #[serde(default = "default_stablecoins")]
+    pub stablecoins: Vec<String>,
+    /// Venues whose `<COIN>_USD` spot mid is the rate (averaged when several are live).
+    #[serde(default = "default_sources")]
+    pub sources: Vec<String>,
+    /// Source quotes older than this are ignored.
+    #[serde(default = "default_max_age_ms")]
+    pub max_age_ms: u64,
+}
+
+impl Default for StablecoinConfig {
+    fn default() -> Self {
+        Self {
+            reference: default_reference(),
+            stablecoins: default_stablecoins(),
+            sources: default_sources(),
+            max_age_ms: default_max_age_ms(),
+        }
+    }
+}
+
+pub struct StablecoinRates {
+    reference: String,
+    sources: Vec<Exchange>,
+    /// Stablecoin → spot id of its USD pair.
+    usd_pairs: Vec<(String, SymbolId)>,
+    max_age: Duration,
+}
+
+impl StablecoinRates {
+    pub fn new(cfg: &StablecoinConfig) -> Self {
+        let sources = cfg
+            .sources
+            .iter()
+            .filter_map(|v| {
+                let e = v.parse::<Exchange>().ok();
+                if e.is_none() {
+                    warn!("stablecoins: unknown source venue '{}'", v);
+                }
+                e
+            })
+            .collect();
+        let usd_pairs = cfg
+            .stablecoins
+            .iter()
+            .filter_map(|coin| {
+                let coin = coin.to_uppercase();
+                let pair = format!("{}_USD", coin);
+                match REGISTRY.lookup(&pair, &InstrumentType::Spot) {
+                    Some(&id) => Some((coin, id)),
+                    None => {
+                        warn!("stablecoins: {} not in registry (add {} to base_assets)", pair, coin);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self {
+            reference: cfg.reference.to_uppercase(),
+            sources,
+            usd_pairs,
+            max_age: Duration::from_millis(cfg.max_age_ms),
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// USD value of one unit of `ccy`: 1 for USD, the mean live source mid
+    /// for a tracked stablecoin, None for anything else or when every
+    /// source is stale.
+    pub fn usd_rate(&self, data: &AllMarketData, ccy: &str, now: Instant) -> Option<f64> {
+        if ccy.eq_ignore_ascii_case("USD") {
+            return Some(1.0);
+        }
+        let (_, id) = self.usd_pairs.iter().find(|(c, _)| c.eq_ignore_ascii_case(ccy))?;
+        let (sum, n) = self
+            .sources
+            .iter()
+            .filter_map(|e| {
+                let md = data.get_collection(e).latest(id)?;
+                let age = now.saturating_duration_since(md.received_instant?);
+                (age <= self.max_age).then(|| md.midquote()).flatten()
+            })
+            .fold((0.0, 0), |(s, n), mid| (s + mid, n + 1));
+        (n > 0).then(|| sum / n as f64)
+    }
+
+    /// Deviation from the USD peg in bps (negative when trading below).
+    pub fn basis_bps(&self, data: &AllMarketData, ccy: &str, now: Instant) -> Option<f64> {
+        Some((self.usd_rate(data, ccy, now)? - 1.0) * 10_000.0)
+    }
+
+    /// Multiplier taking a price in `ccy` to the reference currency.
+    pub fn to_reference(&self, data: &AllMarketData, ccy: &str, now: Instant) -> Option<f64> {
+        if ccy.eq_ignore_ascii_case(&self.reference) {
+            return Some(1.0);
+        }
+        Some(self.usd_rate(data, ccy, now)? / self.usd_rate(data, &self.reference, now)?)
+    }
+
+    /// `md` (a quote for `id`) with prices in the reference currency. None
+    /// if its quote currency has no live rate.
+    pub fn normalize(&self, data: &AllMarketData, id: &SymbolId, md: &MarketData, now: Instant) -> Option<MarketData> {
+        let factor = self.to_reference(data, quote_currency(*id)?, now)?;
+        Some(MarketData {
+            bid: md.bid.map(|p| p * factor),
+            ask: md.ask.map(|p| p * factor),
+            ..*md
+        })
+    }
+
+    /// Latest quote for `id` on `exchange`, normalised.
+    pub fn normalized_latest(&self, data: &AllMarketData, exchange: &Exchange, id: &SymbolId) -> Option<MarketData> {
+        let md = data.get_collection(exchange).latest(id)?;
+        self.normalize(data, id, &md, Instant::now())
+    }
+}
+
+/// Quote currency from the canonical name: "USDT" for "PERP-BTC-USDT".
+pub fn quote_currency(id: SymbolId) -> Option<&'static str> {
+    REGISTRY.get_symbol(id)?.rsplit('-').next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_usdt_quote_to_usd() {
+        let data = AllMarketData::new();
+        let now = Instant::now();
+        let quote = |bid: f64, ask: f64| MarketData {
+            bid: Some(bid),
+            ask: Some(ask),
+            received_instant: Some(now),
+            ..Default::default()
+        };
+        let usdt_usd = *REGISTRY.lookup("USDT_USD", &InstrumentType::Spot).unwrap();
+        data.kraken.push(&usdt_usd, quote(0.9970, 0.9980));
+        data.coinbase.push(&usdt_usd, quote(0.9980, 0.9990));
+
+        let rates = StablecoinRates::new(&StablecoinConfig::default());
+        let rate = rates.usd_rate(&data, "USDT", now).unwrap();
+        assert!((rate - 0.998).abs() < 1e-12);
+        assert!((rates.basis_bps(&data, "USDT", now).unwrap() + 20.0).abs() < 1e-9);
+        // No USDC source quotes yet.
+        assert!(rates.usd_rate(&data, "USDC", now).is_none());
+
+        let btc = *REGISTRY.lookup("BTC_USDT", &InstrumentType::Spot).unwrap();
+        let md = quote(100_000.0, 100_010.0);
+        let n = rates.normalize(&data, &btc, &md, now).unwrap();
+        assert!((n.bid.unwrap() - 99_800.0).abs() < 1e-6);
+
+        // Sources past max_age are ignored.
+        let later = now + Duration::from_secs(120);
+        assert!(rates.usd_rate(&data, "USDT", later).is_none());
+    }
+}
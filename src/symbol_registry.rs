@@ -1,27 +1,164 @@
+use anyhow::Result;
 use once_cell::sync::Lazy;
 use rustc_hash::FxHashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
 
+use crate::app_config::AppConfig;
 use crate::market_data::InstrumentType;
 
+/// Ids every per-symbol collection makes room for at least; see
+/// `symbol_capacity`.
 pub const MAX_SYMBOLS: usize = 1_000;
 pub type SymbolId = usize;
 
+/// Quotes every base is paired with unless `quote_currencies` is set.
 const QUOTE_CURRENCIES: &[&str] = &["USDT", "USDC", "USD", "ETH", "WETH"];
 const INSTRUMENT_TYPES: &[InstrumentType] = &[InstrumentType::Spot, InstrumentType::Perp];
 
+/// Venue names for a base that resolve to its SymbolIds. `1000PEPE` quotes
+/// 1000 PEPE per unit, so its prices are divided and sizes multiplied by
+/// `multiplier` to land on the unscaled asset.
+const DEFAULT_EQUIVALENTS: &[(&str, &str, f64)] = &[
+    ("XBT", "BTC", 1.0),
+    ("1000PEPE", "PEPE", 1000.0),
+    ("1000SHIB", "SHIB", 1000.0),
+    ("1000BONK", "BONK", 1000.0),
+    ("1000FLOKI", "FLOKI", 1000.0),
+];
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+fn default_quote_currencies() -> Vec<String> {
+    QUOTE_CURRENCIES.iter().map(|q| q.to_string()).collect()
+}
+
+/// `alias` names `base`, scaled by `multiplier` units of it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Equivalence {
+    pub alias: String,
+    pub base: String,
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+}
+
+impl Equivalence {
+    pub fn defaults() -> Vec<Equivalence> {
+        DEFAULT_EQUIVALENTS
+            .iter()
+            .map(|&(alias, base, multiplier)| Equivalence { alias: alias.to_string(), base: base.to_string(), multiplier })
+            .collect()
+    }
+}
+
 #[derive(Deserialize)]
 struct Config {
     base_assets: Vec<String>,
+    /// Quotes paired with every base, e.g. `[USDT, USD, EUR, KRW, BTC]`.
+    #[serde(default = "default_quote_currencies")]
+    quote_currencies: Vec<String>,
+    /// Added to `DEFAULT_EQUIVALENTS`; an alias listed here replaces the
+    /// default one.
+    #[serde(default)]
+    equivalents: Vec<Equivalence>,
+    /// Id map to keep SymbolIds stable across edits, relative to the
+    /// yaml file's directory; see `SymbolRegistry::apply_id_map`.
+    #[serde(default)]
+    id_map: Option<String>,
+}
+
+/// One line of an id map file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IdPin {
+    id: SymbolId,
+    symbol: String,
+}
+
+/// The parts of a canonical name: "PERP-ETH-USDC" is a Perp of ETH quoted
+/// in USDC. Case is kept as given; registry names are uppercase.
+#[derive(Debug, Clone)]
+pub struct CanonicalSymbol {
+    pub itype: InstrumentType,
+    pub base: String,
+    pub quote: String,
+}
+
+impl CanonicalSymbol {
+    pub fn new(itype: InstrumentType, base: impl Into<String>, quote: impl Into<String>) -> Self {
+        Self { itype, base: base.into(), quote: quote.into() }
+    }
+
+    /// Parse "PERP-ETH-USDC", or the config spelling "PERP_ETH_USDC".
+    pub fn parse(name: &str) -> Result<Self> {
+        let parts: Vec<&str> = name.split(['-', '_']).collect();
+        match parts.as_slice() {
+            [prefix, base, quote] if !base.is_empty() && !quote.is_empty() => {
+                let itype = InstrumentType::from_prefix(prefix)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown instrument type in symbol: {}", name))?;
+                Ok(Self::new(itype, *base, *quote))
+            }
+            _ => anyhow::bail!("Invalid canonical symbol: {}", name),
+        }
+    }
+
+    /// Parse a normalized config symbol, "ETH_USDC" or "PERP_ETH_USDC",
+    /// as `itype`; a type prefix is dropped in favour of it.
+    pub fn parse_pair(normalized: &str, itype: InstrumentType) -> Result<Self> {
+        let parts: Vec<&str> = normalized.split('_').collect();
+        match parts.as_slice() {
+            [_, base, quote] | [base, quote] if !base.is_empty() && !quote.is_empty() => Ok(Self::new(itype, *base, *quote)),
+            _ => anyhow::bail!("Invalid normalized symbol: {}", normalized),
+        }
+    }
+
+    /// The registry name, "PERP-ETH-USDC".
+    pub fn format(&self) -> String {
+        self.to_string()
+    }
+
+    /// Base and quote in config spelling, "ETH_USDC".
+    pub fn pair(&self) -> String {
+        format!("{}_{}", self.base, self.quote)
+    }
+}
+
+impl fmt::Display for CanonicalSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.itype.as_str(), self.base, self.quote)
+    }
+}
+
+/// One registered symbol, as `SymbolRegistry::to_json` writes it.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryEntry {
+    pub id: SymbolId,
+    pub symbol: String,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RegistryDump<'a> {
+    quotes: &'a [String],
+    symbols: Vec<RegistryEntry>,
 }
 
 pub struct SymbolRegistry {
-    to_symbol: [Option<String>; MAX_SYMBOLS],
+    /// Canonical name by id; None for ids an id map keeps reserved.
+    to_symbol: Vec<Option<String>>,
+    /// Registered names, the `Some`s in `to_symbol`.
+    count: usize,
     spot_to_id: FxHashMap<String, SymbolId>,
     perp_to_id: FxHashMap<String, SymbolId>,
+    quotes: Vec<String>,
+    /// Alias base -> (canonical base, multiplier).
+    equivalents: FxHashMap<String, (String, f64)>,
+    /// Lookup keys of scaled aliases ("1000PEPEUSDT") -> multiplier.
+    multipliers: FxHashMap<String, f64>,
 }
 
 /// Extra base assets seeded by the application before first REGISTRY access.
@@ -39,9 +176,13 @@ pub fn seed_extra_bases(bases: Vec<String>) {
 impl SymbolRegistry {
     fn new() -> Self {
         Self {
-            to_symbol: std::array::from_fn(|_| None),
+            to_symbol: Vec::new(),
+            count: 0,
             spot_to_id: FxHashMap::default(),
             perp_to_id: FxHashMap::default(),
+            quotes: default_quote_currencies(),
+            equivalents: FxHashMap::default(),
+            multipliers: FxHashMap::default(),
         }
     }
 
@@ -56,26 +197,150 @@ impl SymbolRegistry {
         let config: Config =
             serde_yaml::from_str(&content).map_err(|e| format!("Failed to parse YAML: {}", e))?;
 
-        // Merge symbols.yaml bases with extras, deduplicated
+        // Merge symbols.yaml bases with extras
+        let all_bases: Vec<String> = config.base_assets.iter().chain(extra_bases.iter()).cloned().collect();
+        let mut equivalents = Equivalence::defaults();
+        equivalents.extend(config.equivalents);
+        let mut reg = Self::from_parts(&all_bases, &config.quote_currencies, &equivalents)?;
+        if let Some(id_map) = &config.id_map {
+            let dir = Path::new(path).parent().unwrap_or(Path::new("."));
+            reg.apply_id_map(&dir.join(id_map))?;
+        }
+        Ok(reg)
+    }
+
+    /// Registry for `bases`, each paired with every quote currency, without
+    /// reading a file. Bases are uppercased and deduplicated; ids follow
+    /// their order.
+    pub fn from_bases(bases: &[String]) -> Result<Self, String> {
+        Self::from_bases_with_equivalents(bases, &Equivalence::defaults())
+    }
+
+    /// `from_bases` with `equivalents` in place of the defaults. A base
+    /// that is itself an alias registers its canonical base instead.
+    pub fn from_bases_with_equivalents(bases: &[String], equivalents: &[Equivalence]) -> Result<Self, String> {
+        Self::from_parts(bases, &default_quote_currencies(), equivalents)
+    }
+
+    /// Registry pairing `bases` with `quotes` instead of the default quote
+    /// currencies. Quotes are uppercased and deduplicated.
+    pub fn from_parts(bases: &[String], quotes: &[String], equivalents: &[Equivalence]) -> Result<Self, String> {
+        let mut reg = Self::new();
+        reg.quotes.clear();
+        for quote in quotes {
+            let upper = quote.to_uppercase();
+            if !reg.quotes.contains(&upper) {
+                reg.quotes.push(upper);
+            }
+        }
+        if reg.quotes.is_empty() {
+            return Err("No quote currencies configured".to_string());
+        }
+        for eq in equivalents {
+            if !eq.multiplier.is_finite() || eq.multiplier <= 0.0 {
+                return Err(format!("Equivalence {} -> {}: multiplier must be positive", eq.alias, eq.base));
+            }
+            reg.equivalents.insert(eq.alias.to_uppercase(), (eq.base.to_uppercase(), eq.multiplier));
+        }
+
         let mut seen = HashSet::new();
         let mut all_bases = Vec::new();
-        for base in config.base_assets.iter().chain(extra_bases.iter()) {
-            let upper = base.to_uppercase();
-            if seen.insert(upper.clone()) {
-                all_bases.push(upper);
+        for base in bases {
+            let canonical = reg.canonical_base(&base.to_uppercase()).to_string();
+            if seen.insert(canonical.clone()) {
+                all_bases.push(canonical);
             }
         }
 
-        let mut reg = Self::new();
-        reg.register_bases(&all_bases)?;
+        reg.register_bases(&all_bases);
         Ok(reg)
     }
 
-    fn register_bases(&mut self, bases: &[String]) -> Result<(), String> {
+    /// Registry for the bases `cfg`'s symbol lists name plus its
+    /// `extra_bases`, sorted so ids don't depend on map order. Quotes come
+    /// from `cfg.quote_currencies`, or the defaults when it is empty.
+    pub fn from_app_config(cfg: &AppConfig) -> Result<Self, String> {
+        let mut bases = cfg.base_assets();
+        bases.sort();
+        bases.extend(cfg.extra_bases.iter().cloned());
+        let quotes = if cfg.quote_currencies.is_empty() { default_quote_currencies() } else { cfg.quote_currencies.clone() };
+        let mut reg = Self::from_parts(&bases, &quotes, &Equivalence::defaults())?;
+        if let Some(id_map) = &cfg.symbol_id_map {
+            reg.apply_id_map(Path::new(id_map))?;
+        }
+        Ok(reg)
+    }
+
+    /// Renumber to the ids recorded in the JSON file at `path`, so data
+    /// keyed by SymbolId stays valid when bases or quotes are edited.
+    /// Symbols the file doesn't list take the lowest id it leaves free and
+    /// are appended to it; ids of symbols no longer registered stay
+    /// reserved. A missing file is created.
+    pub fn apply_id_map(&mut self, path: &Path) -> Result<(), String> {
+        let mut pins: Vec<IdPin> = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        // Ids are handed out lowest-free, so a written map is dense; allow
+        // gaps from hand edits, but not ids that would size the table.
+        let max_id = pins.len() + self.len();
+        let mut pinned = FxHashMap::default();
+        let mut taken = HashSet::new();
+        for pin in &pins {
+            if pin.id >= max_id || !taken.insert(pin.id) || pinned.insert(pin.symbol.clone(), pin.id).is_some() {
+                return Err(format!("{}: id {} for {} is out of range or listed twice", path.display(), pin.id, pin.symbol));
+            }
+        }
+
+        let mut to_symbol: Vec<Option<String>> = Vec::new();
+        let mut new_ids = vec![0; self.to_symbol.len()];
+        let mut next_free = 0;
+        let mut added = false;
+        for (old, symbol) in self.to_symbol.iter().enumerate() {
+            let Some(symbol) = symbol else { continue };
+            let id = match pinned.get(symbol) {
+                Some(&id) => id,
+                None => {
+                    while taken.contains(&next_free) {
+                        next_free += 1;
+                    }
+                    taken.insert(next_free);
+                    pins.push(IdPin { id: next_free, symbol: symbol.clone() });
+                    added = true;
+                    next_free
+                }
+            };
+            new_ids[old] = id;
+            if to_symbol.len() <= id {
+                to_symbol.resize(id + 1, None);
+            }
+            to_symbol[id] = Some(symbol.clone());
+        }
+        self.to_symbol = to_symbol;
+        for id in self.spot_to_id.values_mut().chain(self.perp_to_id.values_mut()) {
+            *id = new_ids[*id];
+        }
+
+        if added || !path.exists() {
+            pins.sort_by_key(|p| p.id);
+            let json = serde_json::to_string_pretty(&pins).map_err(|e| e.to_string())?;
+            std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Quote currencies every base is paired with, in id order.
+    pub fn quotes(&self) -> &[String] {
+        &self.quotes
+    }
+
+    fn register_bases(&mut self, bases: &[String]) {
+        let quotes = self.quotes.clone();
         for base in bases {
-            for quote in QUOTE_CURRENCIES {
+            for quote in &quotes {
                 for &instrument in INSTRUMENT_TYPES {
-                    let canonical = format!("{}-{}-{}", instrument.as_str(), base, quote);
+                    let canonical = CanonicalSymbol::new(instrument, base.as_str(), quote.as_str()).format();
 
                     // Skip if already registered (dedup)
                     let map = match instrument {
@@ -87,7 +352,7 @@ impl SymbolRegistry {
                         continue;
                     }
 
-                    let id = self.register_symbol(&canonical)?;
+                    let id = self.register_symbol(&canonical);
 
                     match instrument {
                         InstrumentType::Spot => {
@@ -99,7 +364,18 @@ impl SymbolRegistry {
                         _ => {}
                     }
 
-                    let aliases = generate_aliases(base, quote, &instrument);
+                    let mut aliases = generate_aliases(base, quote, &instrument);
+                    for (alias_base, (target, multiplier)) in &self.equivalents {
+                        if target != base {
+                            continue;
+                        }
+                        for alias in generate_aliases(alias_base, quote, &instrument) {
+                            if *multiplier != 1.0 {
+                                self.multipliers.insert(alias.clone(), *multiplier);
+                            }
+                            aliases.push(alias);
+                        }
+                    }
                     for alias in aliases {
                         match instrument {
                             InstrumentType::Spot => {
@@ -114,18 +390,13 @@ impl SymbolRegistry {
                 }
             }
         }
-        Ok(())
     }
 
-    fn register_symbol(&mut self, canonical: &str) -> Result<SymbolId, String> {
-        let count = self.to_symbol.iter().filter(|s| s.is_some()).count();
-
-        if count >= MAX_SYMBOLS {
-            return Err("Symbol registry full".to_string());
-        }
-
-        self.to_symbol[count] = Some(canonical.to_string());
-        Ok(count)
+    fn register_symbol(&mut self, canonical: &str) -> SymbolId {
+        let id = self.to_symbol.len();
+        self.to_symbol.push(Some(canonical.to_string()));
+        self.count += 1;
+        id
     }
 
     pub fn lookup(&self, symbol: &str, itype: &InstrumentType) -> Option<&SymbolId> {
@@ -137,20 +408,94 @@ impl SymbolRegistry {
     }
 
     pub fn get_symbol(&self, id: SymbolId) -> Option<&str> {
-        self.to_symbol[id].as_deref()
+        self.to_symbol.get(id)?.as_deref()
+    }
+
+    /// `get_symbol(id)` split into its parts.
+    pub fn canonical(&self, id: SymbolId) -> Option<CanonicalSymbol> {
+        CanonicalSymbol::parse(self.get_symbol(id)?).ok()
+    }
+
+    /// Number of canonical symbols. Ids run from 0 to `len() - 1` unless
+    /// an id map left gaps.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// One past the highest id, the slots a collection needs for every
+    /// symbol.
+    pub fn id_bound(&self) -> usize {
+        self.to_symbol.len()
+    }
+
+    /// (id, canonical name) of every registered symbol, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (SymbolId, &str)> + '_ {
+        self.to_symbol.iter().enumerate().filter_map(|(id, s)| Some((id, s.as_deref()?)))
+    }
+
+    /// Every name `lookup` resolves to `id` besides its canonical one,
+    /// sorted. Empty for an unregistered id.
+    pub fn aliases_for(&self, id: SymbolId) -> Vec<&str> {
+        let Some(canonical) = self.to_symbol.get(id).and_then(Option::as_deref) else {
+            return Vec::new();
+        };
+        let mut aliases: Vec<&str> = self
+            .spot_to_id
+            .iter()
+            .chain(self.perp_to_id.iter())
+            .filter(|&(name, &other)| other == id && name != canonical)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        aliases.sort_unstable();
+        aliases
+    }
+
+    /// Every symbol with its aliases, in id order.
+    pub fn entries(&self) -> Vec<RegistryEntry> {
+        self.iter()
+            .map(|(id, symbol)| RegistryEntry {
+                id,
+                symbol: symbol.to_string(),
+                aliases: self.aliases_for(id).into_iter().map(str::to_string).collect(),
+            })
+            .collect()
+    }
+
+    /// The quote currencies and `entries()`, as pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&RegistryDump { quotes: &self.quotes, symbols: self.entries() })
+    }
+
+    /// The base `base` is an alias of ("XBT" -> "BTC"), or `base` itself.
+    pub fn canonical_base<'a>(&'a self, base: &'a str) -> &'a str {
+        self.equivalents.get(base).map_or(base, |(target, _)| target.as_str())
+    }
+
+    /// Units of the canonical base one unit of `symbol` stands for: 1000
+    /// for "1000PEPEUSDT", 1 for unscaled symbols.
+    pub fn multiplier(&self, symbol: &str) -> f64 {
+        self.multipliers.get(symbol).copied().unwrap_or(1.0)
+    }
+
+    /// Whether any registered symbol is scaled, so callers can skip the
+    /// per-symbol `multiplier` lookup.
+    pub fn has_multipliers(&self) -> bool {
+        !self.multipliers.is_empty()
     }
 
     /// Resolve a config-style symbol name (e.g. "PERP_BTC_USDT", "SPOT_ETH_USDC")
     /// to a SymbolId. Strips the PERP_/SPOT_ prefix and infers the instrument type.
     /// Logs a warning on miss so silent failures don't happen.
     pub fn resolve(&self, name: &str) -> Option<SymbolId> {
-        let (itype, key) = if let Some(rest) = name.strip_prefix("PERP_") {
-            (InstrumentType::Perp, rest)
-        } else if let Some(rest) = name.strip_prefix("SPOT_") {
-            (InstrumentType::Spot, rest)
-        } else {
-            (InstrumentType::Perp, name)
-        };
+        let prefixed = name.split_once('_').and_then(|(prefix, rest)| match InstrumentType::from_prefix(prefix)? {
+            itype @ (InstrumentType::Spot | InstrumentType::Perp) => Some((itype, rest)),
+            _ => None,
+        });
+        let (itype, key) = prefixed.unwrap_or((InstrumentType::Perp, name));
         match self.lookup(key, &itype) {
             Some(&id) => Some(id),
             None => {
@@ -178,8 +523,72 @@ fn generate_aliases(base: &str, quote: &str, instrument: &InstrumentType) -> Vec
     }
 }
 
-// Static registry - loads on first access, merging symbols.yaml + seeded extras
-pub static REGISTRY: Lazy<SymbolRegistry> = Lazy::new(|| {
+static INSTALLED: OnceLock<SymbolRegistry> = OnceLock::new();
+
+/// Use `registry` as `REGISTRY` instead of loading symbols.yaml, e.g. one
+/// built with `from_app_config` when embedding the crate. Ids index every
+/// collection's slots, so there is one registry per process: this must run
+/// before anything touches `REGISTRY`, and hands `registry` back otherwise.
+///
+/// Also refused if collections already exist with fewer slots than
+/// `registry.id_bound()`.
+pub fn install(registry: SymbolRegistry) -> Result<(), SymbolRegistry> {
+    if CAPACITY.get().is_some_and(|&cap| registry.id_bound() > cap) {
+        return Err(registry);
+    }
+    INSTALLED.set(registry)
+}
+
+/// Build the registry from `cfg` and install it unless one is already in
+/// use, so feeds started from a config need no symbols.yaml or
+/// `SYMBOL_CONFIG`. The first config started wins; the registry can't
+/// change once feeds use it.
+pub fn install_from_app_config(cfg: &AppConfig) -> Result<(), String> {
+    if is_initialized() {
+        return Ok(());
+    }
+    let registry = SymbolRegistry::from_app_config(cfg)?;
+    if let Err(registry) = install(registry) {
+        // Lost a race with another start; either registry is fine to keep.
+        if !is_initialized() {
+            return Err(format!(
+                "config needs {} symbol ids but collections were created for {}; reserve_symbols first",
+                registry.id_bound(),
+                symbol_capacity()
+            ));
+        }
+    }
+    Ok(())
+}
+
+static CAPACITY: OnceLock<usize> = OnceLock::new();
+
+/// Slots every per-symbol collection allocates, so the largest id + 1 any
+/// registry may use in this process. Fixed when the first collection is
+/// built: the larger of `MAX_SYMBOLS`, the installed registry's
+/// `id_bound()` and `reserve_symbols`. Install the registry (or reserve)
+/// first for universes beyond `MAX_SYMBOLS`.
+pub fn symbol_capacity() -> usize {
+    *CAPACITY.get_or_init(|| INSTALLED.get().map_or(MAX_SYMBOLS, |r| r.id_bound().max(MAX_SYMBOLS)))
+}
+
+/// Make collections built from now on room for `n` ids. False if the
+/// capacity is already fixed below `n`.
+pub fn reserve_symbols(n: usize) -> bool {
+    let reserved = n.max(MAX_SYMBOLS).max(INSTALLED.get().map_or(0, SymbolRegistry::id_bound));
+    *CAPACITY.get_or_init(|| reserved) >= n
+}
+
+/// Whether `REGISTRY` is already fixed, by `install` or first access.
+pub fn is_initialized() -> bool {
+    INSTALLED.get().is_some()
+}
+
+// Static registry - the installed one, else loaded on first access from
+// symbols.yaml + seeded extras
+pub static REGISTRY: Lazy<&'static SymbolRegistry> = Lazy::new(|| INSTALLED.get_or_init(load_default));
+
+fn load_default() -> SymbolRegistry {
     let default_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("configs/symbols.yaml");
 
     let path = std::env::var("SYMBOL_CONFIG")
@@ -189,6 +598,148 @@ pub static REGISTRY: Lazy<SymbolRegistry> = Lazy::new(|| {
         .and_then(|mut guard| guard.take())
         .unwrap_or_default();
 
-    SymbolRegistry::from_config_with_extras(&path, &extra)
-        .unwrap_or_else(|e| panic!("Failed to load symbol registry from '{}': {}", path, e))
-});
+    let registry = SymbolRegistry::from_config_with_extras(&path, &extra).unwrap_or_else(|e| {
+        panic!("Failed to load symbol registry from '{}' (or install one first): {}", path, e)
+    });
+    if let Some(&cap) = CAPACITY.get() {
+        if registry.id_bound() > cap {
+            panic!("'{}' needs {} symbol ids but collections were built for {}; touch REGISTRY or call reserve_symbols before creating them", path, registry.id_bound(), cap);
+        }
+    }
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_and_scaled_bases() {
+        let bases = ["BTC".to_string(), "1000pepe".to_string(), "PEPE".to_string()];
+        let reg = SymbolRegistry::from_bases(&bases).unwrap();
+        let btc = reg.lookup("BTC_USD", &InstrumentType::Spot).copied();
+        assert!(btc.is_some());
+        assert_eq!(reg.lookup("XBT/USD", &InstrumentType::Spot).copied(), btc);
+        assert_eq!(reg.multiplier("XBTUSD"), 1.0);
+
+        let pepe = reg.lookup("PEPEUSDT", &InstrumentType::Perp).copied();
+        assert_eq!(reg.lookup("1000PEPEUSDT", &InstrumentType::Perp).copied(), pepe);
+        assert_eq!(reg.get_symbol(pepe.unwrap()), Some("PERP-PEPE-USDT"));
+        assert_eq!(reg.multiplier("1000PEPE_USDT"), 1000.0);
+        assert_eq!(reg.multiplier("PEPE_USDT"), 1.0);
+        assert!(reg.has_multipliers());
+        assert_eq!(reg.canonical_base("1000PEPE"), "PEPE");
+        // 1000PEPE folded into PEPE: two bases registered.
+        assert!(reg.get_symbol(2 * QUOTE_CURRENCIES.len() * INSTRUMENT_TYPES.len()).is_none());
+
+        let bad = Equivalence { alias: "X".into(), base: "BTC".into(), multiplier: 0.0 };
+        assert!(SymbolRegistry::from_bases_with_equivalents(&bases, &[bad]).is_err());
+    }
+
+    #[test]
+    fn test_large_universe_without_cap() {
+        let bases: Vec<String> = (0..200).map(|i| format!("COIN{}", i)).collect();
+        let reg = SymbolRegistry::from_bases(&bases).unwrap();
+        let n = 200 * QUOTE_CURRENCIES.len() * INSTRUMENT_TYPES.len();
+        assert!(n > MAX_SYMBOLS);
+        assert_eq!((reg.len(), reg.id_bound()), (n, n));
+        assert_eq!(reg.get_symbol(n - 1), Some("PERP-COIN199-WETH"));
+        assert!(reg.get_symbol(n).is_none());
+    }
+
+    #[test]
+    fn test_id_map_keeps_ids_stable() {
+        let path = std::env::temp_dir().join(format!("symbol_ids_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let usd = ["USD".to_string()];
+        let lookup = |reg: &SymbolRegistry, name: &str| reg.lookup(name, &InstrumentType::Spot).copied();
+
+        let mut first = SymbolRegistry::from_parts(&["ETH".to_string()], &usd, &[]).unwrap();
+        first.apply_id_map(&path).unwrap();
+        assert_eq!(lookup(&first, "ETHUSD"), Some(0));
+
+        // BTC sorts ahead of ETH and would otherwise take its ids.
+        let mut second = SymbolRegistry::from_parts(&["BTC".to_string(), "ETH".to_string()], &usd, &[]).unwrap();
+        assert_eq!(lookup(&second, "ETHUSD"), Some(2));
+        second.apply_id_map(&path).unwrap();
+        assert_eq!(lookup(&second, "ETHUSD"), Some(0));
+        assert_eq!(second.get_symbol(0), Some("SPOT-ETH-USD"));
+        assert_eq!(lookup(&second, "BTC/USD"), Some(2));
+        assert_eq!(second.get_symbol(3), Some("PERP-BTC-USD"));
+
+        // Dropping ETH leaves its ids reserved.
+        let mut third = SymbolRegistry::from_parts(&["BTC".to_string()], &usd, &[]).unwrap();
+        third.apply_id_map(&path).unwrap();
+        assert_eq!(third.iter().map(|(id, _)| id).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(third.len(), 2);
+
+        std::fs::write(&path, r#"[{"id": 0, "symbol": "SPOT-BTC-USD"}, {"id": 0, "symbol": "PERP-BTC-USD"}]"#).unwrap();
+        assert!(third.apply_id_map(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_canonical_symbol_parts() {
+        let sym = CanonicalSymbol::parse("PERP-ETH-USDC").unwrap();
+        assert!(matches!(sym.itype, InstrumentType::Perp));
+        assert_eq!((sym.base.as_str(), sym.quote.as_str()), ("ETH", "USDC"));
+        assert_eq!(sym.format(), "PERP-ETH-USDC");
+        assert_eq!(sym.pair(), "ETH_USDC");
+        assert_eq!(CanonicalSymbol::parse("SPOT_BTC_USDT").unwrap().format(), "SPOT-BTC-USDT");
+        assert!(CanonicalSymbol::parse("ETH-USDC").is_err());
+        assert!(CanonicalSymbol::parse("SWAP-ETH-USDC").is_err());
+
+        let pair = CanonicalSymbol::parse_pair("SPOT_BTC_USDT", InstrumentType::Perp).unwrap();
+        assert_eq!(pair.format(), "PERP-BTC-USDT");
+        assert_eq!(CanonicalSymbol::parse_pair("1000PEPE_USDT", InstrumentType::Perp).unwrap().base, "1000PEPE");
+        assert!(CanonicalSymbol::parse_pair("BTCUSDT", InstrumentType::Spot).is_err());
+        assert!(CanonicalSymbol::parse_pair("BTC_", InstrumentType::Spot).is_err());
+
+        let reg = SymbolRegistry::from_parts(&["BTC".to_string()], &["USD".to_string()], &[]).unwrap();
+        assert_eq!(reg.canonical(1).unwrap().pair(), "BTC_USD");
+        assert_eq!(reg.resolve("SPOT_BTC_USD"), Some(0));
+        assert_eq!(reg.resolve("BTC_USD"), Some(1));
+    }
+
+    #[test]
+    fn test_enumeration_and_json() {
+        let reg = SymbolRegistry::from_parts(&["BTC".to_string()], &["USD".to_string()], &Equivalence::defaults()).unwrap();
+        assert_eq!(reg.len(), 2);
+        assert!(!reg.is_empty());
+        let symbols: Vec<_> = reg.iter().collect();
+        assert_eq!(symbols, [(0, "SPOT-BTC-USD"), (1, "PERP-BTC-USD")]);
+        assert_eq!(reg.aliases_for(0), ["BTC-USD", "BTC/USD", "BTCUSD", "BTC_USD", "XBT-USD", "XBT/USD", "XBTUSD", "XBT_USD"]);
+        assert!(reg.aliases_for(2).is_empty());
+        assert!(reg.aliases_for(MAX_SYMBOLS).is_empty());
+
+        let v: serde_json::Value = serde_json::from_str(&reg.to_json().unwrap()).unwrap();
+        assert_eq!(v["quotes"], serde_json::json!(["USD"]));
+        assert_eq!(v["symbols"][1]["symbol"], "PERP-BTC-USD");
+        assert_eq!(v["symbols"][1]["aliases"].as_array().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_configured_quotes() {
+        let quotes = ["eur".to_string(), "KRW".to_string(), "BTC".to_string(), "EUR".to_string()];
+        let reg = SymbolRegistry::from_parts(&["ETH".to_string()], &quotes, &[]).unwrap();
+        assert_eq!(reg.quotes(), ["EUR", "KRW", "BTC"]);
+        let eth_btc = reg.lookup("ETHBTC", &InstrumentType::Spot).copied().unwrap();
+        assert_eq!(reg.get_symbol(eth_btc), Some("SPOT-ETH-BTC"));
+        assert_eq!(reg.lookup("ETH/EUR", &InstrumentType::Spot).copied(), Some(0));
+        assert!(reg.lookup("ETHUSDT", &InstrumentType::Spot).is_none());
+        assert!(SymbolRegistry::from_parts(&["ETH".to_string()], &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_from_bases_without_file() {
+        let reg = SymbolRegistry::from_bases(&["btc".to_string(), "ETH".to_string(), "BTC".to_string()]).unwrap();
+        let btc = *reg.lookup("BTC_USDT", &InstrumentType::Spot).unwrap();
+        assert_eq!(reg.lookup("BTCUSDT", &InstrumentType::Spot), Some(&btc));
+        assert_eq!(reg.get_symbol(btc), Some("SPOT-BTC-USDT"));
+        assert!(reg.lookup("ETH-USDC", &InstrumentType::Perp).is_some());
+        assert!(reg.lookup("SOL_USDT", &InstrumentType::Spot).is_none());
+        // Two bases, every quote, spot and perp.
+        assert!(reg.get_symbol(2 * QUOTE_CURRENCIES.len() * INSTRUMENT_TYPES.len() - 1).is_some());
+        assert!(reg.get_symbol(2 * QUOTE_CURRENCIES.len() * INSTRUMENT_TYPES.len()).is_none());
+    }
+}
@@ -0,0 +1,263 @@
+//! Derived cross-pair quotes.
+//!
+//! A synthetic pair BASE/QUOTE is built on one venue from two legs against
+//! a common currency, e.g. SOL/ETH from SOL/USDT and ETH/USDT. The result
+//! is pushed into that venue's collection under the target symbol, so
+//! consumers read it like any other quote. The target slot must not also
+//! be fed directly; venues whose spot or perp config subscribes the target
+//! are skipped at load time.
+
+use crate::app_config::AppConfig;
+use crate::market_data::{AllMarketData, Exchange, InstrumentType, MarketData};
+use crate::symbol_registry::{REGISTRY, SymbolId, SymbolRegistry};
+use anyhow::{Result, anyhow};
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+fn default_poll_interval_ms() -> u64 {
+    50
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyntheticConfig {
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    pub pairs: Vec<SyntheticPair>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyntheticPair {
+    /// Config-style target, e.g. "SPOT_SOL_ETH". Must be a registry symbol.
+    pub symbol: String,
+    /// Common leg currency, e.g. "USDT": legs are SOL_USDT and ETH_USDT.
+    pub via: String,
+    /// Venues to synthesize on. Empty means every venue.
+    #[serde(default)]
+    pub venues: Vec<String>,
+}
+
+/// Resolved target and legs on one venue.
+#[derive(Debug, Clone, Copy)]
+pub struct SyntheticSeries {
+    pub exchange: Exchange,
+    pub target: SymbolId,
+    pub base_leg: SymbolId,
+    pub quote_leg: SymbolId,
+}
+
+impl SyntheticPair {
+    /// Config-style leg names: ("SPOT_SOL_USDT", "SPOT_ETH_USDT") for
+    /// target "SPOT_SOL_ETH" via "USDT".
+    pub fn leg_names(&self) -> Option<(String, String)> {
+        let (prefix, pair) = match self.symbol.split_once('_') {
+            Some((p, rest)) if p == "SPOT" || p == "PERP" => (format!("{}_", p), rest),
+            _ => (String::new(), self.symbol.as_str()),
+        };
+        let (base, quote) = pair.split_once('_')?;
+        let via = self.via.to_uppercase();
+        Some((format!("{}{}_{}", prefix, base, via), format!("{}{}_{}", prefix, quote, via)))
+    }
+}
+
+impl SyntheticConfig {
+    pub fn series(&self) -> Result<Vec<SyntheticSeries>> {
+        let mut out = Vec::new();
+        for pair in &self.pairs {
+            let (base_name, quote_name) = pair
+                .leg_names()
+                .ok_or_else(|| anyhow!("synthetic: cannot split '{}' into BASE_QUOTE", pair.symbol))?;
+            let (Some(target), Some(base_leg), Some(quote_leg)) = (
+                REGISTRY.resolve(&pair.symbol),
+                REGISTRY.resolve(&base_name),
+                REGISTRY.resolve(&quote_name),
+            ) else {
+                warn!("synthetic: {} via {} has unknown symbols, skipping", pair.symbol, pair.via);
+                continue;
+            };
+            let venues: Vec<Exchange> = if pair.venues.is_empty() {
+                Exchange::ALL.iter().filter(|e| !e.is_oracle()).copied().collect()
+            } else {
+                pair.venues
+                    .iter()
+                    .filter_map(|v| {
+                        let e = v.parse::<Exchange>().ok();
+                        if e.is_none() {
+                            warn!("synthetic: unknown venue '{}' for {}", v, pair.symbol);
+                        }
+                        e
+                    })
+                    .collect()
+            };
+            out.extend(venues.into_iter().map(|exchange| SyntheticSeries { exchange, target, base_leg, quote_leg }));
+        }
+        Ok(out)
+    }
+
+    /// `series` minus those whose target `app` also subscribes directly on
+    /// the same venue, which would give the slot a second writer.
+    pub fn series_for(&self, app: &AppConfig) -> Result<Vec<SyntheticSeries>> {
+        let direct = direct_subscriptions(app, &REGISTRY);
+        let mut series = self.series()?;
+        series.retain(|s| {
+            let clash = direct.contains(&(s.exchange, s.target));
+            if clash {
+                warn!(
+                    "synthetic: {} is subscribed directly on {}, not synthesizing there",
+                    REGISTRY.get_symbol(s.target).unwrap_or("?"),
+                    s.exchange.as_str()
+                );
+            }
+            !clash
+        });
+        Ok(series)
+    }
+}
+
+/// Every (venue, id) the `spot` and `perp` sections of `app` subscribe.
+pub fn direct_subscriptions(app: &AppConfig, registry: &SymbolRegistry) -> HashSet<(Exchange, SymbolId)> {
+    let sections = [(&app.spot, InstrumentType::Spot), (&app.perp, InstrumentType::Perp)];
+    let mut out = HashSet::new();
+    for (section, itype) in sections {
+        for (venue, symbols) in section {
+            let Ok(exchange) = venue.parse::<Exchange>() else { continue };
+            out.extend(symbols.iter().filter_map(|s| registry.lookup(s, &itype)).map(|id| (exchange, *id)));
+        }
+    }
+    out
+}
+
+/// Cross quote from BASE/VIA and QUOTE/VIA legs. Selling BASE for QUOTE
+/// hits the base bid and lifts the quote ask, so bid = base_bid / quote_ask
+/// and ask = base_ask / quote_bid. Sizes (in BASE) are limited by whichever
+/// leg is thinner. Timestamps are the older leg's, so staleness checks see
+/// the weakest input.
+pub fn synthesize(base: &MarketData, quote: &MarketData) -> Option<MarketData> {
+    let (base_bid, base_ask) = (base.bid?, base.ask?);
+    let (quote_bid, quote_ask) = (quote.bid?, quote.ask?);
+    if base_bid <= 0.0 || base_ask <= 0.0 || quote_bid <= 0.0 || quote_ask <= 0.0 {
+        return None;
+    }
+    let bid_qty = match (base.bid_qty, quote.ask_qty) {
+        (Some(b), Some(q)) => Some(b.min(q * quote_ask / base_bid)),
+        _ => None,
+    };
+    let ask_qty = match (base.ask_qty, quote.bid_qty) {
+        (Some(b), Some(q)) => Some(b.min(q * quote_bid / base_ask)),
+        _ => None,
+    };
+    let older = |a: Option<_>, b: Option<_>| match (a, b) {
+        (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+        _ => None,
+    };
+    Some(MarketData {
+        bid: Some(base_bid / quote_ask),
+        ask: Some(base_ask / quote_bid),
+        bid_qty,
+        ask_qty,
+        exchange_ts_raw: older(base.exchange_ts_raw, quote.exchange_ts_raw),
+        received_ts: older(base.received_ts, quote.received_ts),
+        received_instant: older(base.received_instant, quote.received_instant),
+        ..Default::default()
+    })
+}
+
+/// Republish synthetic quotes whenever either leg ticks. `series` should
+/// come from `SyntheticConfig::series_for` so no target is fed directly.
+pub async fn run_synthesis(
+    series: Vec<SyntheticSeries>,
+    poll_interval_ms: u64,
+    market_data: Arc<AllMarketData>,
+    shutdown: Arc<Notify>,
+) -> Result<()> {
+    let mut series: Vec<(SyntheticSeries, u64, u64)> = series.into_iter().map(|s| (s, 0, 0)).collect();
+    info!("synthetic: {} series", series.len());
+
+    let mut interval = time::interval(Duration::from_millis(poll_interval_ms.max(1)));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = interval.tick() => {}
+        }
+        for (s, seen_base, seen_quote) in series.iter_mut() {
+            let coll = market_data.get_collection(&s.exchange);
+            let (wc_base, wc_quote) = (coll.write_count(&s.base_leg), coll.write_count(&s.quote_leg));
+            if wc_base == *seen_base && wc_quote == *seen_quote {
+                continue;
+            }
+            *seen_base = wc_base;
+            *seen_quote = wc_quote;
+            let (Some(base), Some(quote)) = (coll.latest(&s.base_leg), coll.latest(&s.quote_leg)) else {
+                continue;
+            };
+            if let Some(md) = synthesize(&base, &quote) {
+                coll.push(&s.target, md);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_synthesize_cross() {
+        let t0 = Instant::now();
+        let sol = MarketData {
+            bid: Some(150.0),
+            ask: Some(150.3),
+            bid_qty: Some(100.0),
+            ask_qty: Some(2.0),
+            received_instant: Some(t0),
+            ..Default::default()
+        };
+        let eth = MarketData {
+            bid: Some(3000.0),
+            ask: Some(3001.0),
+            bid_qty: Some(10.0),
+            ask_qty: Some(1.0),
+            received_instant: Some(t0 + Duration::from_millis(5)),
+            ..Default::default()
+        };
+        let x = synthesize(&sol, &eth).unwrap();
+        assert!((x.bid.unwrap() - 150.0 / 3001.0).abs() < 1e-12);
+        assert!((x.ask.unwrap() - 150.3 / 3000.0).abs() < 1e-12);
+        assert!(x.bid.unwrap() < x.ask.unwrap());
+        // Bid side limited by 1 ETH on the quote ask ≈ 20 SOL.
+        assert!((x.bid_qty.unwrap() - 3001.0 / 150.0).abs() < 1e-9);
+        // Ask side limited by 2 SOL on the base ask.
+        assert_eq!(x.ask_qty, Some(2.0));
+        assert_eq!(x.received_instant, Some(t0));
+
+        assert!(synthesize(&sol, &MarketData::default()).is_none());
+    }
+
+    #[test]
+    fn test_leg_names() {
+        let pair = SyntheticPair { symbol: "SPOT_SOL_ETH".into(), via: "usdt".into(), venues: vec![] };
+        assert_eq!(pair.leg_names(), Some(("SPOT_SOL_USDT".into(), "SPOT_ETH_USDT".into())));
+        let pair = SyntheticPair { symbol: "SOL_ETH".into(), via: "USDC".into(), venues: vec![] };
+        assert_eq!(pair.leg_names(), Some(("SOL_USDC".into(), "ETH_USDC".into())));
+    }
+
+    #[test]
+    fn test_direct_subscriptions_from_config() {
+        let registry = SymbolRegistry::from_bases(&["SOL".to_string(), "ETH".to_string()]).unwrap();
+        let app: AppConfig = serde_yaml::from_str("spot: {binance: [SOL_ETH, SOL_USDT]}\nperp: {bybit: [SOL_ETH]}").unwrap();
+        let direct = direct_subscriptions(&app, &registry);
+        let spot = *registry.lookup("SOL_ETH", &InstrumentType::Spot).unwrap();
+        let perp = *registry.lookup("SOL_ETH", &InstrumentType::Perp).unwrap();
+        assert!(direct.contains(&(Exchange::Binance, spot)));
+        assert!(direct.contains(&(Exchange::Bybit, perp)));
+        assert!(!direct.contains(&(Exchange::Okx, spot)));
+        assert!(!direct.contains(&(Exchange::Bybit, spot)));
+        assert_eq!(direct.len(), 3);
+    }
+}
@@ -1,13 +1,17 @@
+use crate::event_bus::MarketEvent;
 use crate::market_data::{ClockCorrectionConfig, DataSink, Exchange, FeedItem};
 use crate::ring_buffer::RingBuffer;
-use crate::symbol_registry::{MAX_SYMBOLS, SymbolId};
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use crate::trade_flow::{TradeFlow, TradeFlowConfig};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use std::sync::atomic::{AtomicI64, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[repr(u8)]
 pub enum TradeSide {
     #[default]
@@ -16,7 +20,8 @@ pub enum TradeSide {
     Sell = 2,
 }
 
-#[derive(Debug, Copy, Clone)]
+/// JSON shape documented in docs/schema.md.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct TradeData {
     pub price: f64,
     pub qty: f64,
@@ -24,6 +29,7 @@ pub struct TradeData {
     pub exchange_ts_raw: Option<DateTime<Utc>>,
     pub exchange_ts: Option<DateTime<Utc>>,
     pub received_ts: Option<DateTime<Utc>>,
+    #[serde(skip)]
     pub received_instant: Option<Instant>,
     pub feed_latency_ns: u64,
 }
@@ -50,6 +56,13 @@ impl FeedItem for TradeData {
     fn set_feed_latency_ns(&mut self, ns: u64) {
         self.feed_latency_ns = ns;
     }
+    fn to_event(&self, exchange: Exchange, id: SymbolId) -> Option<MarketEvent> {
+        Some(MarketEvent::Trade { exchange, id, data: *self })
+    }
+    fn rescale(&mut self, multiplier: f64) {
+        self.price /= multiplier;
+        self.qty *= multiplier;
+    }
 }
 
 struct TradeSlot {
@@ -61,6 +74,8 @@ struct TradeSlot {
 pub struct TradeDataCollection {
     slots: Box<[TradeSlot]>,
     clock_config: ClockCorrectionConfig,
+    /// Set once by `AllTradeData::install_flow`.
+    flow: OnceLock<Arc<TradeFlow>>,
 }
 
 impl std::fmt::Debug for TradeDataCollection {
@@ -73,8 +88,9 @@ impl std::fmt::Debug for TradeDataCollection {
 
 impl TradeDataCollection {
     pub fn new(clock_config: ClockCorrectionConfig) -> Self {
-        let mut slots = Vec::with_capacity(MAX_SYMBOLS);
-        for _ in 0..MAX_SYMBOLS {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
             slots.push(TradeSlot {
                 ring: OnceLock::new(),
                 clock_offset_ewma_ns: AtomicI64::new(0),
@@ -84,6 +100,7 @@ impl TradeDataCollection {
         Self {
             slots: slots.into_boxed_slice(),
             clock_config,
+            flow: OnceLock::new(),
         }
     }
 
@@ -117,6 +134,9 @@ impl TradeDataCollection {
 
         let ring = slot.ring.get_or_init(|| Box::new(RingBuffer::new()));
         ring.push(trade);
+        if let Some(flow) = self.flow.get() {
+            flow.record(id, &trade);
+        }
     }
 
     pub fn latest(&self, id: &SymbolId) -> Option<TradeData> {
@@ -130,6 +150,11 @@ impl TradeDataCollection {
     pub fn get_buffer(&self, id: &SymbolId) -> Option<&RingBuffer<TradeData>> {
         self.slots[*id].ring.get().map(|b| b.as_ref())
     }
+
+    /// Rolling volume/VWAP windows, once installed.
+    pub fn flow(&self) -> Option<&Arc<TradeFlow>> {
+        self.flow.get()
+    }
 }
 
 impl DataSink<TradeData> for TradeDataCollection {
@@ -159,6 +184,16 @@ pub struct AllTradeData {
     pub zeroone: Arc<TradeDataCollection>,
     pub risex: Arc<TradeDataCollection>,
     pub bulk: Arc<TradeDataCollection>,
+    pub pyth: Arc<TradeDataCollection>,
+    pub chainlink: Arc<TradeDataCollection>,
+    pub dydx: Arc<TradeDataCollection>,
+    pub deribit: Arc<TradeDataCollection>,
+    pub gateio: Arc<TradeDataCollection>,
+    pub htx: Arc<TradeDataCollection>,
+    pub bitfinex: Arc<TradeDataCollection>,
+    pub upbit: Arc<TradeDataCollection>,
+    pub phemex: Arc<TradeDataCollection>,
+    pub vertex: Arc<TradeDataCollection>,
 }
 
 impl std::fmt::Debug for AllTradeData {
@@ -191,6 +226,16 @@ impl AllTradeData {
             (ZeroOne, &self.zeroone),
             (RiseX, &self.risex),
             (Bulk, &self.bulk),
+            (Pyth, &self.pyth),
+            (Chainlink, &self.chainlink),
+            (Dydx, &self.dydx),
+            (Deribit, &self.deribit),
+            (Gateio, &self.gateio),
+            (Htx, &self.htx),
+            (Bitfinex, &self.bitfinex),
+            (Upbit, &self.upbit),
+            (Phemex, &self.phemex),
+            (Vertex, &self.vertex),
         ]
         .into_iter()
     }
@@ -217,6 +262,16 @@ impl AllTradeData {
             Exchange::ZeroOne => &self.zeroone,
             Exchange::RiseX => &self.risex,
             Exchange::Bulk => &self.bulk,
+            Exchange::Pyth => &self.pyth,
+            Exchange::Chainlink => &self.chainlink,
+            Exchange::Dydx => &self.dydx,
+            Exchange::Deribit => &self.deribit,
+            Exchange::Gateio => &self.gateio,
+            Exchange::Htx => &self.htx,
+            Exchange::Bitfinex => &self.bitfinex,
+            Exchange::Upbit => &self.upbit,
+            Exchange::Phemex => &self.phemex,
+            Exchange::Vertex => &self.vertex,
         }
     }
 
@@ -224,6 +279,20 @@ impl AllTradeData {
         Self::with_clock_correction(ClockCorrectionConfig::default())
     }
 
+    /// Track rolling volume and VWAP on every venue's prints from now on.
+    /// Only the first call takes effect.
+    pub fn install_flow(&self, cfg: &TradeFlowConfig) {
+        for (_, coll) in self.iter() {
+            let _ = coll.flow.set(Arc::new(TradeFlow::new(cfg)));
+        }
+    }
+
+    /// Order-flow imbalance (buy minus sell volume) for `id` on `exchange`
+    /// over one of the installed `trade_flow` windows.
+    pub fn get_ofi(&self, exchange: &Exchange, id: &SymbolId, window: Duration) -> Option<f64> {
+        self.get_collection(exchange).flow()?.ofi(id, window)
+    }
+
     pub fn with_clock_correction(clock_config: ClockCorrectionConfig) -> Self {
         let new_coll = || Arc::new(TradeDataCollection::new(clock_config.clone()));
         Self {
@@ -247,6 +316,16 @@ impl AllTradeData {
             zeroone: new_coll(),
             risex: new_coll(),
             bulk: new_coll(),
+            pyth: new_coll(),
+            chainlink: new_coll(),
+            dydx: new_coll(),
+            deribit: new_coll(),
+            gateio: new_coll(),
+            htx: new_coll(),
+            bitfinex: new_coll(),
+            upbit: new_coll(),
+            phemex: new_coll(),
+            vertex: new_coll(),
         }
     }
 }
@@ -0,0 +1,240 @@
+//! Rolling traded volume and VWAP per symbol.
+//!
+//! Each configured window is a ring of fixed-width buckets updated as
+//! prints are pushed into a `TradeDataCollection`, so a read sums at most
+//! `window / bucket` buckets instead of re-scanning trades. Windows are
+//! aligned to bucket boundaries: a 60s window with 1s buckets covers the
+//! current (partial) second and the 59 before it. Prints are bucketed by
+//! receive time, the same clock reads are taken against. The same windows
+//! give the trade order-flow imbalance, aggressor buy minus sell volume.
+
+use crate::symbol_registry::{SymbolId, symbol_capacity};
+use crate::trade_data::{TradeData, TradeSide};
+use chrono::Utc;
+use serde::Deserialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+fn default_windows_s() -> Vec<u64> {
+    vec![60, 300]
+}
+fn default_bucket_ms() -> u64 {
+    1000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeFlowConfig {
+    /// Window lengths in seconds.
+    #[serde(default = "default_windows_s")]
+    pub windows_s: Vec<u64>,
+    /// Bucket width; sets how finely windows roll.
+    #[serde(default = "default_bucket_ms")]
+    pub bucket_ms: u64,
+}
+
+impl Default for TradeFlowConfig {
+    fn default() -> Self {
+        Self {
+            windows_s: default_windows_s(),
+            bucket_ms: default_bucket_ms(),
+        }
+    }
+}
+
+/// Aggregate of the prints in one window.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FlowStats {
+    /// Base-unit volume.
+    pub volume: f64,
+    /// Quote-unit volume (sum of price * qty).
+    pub notional: f64,
+    /// Aggressor-buy and aggressor-sell volume; prints with an unknown
+    /// side count in `volume` only.
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub trades: u64,
+}
+
+impl FlowStats {
+    pub fn vwap(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.notional / self.volume)
+    }
+
+    /// Order-flow imbalance: signed volume, buy minus sell.
+    pub fn ofi(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+
+    /// `ofi` as a share of signed volume, in [-1, 1]. None without any
+    /// prints of known side.
+    pub fn ofi_ratio(&self) -> Option<f64> {
+        let signed = self.buy_volume + self.sell_volume;
+        (signed > 0.0).then(|| self.ofi() / signed)
+    }
+
+    fn add(&mut self, other: &FlowStats) {
+        self.volume += other.volume;
+        self.notional += other.notional;
+        self.buy_volume += other.buy_volume;
+        self.sell_volume += other.sell_volume;
+        self.trades += other.trades;
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    /// `ts_ms / bucket_ms` of the prints held; stale buckets are reset.
+    epoch: i64,
+    stats: FlowStats,
+}
+
+struct FlowWindow {
+    bucket_ms: i64,
+    buckets: Box<[Bucket]>,
+}
+
+impl FlowWindow {
+    fn new(window_ms: i64, bucket_ms: i64) -> Self {
+        let n = (window_ms / bucket_ms).max(1) as usize;
+        Self {
+            bucket_ms,
+            buckets: vec![Bucket::default(); n].into_boxed_slice(),
+        }
+    }
+
+    fn add(&mut self, ts_ms: i64, price: f64, qty: f64, side: TradeSide) {
+        let epoch = ts_ms.div_euclid(self.bucket_ms);
+        let n = self.buckets.len() as i64;
+        let bucket = &mut self.buckets[epoch.rem_euclid(n) as usize];
+        if bucket.epoch > epoch {
+            // Older than the window already rolled past.
+            return;
+        }
+        if bucket.epoch < epoch {
+            *bucket = Bucket { epoch, stats: FlowStats::default() };
+        }
+        let s = &mut bucket.stats;
+        s.volume += qty;
+        s.notional += price * qty;
+        match side {
+            TradeSide::Buy => s.buy_volume += qty,
+            TradeSide::Sell => s.sell_volume += qty,
+            TradeSide::Unknown => {}
+        }
+        s.trades += 1;
+    }
+
+    fn stats(&self, now_ms: i64) -> FlowStats {
+        let now_epoch = now_ms.div_euclid(self.bucket_ms);
+        let oldest = now_epoch - self.buckets.len() as i64 + 1;
+        let mut out = FlowStats::default();
+        for b in self.buckets.iter().filter(|b| b.epoch >= oldest && b.epoch <= now_epoch) {
+            out.add(&b.stats);
+        }
+        out
+    }
+}
+
+/// Rolling windows for every symbol of one venue's trade stream.
+pub struct TradeFlow {
+    windows_ms: Vec<i64>,
+    bucket_ms: i64,
+    slots: Box<[OnceLock<Mutex<Vec<FlowWindow>>>]>,
+}
+
+impl TradeFlow {
+    pub fn new(cfg: &TradeFlowConfig) -> Self {
+        let n = symbol_capacity();
+        let mut slots = Vec::with_capacity(n);
+        for _ in 0..n {
+            slots.push(OnceLock::new());
+        }
+        Self {
+            windows_ms: cfg.windows_s.iter().map(|s| *s as i64 * 1000).collect(),
+            bucket_ms: cfg.bucket_ms.max(1) as i64,
+            slots: slots.into_boxed_slice(),
+        }
+    }
+
+    pub fn windows(&self) -> Vec<Duration> {
+        self.windows_ms.iter().map(|ms| Duration::from_millis(*ms as u64)).collect()
+    }
+
+    pub fn record(&self, id: &SymbolId, trade: &TradeData) {
+        if !(trade.qty > 0.0 && trade.price > 0.0) {
+            return;
+        }
+        let ts_ms = trade.received_ts.unwrap_or_else(Utc::now).timestamp_millis();
+        let slot = self.slots[*id].get_or_init(|| {
+            Mutex::new(self.windows_ms.iter().map(|w| FlowWindow::new(*w, self.bucket_ms)).collect())
+        });
+        for w in slot.lock().unwrap().iter_mut() {
+            w.add(ts_ms, trade.price, trade.qty, trade.side);
+        }
+    }
+
+    /// Stats over `window` ending now. None if `window` is not configured
+    /// or the symbol has had no prints.
+    pub fn stats(&self, id: &SymbolId, window: Duration) -> Option<FlowStats> {
+        self.stats_at(id, window, Utc::now().timestamp_millis())
+    }
+
+    /// Order-flow imbalance over `window` ending now; None as for `stats`.
+    pub fn ofi(&self, id: &SymbolId, window: Duration) -> Option<f64> {
+        self.stats(id, window).map(|s| s.ofi())
+    }
+
+    pub fn stats_at(&self, id: &SymbolId, window: Duration, now_ms: i64) -> Option<FlowStats> {
+        let idx = self.windows_ms.iter().position(|w| *w == window.as_millis() as i64)?;
+        let windows = self.slots[*id].get()?.lock().unwrap();
+        Some(windows[idx].stats(now_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn trade(ts_ms: i64, price: f64, qty: f64, side: TradeSide) -> TradeData {
+        TradeData {
+            price,
+            qty,
+            side,
+            received_ts: DateTime::from_timestamp_millis(ts_ms),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rolling_volume_and_vwap() {
+        let flow = TradeFlow::new(&TradeFlowConfig { windows_s: vec![60, 300], bucket_ms: 1000 });
+        let t0 = 1_700_000_000_000;
+        flow.record(&1, &trade(t0, 100.0, 1.0, TradeSide::Buy));
+        flow.record(&1, &trade(t0 + 40_000, 110.0, 3.0, TradeSide::Sell));
+        flow.record(&1, &trade(t0 + 90_000, 120.0, 1.0, TradeSide::Unknown));
+
+        let m1 = flow.stats_at(&1, Duration::from_secs(60), t0 + 90_000).unwrap();
+        assert_eq!(m1.trades, 2);
+        assert_eq!(m1.volume, 4.0);
+        assert_eq!(m1.sell_volume, 3.0);
+        assert_eq!(m1.vwap(), Some((330.0 + 120.0) / 4.0));
+
+        let m5 = flow.stats_at(&1, Duration::from_secs(300), t0 + 90_000).unwrap();
+        assert_eq!(m5.trades, 3);
+        assert_eq!(m5.buy_volume, 1.0);
+        assert_eq!(m5.vwap(), Some(550.0 / 5.0));
+        assert_eq!(m5.ofi(), -2.0);
+        assert_eq!(m5.ofi_ratio(), Some(-0.5));
+        assert_eq!(m1.ofi_ratio(), Some(-1.0));
+
+        // Everything has rolled out of the 1m window.
+        let later = flow.stats_at(&1, Duration::from_secs(60), t0 + 200_000).unwrap();
+        assert_eq!(later, FlowStats::default());
+        assert_eq!(later.vwap(), None);
+        assert_eq!(later.ofi_ratio(), None);
+
+        assert!(flow.stats_at(&1, Duration::from_secs(120), t0).is_none());
+        assert!(flow.stats_at(&2, Duration::from_secs(60), t0).is_none());
+    }
+}
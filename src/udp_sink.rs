@@ -0,0 +1,268 @@
+//! UDP publisher of quotes and trades.
+//!
+//! Takes every `Bbo` and `Trade` event off the event bus and sends it to a
+//! unicast or multicast address. In SBE (`sbe`, the default) messages are
+//! packed back to back into datagrams of at most `max_datagram` bytes; each
+//! starts with its SBE header, so receivers walk a datagram by
+//! `HEADER_LEN + block_length`. A datagram goes out as soon as the bus has
+//! nothing more queued, so packing only kicks in under bursts. JSON and
+//! MessagePack (`encoding`) send one `QuoteUpdate` / `TradeUpdate` per
+//! datagram. There is no retransmission: a receiver that needs gap-free
+//! data should watch `update_id`.
+
+use crate::encoding::{QuoteUpdate, TradeUpdate, UpdateEncoding};
+use crate::event_bus::{self, MarketEvent};
+use crate::sbe;
+use crate::symbol_registry::REGISTRY;
+use anyhow::{Context, Result, anyhow};
+use log::{info, warn};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::Notify;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UdpSinkConfig {
+    /// Destination, e.g. "239.1.1.1:7500" for multicast.
+    pub addr: String,
+    /// Local address to send from.
+    #[serde(default = "default_bind")]
+    pub bind: String,
+    /// Largest datagram sent; keep it under the path MTU.
+    #[serde(default = "default_max_datagram")]
+    pub max_datagram: usize,
+    /// Hops a multicast datagram may take.
+    #[serde(default = "default_multicast_ttl")]
+    pub multicast_ttl: u32,
+    /// `sbe`, `json` or `msgpack`.
+    #[serde(default)]
+    pub encoding: UdpEncoding,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UdpEncoding {
+    #[default]
+    Sbe,
+    Json,
+    Msgpack,
+}
+
+impl UdpEncoding {
+    /// The record encoding; None for SBE.
+    fn records(&self) -> Option<UpdateEncoding> {
+        match self {
+            UdpEncoding::Sbe => None,
+            UdpEncoding::Json => Some(UpdateEncoding::Json),
+            UdpEncoding::Msgpack => Some(UpdateEncoding::Msgpack),
+        }
+    }
+}
+
+fn default_bind() -> String {
+    "0.0.0.0:0".to_string()
+}
+
+fn default_max_datagram() -> usize {
+    1_400
+}
+
+fn default_multicast_ttl() -> u32 {
+    1
+}
+
+/// SBE size of `event`; None for events the schema has no template for.
+fn sbe_len(event: &MarketEvent) -> Option<usize> {
+    match event {
+        MarketEvent::Bbo { .. } => Some(sbe::QUOTE_LEN),
+        MarketEvent::Trade { .. } => Some(sbe::TRADE_LEN),
+        _ => None,
+    }
+}
+
+/// SBE messages packed back to back into one datagram buffer.
+struct Packer {
+    buf: Vec<u8>,
+    len: usize,
+}
+
+impl Packer {
+    fn new(max_datagram: usize) -> Self {
+        Self { buf: vec![0; max_datagram.max(sbe::QUOTE_LEN).max(sbe::TRADE_LEN)], len: 0 }
+    }
+
+    fn fits(&self, n: usize) -> bool {
+        self.len + n <= self.buf.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Append `event`; false if it has no template or doesn't fit.
+    fn push(&mut self, event: &MarketEvent) -> bool {
+        let out = &mut self.buf[self.len..];
+        let written = match event {
+            MarketEvent::Bbo { exchange, id, data } => sbe::encode_quote(out, *exchange, *id, data),
+            MarketEvent::Trade { exchange, id, data } => sbe::encode_trade(out, *exchange, *id, data),
+            _ => None,
+        };
+        match written {
+            Some(n) => {
+                self.len += n;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The packed datagram, leaving the packer empty.
+    fn take(&mut self) -> &[u8] {
+        let n = std::mem::take(&mut self.len);
+        &self.buf[..n]
+    }
+}
+
+/// `event` as a JSON or MessagePack record appended to `out`; false for
+/// events without a record type.
+fn encode_record(encoding: UpdateEncoding, event: &MarketEvent, out: &mut Vec<u8>) -> Result<bool> {
+    match event {
+        MarketEvent::Bbo { exchange, id, data } => {
+            let symbol = REGISTRY.get_symbol(*id).unwrap_or_default();
+            encoding.encode_into(&QuoteUpdate::new(exchange, symbol, data), out)?;
+        }
+        MarketEvent::Trade { exchange, id, data } => {
+            let symbol = REGISTRY.get_symbol(*id).unwrap_or_default();
+            encoding.encode_into(&TradeUpdate::new(exchange, symbol, data), out)?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+struct Publisher {
+    socket: UdpSocket,
+    dest: SocketAddr,
+    records: Option<UpdateEncoding>,
+    packer: Packer,
+    /// One encoded record, for JSON and MessagePack.
+    scratch: Vec<u8>,
+}
+
+impl Publisher {
+    async fn send(socket: &UdpSocket, dest: SocketAddr, datagram: &[u8]) {
+        if let Err(e) = socket.send_to(datagram, dest).await {
+            warn!("UDP sink send to {} failed: {}", dest, e);
+        }
+    }
+
+    async fn add(&mut self, event: &MarketEvent) {
+        let Some(encoding) = self.records else {
+            let Some(n) = sbe_len(event) else { return };
+            if !self.packer.fits(n) {
+                self.flush().await;
+            }
+            self.packer.push(event);
+            return;
+        };
+        self.scratch.clear();
+        match encode_record(encoding, event, &mut self.scratch) {
+            Ok(true) => Self::send(&self.socket, self.dest, &self.scratch).await,
+            Ok(false) => {}
+            Err(e) => warn!("UDP sink failed to encode event: {}", e),
+        }
+    }
+
+    async fn flush(&mut self) {
+        if !self.packer.is_empty() {
+            Self::send(&self.socket, self.dest, self.packer.take()).await;
+        }
+    }
+}
+
+/// Publish bus quotes and trades to `cfg.addr` until shutdown. Needs the
+/// event bus installed first.
+pub async fn run_udp_sink(cfg: UdpSinkConfig, shutdown: Arc<Notify>) -> Result<()> {
+    let mut rx = event_bus::subscribe().ok_or_else(|| anyhow!("UDP sink needs the event bus installed"))?;
+    let dest: SocketAddr = cfg.addr.parse().with_context(|| format!("invalid UDP sink addr {}", cfg.addr))?;
+    let socket = UdpSocket::bind(&cfg.bind).await.with_context(|| format!("failed to bind UDP sink on {}", cfg.bind))?;
+    if dest.ip().is_multicast() && dest.is_ipv4() {
+        socket.set_multicast_ttl_v4(cfg.multicast_ttl)?;
+    }
+    let mut publisher = Publisher {
+        socket,
+        dest,
+        records: cfg.encoding.records(),
+        packer: Packer::new(cfg.max_datagram),
+        scratch: Vec::with_capacity(256),
+    };
+    info!("UDP sink publishing {:?} to {}", cfg.encoding, dest);
+
+    loop {
+        let event = tokio::select! {
+            _ = shutdown.notified() => break,
+            ev = rx.recv() => ev,
+        };
+        match event {
+            Ok(event) => publisher.add(&event).await,
+            Err(RecvError::Lagged(n)) => warn!("UDP sink fell behind, skipped {} events", n),
+            Err(RecvError::Closed) => break,
+        }
+        // Pack whatever else is already queued, then send.
+        loop {
+            match rx.try_recv() {
+                Ok(event) => publisher.add(&event).await,
+                Err(TryRecvError::Lagged(n)) => warn!("UDP sink fell behind, skipped {} events", n),
+                Err(_) => break,
+            }
+        }
+        publisher.flush().await;
+    }
+
+    info!("Stopped UDP sink to {}", dest);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{Exchange, MarketData};
+    use crate::trade_data::TradeData;
+
+    #[test]
+    fn test_packs_messages_back_to_back() {
+        let quote = MarketEvent::Bbo { exchange: Exchange::Binance, id: 4, data: MarketData { bid: Some(10.0), ..Default::default() } };
+        let trade = MarketEvent::Trade { exchange: Exchange::Okx, id: 9, data: TradeData { price: 11.0, qty: 2.0, ..Default::default() } };
+        let mut packer = Packer::new(sbe::QUOTE_LEN + sbe::TRADE_LEN);
+        assert!(packer.push(&quote));
+        assert!(!packer.fits(sbe::QUOTE_LEN));
+        assert!(packer.push(&trade));
+
+        let datagram = packer.take().to_vec();
+        assert!(packer.is_empty());
+        let (ex, id, md) = sbe::decode_quote(&datagram).unwrap();
+        assert!(ex == Exchange::Binance);
+        assert_eq!((id, md.bid), (4, Some(10.0)));
+        let (ex, id, t) = sbe::decode_trade(&datagram[sbe::QUOTE_LEN..]).unwrap();
+        assert!(ex == Exchange::Okx);
+        assert_eq!((id, t.price, t.qty), (9, 11.0, 2.0));
+    }
+
+    #[test]
+    fn test_records_per_encoding() {
+        let cfg: UdpSinkConfig = serde_yaml::from_str("addr: \"127.0.0.1:7500\"\nencoding: msgpack").unwrap();
+        assert_eq!(cfg.encoding.records(), Some(UpdateEncoding::Msgpack));
+        assert_eq!(UdpEncoding::default().records(), None);
+
+        let trade = MarketEvent::Trade { exchange: Exchange::Okx, id: 9, data: TradeData { price: 11.0, qty: 2.0, ..Default::default() } };
+        let mut out = Vec::new();
+        assert!(encode_record(UpdateEncoding::Json, &trade, &mut out).unwrap());
+        let back: TradeUpdate = UpdateEncoding::Json.decode(&out).unwrap();
+        assert_eq!((back.exchange.as_str(), back.price, back.qty), ("okx", 11.0, 2.0));
+
+        let status = MarketEvent::BookReset { exchange: Exchange::Okx, id: 9 };
+        assert!(!encode_record(UpdateEncoding::Json, &status, &mut out).unwrap());
+    }
+}
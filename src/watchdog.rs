@@ -0,0 +1,229 @@
+//! Dead-man's switch.
+//!
+//! Fires when every (venue, symbol) in the watched universe is older than
+//! `max_age_s` at once, i.e. the node has lost all connectivity rather than
+//! one feed. It fires once per outage and re-arms when any quote is fresh
+//! again. Actions: an in-process callback, a webhook POST, and/or exiting
+//! the process with a chosen code so the orchestrator restarts it.
+
+use crate::market_data::{AllMarketData, Exchange, InstrumentType};
+use crate::symbol_registry::{REGISTRY, SymbolId};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+fn default_max_age_s() -> u64 {
+    30
+}
+fn default_check_interval_ms() -> u64 {
+    1_000
+}
+fn default_startup_grace_s() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeadManConfig {
+    #[serde(default = "default_max_age_s")]
+    pub max_age_s: u64,
+    #[serde(default = "default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// No trigger before this long after start, while feeds connect.
+    #[serde(default = "default_startup_grace_s")]
+    pub startup_grace_s: u64,
+    /// Venue → config-style symbols. Empty means every configured spot/perp symbol.
+    #[serde(default)]
+    pub symbols: HashMap<String, Vec<String>>,
+    /// POSTed a JSON body when the switch fires (needs the analytics feature).
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Exit the process with this code when the switch fires.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DeadManEvent {
+    /// Age of the freshest quote in the universe; None if none has ever arrived.
+    pub freshest_age: Option<Duration>,
+    pub universe_size: usize,
+}
+
+pub type DeadManCallback = Arc<dyn Fn(&DeadManEvent) + Send + Sync>;
+
+/// Age of the freshest quote across `universe`. Evicted or never-seen
+/// quotes don't count.
+pub fn freshest_age(data: &AllMarketData, universe: &[(Exchange, SymbolId)], now: Instant) -> Option<Duration> {
+    universe
+        .iter()
+        .filter_map(|(exchange, id)| {
+            let md = data.get_collection(exchange).latest(id)?;
+            Some(now.saturating_duration_since(md.received_instant?))
+        })
+        .min()
+}
+
+/// Arming logic, separate from the timer so it can be driven directly.
+pub struct DeadManState {
+    started: Instant,
+    max_age: Duration,
+    grace: Duration,
+    tripped: bool,
+}
+
+impl DeadManState {
+    pub fn new(cfg: &DeadManConfig, started: Instant) -> Self {
+        Self {
+            started,
+            max_age: Duration::from_secs(cfg.max_age_s),
+            grace: Duration::from_secs(cfg.startup_grace_s),
+            tripped: false,
+        }
+    }
+
+    /// True exactly once per outage, when it starts.
+    pub fn check(&mut self, freshest: Option<Duration>, now: Instant) -> bool {
+        let all_stale = freshest.is_none_or(|age| age > self.max_age);
+        if !all_stale {
+            if self.tripped {
+                info!("Dead-man switch re-armed: data is flowing again");
+            }
+            self.tripped = false;
+            return false;
+        }
+        if self.tripped || now.saturating_duration_since(self.started) < self.grace {
+            return false;
+        }
+        self.tripped = true;
+        true
+    }
+}
+
+impl DeadManConfig {
+    /// Resolve the watched universe; falls back to the feeds' own symbol lists.
+    pub fn universe(
+        &self,
+        spot: &HashMap<String, Vec<String>>,
+        perp: &HashMap<String, Vec<String>>,
+    ) -> Vec<(Exchange, SymbolId)> {
+        let mut out = Vec::new();
+        if !self.symbols.is_empty() {
+            for (venue, syms) in &self.symbols {
+                let Some(exchange) = venue.parse::<Exchange>().ok() else {
+                    warn!("dead_man: unknown venue '{}', skipping", venue);
+                    continue;
+                };
+                out.extend(syms.iter().filter_map(|s| REGISTRY.resolve(s)).map(|id| (exchange, id)));
+            }
+            return out;
+        }
+        for (map, itype) in [(spot, InstrumentType::Spot), (perp, InstrumentType::Perp)] {
+            for (venue, syms) in map {
+                let Some(exchange) = venue.parse::<Exchange>().ok() else { continue };
+                out.extend(syms.iter().filter_map(|s| REGISTRY.lookup(s, &itype)).map(|&id| (exchange, id)));
+            }
+        }
+        out
+    }
+}
+
+pub async fn run_dead_man(
+    cfg: DeadManConfig,
+    universe: Vec<(Exchange, SymbolId)>,
+    market_data: Arc<AllMarketData>,
+    callback: Option<DeadManCallback>,
+    shutdown: Arc<Notify>,
+) {
+    if universe.is_empty() {
+        warn!("dead_man: empty universe, not starting");
+        return;
+    }
+    let mut state = DeadManState::new(&cfg, Instant::now());
+    let mut interval = tokio::time::interval(Duration::from_millis(cfg.check_interval_ms.max(1)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => break,
+            _ = interval.tick() => {}
+        }
+        let now = Instant::now();
+        let freshest = freshest_age(&market_data, &universe, now);
+        if !state.check(freshest, now) {
+            continue;
+        }
+        let event = DeadManEvent { freshest_age: freshest, universe_size: universe.len() };
+        error!(
+            "Dead-man switch fired: all {} watched quotes stale (freshest {:?})",
+            event.universe_size, event.freshest_age
+        );
+        if let Some(cb) = &callback {
+            cb(&event);
+        }
+        if let Some(url) = &cfg.webhook_url {
+            post_webhook(url, &event).await;
+        }
+        if let Some(code) = cfg.exit_code {
+            error!("Dead-man switch exiting with code {}", code);
+            std::process::exit(code);
+        }
+    }
+}
+
+#[cfg(feature = "analytics")]
+async fn post_webhook(url: &str, event: &DeadManEvent) {
+    let body = serde_json::json!({
+        "event": "dead_man",
+        "universe_size": event.universe_size,
+        "freshest_age_ms": event.freshest_age.map(|d| d.as_millis() as u64),
+        "ts": chrono::Utc::now().to_rfc3339(),
+    });
+    let client = reqwest::Client::new();
+    match client.post(url).json(&body).timeout(Duration::from_secs(5)).send().await {
+        Ok(resp) if resp.status().is_success() => {}
+        Ok(resp) => warn!("dead_man webhook returned {}", resp.status()),
+        Err(e) => warn!("dead_man webhook failed: {}", e),
+    }
+}
+
+#[cfg(not(feature = "analytics"))]
+async fn post_webhook(_url: &str, _event: &DeadManEvent) {
+    warn!("dead_man webhook needs the analytics feature");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::MarketData;
+
+    #[test]
+    fn test_fires_once_per_outage() {
+        let cfg: DeadManConfig = serde_yaml::from_str("max_age_s: 10\nstartup_grace_s: 5\n").unwrap();
+        let t0 = Instant::now();
+        let mut state = DeadManState::new(&cfg, t0);
+        let s = Duration::from_secs;
+
+        // Nothing yet, but inside the startup grace.
+        assert!(!state.check(None, t0 + s(1)));
+        assert!(state.check(None, t0 + s(6)));
+        assert!(!state.check(None, t0 + s(7)));
+        // Data back: re-arms, then a second outage fires again.
+        assert!(!state.check(Some(s(1)), t0 + s(8)));
+        assert!(!state.check(Some(s(10)), t0 + s(17)));
+        assert!(state.check(Some(s(11)), t0 + s(18)));
+    }
+
+    #[test]
+    fn test_freshest_age_across_venues() {
+        let data = AllMarketData::new();
+        let t0 = Instant::now();
+        let tick = |at: Instant| MarketData { bid: Some(1.0), ask: Some(2.0), received_instant: Some(at), ..Default::default() };
+        data.binance.push(&1, tick(t0));
+        data.okx.push(&1, tick(t0 + Duration::from_secs(3)));
+        let universe = [(Exchange::Binance, 1), (Exchange::Okx, 1), (Exchange::Bybit, 1)];
+        assert_eq!(freshest_age(&data, &universe, t0 + Duration::from_secs(5)), Some(Duration::from_secs(2)));
+        assert_eq!(freshest_age(&data, &[(Exchange::Bybit, 1)], t0), None);
+    }
+}